@@ -0,0 +1,62 @@
+//! 游戏代码读取 [`BuiltCurves`] 的例子：一个方块沿正在编辑的 B-Spline 循环移动，朝向曲线的切线方向。
+//! 编辑控制点时方块随即走新的曲线，点数不够、构造不出 B-Spline 时隐藏。
+//!
+//! `cargo run --example follow_curve`
+
+use bevy::prelude::*;
+use my_bevy_game::{BuiltCurves, SplineShowcasePlugin};
+
+// 走完整条曲线的秒数，与段数无关
+const LAP_SECONDS: f32 = 6.0;
+const CUBE_SIZE: f32 = 24.0;
+
+#[derive(Component)]
+struct Follower;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            SplineShowcasePlugin::with_points([
+                Vec2::new(-300.0, -100.0),
+                Vec2::new(-150.0, 150.0),
+                Vec2::new(0.0, -150.0),
+                Vec2::new(150.0, 150.0),
+                Vec2::new(300.0, -100.0),
+            ]),
+        ))
+        .add_systems(Startup, spawn_follower)
+        .add_systems(Update, follow_b_spline)
+        .run();
+}
+
+fn spawn_follower(mut commands: Commands) {
+    commands.spawn((
+        Sprite::from_color(Color::srgb(1.0, 0.8, 0.2), Vec2::splat(CUBE_SIZE)),
+        Transform::from_xyz(0.0, 0.0, 1.0),
+        Visibility::Hidden,
+        Follower,
+    ));
+}
+
+fn follow_b_spline(
+    time: Res<Time>,
+    curves: Res<BuiltCurves>,
+    mut follower: Query<(&mut Transform, &mut Visibility), With<Follower>>,
+) {
+    let Ok((mut transform, mut visibility)) = follower.get_single_mut() else {
+        return;
+    };
+    let Some(curve) = &curves.b_spline else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+    // 参数范围是 0 到段数
+    let t = (time.elapsed_secs() / LAP_SECONDS).fract() * curve.segments().len() as f32;
+    transform.translation = curve.position(t).extend(transform.translation.z);
+    let velocity = curve.velocity(t);
+    if velocity != Vec2::ZERO {
+        transform.rotation = Quat::from_rotation_z(velocity.to_angle());
+    }
+}
@@ -0,0 +1,167 @@
+//! Akima 插值。
+//!
+//! 把控制点当作以弦长为参数的参数曲线，这样 x 不单调时也能工作。
+//! 斜率在两侧邻居的差异较大时偏向更平稳的一侧，从而避免 Catmull-Rom 在急转处的过冲。
+
+use bevy::math::{
+    cubic_splines::{
+        CubicCardinalSpline, CubicCurve, CubicGenerator, CubicSegment, InsufficientDataError,
+    },
+    Vec2,
+};
+
+/// Akima 公式需要左右各两个邻居，点数少于此值时退化为 Catmull-Rom。
+pub const AKIMA_MIN_POINTS: usize = 5;
+
+pub struct AkimaSpline {
+    points: Vec<Vec2>,
+}
+
+impl AkimaSpline {
+    pub fn new(points: impl IntoIterator<Item = Vec2>) -> Self {
+        Self {
+            points: points.into_iter().collect(),
+        }
+    }
+
+    /// 每一段的 Bezier 控制点，点数不足 [`AKIMA_MIN_POINTS`] 时为空。
+    pub fn bezier_segments(&self) -> Vec<[Vec2; 4]> {
+        let points = &self.points;
+        let n = points.len();
+        if n < AKIMA_MIN_POINTS {
+            return Vec::new();
+        }
+
+        let chords: Vec<f32> = points.windows(2).map(|w| w[0].distance(w[1])).collect();
+        let slopes: Vec<Vec2> = points
+            .windows(2)
+            .zip(&chords)
+            .map(|(w, &h)| {
+                if h > f32::EPSILON {
+                    (w[1] - w[0]) / h
+                } else {
+                    Vec2::ZERO
+                }
+            })
+            .collect();
+
+        // 两端各外推两个虚拟斜率，补齐缺失的邻居
+        let m = slopes.len();
+        let mut extended = Vec::with_capacity(m + 4);
+        let before = 2.0 * slopes[0] - slopes[1];
+        extended.push(2.0 * before - slopes[0]);
+        extended.push(before);
+        extended.extend_from_slice(&slopes);
+        let after = 2.0 * slopes[m - 1] - slopes[m - 2];
+        extended.push(after);
+        extended.push(2.0 * after - slopes[m - 1]);
+
+        let tangents: Vec<Vec2> = (0..n)
+            .map(|i| {
+                let [m0, m1, m2, m3] = [
+                    extended[i],
+                    extended[i + 1],
+                    extended[i + 2],
+                    extended[i + 3],
+                ];
+                let w1 = (m3 - m2).length();
+                let w2 = (m1 - m0).length();
+                if w1 + w2 > f32::EPSILON {
+                    (w1 * m1 + w2 * m2) / (w1 + w2)
+                } else {
+                    (m1 + m2) * 0.5
+                }
+            })
+            .collect();
+
+        (0..n - 1)
+            .map(|i| {
+                let h = chords[i];
+                let (p0, p1) = (points[i], points[i + 1]);
+                [
+                    p0,
+                    p0 + tangents[i] * h / 3.0,
+                    p1 - tangents[i + 1] * h / 3.0,
+                    p1,
+                ]
+            })
+            .collect()
+    }
+}
+
+impl CubicGenerator<Vec2> for AkimaSpline {
+    type Error = InsufficientDataError;
+
+    fn to_curve(&self) -> Result<CubicCurve<Vec2>, Self::Error> {
+        if self.points.len() < AKIMA_MIN_POINTS {
+            return CubicCardinalSpline::new_catmull_rom(self.points.clone()).to_curve();
+        }
+        let segments: Vec<CubicSegment<Vec2>> = self
+            .bezier_segments()
+            .into_iter()
+            .map(CubicSegment::new_bezier)
+            .collect();
+        CubicCurve::from_segments(segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 台阶形状的点：前三个点在 y = 0，后三个点在 y = 100
+    fn step_points() -> Vec<Vec2> {
+        [0.0, 0.0, 0.0, 100.0, 100.0, 100.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &y)| Vec2::new(i as f32 * 100.0, y))
+            .collect()
+    }
+
+    // 每段中点超出 [0, 100] 范围的最大距离
+    fn midpoint_overshoot(curve: &CubicCurve<Vec2>) -> f32 {
+        (0..curve.segments().len())
+            .map(|i| curve.position(i as f32 + 0.5).y)
+            .map(|y| (-y).max(y - 100.0).max(0.0))
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn less_overshoot_than_catmull_rom_on_steps() {
+        let points = step_points();
+        let akima = AkimaSpline::new(points.clone()).to_curve().unwrap();
+        let catmull_rom = CubicCardinalSpline::new_catmull_rom(points)
+            .to_curve()
+            .unwrap();
+        let akima_overshoot = midpoint_overshoot(&akima);
+        let catmull_rom_overshoot = midpoint_overshoot(&catmull_rom);
+        assert!(catmull_rom_overshoot > 1.0);
+        assert!(akima_overshoot < catmull_rom_overshoot);
+    }
+
+    #[test]
+    fn interpolates_every_point() {
+        let points = step_points();
+        let curve = AkimaSpline::new(points.clone()).to_curve().unwrap();
+        for (i, point) in points.iter().enumerate() {
+            assert!(curve.position(i as f32).distance(*point) < 1e-3);
+        }
+    }
+
+    #[test]
+    fn few_points_fall_back_to_catmull_rom() {
+        let points = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(50.0, 80.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(150.0, 40.0),
+        ];
+        let akima = AkimaSpline::new(points.clone()).to_curve().unwrap();
+        let catmull_rom = CubicCardinalSpline::new_catmull_rom(points)
+            .to_curve()
+            .unwrap();
+        for (a, b) in akima.iter_positions(20).zip(catmull_rom.iter_positions(20)) {
+            assert!(a.distance(b) < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! 选中的点的对齐和等距分布。输入是选中的点按 ControlPoints 中顺序排列的位置。
+
+use bevy::math::Vec2;
+
+/// 所有点的 y 设为平均值，排成一条水平线。
+pub fn align_horizontally(positions: &mut [Vec2]) {
+    let Some(mean) = mean(positions) else {
+        return;
+    };
+    for p in positions {
+        p.y = mean.y;
+    }
+}
+
+/// 所有点的 x 设为平均值，排成一条竖直线。
+pub fn align_vertically(positions: &mut [Vec2]) {
+    let Some(mean) = mean(positions) else {
+        return;
+    };
+    for p in positions {
+        p.x = mean.x;
+    }
+}
+
+/// 首尾两点不动，中间的点按顺序等距地放在两点的连线上。
+pub fn distribute(positions: &mut [Vec2]) {
+    let n = positions.len();
+    if n < 3 {
+        return;
+    }
+    let (first, last) = (positions[0], positions[n - 1]);
+    for (i, p) in positions.iter_mut().enumerate().take(n - 1).skip(1) {
+        *p = first.lerp(last, i as f32 / (n - 1) as f32);
+    }
+}
+
+fn mean(positions: &[Vec2]) -> Option<Vec2> {
+    if positions.is_empty() {
+        return None;
+    }
+    Some(positions.iter().sum::<Vec2>() / positions.len() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_to_the_mean() {
+        let mut positions = [
+            Vec2::new(0.0, 10.0),
+            Vec2::new(30.0, -40.0),
+            Vec2::new(60.0, 0.0),
+        ];
+        align_horizontally(&mut positions);
+        assert_eq!(
+            positions,
+            [
+                Vec2::new(0.0, -10.0),
+                Vec2::new(30.0, -10.0),
+                Vec2::new(60.0, -10.0),
+            ]
+        );
+        align_vertically(&mut positions);
+        assert!(positions.iter().all(|p| *p == Vec2::new(30.0, -10.0)));
+    }
+
+    // 中间的点按原来的顺序放到连线上，与它们原来的位置无关
+    #[test]
+    fn distributes_along_the_line_between_the_ends() {
+        let mut positions = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(90.0, 50.0),
+            Vec2::new(-20.0, 10.0),
+            Vec2::new(5.0, 5.0),
+            Vec2::new(100.0, 200.0),
+        ];
+        distribute(&mut positions);
+        assert_eq!(
+            positions,
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(25.0, 50.0),
+                Vec2::new(50.0, 100.0),
+                Vec2::new(75.0, 150.0),
+                Vec2::new(100.0, 200.0),
+            ]
+        );
+        let mut two = [Vec2::ZERO, Vec2::ONE];
+        distribute(&mut two);
+        assert_eq!(two, [Vec2::ZERO, Vec2::ONE]);
+    }
+}
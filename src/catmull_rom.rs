@@ -0,0 +1,220 @@
+//! 非均匀参数化的 Catmull-Rom。
+//!
+//! bevy 的 `new_catmull_rom` 使用均匀参数，点距相差很大时曲线会在短的一段里打圈或过冲。
+//! 把相邻节点的间隔取为弦长的 alpha 次方可以避免：alpha = 0.5 是向心参数化，1 是弦长参数化。
+//! bevy 只提供均匀的版本，这里按 Barry–Goldman 的递推求出每段两端在段内参数下的切线，
+//! 再把每段写成等价的 Bezier。
+
+use bevy::math::{
+    cubic_splines::{CubicCurve, CubicSegment},
+    Vec2,
+};
+use serde::{Deserialize, Serialize};
+
+/// 向心参数化的默认 alpha。
+pub const CENTRIPETAL_ALPHA: f32 = 0.5;
+
+// 重合的点让节点间隔为零，用一个很小的间隔代替
+const MIN_KNOT_INTERVAL: f32 = 1e-6;
+
+/// Catmull-Rom 的节点间隔怎样由相邻点的距离决定。
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum Parameterization {
+    /// 间隔都是 1，与 bevy 的 Catmull-Rom 相同
+    #[default]
+    Uniform,
+    /// 间隔是弦长
+    ChordLength,
+    /// 间隔是弦长的 alpha 次方
+    Centripetal { alpha: f32 },
+}
+
+impl Parameterization {
+    pub fn alpha(self) -> f32 {
+        match self {
+            Parameterization::Uniform => 0.0,
+            Parameterization::ChordLength => 1.0,
+            Parameterization::Centripetal { alpha } => alpha,
+        }
+    }
+
+    /// 均匀、向心、弦长依次循环。
+    pub fn next(self) -> Self {
+        match self {
+            Parameterization::Uniform => Parameterization::Centripetal {
+                alpha: CENTRIPETAL_ALPHA,
+            },
+            Parameterization::Centripetal { .. } => Parameterization::ChordLength,
+            Parameterization::ChordLength => Parameterization::Uniform,
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Parameterization::Uniform => "uniform".to_string(),
+            Parameterization::ChordLength => "chord length".to_string(),
+            Parameterization::Centripetal { alpha } => format!("centripetal (alpha {alpha:.2})"),
+        }
+    }
+}
+
+/// 每段的 Bezier 控制点。`tensions` 与 Cardinal 样条的张力相同，0.5 时是标准的 Catmull-Rom；
+/// 非闭合时与 `cardinal_tangents` 一样在两端各镜像一个点，闭合时多出连回第一个点的一段。
+pub fn catmull_rom_segments(
+    points: &[Vec2],
+    tensions: &[f32],
+    closed: bool,
+    alpha: f32,
+) -> Vec<[Vec2; 4]> {
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let point = |i: isize| -> Vec2 {
+        if closed {
+            points[i.rem_euclid(n as isize) as usize]
+        } else if i < 0 {
+            points[0] * 2.0 - points[1]
+        } else if i as usize >= n {
+            points[n - 1] * 2.0 - points[n - 2]
+        } else {
+            points[i as usize]
+        }
+    };
+    let interval = |a: Vec2, b: Vec2| a.distance(b).powf(alpha).max(MIN_KNOT_INTERVAL);
+    let segments = if closed { n } else { n - 1 };
+    (0..segments)
+        .map(|j| {
+            let i = j as isize;
+            let [p0, p1, p2, p3] = [point(i - 1), point(i), point(i + 1), point(i + 2)];
+            let (d0, d1, d2) = (interval(p0, p1), interval(p1, p2), interval(p2, p3));
+            // 全局参数下的速度乘以这一段的间隔，换算成段内参数 [0, 1] 下的切线
+            let start = ((p1 - p0) / d0 - (p2 - p0) / (d0 + d1) + (p2 - p1) / d1) * d1;
+            let end = ((p2 - p1) / d1 - (p3 - p1) / (d1 + d2) + (p3 - p2) / d2) * d1;
+            let start = start * 2.0 * tensions[j];
+            let end = end * 2.0 * tensions[(j + 1) % n];
+            [p1, p1 + start / 3.0, p2 - end / 3.0, p2]
+        })
+        .collect()
+}
+
+/// 由 [`catmull_rom_segments`] 构造曲线，少于两个点时返回 None。
+pub fn catmull_rom_curve(
+    points: &[Vec2],
+    tensions: &[f32],
+    closed: bool,
+    alpha: f32,
+) -> Option<CubicCurve<Vec2>> {
+    let segments: Vec<CubicSegment<Vec2>> = catmull_rom_segments(points, tensions, closed, alpha)
+        .into_iter()
+        .map(CubicSegment::new_bezier)
+        .collect();
+    CubicCurve::from_segments(segments).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{curves::cardinal_tangents, intersect::self_intersections};
+
+    fn samples(curve: &CubicCurve<Vec2>) -> Vec<Vec2> {
+        curve.iter_positions(192).collect()
+    }
+
+    // alpha 为 0 时切线是 (p_{i+1} - p_{i-1}) / 2，与均匀的 Catmull-Rom 相同
+    #[test]
+    fn zero_alpha_is_uniform_catmull_rom() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 30.0),
+            Vec2::new(150.0, -20.0),
+            Vec2::new(300.0, 0.0),
+        ];
+        let segments = catmull_rom_segments(&points, &[0.5; 4], false, 0.0);
+        assert_eq!(segments.len(), 3);
+        let middle = segments[1];
+        assert_eq!(middle[0], points[1]);
+        assert_eq!(middle[3], points[2]);
+        assert!(middle[1].distance(points[1] + (points[2] - points[0]) / 6.0) < 1e-4);
+        assert!(middle[2].distance(points[2] - (points[3] - points[1]) / 6.0) < 1e-4);
+    }
+
+    // 三个几乎共线的点加一个远处的点：均匀参数在短的一段里打圈，向心参数不会
+    #[test]
+    fn centripetal_has_no_loop_where_uniform_does() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 1.0),
+            Vec2::new(110.0, 0.0),
+            Vec2::new(110.0, 200.0),
+        ];
+        let tensions = [0.5; 4];
+        let uniform = catmull_rom_curve(&points, &tensions, false, 0.0).unwrap();
+        let centripetal = catmull_rom_curve(&points, &tensions, false, CENTRIPETAL_ALPHA).unwrap();
+        assert!(!self_intersections(&samples(&uniform), 1e-4).is_empty());
+        assert!(self_intersections(&samples(&centripetal), 1e-4).is_empty());
+        // 短的一段里速度方向始终沿着弦，没有尖点
+        let chord = points[2] - points[1];
+        for i in 0..=32 {
+            let t = 1.0 + i as f32 / 32.0;
+            assert!(centripetal.velocity(t).dot(chord) > 0.0, "t = {t}");
+        }
+    }
+
+    #[test]
+    fn closed_curves_wrap_around() {
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(0.0, 100.0),
+        ];
+        let segments = catmull_rom_segments(&square, &[0.5; 4], true, CENTRIPETAL_ALPHA);
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[3][3], square[0]);
+        // 对称的正方形上每段的切线长度相同
+        let handle = |s: &[Vec2; 4]| s[1].distance(s[0]);
+        assert!(segments
+            .iter()
+            .all(|s| (handle(s) - handle(&segments[0])).abs() < 1e-3));
+    }
+
+    // Cardinal 张力为 0 时拐角处的切线为零，曲线直接折过去；张力越大越冲出拐角
+    #[test]
+    fn cardinal_tension_zero_is_a_corner_and_one_overshoots() {
+        let corner = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 100.0),
+        ];
+        // 拐角之后的一段偏出 x = 100 的最大距离
+        let overshoot = |tension: f32| {
+            let tangents = cardinal_tangents(&corner, &[tension; 3], false);
+            let segment = CubicSegment::new_bezier([
+                corner[1],
+                corner[1] + tangents[1] / 3.0,
+                corner[2] - tangents[2] / 3.0,
+                corner[2],
+            ]);
+            (0..=64)
+                .map(|i| segment.position(i as f32 / 64.0).x - 100.0)
+                .fold(0.0, f32::max)
+        };
+        assert_eq!(cardinal_tangents(&corner, &[0.0; 3], false)[1], Vec2::ZERO);
+        assert!(overshoot(0.0) < 1e-4);
+        assert!(overshoot(0.5) > 1.0);
+        assert!(overshoot(1.0) > 1.5 * overshoot(0.5));
+    }
+
+    #[test]
+    fn parameterizations_cycle() {
+        let mut parameterization = Parameterization::default();
+        let mut alphas = Vec::new();
+        for _ in 0..3 {
+            alphas.push(parameterization.alpha());
+            parameterization = parameterization.next();
+        }
+        assert_eq!(alphas, [0.0, CENTRIPETAL_ALPHA, 1.0]);
+        assert_eq!(parameterization, Parameterization::Uniform);
+    }
+}
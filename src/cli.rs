@@ -0,0 +1,263 @@
+//! 命令行参数，手写解析；格式错误时返回可读的错误，由 main 打印用法后退出。
+
+use std::{fmt, path::PathBuf};
+
+use crate::{CurveResolution, RandomScatter};
+
+pub use crate::presets::Preset;
+
+pub const USAGE: &str = "\
+usage: my_bevy_game [options]
+
+  --points <file>      load control points from a RON or CSV file
+  --preset <name>      start with a preset: circle, sine, zigzag, s-curve or star
+  --resolution <n>     samples per segment (2..=500)
+  --seed <n>           seed of the first random layout (F10)
+  --random-count <n>   points per random layout (2..=100, default 8)
+  --window <W>x<H>     window size in logical pixels, e.g. 1600x900
+  --3d                 start the 3D mode instead (ignores the 2D options)
+  --no-persist         neither load nor save showcase_settings.ron
+  -h, --help           show this message";
+
+/// 解析后的命令行参数，没有给出的选项为 None。
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Options {
+    pub points: Option<PathBuf>,
+    pub preset: Option<Preset>,
+    pub resolution: Option<usize>,
+    pub seed: Option<u64>,
+    pub random_count: Option<usize>,
+    pub window: Option<(u32, u32)>,
+    pub space: bool,
+    pub no_persist: bool,
+    pub help: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    Unknown(String),
+    MissingValue(&'static str),
+    Invalid {
+        option: &'static str,
+        value: String,
+        expected: String,
+    },
+    Conflict(&'static str, &'static str),
+    // 解析之后才能发现的错误，例如点文件读取失败
+    Points {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Unknown(arg) => write!(f, "unknown option `{arg}`"),
+            CliError::MissingValue(option) => write!(f, "{option} needs a value"),
+            CliError::Invalid {
+                option,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value `{value}` for {option}: expected {expected}"
+            ),
+            CliError::Conflict(a, b) => write!(f, "{a} and {b} cannot be used together"),
+            CliError::Points { path, message } => {
+                write!(f, "cannot load {}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+const MAX_WINDOW_SIDE: u32 = 16384;
+
+/// 解析参数（不含程序名），同时接受 `--option value` 和 `--option=value`。
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, CliError> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |option: &'static str| -> Result<String, CliError> {
+            match inline {
+                Some(value) => Ok(value.to_string()),
+                None => args.next().ok_or(CliError::MissingValue(option)),
+            }
+        };
+        match name.as_str() {
+            "--points" => options.points = Some(PathBuf::from(value("--points")?)),
+            "--preset" => options.preset = Some(parse_preset(&value("--preset")?)?),
+            "--resolution" => options.resolution = Some(parse_resolution(&value("--resolution")?)?),
+            "--seed" => options.seed = Some(parse_seed(&value("--seed")?)?),
+            "--random-count" => {
+                options.random_count = Some(parse_random_count(&value("--random-count")?)?)
+            }
+            "--window" => options.window = Some(parse_window(&value("--window")?)?),
+            "--3d" if inline.is_none() => options.space = true,
+            "--no-persist" if inline.is_none() => options.no_persist = true,
+            "-h" | "--help" if inline.is_none() => options.help = true,
+            _ => return Err(CliError::Unknown(arg)),
+        }
+    }
+    if options.points.is_some() && options.preset.is_some() {
+        return Err(CliError::Conflict("--points", "--preset"));
+    }
+    Ok(options)
+}
+
+fn parse_preset(value: &str) -> Result<Preset, CliError> {
+    Preset::ALL
+        .into_iter()
+        .find(|preset| preset.name().eq_ignore_ascii_case(value))
+        .ok_or_else(|| CliError::Invalid {
+            option: "--preset",
+            value: value.to_string(),
+            expected: Preset::ALL.map(Preset::name).join(", "),
+        })
+}
+
+fn parse_resolution(value: &str) -> Result<usize, CliError> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|n| (CurveResolution::MIN..=CurveResolution::MAX).contains(n))
+        .ok_or_else(|| CliError::Invalid {
+            option: "--resolution",
+            value: value.to_string(),
+            expected: format!(
+                "an integer from {} to {}",
+                CurveResolution::MIN,
+                CurveResolution::MAX
+            ),
+        })
+}
+
+fn parse_seed(value: &str) -> Result<u64, CliError> {
+    value.parse::<u64>().map_err(|_| CliError::Invalid {
+        option: "--seed",
+        value: value.to_string(),
+        expected: "a non-negative integer".to_string(),
+    })
+}
+
+fn parse_random_count(value: &str) -> Result<usize, CliError> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|n| (RandomScatter::MIN_COUNT..=RandomScatter::MAX_COUNT).contains(n))
+        .ok_or_else(|| CliError::Invalid {
+            option: "--random-count",
+            value: value.to_string(),
+            expected: format!(
+                "an integer from {} to {}",
+                RandomScatter::MIN_COUNT,
+                RandomScatter::MAX_COUNT
+            ),
+        })
+}
+
+fn parse_window(value: &str) -> Result<(u32, u32), CliError> {
+    let side = |s: &str| {
+        s.trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (1..=MAX_WINDOW_SIDE).contains(n))
+    };
+    value
+        .split_once(['x', 'X'])
+        .and_then(|(w, h)| Some((side(w)?, side(h)?)))
+        .ok_or_else(|| CliError::Invalid {
+            option: "--window",
+            value: value.to_string(),
+            expected: "<width>x<height>, e.g. 1600x900".to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Result<Options, CliError> {
+        parse(list.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn parses_all_options() {
+        let options = args(&[
+            "--preset",
+            "sine",
+            "--resolution=50",
+            "--window",
+            "1600x900",
+            "--seed",
+            "42",
+            "--random-count=12",
+        ])
+        .unwrap();
+        assert_eq!(options.preset, Some(Preset::Sine));
+        assert_eq!(options.resolution, Some(50));
+        assert_eq!(options.window, Some((1600, 900)));
+        assert_eq!(options.seed, Some(42));
+        assert_eq!(options.random_count, Some(12));
+        assert_eq!(options.points, None);
+
+        let options = args(&["--points", "scene.ron", "--3d", "--no-persist"]).unwrap();
+        assert_eq!(options.points, Some(PathBuf::from("scene.ron")));
+        assert!(options.space);
+        assert!(options.no_persist);
+        assert_eq!(args(&[]).unwrap(), Options::default());
+    }
+
+    #[test]
+    fn points_and_preset_conflict() {
+        assert_eq!(
+            args(&["--points", "a.ron", "--preset", "circle"]),
+            Err(CliError::Conflict("--points", "--preset"))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        for list in [
+            &["--resolution", "fifty"][..],
+            &["--resolution", "1"],
+            &["--resolution", "501"],
+            &["--window", "1600"],
+            &["--window", "0x900"],
+            &["--window", "1600x-1"],
+            &["--preset", "spiral"],
+            &["--seed", "-1"],
+            &["--random-count", "1"],
+            &["--random-count", "101"],
+        ] {
+            assert!(
+                matches!(args(list), Err(CliError::Invalid { .. })),
+                "{list:?}"
+            );
+        }
+        assert_eq!(args(&["--window"]), Err(CliError::MissingValue("--window")));
+        assert_eq!(
+            args(&["--frobnicate"]),
+            Err(CliError::Unknown("--frobnicate".to_string()))
+        );
+        assert_eq!(
+            args(&["--3d=yes"]),
+            Err(CliError::Unknown("--3d=yes".to_string()))
+        );
+    }
+
+    #[test]
+    fn error_messages_name_the_option() {
+        let error = args(&["--resolution", "abc"]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid value `abc` for --resolution: expected an integer from 2 to 500"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! 通过系统剪贴板复制粘贴控制点，格式为每行一个 `x,y`。
+
+use std::fmt;
+
+use bevy::math::Vec2;
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub content: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected `x,y`, got `{}`",
+            self.line, self.content
+        )
+    }
+}
+
+pub fn format_points(points: &[Vec2]) -> String {
+    points
+        .iter()
+        .map(|p| format!("{},{}\n", p.x, p.y))
+        .collect()
+}
+
+/// 忽略空行和多余的空白，任何一行不是两个有限数时返回出错的行号。
+pub fn parse_points(text: &str) -> Result<Vec<Vec2>, ParseError> {
+    let mut points = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = || ParseError {
+            line: index + 1,
+            content: line.to_string(),
+        };
+        let mut values = line.split(',').map(|v| v.trim().parse::<f32>());
+        let (Some(Ok(x)), Some(Ok(y)), None) = (values.next(), values.next(), values.next()) else {
+            return Err(error());
+        };
+        if !x.is_finite() || !y.is_finite() {
+            return Err(error());
+        }
+        points.push(Vec2::new(x, y));
+    }
+    Ok(points)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_text() -> Result<String, String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_text(text: String) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn get_text() -> Result<String, String> {
+    Err("clipboard is not available in this build".to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn set_text(_text: String) -> Result<(), String> {
+    Err("clipboard is not available in this build".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_points() {
+        let points = vec![Vec2::new(-120.5, 30.0), Vec2::new(0.0, 1e-3)];
+        assert_eq!(parse_points(&format_points(&points)), Ok(points));
+    }
+
+    #[test]
+    fn tolerates_whitespace_and_blank_lines() {
+        let text = "\n  1 , 2 \r\n\t-3.5,4\n\n";
+        assert_eq!(
+            parse_points(text),
+            Ok(vec![Vec2::new(1.0, 2.0), Vec2::new(-3.5, 4.0)])
+        );
+    }
+
+    #[test]
+    fn rejects_garbage_with_line_number() {
+        assert_eq!(parse_points("1,2\nhello").unwrap_err().line, 2);
+        assert!(parse_points("1,2,3").is_err());
+        assert!(parse_points("1").is_err());
+        assert!(parse_points("NaN,2").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! 几乎重合的相邻控制点。插值样条在相邻点之间按弦长分配参数、用差商估计斜率，
+//! 重合的点让弦长为零，系数里出现除以零，采样得到 NaN；构造这些曲线之前把重合的点合并成一个。
+//!
+//! B-Spline 等逼近曲线不合并，重复的控制点在那里把曲线拉向该点，是常用的手法。
+
+use bevy::math::Vec2;
+
+/// 距离小于这个值的相邻控制点视为重合，单位与点的坐标相同。
+pub const COINCIDENT_EPSILON: f32 = 1e-3;
+
+/// 合并后保留的点的下标：与前一个保留的点重合的点被去掉；
+/// 闭合时最后一个点与第一个点重合也去掉。
+pub fn distinct_indices(points: &[Vec2], closed: bool, epsilon: f32) -> Vec<usize> {
+    let mut kept: Vec<usize> = Vec::with_capacity(points.len());
+    for (i, p) in points.iter().enumerate() {
+        match kept.last() {
+            Some(&last) if points[last].distance(*p) < epsilon => {}
+            _ => kept.push(i),
+        }
+    }
+    if closed && kept.len() > 1 {
+        let (first, last) = (points[kept[0]], points[kept[kept.len() - 1]]);
+        if first.distance(last) < epsilon {
+            kept.pop();
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Vec2 = Vec2::new(0.0, 0.0);
+    const B: Vec2 = Vec2::new(10.0, 0.0);
+    const C: Vec2 = Vec2::new(10.0, 10.0);
+
+    #[test]
+    fn consecutive_duplicates_are_merged() {
+        let points = [A, A, B, B + Vec2::splat(1e-4), B, C];
+        assert_eq!(
+            distinct_indices(&points, false, COINCIDENT_EPSILON),
+            [0, 2, 5]
+        );
+        assert_eq!(
+            distinct_indices(&[A, B, C], false, COINCIDENT_EPSILON),
+            [0, 1, 2]
+        );
+        assert_eq!(distinct_indices(&[A; 3], false, COINCIDENT_EPSILON), [0]);
+        assert!(distinct_indices(&[], false, COINCIDENT_EPSILON).is_empty());
+    }
+
+    // 不相邻的重复点（例如来回折返）保留，闭合时首尾相接处也检查
+    #[test]
+    fn only_neighbours_count() {
+        let points = [A, B, A, C];
+        assert_eq!(
+            distinct_indices(&points, false, COINCIDENT_EPSILON),
+            [0, 1, 2, 3]
+        );
+        let ring = [A, B, C, A];
+        assert_eq!(
+            distinct_indices(&ring, false, COINCIDENT_EPSILON),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(distinct_indices(&ring, true, COINCIDENT_EPSILON), [0, 1, 2]);
+        assert_eq!(distinct_indices(&[A, A], true, COINCIDENT_EPSILON), [0]);
+    }
+}
@@ -0,0 +1,105 @@
+//! 样条类型之间的转换。Hermite 形式的一段 `[p0, m0, p1, m1]` 与 Bezier
+//! `[p0, p0 + m0 / 3, p1 - m1 / 3, p1]` 是同一条曲线，Catmull-Rom 的每段都能精确写成 Bezier；
+//! 反过来，经过 Bezier 链上各段端点的 Catmull-Rom 只在内部控制点正好落在它的切线上时才重合。
+
+use bevy::math::Vec2;
+
+// 首尾距离小于这个值时认为 Bezier 链是闭合的
+const CLOSED_EPSILON: f32 = 1e-3;
+
+/// 首尾相接的各段 Bezier 合成一个控制网：相邻两段共享端点，n 段共 3n + 1 个点。
+pub fn bezier_net(segments: &[[Vec2; 4]]) -> Vec<Vec2> {
+    let Some(first) = segments.first() else {
+        return Vec::new();
+    };
+    let mut net = Vec::with_capacity(segments.len() * 3 + 1);
+    net.push(first[0]);
+    for segment in segments {
+        net.extend_from_slice(&segment[1..]);
+    }
+    net
+}
+
+/// Bezier 控制网中落在曲线上的点，即每段的端点；凑不满一段的尾部点与 Bezier 链的构造一样忽略。
+/// 最后一个端点回到第一个点时去掉它，并返回 true 表示应当闭合。
+pub fn on_curve_points(net: &[Vec2]) -> (Vec<Vec2>, bool) {
+    if net.len() < 4 {
+        return (Vec::new(), false);
+    }
+    let segments = (net.len() - 1) / 3;
+    let mut points: Vec<Vec2> = (0..=segments).map(|i| net[i * 3]).collect();
+    let closed = segments > 1 && points[0].distance(points[segments]) < CLOSED_EPSILON;
+    if closed {
+        points.pop();
+    }
+    (points, closed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        catmull_rom::{catmull_rom_curve, catmull_rom_segments, CENTRIPETAL_ALPHA},
+        curves::bezier_chain,
+    };
+    use bevy::math::cubic_splines::{CubicBezier, CubicCurve, CubicGenerator};
+
+    const POINTS: [Vec2; 5] = [
+        Vec2::new(-200.0, 0.0),
+        Vec2::new(-80.0, 120.0),
+        Vec2::new(0.0, -60.0),
+        Vec2::new(90.0, 40.0),
+        Vec2::new(220.0, -30.0),
+    ];
+
+    fn bezier_curve(net: &[Vec2]) -> CubicCurve<Vec2> {
+        CubicBezier::new(bezier_chain(net)).to_curve().unwrap()
+    }
+
+    // 两条曲线在同样的参数处位置相同，参数域也相同
+    fn assert_overlap(a: &CubicCurve<Vec2>, b: &CubicCurve<Vec2>) {
+        let (a, b): (Vec<Vec2>, Vec<Vec2>) = (
+            a.iter_positions(64).collect(),
+            b.iter_positions(64).collect(),
+        );
+        assert_eq!(a.len(), b.len());
+        for (p, q) in a.iter().zip(&b) {
+            assert!(p.distance(*q) < 1e-3, "{p} != {q}");
+        }
+    }
+
+    #[test]
+    fn catmull_rom_becomes_an_identical_bezier_chain() {
+        let tensions = [0.5, 0.3, 0.8, 0.5, 0.5];
+        for (closed, alpha) in [(false, 0.0), (false, CENTRIPETAL_ALPHA), (true, 0.0)] {
+            let segments = catmull_rom_segments(&POINTS, &tensions, closed, alpha);
+            let net = bezier_net(&segments);
+            assert_eq!(net.len(), segments.len() * 3 + 1);
+            let catmull_rom = catmull_rom_curve(&POINTS, &tensions, closed, alpha).unwrap();
+            assert_overlap(&catmull_rom, &bezier_curve(&net));
+        }
+    }
+
+    // 由 Catmull-Rom 转换来的 Bezier 再拟合回去，得到原来的点和同一条曲线
+    #[test]
+    fn fitting_the_converted_chain_gives_back_the_catmull_rom() {
+        for closed in [false, true] {
+            let segments = catmull_rom_segments(&POINTS, &[0.5; 5], closed, 0.0);
+            let (points, fitted_closed) = on_curve_points(&bezier_net(&segments));
+            assert_eq!(points, POINTS);
+            assert_eq!(fitted_closed, closed);
+            let original = catmull_rom_curve(&POINTS, &[0.5; 5], closed, 0.0).unwrap();
+            let fitted = catmull_rom_curve(&points, &[0.5; 5], fitted_closed, 0.0).unwrap();
+            assert_overlap(&original, &fitted);
+        }
+    }
+
+    #[test]
+    fn incomplete_tails_are_ignored() {
+        assert_eq!(on_curve_points(&POINTS[..3]), (Vec::new(), false));
+        let (points, closed) = on_curve_points(&POINTS);
+        assert_eq!(points, [POINTS[0], POINTS[3]]);
+        assert!(!closed);
+        assert!(bezier_net(&[]).is_empty());
+    }
+}
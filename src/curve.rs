@@ -0,0 +1,520 @@
+//! 所有曲线类型的统一接口，供渲染和各种分析叠加层使用。
+
+use bevy::math::{cubic_splines::CubicCurve, Vec2};
+
+// 数值微分的步长，单位为参数 t
+const DIFFERENCE_STEP: f32 = 1e-3;
+
+/// 一组采样点及其对应的曲线参数。
+#[derive(Default)]
+pub struct Samples {
+    pub params: Vec<f32>,
+    pub positions: Vec<Vec2>,
+}
+
+impl Samples {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            params: Vec::with_capacity(capacity),
+            positions: Vec::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, t: f32, position: Vec2) {
+        self.params.push(t);
+        self.positions.push(position);
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+/// 参数域为 `0..segment_count` 的平面曲线，与 bevy 的 [`CubicCurve`] 一致。
+pub trait PlanarCurve: Send + Sync {
+    fn segment_count(&self) -> usize;
+
+    fn position(&self, t: f32) -> Vec2;
+
+    fn domain_end(&self) -> f32 {
+        self.segment_count() as f32
+    }
+
+    /// 默认使用中心差分，靠近端点时退化为单侧差分。
+    fn velocity(&self, t: f32) -> Vec2 {
+        let (a, b) = difference_span(t, self.domain_end());
+        (self.position(b) - self.position(a)) / (b - a)
+    }
+
+    fn acceleration(&self, t: f32) -> Vec2 {
+        let (a, b) = difference_span(t, self.domain_end());
+        (self.velocity(b) - self.velocity(a)) / (b - a)
+    }
+
+    /// 渲染用的采样点，默认每段按参数均匀采样。
+    fn sample(&self, samples_per_segment: usize) -> Samples {
+        let segments = self.segment_count();
+        let resolution = samples_per_segment * segments;
+        let mut samples = Samples::with_capacity(resolution + 1);
+        if resolution == 0 {
+            return samples;
+        }
+        let step = segments as f32 / resolution as f32;
+        for i in 0..=resolution {
+            let t = i as f32 * step;
+            samples.push(t, self.position(t));
+        }
+        samples
+    }
+}
+
+fn difference_span(t: f32, end: f32) -> (f32, f32) {
+    let a = (t - DIFFERENCE_STEP).max(0.0);
+    let b = (t + DIFFERENCE_STEP).min(end);
+    (a, b)
+}
+
+impl PlanarCurve for CubicCurve<Vec2> {
+    fn segment_count(&self) -> usize {
+        self.segments().len()
+    }
+
+    fn position(&self, t: f32) -> Vec2 {
+        CubicCurve::position(self, t)
+    }
+
+    fn velocity(&self, t: f32) -> Vec2 {
+        CubicCurve::velocity(self, t)
+    }
+
+    fn acceleration(&self, t: f32) -> Vec2 {
+        CubicCurve::acceleration(self, t)
+    }
+}
+
+/// 折线，用于控制多边形。
+pub struct Polyline(pub Vec<Vec2>);
+
+impl PlanarCurve for Polyline {
+    fn segment_count(&self) -> usize {
+        self.0.len().saturating_sub(1)
+    }
+
+    fn position(&self, t: f32) -> Vec2 {
+        let (index, u) = split_parameter(t, self.segment_count());
+        self.0[index].lerp(self.0[index + 1], u)
+    }
+
+    fn velocity(&self, t: f32) -> Vec2 {
+        let (index, _) = split_parameter(t, self.segment_count());
+        self.0[index + 1] - self.0[index]
+    }
+
+    fn acceleration(&self, _t: f32) -> Vec2 {
+        Vec2::ZERO
+    }
+
+    // 折线直接使用顶点
+    fn sample(&self, _samples_per_segment: usize) -> Samples {
+        Samples {
+            params: (0..self.0.len()).map(|i| i as f32).collect(),
+            positions: self.0.clone(),
+        }
+    }
+}
+
+/// 把全局参数拆成段索引和段内参数（0..=1）。
+pub fn split_parameter(t: f32, segments: usize) -> (usize, f32) {
+    let t = t.clamp(0.0, segments as f32);
+    let index = (t as usize).min(segments - 1);
+    (index, t - index as f32)
+}
+
+/// 折线总长度，对曲线的采样点求和即得到近似弧长。
+pub fn polyline_length(points: &[Vec2]) -> f32 {
+    points.windows(2).map(|w| w[0].distance(w[1])).sum()
+}
+
+// 弧长表每段的采样数，越大重采样越均匀
+const ARC_LENGTH_TABLE_SAMPLES: usize = 256;
+
+/// 参数 t 与累计弧长的对照表，用于按弧长均匀采样。
+pub struct ArcLengthTable {
+    params: Vec<f32>,
+    lengths: Vec<f32>,
+}
+
+impl ArcLengthTable {
+    pub fn new(curve: &dyn PlanarCurve) -> Self {
+        let segments = curve.segment_count();
+        let resolution = (segments * ARC_LENGTH_TABLE_SAMPLES).max(1);
+        let step = curve.domain_end() / resolution as f32;
+        let params: Vec<f32> = (0..=resolution).map(|i| i as f32 * step).collect();
+        let mut lengths = Vec::with_capacity(params.len());
+        let mut total = 0.0;
+        let mut previous = curve.position(0.0);
+        for &t in &params {
+            let position = curve.position(t);
+            total += position.distance(previous);
+            lengths.push(total);
+            previous = position;
+        }
+        Self { params, lengths }
+    }
+
+    pub fn total_length(&self) -> f32 {
+        self.lengths.last().copied().unwrap_or(0.0)
+    }
+
+    /// 累计弧长为 `distance` 处的参数，表内做线性插值。
+    pub fn parameter_at(&self, distance: f32) -> f32 {
+        let index = self.lengths.partition_point(|&l| l < distance);
+        if index == 0 {
+            return self.params[0];
+        }
+        if index >= self.lengths.len() {
+            return self.params[self.params.len() - 1];
+        }
+        let (l0, l1) = (self.lengths[index - 1], self.lengths[index]);
+        let (t0, t1) = (self.params[index - 1], self.params[index]);
+        if l1 - l0 <= f32::EPSILON {
+            return t0;
+        }
+        t0 + (t1 - t0) * (distance - l0) / (l1 - l0)
+    }
+}
+
+/// 按弧长均匀采样，点数与按参数均匀采样相同。
+pub fn sample_arc_length(curve: &dyn PlanarCurve, samples_per_segment: usize) -> Samples {
+    let resolution = samples_per_segment * curve.segment_count();
+    let mut samples = Samples::with_capacity(resolution + 1);
+    if resolution == 0 {
+        return samples;
+    }
+    let table = ArcLengthTable::new(curve);
+    let step = table.total_length() / resolution as f32;
+    for i in 0..=resolution {
+        let t = table.parameter_at(i as f32 * step);
+        samples.push(t, curve.position(t));
+    }
+    samples
+}
+
+// 自适应细分的最小和最大递归深度：最小深度避免中点恰好落在弦上的 S 形段被误判为平直，
+// 最大深度保证退化段（例如四个控制点重合）也一定终止
+const ADAPTIVE_MIN_DEPTH: u32 = 2;
+const ADAPTIVE_MAX_DEPTH: u32 = 12;
+
+/// 自适应采样：递归二分每一段，直到中点到弦的距离小于 `epsilon`。
+pub fn sample_adaptive(curve: &dyn PlanarCurve, epsilon: f32) -> Samples {
+    let segments = curve.segment_count();
+    let mut samples = Samples::default();
+    if segments == 0 {
+        return samples;
+    }
+    samples.push(0.0, curve.position(0.0));
+    for i in 0..segments {
+        let (t0, t1) = (i as f32, (i + 1) as f32);
+        subdivide(
+            curve,
+            (t0, curve.position(t0)),
+            (t1, curve.position(t1)),
+            epsilon,
+            0,
+            &mut samples,
+        );
+    }
+    samples
+}
+
+fn subdivide(
+    curve: &dyn PlanarCurve,
+    (t0, p0): (f32, Vec2),
+    (t1, p1): (f32, Vec2),
+    epsilon: f32,
+    depth: u32,
+    samples: &mut Samples,
+) {
+    let tm = (t0 + t1) * 0.5;
+    let pm = curve.position(tm);
+    let flat = distance_to_segment(pm, p0, p1) <= epsilon;
+    if depth >= ADAPTIVE_MAX_DEPTH || (depth >= ADAPTIVE_MIN_DEPTH && flat) {
+        samples.push(t1, p1);
+        return;
+    }
+    subdivide(curve, (t0, p0), (tm, pm), epsilon, depth + 1, samples);
+    subdivide(curve, (tm, pm), (t1, p1), epsilon, depth + 1, samples);
+}
+
+/// 点到线段的距离，线段退化为一个点时返回到该点的距离。
+pub fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared <= f32::EPSILON {
+        return point.distance(a);
+    }
+    let t = ((point - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
+// 最近点搜索的粗采样数、细化的最大迭代次数和停止迭代的参数步长
+const CLOSEST_COARSE_SAMPLES: usize = 64;
+const CLOSEST_REFINE_STEPS: usize = 20;
+const CLOSEST_TOLERANCE: f32 = 1e-6;
+
+/// 曲线上离 `point` 最近的点及其参数。先粗采样找到最近的采样点，再在它两侧的区间里
+/// 求距离平方的导数 g(t) = (c(t) - p)·c'(t) 由负变正的根，用带二分保护的牛顿迭代；
+/// 没有这样的根时最近点在区间端点上，曲线两端的最近点也由此得到。
+pub fn closest_point_on_curve(curve: &dyn PlanarCurve, point: Vec2) -> (f32, Vec2) {
+    let end = curve.domain_end();
+    let resolution = (curve.segment_count() * CLOSEST_COARSE_SAMPLES).max(1);
+    let step = end / resolution as f32;
+    let distance = |t: f32| curve.position(t).distance_squared(point);
+    let nearest = (0..=resolution)
+        .min_by(|&a, &b| distance(a as f32 * step).total_cmp(&distance(b as f32 * step)))
+        .unwrap_or(0);
+    let mid = nearest as f32 * step;
+    let lo = (mid - step).max(0.0);
+    let hi = (mid + step).min(end);
+    let slope = |t: f32| (curve.position(t) - point).dot(curve.velocity(t));
+    let mut candidates = vec![lo, mid, hi];
+    for (a, b) in [(lo, mid), (mid, hi)] {
+        if a < b && slope(a) < 0.0 && slope(b) > 0.0 {
+            candidates.push(refine_closest(curve, point, a, b));
+        }
+    }
+    let t = candidates
+        .into_iter()
+        .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+        .unwrap_or(0.0);
+    (t, curve.position(t))
+}
+
+/// 曲线上离 `point` 最近处的参数，见 [`closest_point_on_curve`]。
+pub fn closest_parameter(curve: &dyn PlanarCurve, point: Vec2) -> f32 {
+    closest_point_on_curve(curve, point).0
+}
+
+// 在 g(a) < 0 < g(b) 的区间内求 g 的根；牛顿步落到区间外或二阶导数不为正时改用二分
+fn refine_closest(curve: &dyn PlanarCurve, point: Vec2, mut a: f32, mut b: f32) -> f32 {
+    let mut t = (a + b) * 0.5;
+    for _ in 0..CLOSEST_REFINE_STEPS {
+        let offset = curve.position(t) - point;
+        let velocity = curve.velocity(t);
+        let slope = offset.dot(velocity);
+        if slope < 0.0 {
+            a = t;
+        } else {
+            b = t;
+        }
+        let curvature = velocity.length_squared() + offset.dot(curve.acceleration(t));
+        let newton = t - slope / curvature;
+        let next = if curvature > 0.0 && newton > a && newton < b {
+            newton
+        } else {
+            (a + b) * 0.5
+        };
+        let done = (next - t).abs() < CLOSEST_TOLERANCE;
+        t = next;
+        if done {
+            break;
+        }
+    }
+    t
+}
+
+/// 从一条曲线的采样点到另一条曲线的距离。
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Deviation {
+    pub max: f32,
+    pub mean: f32,
+}
+
+/// 把 `samples` 逐个投影到 `other` 上。平均距离按相邻采样点的间距加权（梯形公式），
+/// 采样不均匀时也是沿弧长的平均；少于两个采样点时返回 None。
+pub fn deviation(samples: &[Vec2], other: &dyn PlanarCurve) -> Option<Deviation> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let distances: Vec<f32> = samples
+        .iter()
+        .map(|&p| closest_point_on_curve(other, p).1.distance(p))
+        .collect();
+    let max = distances.iter().copied().fold(0.0, f32::max);
+    let (mut weighted, mut length) = (0.0, 0.0);
+    for (w, d) in samples.windows(2).zip(distances.windows(2)) {
+        let step = w[0].distance(w[1]);
+        weighted += (d[0] + d[1]) * 0.5 * step;
+        length += step;
+    }
+    // 采样点全部重合时退化为算术平均
+    let mean = if length > 0.0 {
+        weighted / length
+    } else {
+        distances.iter().sum::<f32>() / distances.len() as f32
+    };
+    Some(Deviation { max, mean })
+}
+
+/// 有符号曲率，速度接近零时返回 None，避免除以零产生尖刺。
+pub fn signed_curvature(curve: &dyn PlanarCurve, t: f32) -> Option<f32> {
+    let velocity = curve.velocity(t);
+    let speed = velocity.length();
+    if speed < 1e-4 {
+        return None;
+    }
+    let curvature = velocity.perp_dot(curve.acceleration(t)) / speed.powi(3);
+    curvature.is_finite().then_some(curvature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::cubic_splines::{CubicBezier, CubicGenerator};
+
+    #[test]
+    fn arc_length_samples_are_evenly_spaced() {
+        // 第一段很短、第二段很长，且控制点间距不均匀，按参数采样时间距相差很大
+        let curve = CubicBezier::new([
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(2.0, 3.0),
+                Vec2::new(7.0, 4.0),
+                Vec2::new(10.0, 0.0),
+            ],
+            [
+                Vec2::new(10.0, 0.0),
+                Vec2::new(300.0, -200.0),
+                Vec2::new(420.0, 150.0),
+                Vec2::new(510.0, 0.0),
+            ],
+        ])
+        .to_curve()
+        .unwrap();
+
+        let spacings = |samples: Samples| -> (f32, f32) {
+            samples
+                .positions
+                .windows(2)
+                .map(|w| w[0].distance(w[1]))
+                .fold((f32::MAX, 0.0), |(lo, hi), d| (lo.min(d), hi.max(d)))
+        };
+
+        let (uniform_min, uniform_max) = spacings(curve.sample(50));
+        assert!(uniform_max / uniform_min > 10.0);
+
+        let (even_min, even_max) = spacings(sample_arc_length(&curve, 50));
+        assert!(even_max / even_min < 1.03);
+    }
+
+    #[test]
+    fn adaptive_sampling_terminates_on_coincident_points() {
+        let p = Vec2::new(5.0, 5.0);
+        let curve = CubicBezier::new([[p, p, p, p]]).to_curve().unwrap();
+        let samples = sample_adaptive(&curve, 0.5);
+        assert_eq!(samples.len(), 1 + (1 << ADAPTIVE_MIN_DEPTH));
+    }
+
+    #[test]
+    fn closest_parameter_finds_projection_on_polyline() {
+        let line = Polyline(vec![
+            Vec2::ZERO,
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 100.0),
+        ]);
+        assert!((closest_parameter(&line, Vec2::new(30.0, 20.0)) - 0.3).abs() < 1e-3);
+        assert!((closest_parameter(&line, Vec2::new(150.0, 50.0)) - 1.5).abs() < 1e-3);
+        assert!(closest_parameter(&line, Vec2::new(-50.0, 0.0)) < 1e-3);
+    }
+
+    fn two_segment_bezier() -> CubicCurve<Vec2> {
+        CubicBezier::new([
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(50.0, 120.0),
+                Vec2::new(150.0, -80.0),
+                Vec2::new(200.0, 40.0),
+            ],
+            [
+                Vec2::new(200.0, 40.0),
+                Vec2::new(250.0, 160.0),
+                Vec2::new(320.0, 160.0),
+                Vec2::new(360.0, 0.0),
+            ],
+        ])
+        .to_curve()
+        .unwrap()
+    }
+
+    #[test]
+    fn closest_point_matches_dense_sampling() {
+        let curve = two_segment_bezier();
+        let dense = 50_000;
+        let brute = |point: Vec2| {
+            (0..=dense)
+                .map(|i| {
+                    curve
+                        .position(2.0 * i as f32 / dense as f32)
+                        .distance(point)
+                })
+                .fold(f32::MAX, f32::min)
+        };
+        for y in (-150..=250).step_by(50) {
+            for x in (-100..=450).step_by(50) {
+                let point = Vec2::new(x as f32, y as f32);
+                let (t, position) = closest_point_on_curve(&curve, point);
+                assert!((0.0..=2.0).contains(&t));
+                assert!(position.distance(curve.position(t)) < 1e-4);
+                let found = position.distance(point);
+                assert!((found - brute(point)).abs() < 1e-2, "{point}: {found}");
+            }
+        }
+    }
+
+    #[test]
+    fn closest_point_clamps_to_the_ends() {
+        let curve = two_segment_bezier();
+        // 起点的切线指向 (50, 120)，沿切线反方向远离起点
+        let (t, position) = closest_point_on_curve(&curve, Vec2::new(-50.0, -120.0));
+        assert!(t.abs() < 1e-4, "{t}");
+        assert!(position.distance(Vec2::ZERO) < 1e-2);
+        // 终点的切线从 (320, 160) 指向 (360, 0)
+        let (t, position) = closest_point_on_curve(&curve, Vec2::new(400.0, -160.0));
+        assert!((t - 2.0).abs() < 1e-4, "{t}");
+        assert!(position.distance(Vec2::new(360.0, 0.0)) < 1e-2);
+    }
+
+    #[test]
+    fn arc_length_table_matches_polyline_length() {
+        let line = Polyline(vec![
+            Vec2::ZERO,
+            Vec2::new(30.0, 40.0),
+            Vec2::new(30.0, 0.0),
+        ]);
+        let table = ArcLengthTable::new(&line);
+        assert!((table.total_length() - 90.0).abs() < 1e-3);
+        assert!((table.parameter_at(50.0) - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn deviation_between_lines() {
+        let samples: Vec<Vec2> = (0..=20).map(|i| Vec2::new(i as f32 * 5.0, 0.0)).collect();
+        let parallel = Polyline(vec![Vec2::new(-10.0, 10.0), Vec2::new(110.0, 10.0)]);
+        let result = deviation(&samples, &parallel).unwrap();
+        assert!((result.max - 10.0).abs() < 1e-3, "{result:?}");
+        assert!((result.mean - 10.0).abs() < 1e-3, "{result:?}");
+
+        // 距离沿 x 线性增大，平均值是最大值的一半
+        let slanted = Polyline(vec![Vec2::ZERO, Vec2::new(100.0, 20.0)]);
+        let result = deviation(&samples, &slanted).unwrap();
+        let far = 100.0 * 20.0 / Vec2::new(100.0, 20.0).length();
+        assert!((result.max - far).abs() < 1e-2, "{result:?}");
+        assert!((result.mean - far * 0.5).abs() < 1e-2, "{result:?}");
+
+        assert_eq!(deviation(&samples[..1], &slanted), None);
+    }
+}
@@ -0,0 +1,680 @@
+//! 由控制点构造各类曲线，以及不依赖 Bevy App 的采样和着色辅助函数。
+
+use super::*;
+
+// Catmull-Rom 风格的切线估计；闭合时使用环绕的邻居，否则端点使用单侧差分
+pub(crate) fn estimate_tangent<P: VectorSpace>(positions: &[P], index: usize, closed: bool) -> P {
+    let n = positions.len();
+    if n < 2 {
+        return P::ZERO;
+    }
+    if closed {
+        return (positions[(index + 1) % n] - positions[(index + n - 1) % n]) * 0.5;
+    }
+    if index == 0 {
+        positions[1] - positions[0]
+    } else if index == n - 1 {
+        positions[n - 1] - positions[n - 2]
+    } else {
+        (positions[index + 1] - positions[index - 1]) * 0.5
+    }
+}
+
+// 逐点张力的 Cardinal 切线 m_i = s_i * (p_{i+1} - p_{i-1})；
+// 非闭合时与 bevy 的 CubicCardinalSpline 一样在两端镜像一个点，端点切线为 2 * s * (p_1 - p_0)
+pub(crate) fn cardinal_tangents<P: VectorSpace>(
+    positions: &[P],
+    tensions: &[f32],
+    closed: bool,
+) -> Vec<P> {
+    let n = positions.len();
+    (0..n)
+        .map(|i| {
+            let (prev, next) = if closed {
+                (positions[(i + n - 1) % n], positions[(i + 1) % n])
+            } else {
+                let prev = if i == 0 {
+                    positions[0] * 2.0 - positions[1]
+                } else {
+                    positions[i - 1]
+                };
+                let next = if i == n - 1 {
+                    positions[n - 1] * 2.0 - positions[n - 2]
+                } else {
+                    positions[i + 1]
+                };
+                (prev, next)
+            };
+            (next - prev) * tensions[i]
+        })
+        .collect()
+}
+
+// 钳制的 B-Spline 的构造输入：首尾两个点各重复三次，均匀节点下曲线就从第一个点开始、
+// 到最后一个点结束。只用于构造，ControlPoints 本身不变
+pub(crate) fn clamped_b_spline_points<P: VectorSpace>(points: &[P]) -> Vec<P> {
+    let (Some(&first), Some(&last)) = (points.first(), points.last()) else {
+        return Vec::new();
+    };
+    let mut clamped = Vec::with_capacity(points.len() + 4);
+    clamped.extend([first, first]);
+    clamped.extend_from_slice(points);
+    clamped.extend([last, last]);
+    clamped
+}
+
+// Hermite 形式的每段转成 Bezier 控制点，与 bevy 的 CubicHermite 相同
+pub(crate) fn hermite_segments(points: &[Vec2], tangents: &[Vec2]) -> Vec<[Vec2; 4]> {
+    points
+        .windows(2)
+        .zip(tangents.windows(2))
+        .map(|(p, m)| [p[0], p[0] + m[0] / 3.0, p[1] - m[1] / 3.0, p[1]])
+        .collect()
+}
+
+// 非闭合的插值样条按端点条件构造：自然样条改方程组的首尾两行，
+// Catmull-Rom 和 Hermite 先按原来的方式求出每段，再替换首尾两个切线
+fn end_conditioned_curve(kind: CurveKind, inputs: &CurveInputs) -> Option<CubicCurve<Vec2>> {
+    let (points, condition) = (&inputs.positions, inputs.end_condition);
+    if kind == CurveKind::Natural {
+        return NaturalCubicSpline::new(points.iter().copied())
+            .with_end_condition(condition, inputs.end_tangents)
+            .to_curve()
+            .ok();
+    }
+    let segments = if kind == CurveKind::CatmullRom {
+        catmull_rom_bezier_segments(inputs)
+    } else {
+        let mut segments = hermite_segments(points, &inputs.tangents);
+        apply_end_condition(&mut segments, condition, inputs.end_tangents);
+        segments
+    };
+    let segments: Vec<CubicSegment<Vec2>> =
+        segments.into_iter().map(CubicSegment::new_bezier).collect();
+    CubicCurve::from_segments(segments).ok()
+}
+
+// 画出来的 Catmull-Rom 的每一段，写成 Bezier 控制点：包括逐点张力、参数化、闭合和端点条件，
+// 有重合点时使用合并后的点。转换成 Bezier 链时用
+pub(crate) fn catmull_rom_bezier_segments(inputs: &CurveInputs) -> Vec<[Vec2; 4]> {
+    let inputs = inputs.merged.as_deref().unwrap_or(inputs);
+    let (points, tensions, closed) = (&inputs.positions, &inputs.tensions, inputs.closed);
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let mut segments = if inputs.parameterization != Parameterization::Uniform {
+        catmull_rom_segments(points, tensions, closed, inputs.parameterization.alpha())
+    } else {
+        let tangents = cardinal_tangents(points, tensions, closed);
+        let mut segments = hermite_segments(points, &tangents);
+        // 与 CubicHermite 的 to_curve_cyclic 一样多出连回第一个点的一段
+        if closed {
+            segments.extend(hermite_segments(
+                &[points[n - 1], points[0]],
+                &[tangents[n - 1], tangents[0]],
+            ));
+        }
+        segments
+    };
+    if !closed {
+        apply_end_condition(&mut segments, inputs.end_condition, inputs.end_tangents);
+    }
+    segments
+}
+
+// 把控制点分成共享端点的 4 点一组：0-3、3-6、6-9……
+// 凑不满一组的尾部点直接忽略，不做补全，避免生成退化的段
+pub(crate) fn bezier_chain<P: VectorSpace>(points: &[P]) -> Vec<[P; 4]> {
+    points
+        .windows(4)
+        .step_by(3)
+        .map(|w| [w[0], w[1], w[2], w[3]])
+        .collect()
+}
+
+/// 构造曲线用到的逐点数据。重建所有曲线时只从控制点收集一次，各种曲线共用；
+/// 用 [`CurveInputs::collect`] 重新收集时沿用已有的缓冲区，点数不变就不再分配。
+#[derive(Default)]
+pub(crate) struct CurveInputs {
+    pub(crate) positions: Vec<Vec2>,
+    pub(crate) tensions: Vec<f32>,
+    pub(crate) weights: Vec<f32>,
+    // Hermite 使用的切线，没有手动设置的点使用估计值
+    pub(crate) tangents: Vec<Vec2>,
+    pub(crate) closed: bool,
+    pub(crate) parameterization: Parameterization,
+    pub(crate) clamped: bool,
+    pub(crate) end_condition: EndCondition,
+    pub(crate) end_tangents: [Vec2; 2],
+    // 合并重合的相邻点后的输入，插值样条用它构造；没有重合点时为 None
+    pub(crate) merged: Option<Box<CurveInputs>>,
+    // 被合并掉的点的位置，用来画警告标记
+    pub(crate) coincident: Vec<Vec2>,
+}
+
+impl CurveInputs {
+    pub(crate) fn new(control_points: &ControlPoints) -> Self {
+        let mut inputs = Self::default();
+        inputs.collect(control_points);
+        inputs
+    }
+
+    pub(crate) fn collect(&mut self, control_points: &ControlPoints) {
+        let curve_points = control_points.curve_points();
+        let points = &*curve_points;
+        self.closed = control_points.closed;
+        self.parameterization = control_points.parameterization;
+        self.clamped = control_points.clamped;
+        self.end_condition = control_points.end_condition;
+        let start = control_points.end_tangent(0);
+        // 对称时终点是起点的镜像，切线也镜像并反向
+        let end = match control_points.symmetry {
+            Some(_) => Vec2::new(start.x, -start.y),
+            None => control_points.end_tangent(1),
+        };
+        self.end_tangents = [start, end];
+        self.positions.clear();
+        self.positions.extend(points.iter().map(|p| p.position));
+        self.tensions.clear();
+        self.tensions.extend(points.iter().map(|p| p.tension));
+        self.weights.clear();
+        self.weights.extend(points.iter().map(|p| p.weight));
+        let (positions, closed) = (&self.positions, self.closed);
+        self.tangents.clear();
+        self.tangents
+            .extend(points.iter().enumerate().map(|(i, p)| {
+                p.tangent
+                    .unwrap_or_else(|| estimate_tangent(positions, i, closed))
+            }));
+
+        let kept = distinct_indices(&self.positions, closed, COINCIDENT_EPSILON);
+        self.coincident.clear();
+        if kept.len() == points.len() {
+            self.merged = None;
+            return;
+        }
+        let mut next = kept.iter().peekable();
+        for (i, &position) in self.positions.iter().enumerate() {
+            if next.next_if_eq(&&i).is_none() {
+                self.coincident.push(position);
+            }
+        }
+        // 手动设置的切线随点保留，估计的切线按合并后的邻居重新估计
+        self.merged = Some(Box::new(Self::new(&ControlPoints {
+            points: kept.iter().map(|&i| points[i].clone()).collect(),
+            closed,
+            parameterization: self.parameterization,
+            clamped: self.clamped,
+            end_condition: self.end_condition,
+            end_tangents: self.end_tangents.map(Some),
+            ..default()
+        })));
+    }
+}
+
+// 根据曲线类型从控制点构造曲线，点数不足或无法构造时返回 None；
+// 需要构造多种曲线时先收集一次 CurveInputs，再用 curve_from_inputs
+pub(crate) fn build_curve(
+    kind: CurveKind,
+    control_points: &ControlPoints,
+) -> Option<Box<dyn PlanarCurve>> {
+    curve_from_inputs(kind, &CurveInputs::new(control_points))
+}
+
+pub(crate) fn curve_from_inputs(
+    kind: CurveKind,
+    inputs: &CurveInputs,
+) -> Option<Box<dyn PlanarCurve>> {
+    let inputs = effective_inputs(kind, inputs)?;
+    let points = &inputs.positions;
+    let closed = inputs.closed;
+    let curve: Box<dyn PlanarCurve> = match kind {
+        // 闭合时控制多边形连回第一个点
+        CurveKind::ControlPolygon => {
+            let mut vertices = Vec::with_capacity(points.len() + 1);
+            vertices.extend_from_slice(points);
+            if closed {
+                vertices.push(points[0]);
+            }
+            Box::new(Polyline(vertices))
+        }
+        CurveKind::BSpline
+        | CurveKind::CatmullRom
+        | CurveKind::Bezier
+        | CurveKind::Hermite
+        | CurveKind::Natural
+        | CurveKind::Akima => Box::new(planar_cubic(kind, inputs)?),
+        // 权重全为 1 时与 B-Spline 重合
+        CurveKind::Nurbs => Box::new(RationalBSpline::new(
+            points.iter().copied(),
+            inputs.weights.iter().copied(),
+        )),
+        // 只在控制点按 x 单调时存在
+        CurveKind::Monotone => Box::new(MonotoneCubic::new(points)?),
+        CurveKind::Quadratic => Box::new(QuadraticBezierChain::new(points)),
+    };
+    (curve.segment_count() > 0).then_some(curve)
+}
+
+/// 结果是 bevy 的 `CubicCurve` 的曲线类型，其余类型和无法构造时返回 None。
+pub(crate) fn cubic_from_inputs(kind: CurveKind, inputs: &CurveInputs) -> Option<CubicCurve<Vec2>> {
+    let curve = planar_cubic(kind, effective_inputs(kind, inputs)?)?;
+    (curve.segment_count() > 0).then_some(curve)
+}
+
+// 插值的曲线使用合并了重合点之后的输入；不足两个点时什么曲线都构造不出来
+fn effective_inputs(kind: CurveKind, inputs: &CurveInputs) -> Option<&CurveInputs> {
+    let inputs = match &inputs.merged {
+        Some(merged) if kind.interpolates() => merged,
+        _ => inputs,
+    };
+    (inputs.positions.len() >= 2).then_some(inputs)
+}
+
+fn planar_cubic(kind: CurveKind, inputs: &CurveInputs) -> Option<CubicCurve<Vec2>> {
+    let points = &inputs.positions;
+    let closed = inputs.closed;
+    match kind {
+        CurveKind::BSpline if inputs.clamped && !closed => cubic_curve(
+            kind,
+            &clamped_b_spline_points(points),
+            &inputs.tensions,
+            &inputs.tangents,
+            closed,
+        ),
+        CurveKind::CatmullRom | CurveKind::Hermite | CurveKind::Natural
+            if !closed && inputs.end_condition != EndCondition::Auto =>
+        {
+            end_conditioned_curve(kind, inputs)
+        }
+        // 非均匀参数化由 catmull_rom 模块按段构造，均匀时与 3D 模式共用 cubic_curve
+        CurveKind::CatmullRom if inputs.parameterization != Parameterization::Uniform => {
+            catmull_rom_curve(
+                points,
+                &inputs.tensions,
+                closed,
+                inputs.parameterization.alpha(),
+            )
+        }
+        // 点数不足时退化为 Catmull-Rom
+        CurveKind::Akima => AkimaSpline::new(points.iter().copied()).to_curve().ok(),
+        _ => cubic_curve(kind, points, &inputs.tensions, &inputs.tangents, closed),
+    }
+}
+
+// 对任意 VectorSpace 通用的曲线类型，2D 和 3D 模式共用；其余类型只有平面实现，返回 None。
+// tangents 是 Hermite 使用的每个点的切线
+pub(crate) fn cubic_curve<P: VectorSpace>(
+    kind: CurveKind,
+    points: &[P],
+    tensions: &[f32],
+    tangents: &[P],
+    closed: bool,
+) -> Option<CubicCurve<P>> {
+    match kind {
+        // 闭合时使用周期节点
+        CurveKind::BSpline => {
+            let b_spline = CubicBSpline::new(points.iter().copied());
+            let curve = if closed {
+                b_spline.to_curve_cyclic()
+            } else {
+                b_spline.to_curve()
+            };
+            curve.ok()
+        }
+        // bevy 的 CubicCardinalSpline 只有全局张力，这里用逐点张力算出切线后构造 Hermite；
+        // 闭合时切线使用环绕的邻居
+        CurveKind::CatmullRom => {
+            let tangents = cardinal_tangents(points, tensions, closed);
+            let cardinal_spline = CubicHermite::new(points.iter().copied(), tangents);
+            let curve = if closed {
+                cardinal_spline.to_curve_cyclic()
+            } else {
+                cardinal_spline.to_curve()
+            };
+            curve.ok()
+        }
+        // 首尾相接的 Bezier 链，不足 4 个点时 to_curve 失败
+        CurveKind::Bezier => CubicBezier::new(bezier_chain(points)).to_curve().ok(),
+        // 使用每个点的切线
+        CurveKind::Hermite => {
+            let hermite_spline =
+                CubicHermite::new(points.iter().copied(), tangents.iter().copied());
+            let curve = if closed {
+                hermite_spline.to_curve_cyclic()
+            } else {
+                hermite_spline.to_curve()
+            };
+            curve.ok()
+        }
+        CurveKind::Natural => NaturalCubicSpline::new(points.iter().copied())
+            .to_curve()
+            .ok(),
+        _ => None,
+    }
+}
+
+// 每一小段参数中点处的曲率大小，速度为零的退化处记为 0
+pub(crate) fn segment_curvatures(curve: &dyn PlanarCurve, samples: &Samples) -> Vec<f32> {
+    samples
+        .params
+        .windows(2)
+        .map(|w| signed_curvature(curve, (w[0] + w[1]) * 0.5).map_or(0.0, f32::abs))
+        .collect()
+}
+
+pub(crate) fn sample_curve(
+    curve: &dyn PlanarCurve,
+    mode: SamplingMode,
+    resolution: usize,
+    epsilon: f32,
+) -> Samples {
+    match mode {
+        SamplingMode::Uniform => curve.sample(resolution),
+        SamplingMode::ArcLength => sample_arc_length(curve, resolution),
+        SamplingMode::Adaptive => sample_adaptive(curve, epsilon),
+    }
+}
+
+// 虚线的分段端点，每两个点是一段；相位从折线起点开始累计，拖动时虚线不会"爬行"
+pub(crate) fn dash_segments(points: &[Vec2], dash: f32, gap: f32) -> Vec<Vec2> {
+    let mut segments = Vec::new();
+    let period = dash + gap;
+    if dash <= 0.0 || gap <= 0.0 {
+        return segments;
+    }
+    let mut travelled = 0.0;
+    for w in points.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let length = a.distance(b);
+        if length <= f32::EPSILON {
+            continue;
+        }
+        let direction = (b - a) / length;
+        let mut s = 0.0;
+        while s < length {
+            let phase = (travelled + s) % period;
+            if phase < dash {
+                let end = (s + dash - phase).min(length);
+                segments.push(a + direction * s);
+                segments.push(a + direction * end);
+                s = end;
+            } else {
+                s = (s + period - phase).min(length);
+            }
+        }
+        travelled += length;
+    }
+    segments
+}
+
+// 逐段着色，第 i 段连接采样点 i 和 i + 1；渐变取两端参数中点处的颜色
+pub(crate) fn segment_colors(params: &[f32], paint: &CurvePaint, domain_end: f32) -> Vec<Srgba> {
+    (1..params.len())
+        .map(|i| match paint {
+            CurvePaint::Solid(color) => *color,
+            CurvePaint::Gradient(start, end) => {
+                let t = (params[i - 1] + params[i]) * 0.5;
+                start.mix(end, t / domain_end)
+            }
+            CurvePaint::Heatmap { curvatures, scale } => {
+                if *scale > 0.0 {
+                    heatmap::ramp(curvatures[i - 1] / scale)
+                } else {
+                    heatmap::ramp(0.0)
+                }
+            }
+        })
+        .collect()
+}
+
+// 逐个采样点着色，作为网格的顶点颜色：取相邻两段颜色的平均，两端取唯一相邻的一段
+pub(crate) fn sample_colors(params: &[f32], paint: &CurvePaint, domain_end: f32) -> Vec<Srgba> {
+    let segments = segment_colors(params, paint, domain_end);
+    (0..params.len())
+        .map(|i| {
+            let before = i.checked_sub(1).and_then(|j| segments.get(j));
+            match (before, segments.get(i)) {
+                (Some(a), Some(b)) => a.mix(b, 0.5),
+                (Some(color), None) | (None, Some(color)) => *color,
+                (None, None) => Srgba::NONE,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_points(actual: &[Vec2], expected: &[Vec2]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(a.distance(*e) < 1e-4, "{actual:?} != {expected:?}");
+        }
+    }
+
+    fn wave(n: usize) -> ControlPoints {
+        ControlPoints {
+            points: (0..n)
+                .map(|i| {
+                    let x = i as f32;
+                    MovablePoint::new(Vec2::new(x * 10.0, (x * 0.3).sin() * 100.0))
+                })
+                .collect(),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn collecting_again_reuses_the_buffers() {
+        let control_points = wave(1000);
+        let mut inputs = CurveInputs::new(&control_points);
+        let buffers = (inputs.positions.as_ptr(), inputs.tangents.as_ptr());
+        inputs.collect(&control_points);
+        assert_eq!(
+            (inputs.positions.as_ptr(), inputs.tangents.as_ptr()),
+            buffers
+        );
+        assert_eq!(inputs.positions, control_points.positions());
+        assert_eq!(inputs.tangents, control_points.tangents());
+    }
+
+    #[test]
+    fn clamped_b_spline_touches_the_end_points() {
+        let mut control_points = wave(6);
+        let positions = control_points.positions();
+        let open = build_curve(CurveKind::BSpline, &control_points).unwrap();
+        let end = open.domain_end();
+        assert!(open.position(0.0).distance(positions[0]) > 1.0);
+
+        control_points.clamped = true;
+        let clamped = build_curve(CurveKind::BSpline, &control_points).unwrap();
+        assert!(clamped.position(0.0).distance(positions[0]) < 1e-3);
+        let end_point = clamped.position(clamped.domain_end());
+        assert!(end_point.distance(positions[5]) < 1e-3);
+        assert!(clamped.domain_end() > end);
+        // 只改变构造的输入
+        assert_eq!(control_points.positions(), positions);
+    }
+
+    // 钳制时三种插值样条都从端点切线出发，切换回 Auto 恢复原来的曲线
+    #[test]
+    fn clamped_end_tangents_apply_to_every_interpolating_spline() {
+        let mut control_points = wave(6);
+        let tangents = [Vec2::new(0.0, 120.0), Vec2::new(-30.0, 60.0)];
+        control_points.end_tangents = tangents.map(Some);
+        for kind in [
+            CurveKind::CatmullRom,
+            CurveKind::Hermite,
+            CurveKind::Natural,
+        ] {
+            control_points.end_condition = EndCondition::Clamped;
+            let clamped = build_curve(kind, &control_points).unwrap();
+            let end = clamped.domain_end();
+            assert!(
+                clamped.velocity(0.0).distance(tangents[0]) < 1e-3,
+                "{kind:?}"
+            );
+            assert!(
+                clamped.velocity(end).distance(tangents[1]) < 1e-3,
+                "{kind:?}"
+            );
+            for (i, p) in control_points.positions().iter().enumerate() {
+                assert!(clamped.position(i as f32).distance(*p) < 1e-3, "{kind:?}");
+            }
+
+            control_points.end_condition = EndCondition::Auto;
+            let auto = build_curve(kind, &control_points).unwrap();
+            assert!(auto.velocity(0.0).distance(tangents[0]) > 1.0, "{kind:?}");
+        }
+    }
+
+    // 转换用的分段与画出来的 Catmull-Rom 重合，包括逐点张力和闭合的最后一段
+    #[test]
+    fn catmull_rom_segments_match_the_drawn_curve() {
+        let mut control_points = wave(7);
+        control_points.points[2].tension = 0.2;
+        control_points.points[4].tension = 0.9;
+        for closed in [false, true] {
+            control_points.closed = closed;
+            let drawn = build_curve(CurveKind::CatmullRom, &control_points).unwrap();
+            let segments = catmull_rom_bezier_segments(&CurveInputs::new(&control_points));
+            assert_eq!(segments.len(), drawn.segment_count());
+            let chain = CubicBezier::new(segments).to_curve().unwrap();
+            for i in 0..=60 {
+                let t = drawn.domain_end() * i as f32 / 60.0;
+                assert!(drawn.position(t).distance(chain.position(t)) < 1e-3);
+            }
+        }
+    }
+
+    // 完全重合的相邻点：插值样条合并后再构造，所有曲线的采样都是有限值
+    #[test]
+    fn duplicate_points_give_finite_samples() {
+        for closed in [false, true] {
+            let mut control_points = wave(8);
+            for i in [7, 3, 0] {
+                let duplicate = control_points.points[i].clone();
+                control_points.points.insert(i, duplicate);
+            }
+            control_points.closed = closed;
+            let inputs = CurveInputs::new(&control_points);
+            assert_eq!(inputs.coincident.len(), 3);
+            assert_eq!(inputs.merged.as_ref().unwrap().positions.len(), 8);
+            for kind in CurveKind::ALL {
+                let curve = curve_from_inputs(kind, &inputs);
+                assert!(closed || curve.is_some(), "{kind:?}");
+                let Some(curve) = curve else { continue };
+                let samples = curve.sample(16);
+                assert!(
+                    samples.positions.iter().all(|p| p.is_finite()),
+                    "{kind:?} closed={closed}"
+                );
+            }
+        }
+        // 没有重合点时不做合并
+        assert!(CurveInputs::new(&wave(8)).merged.is_none());
+    }
+
+    // 1000 个点时重建全部曲线的耗时：每种曲线各自收集控制点，对比收集一次后共用。
+    // cargo test --release timed_rebuild -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn timed_rebuild_of_all_curves() {
+        let control_points = wave(1000);
+        let rounds = 20;
+        let started = Instant::now();
+        for _ in 0..rounds {
+            for kind in CurveKind::ALL {
+                std::hint::black_box(build_curve(kind, &control_points));
+            }
+        }
+        let per_kind = started.elapsed() / rounds;
+        let mut inputs = CurveInputs::default();
+        let started = Instant::now();
+        for _ in 0..rounds {
+            inputs.collect(&control_points);
+            for kind in CurveKind::ALL {
+                std::hint::black_box(curve_from_inputs(kind, &inputs));
+            }
+        }
+        let shared = started.elapsed() / rounds;
+        println!("all curves, 1000 points: collected per kind {per_kind:?}, once {shared:?}");
+    }
+
+    #[test]
+    fn dashes_along_a_straight_line() {
+        let points = [Vec2::ZERO, Vec2::new(40.0, 0.0)];
+        let segments = dash_segments(&points, 10.0, 5.0);
+        assert_points(
+            &segments,
+            &[
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 0.0),
+                Vec2::new(15.0, 0.0),
+                Vec2::new(25.0, 0.0),
+                Vec2::new(30.0, 0.0),
+                Vec2::new(40.0, 0.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn dash_phase_carries_across_vertices() {
+        let points = [Vec2::ZERO, Vec2::new(12.0, 0.0), Vec2::new(12.0, 20.0)];
+        let segments = dash_segments(&points, 10.0, 5.0);
+        assert_points(
+            &segments,
+            &[
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 0.0),
+                Vec2::new(12.0, 3.0),
+                Vec2::new(12.0, 13.0),
+                Vec2::new(12.0, 18.0),
+                Vec2::new(12.0, 20.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn dashes_need_positive_lengths() {
+        let points = [Vec2::ZERO, Vec2::new(40.0, 0.0)];
+        assert!(dash_segments(&points, 0.0, 5.0).is_empty());
+        assert!(dash_segments(&points, 10.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn gradient_uses_segment_midpoints() {
+        let paint = CurvePaint::Gradient(Srgba::BLACK, Srgba::WHITE);
+        let colors = segment_colors(&[0.0, 1.0, 2.0], &paint, 2.0);
+        assert_eq!(colors.len(), 2);
+        assert!((colors[0].red - 0.25).abs() < 1e-5);
+        assert!((colors[1].red - 0.75).abs() < 1e-5);
+    }
+
+    #[test]
+    fn heatmap_without_scale_is_flat() {
+        let paint = CurvePaint::Heatmap {
+            curvatures: vec![1.0, 2.0],
+            scale: 0.0,
+        };
+        let colors = segment_colors(&[0.0, 0.5, 1.0], &paint, 1.0);
+        assert_eq!(colors, vec![heatmap::ramp(0.0); 2]);
+    }
+
+    // 均匀参数时每个采样点正好取到自己参数处的渐变色
+    #[test]
+    fn sample_colors_average_neighbouring_segments() {
+        let paint = CurvePaint::Gradient(Srgba::BLACK, Srgba::WHITE);
+        let colors = sample_colors(&[0.0, 1.0, 2.0], &paint, 2.0);
+        let reds: Vec<f32> = colors.iter().map(|c| c.red).collect();
+        assert_eq!(reds.len(), 3);
+        assert!((reds[0] - 0.25).abs() < 1e-5);
+        assert!((reds[1] - 0.5).abs() < 1e-5);
+        assert!((reds[2] - 0.75).abs() < 1e-5);
+        let solid = sample_colors(&[0.0, 0.5, 1.0], &CurvePaint::Solid(Srgba::RED), 1.0);
+        assert_eq!(solid, vec![Srgba::RED; 3]);
+    }
+}
@@ -0,0 +1,116 @@
+//! Bezier 的升阶：n 次控制多边形换成 n+1 次的，曲线本身不变。
+//!
+//! 新的控制点 Q_i = i/(n+1)·P_{i-1} + (1 - i/(n+1))·P_i，两端点不动。反复升阶时
+//! 控制多边形收敛到曲线上。
+
+use bevy::math::VectorSpace;
+
+/// 把控制多边形升高一阶，返回多一个点的新多边形。空输入原样返回。
+pub fn elevate<P: VectorSpace>(points: &[P]) -> Vec<P> {
+    let Some((&first, _)) = points.split_first() else {
+        return Vec::new();
+    };
+    let degree = points.len() as f32;
+    let mut elevated = Vec::with_capacity(points.len() + 1);
+    elevated.push(first);
+    elevated.extend(
+        points
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| w[1].lerp(w[0], (i + 1) as f32 / degree)),
+    );
+    elevated.push(points[points.len() - 1]);
+    elevated
+}
+
+/// 三次段连续升阶 `levels` 次，依次返回 4 次、5 次……的控制多边形。
+pub fn elevations<P: VectorSpace>(segment: [P; 4], levels: usize) -> Vec<Vec<P>> {
+    let mut polygons: Vec<Vec<P>> = Vec::with_capacity(levels);
+    for _ in 0..levels {
+        let previous = polygons.last().map_or(&segment[..], Vec::as_slice);
+        polygons.push(elevate(previous));
+    }
+    polygons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::Vec2;
+
+    // 任意次数的 de Casteljau 求值
+    fn evaluate(points: &[Vec2], t: f32) -> Vec2 {
+        let mut level = points.to_vec();
+        while level.len() > 1 {
+            level = level.windows(2).map(|w| w[0].lerp(w[1], t)).collect();
+        }
+        level[0]
+    }
+
+    const SEGMENT: [Vec2; 4] = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(50.0, 120.0),
+        Vec2::new(150.0, -80.0),
+        Vec2::new(200.0, 40.0),
+    ];
+
+    #[test]
+    fn elevated_polygons_describe_the_same_curve() {
+        let polygons = elevations(SEGMENT, 5);
+        assert_eq!(
+            polygons.iter().map(Vec::len).collect::<Vec<_>>(),
+            [5, 6, 7, 8, 9]
+        );
+        for polygon in &polygons {
+            assert_eq!(polygon[0], SEGMENT[0]);
+            assert_eq!(polygon[polygon.len() - 1], SEGMENT[3]);
+            for i in 0..=10 {
+                let t = i as f32 / 10.0;
+                let (expected, actual) = (evaluate(&SEGMENT, t), evaluate(polygon, t));
+                assert!(actual.distance(expected) < 1e-3, "{actual} != {expected}");
+            }
+        }
+    }
+
+    #[test]
+    fn first_elevation_matches_the_formula() {
+        let [p0, p1, p2, p3] = SEGMENT;
+        let q = elevate(&SEGMENT);
+        let expected = [
+            p0,
+            p0 * 0.25 + p1 * 0.75,
+            p1 * 0.5 + p2 * 0.5,
+            p2 * 0.75 + p3 * 0.25,
+            p3,
+        ];
+        for (q, e) in q.iter().zip(expected) {
+            assert!(q.distance(e) < 1e-4, "{q} != {e}");
+        }
+        assert!(elevate::<Vec2>(&[]).is_empty());
+    }
+
+    // 控制多边形顶点到曲线的最大距离随升阶单调减小
+    #[test]
+    fn polygon_approaches_the_curve() {
+        let curve: Vec<Vec2> = (0..=1000)
+            .map(|i| evaluate(&SEGMENT, i as f32 / 1000.0))
+            .collect();
+        let gap = |polygon: &[Vec2]| {
+            polygon
+                .iter()
+                .map(|p| {
+                    curve
+                        .iter()
+                        .map(|c| c.distance(*p))
+                        .fold(f32::INFINITY, f32::min)
+                })
+                .fold(0.0, f32::max)
+        };
+        let mut previous = gap(&SEGMENT);
+        for polygon in elevations(SEGMENT, 8) {
+            let current = gap(&polygon);
+            assert!(current < previous, "{current} >= {previous}");
+            previous = current;
+        }
+    }
+}
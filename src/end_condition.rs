@@ -0,0 +1,195 @@
+//! 非闭合插值样条的端点条件。Catmull-Rom 和 Hermite 的内部切线由相邻点决定，
+//! 这里只替换首尾两个切线；自然样条的端点条件是方程组的首尾两行，在 natural_spline 里处理。
+//!
+//! 三种条件都按均匀参数（每段的参数长度为 1）推导，与 bevy 的 Hermite 构造一致。
+
+use bevy::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// 曲线怎样离开第一个点、到达最后一个点。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum EndCondition {
+    /// 每种曲线原来的端点：Catmull-Rom 镜像端点，Hermite 用手动或估计的切线，自然样条二阶导数为零
+    #[default]
+    Auto,
+    /// 端点处二阶导数为零
+    Natural,
+    /// 端点切线由两个单独保存的手柄给出
+    Clamped,
+    /// 第二个和倒数第二个节点两侧的三阶导数相同，前两段（后两段）是同一个三次多项式
+    NotAKnot,
+}
+
+impl EndCondition {
+    pub fn next(self) -> Self {
+        match self {
+            EndCondition::Auto => EndCondition::Natural,
+            EndCondition::Natural => EndCondition::Clamped,
+            EndCondition::Clamped => EndCondition::NotAKnot,
+            EndCondition::NotAKnot => EndCondition::Auto,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EndCondition::Auto => "auto",
+            EndCondition::Natural => "natural",
+            EndCondition::Clamped => "clamped",
+            EndCondition::NotAKnot => "not-a-knot",
+        }
+    }
+}
+
+// 按 Bezier 控制点计算段内参数下的导数
+fn start_velocity(s: &[Vec2; 4]) -> Vec2 {
+    (s[1] - s[0]) * 3.0
+}
+
+fn end_velocity(s: &[Vec2; 4]) -> Vec2 {
+    (s[3] - s[2]) * 3.0
+}
+
+/// 按端点条件替换首段的起点切线和末段的终点切线，`tangents` 是钳制时首尾的切线。
+/// 每段是 Hermite 转成的 Bezier 控制点 `[p0, p0 + m0 / 3, p1 - m1 / 3, p1]`。
+pub fn apply_end_condition(
+    segments: &mut [[Vec2; 4]],
+    condition: EndCondition,
+    tangents: [Vec2; 2],
+) {
+    let n = segments.len();
+    if n == 0 {
+        return;
+    }
+    let (start, end) = match condition {
+        EndCondition::Auto => return,
+        EndCondition::Clamped => (tangents[0], tangents[1]),
+        // 只有一段时两端互相依赖，解出来是直线
+        EndCondition::Natural | EndCondition::NotAKnot if n == 1 => {
+            let chord = segments[0][3] - segments[0][0];
+            (chord, chord)
+        }
+        // 段内二阶导数在起点为零：2 m0 + m1 = 3 (p1 - p0)
+        EndCondition::Natural => {
+            let (first, last) = (&segments[0], &segments[n - 1]);
+            (
+                ((first[3] - first[0]) * 3.0 - end_velocity(first)) / 2.0,
+                ((last[3] - last[0]) * 3.0 - start_velocity(last)) / 2.0,
+            )
+        }
+        // 两段时三个点确定一条抛物线，取它在两端的切线
+        EndCondition::NotAKnot if n == 2 => {
+            let (p0, p1, p2) = (segments[0][0], segments[0][3], segments[1][3]);
+            (
+                (p1 * 4.0 - p0 * 3.0 - p2) / 2.0,
+                (p2 * 3.0 - p1 * 4.0 + p0) / 2.0,
+            )
+        }
+        // 三阶导数 12 (p_i - p_{i+1}) + 6 (m_i + m_{i+1}) 在第二个节点两侧相等：
+        //   m0 = m2 + 2 (2 p1 - p0 - p2)
+        EndCondition::NotAKnot => {
+            let (first, second) = (&segments[0], &segments[1]);
+            let (p0, p1, p2) = (first[0], first[3], second[3]);
+            let (last, before) = (&segments[n - 1], &segments[n - 2]);
+            let (q0, q1, q2) = (last[3], last[0], before[0]);
+            (
+                end_velocity(second) + (p1 * 2.0 - p0 - p2) * 2.0,
+                start_velocity(before) - (q1 * 2.0 - q0 - q2) * 2.0,
+            )
+        }
+    };
+    segments[0][1] = segments[0][0] + start / 3.0;
+    segments[n - 1][2] = segments[n - 1][3] - end / 3.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::cubic_splines::CubicSegment;
+
+    const POINTS: [Vec2; 5] = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(60.0, 80.0),
+        Vec2::new(150.0, -20.0),
+        Vec2::new(220.0, 40.0),
+        Vec2::new(300.0, 10.0),
+    ];
+
+    // Catmull-Rom 的内部切线，端点先用单侧差分
+    fn hermite_segments(points: &[Vec2]) -> Vec<[Vec2; 4]> {
+        let n = points.len();
+        let tangent = |i: usize| match i {
+            0 => points[1] - points[0],
+            i if i == n - 1 => points[n - 1] - points[n - 2],
+            i => (points[i + 1] - points[i - 1]) / 2.0,
+        };
+        (0..n - 1)
+            .map(|i| {
+                let (a, b) = (points[i], points[i + 1]);
+                [a, a + tangent(i) / 3.0, b - tangent(i + 1) / 3.0, b]
+            })
+            .collect()
+    }
+
+    fn acceleration(s: &[Vec2; 4], t: f32) -> Vec2 {
+        CubicSegment::new_bezier(*s).acceleration(t)
+    }
+
+    fn jerk(s: &[Vec2; 4]) -> Vec2 {
+        (s[3] - s[2] * 3.0 + s[1] * 3.0 - s[0]) * 6.0
+    }
+
+    #[test]
+    fn natural_ends_have_no_acceleration() {
+        let mut segments = hermite_segments(&POINTS);
+        assert!(acceleration(&segments[0], 0.0).length() > 1.0);
+        apply_end_condition(&mut segments, EndCondition::Natural, [Vec2::ZERO; 2]);
+        assert!(acceleration(&segments[0], 0.0).length() < 1e-3);
+        assert!(acceleration(&segments[3], 1.0).length() < 1e-3);
+        // 端点位置不变
+        assert_eq!(segments[0][0], POINTS[0]);
+        assert_eq!(segments[3][3], POINTS[4]);
+    }
+
+    #[test]
+    fn clamped_ends_use_the_given_tangents() {
+        let mut segments = hermite_segments(&POINTS);
+        let tangents = [Vec2::new(0.0, 200.0), Vec2::new(150.0, -90.0)];
+        apply_end_condition(&mut segments, EndCondition::Clamped, tangents);
+        let first = CubicSegment::new_bezier(segments[0]);
+        let last = CubicSegment::new_bezier(segments[3]);
+        assert!(first.velocity(0.0).distance(tangents[0]) < 1e-3);
+        assert!(last.velocity(1.0).distance(tangents[1]) < 1e-3);
+    }
+
+    #[test]
+    fn not_a_knot_joins_the_first_two_segments() {
+        let mut segments = hermite_segments(&POINTS);
+        assert!(jerk(&segments[0]).distance(jerk(&segments[1])) > 1.0);
+        apply_end_condition(&mut segments, EndCondition::NotAKnot, [Vec2::ZERO; 2]);
+        assert!(jerk(&segments[0]).distance(jerk(&segments[1])) < 1e-2);
+        assert!(jerk(&segments[3]).distance(jerk(&segments[2])) < 1e-2);
+    }
+
+    // 三个点时是经过它们的抛物线，两段的三阶导数都是零
+    #[test]
+    fn not_a_knot_with_three_points_is_a_parabola() {
+        let points = [POINTS[0], POINTS[1], POINTS[2]];
+        let mut segments = hermite_segments(&points);
+        apply_end_condition(&mut segments, EndCondition::NotAKnot, [Vec2::ZERO; 2]);
+        for s in &segments {
+            assert!(jerk(s).length() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn end_conditions_cycle() {
+        let mut condition = EndCondition::default();
+        let mut names = Vec::new();
+        for _ in 0..4 {
+            names.push(condition.name());
+            condition = condition.next();
+        }
+        assert_eq!(names, ["auto", "natural", "clamped", "not-a-knot"]);
+        assert_eq!(condition, EndCondition::Auto);
+    }
+}
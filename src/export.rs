@@ -0,0 +1,54 @@
+//! 把渲染用的采样点导出为 CSV。
+
+use std::fmt::Write;
+
+use crate::curve::Samples;
+
+/// 每行一个采样点：参数 t 和位置 x、y。
+pub fn samples_to_csv(samples: &Samples) -> String {
+    let mut csv = String::from("t,x,y\n");
+    for (t, p) in samples.params.iter().zip(&samples.positions) {
+        let _ = writeln!(csv, "{t},{},{}", p.x, p.y);
+    }
+    csv
+}
+
+/// 文件名中的曲线名称：小写，非字母数字替换为 `-`。
+pub fn file_slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    slug.split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::Vec2;
+
+    #[test]
+    fn writes_header_and_one_row_per_sample() {
+        let samples = Samples {
+            params: vec![0.0, 0.5],
+            positions: vec![Vec2::new(1.0, 2.0), Vec2::new(-3.5, 4.25)],
+        };
+        assert_eq!(samples_to_csv(&samples), "t,x,y\n0,1,2\n0.5,-3.5,4.25\n");
+    }
+
+    #[test]
+    fn slugs_curve_names() {
+        assert_eq!(file_slug("Catmull-Rom"), "catmull-rom");
+        assert_eq!(file_slug("Quadratic Bezier"), "quadratic-bezier");
+        assert_eq!(file_slug("NURBS"), "nurbs");
+    }
+}
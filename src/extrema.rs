@@ -0,0 +1,277 @@
+//! 曲率的特征点：有符号曲率变号处（拐点）和 |曲率| 的局部最大值。
+//!
+//! 先按参数均匀采样曲率，再在采样点之间细化。曲率在一段区间上几乎为零（直线段）时
+//! 只在区间中点报告一次拐点；|曲率| 的峰必须比两侧的谷高出一定比例才算最大值，
+//! 数值微分带来的小抖动不会产生一串标记。
+
+use bevy::math::Vec2;
+
+use crate::curve::{signed_curvature, PlanarCurve};
+
+const SAMPLES_PER_SEGMENT: usize = 64;
+// 相对于整条曲线最大 |曲率| 的比例：低于 FLAT 当作零，峰和谷相差超过 PROMINENCE 才算最大值
+const FLAT: f32 = 0.01;
+const PROMINENCE: f32 = 0.05;
+const REFINE_STEPS: usize = 30;
+// 不超过这么多个采样点宽的峰用黄金分割细化，更宽的平顶取中点
+const NARROW_PEAK: usize = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeatureKind {
+    Inflection,
+    Maximum,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CurvatureFeature {
+    pub kind: FeatureKind,
+    pub t: f32,
+    pub position: Vec2,
+}
+
+/// 曲线上所有的拐点和曲率最大值，按参数排序。直线或采样不到曲率时为空。
+pub fn curvature_features(curve: &dyn PlanarCurve) -> Vec<CurvatureFeature> {
+    let end = curve.domain_end();
+    let resolution = curve.segment_count() * SAMPLES_PER_SEGMENT;
+    if resolution == 0 {
+        return Vec::new();
+    }
+    let params: Vec<f32> = (0..=resolution)
+        .map(|i| i as f32 / resolution as f32 * end)
+        .collect();
+    let curvatures: Vec<Option<f32>> = params.iter().map(|&t| signed_curvature(curve, t)).collect();
+    let largest = curvatures
+        .iter()
+        .flatten()
+        .fold(0.0f32, |largest, k| largest.max(k.abs()));
+    if largest <= 0.0 {
+        return Vec::new();
+    }
+    let mut found: Vec<(FeatureKind, f32)> = Vec::new();
+    found.extend(
+        inflections(curve, &params, &curvatures, largest * FLAT)
+            .into_iter()
+            .map(|t| (FeatureKind::Inflection, t)),
+    );
+    found.extend(
+        maxima(curve, &params, &curvatures, largest)
+            .into_iter()
+            .map(|t| (FeatureKind::Maximum, t)),
+    );
+    found.sort_by(|a, b| a.1.total_cmp(&b.1));
+    found
+        .into_iter()
+        .map(|(kind, t)| CurvatureFeature {
+            kind,
+            t,
+            position: curve.position(t),
+        })
+        .collect()
+}
+
+// 相邻两个采样点异号时二分求根；中间隔着一段近似为零的采样时取这段的中点
+fn inflections(
+    curve: &dyn PlanarCurve,
+    params: &[f32],
+    curvatures: &[Option<f32>],
+    flat: f32,
+) -> Vec<f32> {
+    let sign = |k: Option<f32>| match k {
+        Some(k) if k > flat => 1,
+        Some(k) if k < -flat => -1,
+        _ => 0,
+    };
+    let mut found = Vec::new();
+    let mut last: Option<(i32, usize)> = None;
+    for (i, &k) in curvatures.iter().enumerate() {
+        let s = sign(k);
+        if s == 0 {
+            continue;
+        }
+        if let Some((previous, j)) = last {
+            if previous != s {
+                found.push(if i == j + 1 {
+                    bisect_sign_change(curve, params[j], params[i], s)
+                } else {
+                    (params[j] + params[i]) * 0.5
+                });
+            }
+        }
+        last = Some((s, i));
+    }
+    found
+}
+
+// 曲率在 a 处的符号与 b 处的 `sign_b` 相反
+fn bisect_sign_change(curve: &dyn PlanarCurve, mut a: f32, mut b: f32, sign_b: i32) -> f32 {
+    for _ in 0..REFINE_STEPS {
+        let mid = (a + b) * 0.5;
+        let Some(k) = signed_curvature(curve, mid) else {
+            break;
+        };
+        if (k > 0.0) == (sign_b > 0) {
+            b = mid;
+        } else {
+            a = mid;
+        }
+    }
+    (a + b) * 0.5
+}
+
+// 带滞回的峰值检测：从谷底上升超过 prominence 之后开始找峰，峰之后下降超过 prominence
+// 才确认这个峰。和峰值相差不到 flat 的相邻采样都算作峰顶，峰顶很宽时取它的中点；
+// 曲线两端的值不算局部最大值
+fn maxima(
+    curve: &dyn PlanarCurve,
+    params: &[f32],
+    curvatures: &[Option<f32>],
+    largest: f32,
+) -> Vec<f32> {
+    let prominence = largest * PROMINENCE;
+    let flat = largest * FLAT;
+    let magnitude: Vec<f32> = curvatures.iter().map(|k| k.map_or(0.0, f32::abs)).collect();
+    let last = magnitude.len() - 1;
+    let mut found = Vec::new();
+    let mut low = magnitude[0];
+    let mut rising = true;
+    let (mut peak, mut top_start, mut top_end) = (f32::NEG_INFINITY, 0, 0);
+    for (i, &m) in magnitude.iter().enumerate() {
+        if !rising {
+            low = low.min(m);
+            if m > low + prominence {
+                rising = true;
+                (peak, top_start, top_end) = (m, i, i);
+            }
+            continue;
+        }
+        if m > peak {
+            (peak, top_start, top_end) = (m, i, i);
+        } else if m >= peak - flat {
+            top_end = i;
+        }
+        if m < peak - prominence {
+            if top_start > 0 && peak >= low + prominence {
+                found.push(if top_end - top_start <= NARROW_PEAK {
+                    let a = params[top_start - 1];
+                    let b = params[(top_end + 1).min(last)];
+                    golden_section_peak(curve, a, b)
+                } else {
+                    (params[top_start] + params[top_end]) * 0.5
+                });
+            }
+            rising = false;
+            low = m;
+        }
+    }
+    found
+}
+
+fn golden_section_peak(curve: &dyn PlanarCurve, mut a: f32, mut b: f32) -> f32 {
+    let ratio = (5f32.sqrt() - 1.0) * 0.5;
+    let magnitude = |t: f32| signed_curvature(curve, t).map_or(0.0, f32::abs);
+    for _ in 0..REFINE_STEPS {
+        let left = b - ratio * (b - a);
+        let right = a + ratio * (b - a);
+        if magnitude(left) < magnitude(right) {
+            a = left;
+        } else {
+            b = right;
+        }
+    }
+    (a + b) * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::cubic_splines::{CubicBezier, CubicCurve, CubicGenerator};
+
+    fn bezier(segments: &[[Vec2; 4]]) -> CubicCurve<Vec2> {
+        CubicBezier::new(segments.to_vec()).to_curve().unwrap()
+    }
+
+    fn of_kind(features: &[CurvatureFeature], kind: FeatureKind) -> Vec<f32> {
+        features
+            .iter()
+            .filter(|f| f.kind == kind)
+            .map(|f| f.t)
+            .collect()
+    }
+
+    // 关于 (150, 0) 中心对称的 S 形：拐点在正中，两个最大值对称分布
+    #[test]
+    fn s_curve_has_one_inflection_between_two_maxima() {
+        let curve = bezier(&[[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 200.0),
+            Vec2::new(200.0, -200.0),
+            Vec2::new(300.0, 0.0),
+        ]]);
+        let features = curvature_features(&curve);
+        let inflections = of_kind(&features, FeatureKind::Inflection);
+        assert_eq!(inflections.len(), 1, "{features:?}");
+        assert!((inflections[0] - 0.5).abs() < 1e-4, "{inflections:?}");
+
+        let maxima = of_kind(&features, FeatureKind::Maximum);
+        assert_eq!(maxima.len(), 2, "{features:?}");
+        // 与密集采样得到的 |曲率| 最大处比较
+        let dense = (0..=5000)
+            .map(|i| i as f32 / 10000.0)
+            .max_by(|&a, &b| {
+                let k = |t| signed_curvature(&curve, t).unwrap().abs();
+                k(a).total_cmp(&k(b))
+            })
+            .unwrap();
+        assert!((maxima[0] - dense).abs() < 2e-3, "{maxima:?} vs {dense}");
+        assert!(
+            (maxima[1] - (1.0 - dense)).abs() < 2e-3,
+            "{maxima:?} vs {dense}"
+        );
+        assert!(features.windows(2).all(|w| w[0].t <= w[1].t));
+    }
+
+    fn bend(up: bool) -> [Vec2; 4] {
+        let y = if up { 1.0 } else { -1.0 };
+        [
+            Vec2::new(200.0, 0.0),
+            Vec2::new(250.0, 0.0),
+            Vec2::new(300.0, 50.0 * y),
+            Vec2::new(300.0, 100.0 * y),
+        ]
+    }
+
+    // 中间一段是直线：两侧弯向相反时只在直线段中间报告一次拐点，弯向相同时没有拐点
+    #[test]
+    fn straight_run_reports_a_single_midpoint() {
+        let start = [
+            Vec2::new(0.0, -100.0),
+            Vec2::new(0.0, -50.0),
+            Vec2::new(50.0, 0.0),
+            Vec2::new(100.0, 0.0),
+        ];
+        let straight = [
+            Vec2::new(100.0, 0.0),
+            Vec2::new(400.0 / 3.0, 0.0),
+            Vec2::new(500.0 / 3.0, 0.0),
+            Vec2::new(200.0, 0.0),
+        ];
+        let features = curvature_features(&bezier(&[start, straight, bend(true)]));
+        let inflections = of_kind(&features, FeatureKind::Inflection);
+        assert_eq!(inflections.len(), 1, "{features:?}");
+        assert!((inflections[0] - 1.5).abs() < 0.02, "{inflections:?}");
+
+        let features = curvature_features(&bezier(&[start, straight, bend(false)]));
+        assert!(of_kind(&features, FeatureKind::Inflection).is_empty());
+    }
+
+    #[test]
+    fn straight_line_has_no_features() {
+        let line = bezier(&[[
+            Vec2::ZERO,
+            Vec2::splat(10.0),
+            Vec2::splat(20.0),
+            Vec2::splat(30.0),
+        ]]);
+        assert!(curvature_features(&line).is_empty());
+    }
+}
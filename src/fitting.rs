@@ -0,0 +1,247 @@
+//! 最小二乘的 B-Spline 拟合：控制点不再定义曲线，而是当作要逼近的数据。
+//!
+//! 数据点按弦长分配参数，用首尾节点重复四次的三次 B-Spline（曲线从第一个控制点开始、
+//! 到最后一个控制点结束）逼近，控制点个数由用户选择。控制点是线性最小二乘的解，
+//! 由法方程 NᵀN c = Nᵀd 求出；NᵀN 对称正定，用 Cholesky 分解求解，内部计算使用 f64。
+
+use bevy::math::Vec2;
+
+use crate::knots::KnotVectorBSpline;
+
+const DEGREE: usize = 3;
+
+/// 拟合至少使用的控制点个数，四个点时是一段三次 Bezier。
+pub const MIN_CONTROL_POINTS: usize = DEGREE + 1;
+
+/// 拟合的结果。
+pub struct BSplineFit {
+    pub spline: KnotVectorBSpline,
+    /// 每个数据点的参数，取值范围与 `spline.domain()` 相同
+    pub parameters: Vec<f32>,
+    /// 数据点到曲线上同一参数处的最大距离
+    pub max_residual: f32,
+}
+
+/// 首尾重复四次、内部均匀的节点向量，参数范围是 0..=count - 3。
+pub fn clamped_knots(count: usize) -> Vec<f32> {
+    let end = (count - DEGREE) as f32;
+    (0..count + DEGREE + 1)
+        .map(|j| (j as f32 - DEGREE as f32).clamp(0.0, end))
+        .collect()
+}
+
+/// 按累计弦长把 0..=end 分给各个数据点；所有点重合时平均分配。
+pub fn chord_length_parameters(data: &[Vec2], end: f32) -> Vec<f32> {
+    let mut lengths = Vec::with_capacity(data.len());
+    let mut total = 0.0;
+    lengths.push(0.0);
+    for w in data.windows(2) {
+        total += w[0].distance(w[1]);
+        lengths.push(total);
+    }
+    if total <= f32::EPSILON {
+        let last = data.len().saturating_sub(1).max(1) as f32;
+        return (0..data.len()).map(|i| i as f32 / last * end).collect();
+    }
+    lengths.into_iter().map(|l| l / total * end).collect()
+}
+
+/// 用 `control_count` 个控制点拟合 `data`。控制点少于四个、数据点少于控制点，
+/// 或者数据在某些控制点的支撑区间里没有点导致方程组奇异时返回 None。
+pub fn fit_b_spline(data: &[Vec2], control_count: usize) -> Option<BSplineFit> {
+    if control_count < MIN_CONTROL_POINTS || data.len() < control_count {
+        return None;
+    }
+    let knots = clamped_knots(control_count);
+    let parameters = chord_length_parameters(data, (control_count - DEGREE) as f32);
+
+    // 法方程，每个数据点只影响相邻四个控制点
+    let n = control_count;
+    let mut normal = vec![0.0f64; n * n];
+    let mut rhs = vec![[0.0f64; 2]; n];
+    for (point, &t) in data.iter().zip(&parameters) {
+        let (first, weights) = basis(&knots, n, t);
+        for (a, wa) in weights.iter().enumerate() {
+            rhs[first + a][0] += wa * point.x as f64;
+            rhs[first + a][1] += wa * point.y as f64;
+            for (b, wb) in weights.iter().enumerate() {
+                normal[(first + a) * n + first + b] += wa * wb;
+            }
+        }
+    }
+    let solution = solve_symmetric(normal, n, rhs)?;
+    let points: Vec<Vec2> = solution
+        .iter()
+        .map(|[x, y]| Vec2::new(*x as f32, *y as f32))
+        .collect();
+
+    let max_residual = data
+        .iter()
+        .zip(&parameters)
+        .map(|(point, &t)| {
+            let (first, weights) = basis(&knots, n, t);
+            let on_curve = weights.iter().enumerate().fold(Vec2::ZERO, |sum, (a, w)| {
+                sum + points[first + a] * *w as f32
+            });
+            on_curve.distance(*point)
+        })
+        .fold(0.0, f32::max);
+    Some(BSplineFit {
+        spline: KnotVectorBSpline { knots, points },
+        parameters,
+        max_residual,
+    })
+}
+
+// 参数 t 处不为零的四个基函数值和第一个对应的控制点下标（Cox–de Boor 递推的三角形式）
+fn basis(knots: &[f32], count: usize, t: f32) -> (usize, [f64; DEGREE + 1]) {
+    let span = (DEGREE..count)
+        .rev()
+        .find(|&s| knots[s] <= t && knots[s] < knots[s + 1])
+        .unwrap_or(DEGREE);
+    let (t, knot) = (t as f64, |i: usize| knots[i] as f64);
+    let mut values = [0.0; DEGREE + 1];
+    let mut left = [0.0; DEGREE + 1];
+    let mut right = [0.0; DEGREE + 1];
+    values[0] = 1.0;
+    for j in 1..=DEGREE {
+        left[j] = t - knot(span + 1 - j);
+        right[j] = knot(span + j) - t;
+        let mut saved = 0.0;
+        for r in 0..j {
+            let temp = values[r] / (right[r + 1] + left[j - r]);
+            values[r] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+        values[j] = saved;
+    }
+    (span - DEGREE, values)
+}
+
+/// 用 Cholesky 分解求解对称正定的 `n` 阶方程组，`matrix` 按行存放，每个右端有 x、y 两列。
+/// 矩阵不正定（主元不为正）时返回 None。
+pub fn solve_symmetric(
+    mut matrix: Vec<f64>,
+    n: usize,
+    mut rhs: Vec<[f64; 2]>,
+) -> Option<Vec<[f64; 2]>> {
+    // 就地分解为 L Lᵀ，L 存放在下三角
+    for j in 0..n {
+        let mut diagonal = matrix[j * n + j];
+        for k in 0..j {
+            diagonal -= matrix[j * n + k] * matrix[j * n + k];
+        }
+        if diagonal <= 1e-12 {
+            return None;
+        }
+        let diagonal = diagonal.sqrt();
+        matrix[j * n + j] = diagonal;
+        for i in j + 1..n {
+            let mut value = matrix[i * n + j];
+            for k in 0..j {
+                value -= matrix[i * n + k] * matrix[j * n + k];
+            }
+            matrix[i * n + j] = value / diagonal;
+        }
+    }
+    // 前代 L y = b，回代 Lᵀ x = y
+    for i in 0..n {
+        for k in 0..i {
+            let l = matrix[i * n + k];
+            for c in 0..2 {
+                rhs[i][c] -= l * rhs[k][c];
+            }
+        }
+        for c in 0..2 {
+            rhs[i][c] /= matrix[i * n + i];
+        }
+    }
+    for i in (0..n).rev() {
+        for k in i + 1..n {
+            let l = matrix[k * n + i];
+            for c in 0..2 {
+                rhs[i][c] -= l * rhs[k][c];
+            }
+        }
+        for c in 0..2 {
+            rhs[i][c] /= matrix[i * n + i];
+        }
+    }
+    Some(rhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    // 一个周期的正弦波，200 个采样点
+    fn sine_wave() -> Vec<Vec2> {
+        (0..200)
+            .map(|i| {
+                let s = i as f32 / 199.0;
+                Vec2::new(-300.0 + 600.0 * s, 100.0 * (s * TAU).sin())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn solves_a_known_system() {
+        // [[4, 2, 0], [2, 5, 1], [0, 1, 3]] x = b，b 由 x = (1, -2, 3) 和 (0.5, 0, -1) 算出
+        let matrix = vec![4.0, 2.0, 0.0, 2.0, 5.0, 1.0, 0.0, 1.0, 3.0];
+        let rhs = vec![[0.0, 2.0], [-5.0, 0.0], [7.0, -3.0]];
+        let x = solve_symmetric(matrix, 3, rhs).unwrap();
+        let expected = [[1.0, 0.5], [-2.0, 0.0], [3.0, -1.0]];
+        for (row, e) in x.iter().zip(expected) {
+            assert!((row[0] - e[0]).abs() < 1e-12 && (row[1] - e[1]).abs() < 1e-12);
+        }
+        // 不正定
+        assert!(solve_symmetric(vec![1.0, 2.0, 2.0, 1.0], 2, vec![[0.0; 2]; 2]).is_none());
+    }
+
+    #[test]
+    fn basis_functions_sum_to_one() {
+        let knots = clamped_knots(7);
+        assert_eq!(
+            knots,
+            [0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0, 4.0, 4.0, 4.0]
+        );
+        for i in 0..=40 {
+            let t = i as f32 / 10.0;
+            let (first, weights) = basis(&knots, 7, t);
+            assert!(first + DEGREE < 7);
+            assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-9, "t = {t}");
+            assert!(weights.iter().all(|w| *w >= -1e-12));
+        }
+    }
+
+    // 控制点越多拟合越紧；最大残差与拟合出的曲线一致
+    #[test]
+    fn sine_wave_residual_shrinks_with_more_control_points() {
+        let data = sine_wave();
+        let residuals: Vec<f32> = [6, 8, 12, 24]
+            .into_iter()
+            .map(|count| fit_b_spline(&data, count).unwrap().max_residual)
+            .collect();
+        assert!(residuals.windows(2).all(|w| w[1] < w[0]), "{residuals:?}");
+        assert!(residuals[2] < 1.0, "{residuals:?}");
+        assert!(residuals[3] < 0.05, "{residuals:?}");
+
+        let fit = fit_b_spline(&data, 12).unwrap();
+        assert_eq!(fit.spline.points.len(), 12);
+        assert_eq!(fit.spline.domain(), (0.0, 9.0));
+        // 两端的控制点就是曲线的端点，落在数据的端点附近
+        assert!(fit.spline.points[0].distance(data[0]) < 1.0);
+        for (point, &t) in data.iter().zip(&fit.parameters) {
+            assert!(fit.spline.position(t).distance(*point) <= fit.max_residual + 1e-3);
+        }
+    }
+
+    #[test]
+    fn too_little_data_is_rejected() {
+        let data = sine_wave();
+        assert!(fit_b_spline(&data[..5], 6).is_none());
+        assert!(fit_b_spline(&data, 3).is_none());
+        assert!(fit_b_spline(&data[..6], 6).is_some());
+    }
+}
@@ -0,0 +1,99 @@
+//! 沿曲线运动的箭头的朝向：平滑地转向速度方向，在尖点附近不突然翻转。
+
+use bevy::math::Vec2;
+use std::f32::consts::{PI, TAU};
+
+// 速度低于这个值（世界单位每单位参数）时方向不可靠，保持原来的朝向
+const MIN_SPEED: f32 = 1e-2;
+// 转向的速率（1/秒），越大越快跟上速度方向
+const TURN_RATE: f32 = 15.0;
+// 目标方向与当前朝向相差超过这个角度时视为反向
+const REVERSAL_ANGLE: f32 = PI * 5.0 / 6.0;
+// 反向持续超过这个时间（秒）才转过去，短暂的反向直接忽略
+const REVERSAL_DELAY: f32 = 0.2;
+
+/// 箭头的朝向，角度从 x 轴起算。
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Heading {
+    pub angle: f32,
+    reversed_for: f32,
+}
+
+impl Heading {
+    /// 向 `velocity` 的方向转动一帧。
+    pub fn steer(&mut self, velocity: Vec2, dt: f32) {
+        if !velocity.is_finite() || velocity.length() < MIN_SPEED {
+            return;
+        }
+        let delta = wrap_angle(velocity.to_angle() - self.angle);
+        if delta.abs() > REVERSAL_ANGLE {
+            self.reversed_for += dt;
+            if self.reversed_for < REVERSAL_DELAY {
+                return;
+            }
+        } else {
+            self.reversed_for = 0.0;
+        }
+        self.angle = wrap_angle(self.angle + delta * (1.0 - (-TURN_RATE * dt).exp()));
+    }
+}
+
+// 把角度归到 -PI..PI，转向时走较短的一边
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn steer_for(heading: &mut Heading, velocity: Vec2, seconds: f32) {
+        for _ in 0..(seconds / DT).round() as usize {
+            heading.steer(velocity, DT);
+        }
+    }
+
+    #[test]
+    fn turns_towards_the_velocity() {
+        let mut heading = Heading::default();
+        steer_for(&mut heading, Vec2::Y, 1.0);
+        assert!((heading.angle - PI / 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn keeps_the_angle_when_nearly_stopped() {
+        let mut heading = Heading {
+            angle: 1.0,
+            ..Heading::default()
+        };
+        heading.steer(Vec2::new(1e-4, -1e-4), DT);
+        heading.steer(Vec2::ZERO, DT);
+        heading.steer(Vec2::NAN, DT);
+        assert_eq!(heading.angle, 1.0);
+    }
+
+    #[test]
+    fn turns_the_short_way_across_the_seam() {
+        let mut heading = Heading {
+            angle: PI - 0.1,
+            ..Heading::default()
+        };
+        heading.steer(Vec2::from_angle(-PI + 0.1), DT);
+        // 经过 ±PI 转 0.2 弧度，而不是反向转一整圈
+        let moved = wrap_angle(heading.angle - (PI - 0.1));
+        assert!(moved > 0.0 && moved < 0.2);
+    }
+
+    #[test]
+    fn ignores_a_brief_reversal() {
+        let mut heading = Heading::default();
+        steer_for(&mut heading, Vec2::NEG_X, 0.1);
+        assert_eq!(heading.angle, 0.0);
+        steer_for(&mut heading, Vec2::X, 0.1);
+        // 持续的反向最终会转过去
+        steer_for(&mut heading, Vec2::NEG_X, 1.0);
+        assert!(wrap_angle(heading.angle - PI).abs() < 1e-2);
+    }
+}
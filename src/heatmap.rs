@@ -0,0 +1,74 @@
+//! 曲率热力图用到的色带、分位数和随时间平滑的缩放。
+
+use bevy::color::{Mix, Srgba};
+
+// 色带从蓝经青、绿、黄到红，等距分布
+const RAMP_STOPS: [Srgba; 5] = [
+    Srgba::rgb(0.0, 0.0, 1.0),
+    Srgba::rgb(0.0, 1.0, 1.0),
+    Srgba::rgb(0.0, 1.0, 0.0),
+    Srgba::rgb(1.0, 1.0, 0.0),
+    Srgba::rgb(1.0, 0.0, 0.0),
+];
+
+/// 把 0..=1 映射到蓝→红色带，超出范围的值被截断到两端。
+pub fn ramp(x: f32) -> Srgba {
+    let x = if x.is_nan() { 0.0 } else { x.clamp(0.0, 1.0) };
+    let scaled = x * (RAMP_STOPS.len() - 1) as f32;
+    let index = (scaled as usize).min(RAMP_STOPS.len() - 2);
+    RAMP_STOPS[index].mix(&RAMP_STOPS[index + 1], scaled - index as f32)
+}
+
+/// 第 `p`（0..=1）分位数，取最近秩，空输入返回 None。
+pub fn percentile(values: &[f32], p: f32) -> Option<f32> {
+    let mut sorted: Vec<f32> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(f32::total_cmp);
+    let rank = (p.clamp(0.0, 1.0) * (sorted.len() - 1) as f32).round() as usize;
+    Some(sorted[rank])
+}
+
+/// 指数平滑：每秒按 `rate` 向目标靠近，与帧率无关且不会越过目标。
+pub fn smooth_towards(current: f32, target: f32, rate: f32, dt: f32) -> f32 {
+    current + (target - current) * (1.0 - (-rate * dt).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramp_goes_from_blue_to_red() {
+        assert_eq!(ramp(0.0), Srgba::rgb(0.0, 0.0, 1.0));
+        assert_eq!(ramp(1.0), Srgba::rgb(1.0, 0.0, 0.0));
+        assert_eq!(ramp(-3.0), ramp(0.0));
+        assert_eq!(ramp(7.0), ramp(1.0));
+        assert_eq!(ramp(f32::NAN), ramp(0.0));
+        // 红色分量单调不减
+        let reds: Vec<f32> = (0..=20).map(|i| ramp(i as f32 / 20.0).red).collect();
+        assert!(reds.windows(2).all(|w| w[1] >= w[0]));
+    }
+
+    #[test]
+    fn percentile_ignores_a_single_outlier() {
+        let mut values = vec![1.0; 99];
+        values.push(1000.0);
+        assert_eq!(percentile(&values, 0.95), Some(1.0));
+        assert_eq!(percentile(&values, 1.0), Some(1000.0));
+        assert_eq!(percentile(&[], 0.95), None);
+        assert_eq!(percentile(&[f32::NAN, 2.0], 0.5), Some(2.0));
+    }
+
+    #[test]
+    fn smoothing_converges_without_overshoot() {
+        let mut value = 0.0;
+        for _ in 0..600 {
+            value = smooth_towards(value, 10.0, 4.0, 1.0 / 60.0);
+            assert!(value <= 10.0);
+        }
+        assert!((value - 10.0).abs() < 1e-3);
+        assert_eq!(smooth_towards(3.0, 10.0, 4.0, 0.0), 3.0);
+    }
+}
@@ -0,0 +1,129 @@
+//! 撤销/重做历史，保存编辑前的快照。
+
+use std::collections::VecDeque;
+
+pub struct History<T> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    // 拖动开始时的快照，松开时与当前状态比较，变化了才记录
+    pending: Option<T>,
+    capacity: usize,
+}
+
+impl<T: Clone + PartialEq> History<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            pending: None,
+            capacity,
+        }
+    }
+
+    /// 记录一次已完成的编辑，`before` 是编辑前的状态；新的编辑会清空重做栈。
+    pub fn record(&mut self, before: T) {
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(before);
+        self.redo.clear();
+    }
+
+    /// 开始一次持续的编辑（例如拖动），期间不产生任何记录。
+    pub fn begin(&mut self, before: T) {
+        self.pending = Some(before);
+    }
+
+    /// 结束持续的编辑，状态确实改变时只记录一次。
+    pub fn finish(&mut self, current: &T) {
+        if let Some(before) = self.pending.take() {
+            if before != *current {
+                self.record(before);
+            }
+        }
+    }
+
+    /// 返回要恢复的状态，`current` 被移入重做栈。
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 用点的 x 坐标列表模拟控制点
+    #[test]
+    fn add_drag_undo_undo_redo() {
+        let mut history = History::new(100);
+        let mut points: Vec<i32> = vec![];
+
+        history.record(points.clone());
+        points.push(10);
+
+        history.begin(points.clone());
+        points[0] = 11;
+        points[0] = 12;
+        points[0] = 15;
+        history.finish(&points);
+
+        points = history.undo(points).unwrap();
+        assert_eq!(points, vec![10]);
+        points = history.undo(points).unwrap();
+        assert!(points.is_empty());
+        assert!(history.undo(points.clone()).is_none());
+
+        points = history.redo(points).unwrap();
+        assert_eq!(points, vec![10]);
+        points = history.redo(points).unwrap();
+        assert_eq!(points, vec![15]);
+        assert!(history.redo(points.clone()).is_none());
+    }
+
+    #[test]
+    fn drag_without_movement_is_not_recorded() {
+        let mut history = History::new(100);
+        let points = vec![1, 2];
+        history.begin(points.clone());
+        history.finish(&points);
+        assert!(history.undo(points).is_none());
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let mut history = History::new(100);
+        let mut points = vec![1];
+        history.record(points.clone());
+        points.push(2);
+        points = history.undo(points).unwrap();
+        history.record(points.clone());
+        points.push(3);
+        assert!(history.redo(points.clone()).is_none());
+        assert_eq!(history.undo(points).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn oldest_entries_are_dropped_past_capacity() {
+        let mut history = History::new(3);
+        let mut state = 0;
+        for _ in 0..5 {
+            history.record(state);
+            state += 1;
+        }
+        let mut restored = Vec::new();
+        while let Some(previous) = history.undo(state) {
+            state = previous;
+            restored.push(state);
+        }
+        assert_eq!(restored, vec![4, 3, 2]);
+    }
+}
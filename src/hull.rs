@@ -0,0 +1,99 @@
+//! 点集的凸包，Andrew 单调链算法。
+
+use bevy::math::Vec2;
+
+// (b - a) × (c - a)，大于 0 时 a→b→c 左转
+fn cross(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b - a).perp_dot(c - a)
+}
+
+/// 逆时针排列的凸包顶点，从 x 最小（相同时 y 最小）的点开始，不含共线的点。
+/// 重复的点只算一次；所有点共线时返回两个端点，只有一个不同的点时返回它本身。
+pub fn convex_hull(points: &[Vec2]) -> Vec<Vec2> {
+    let mut sorted: Vec<Vec2> = points.iter().copied().filter(|p| p.is_finite()).collect();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    let mut hull: Vec<Vec2> = Vec::with_capacity(sorted.len() + 1);
+    // 下半部分从左到右，上半部分从右到左；不是严格左转的点弹出，共线的点也去掉
+    for &p in &sorted {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    let lower = hull.len() + 1;
+    for &p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // 最后加入的是起点
+    hull.pop();
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square_drops_interior_collinear_and_duplicate_points() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(5.0, 5.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(5.0, 0.0),
+            Vec2::new(0.0, 10.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(0.0, 5.0),
+        ];
+        assert_eq!(
+            convex_hull(&points),
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 0.0),
+                Vec2::new(10.0, 10.0),
+                Vec2::new(0.0, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn degenerate_inputs_do_not_panic() {
+        assert!(convex_hull(&[]).is_empty());
+        assert_eq!(convex_hull(&[Vec2::ONE; 5]), [Vec2::ONE]);
+        assert_eq!(
+            convex_hull(&[Vec2::ONE, Vec2::ZERO, Vec2::ONE]),
+            [Vec2::ZERO, Vec2::ONE]
+        );
+        let line: Vec<Vec2> = (0..10).map(|i| Vec2::splat(i as f32)).rev().collect();
+        assert_eq!(convex_hull(&line), [Vec2::ZERO, Vec2::splat(9.0)]);
+        let with_nan = [Vec2::ZERO, Vec2::X, Vec2::NAN, Vec2::Y];
+        assert_eq!(convex_hull(&with_nan), [Vec2::ZERO, Vec2::X, Vec2::Y]);
+    }
+
+    #[test]
+    fn every_point_is_inside_the_hull() {
+        let points: Vec<Vec2> = (0..200)
+            .map(|i| {
+                let t = i as f32 * 0.37;
+                Vec2::new(t.sin() * (40.0 + t), (t * 1.3).cos() * 25.0)
+            })
+            .collect();
+        let hull = convex_hull(&points);
+        assert!(hull.len() >= 3);
+        for (i, &a) in hull.iter().enumerate() {
+            let b = hull[(i + 1) % hull.len()];
+            let c = hull[(i + 2) % hull.len()];
+            assert!(cross(a, b, c) > 0.0, "not strictly convex at {b}");
+            for &p in &points {
+                assert!(cross(a, b, p) >= -1e-3, "{p} outside edge {a} -> {b}");
+            }
+        }
+    }
+}
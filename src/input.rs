@@ -0,0 +1,2497 @@
+//! 鼠标和键盘输入系统，快捷键都通过 [`KeyBindings`] 查询。
+
+use super::*;
+
+// 复制点时副本的偏移，避免与原来的点完全重合
+pub(crate) const DUPLICATE_OFFSET: Vec2 = Vec2::new(8.0, -8.0);
+
+// 方向键微调：按住超过 NUDGE_REPEAT_DELAY 秒后每 NUDGE_REPEAT_INTERVAL 秒重复一次
+pub(crate) const NUDGE_STEP: f32 = 1.0;
+
+pub(crate) const NUDGE_STEP_SHIFT: f32 = 10.0;
+
+pub(crate) const NUDGE_REPEAT_DELAY: f32 = 0.4;
+
+pub(crate) const NUDGE_REPEAT_INTERVAL: f32 = 0.05;
+
+// Space 播放或暂停，R 回到起点，Shift+R 切换播放方式，B 切换到终点后的行为，没有选中的点时上下方向键调整倍速；
+// 按住 Space 平移过相机时松开不切换
+pub(crate) fn animation_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    pan: Res<CameraPan>,
+    control_points: Res<ControlPoints>,
+    cursor: WorldCursor,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+    mut animation: ResMut<CurveAnimation>,
+    mut status: ResMut<StatusMessage>,
+    mut panned: Local<bool>,
+) {
+    if bindings.just_pressed(Action::ToggleAnimation, &keyboard) {
+        *panned = false;
+    }
+    if pan.0.is_some() {
+        *panned = true;
+    }
+    if bindings.just_released(Action::ToggleAnimation, &keyboard) && !*panned {
+        // 已经停在终点时重新播放从头开始
+        if !animation.playing && animation.finished() {
+            animation.reset();
+        }
+        animation.playing = !animation.playing;
+    }
+    if bindings.just_pressed(Action::ResetAnimation, &keyboard) {
+        animation.reset();
+    }
+    if bindings.just_pressed(Action::CycleEndBehavior, &keyboard) {
+        animation.end = animation.end.next();
+        status.info(format!("Marker: {}", animation.end.name()));
+    }
+    if bindings.just_pressed(Action::CyclePlaybackMode, &keyboard) {
+        animation.mode = animation.mode.next();
+        status.info(format!("Marker: {}", animation.mode.name()));
+    }
+    // 方向键正在移动点时不调节速度
+    if !control_points
+        .arrow_targets(&point_index, cursor.position(), view.0)
+        .is_empty()
+    {
+        return;
+    }
+    let factor = if bindings.just_pressed(Action::PlaybackFaster, &keyboard) {
+        CurveAnimation::RATE_STEP
+    } else if bindings.just_pressed(Action::PlaybackSlower, &keyboard) {
+        1.0 / CurveAnimation::RATE_STEP
+    } else {
+        return;
+    };
+    let rate = animation.rate * factor;
+    animation.set_rate(rate);
+    status.info(format!("Playback {:.2}x", animation.rate));
+}
+
+// 左键按在时间轴上开始拖动：暂停播放并把标记放到光标对应的位置；
+// 在其他鼠标系统之前运行，并消耗这次点击，时间轴下面的点不会被选中或拖动
+pub(crate) fn scrub_timeline(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut input: ResMut<ButtonInput<MouseButton>>,
+    mouse_position: Res<MousePosition>,
+    windows: Query<&Window>,
+    captured: Res<PointerCaptured>,
+    clean_capture: Res<CleanCapture>,
+    mut scrub: ResMut<TimelineScrub>,
+    mut animation: ResMut<CurveAnimation>,
+) {
+    let Some(cursor) = mouse_position.0 else {
+        return;
+    };
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let rect = timeline_rect(window);
+    if !scrub.0 {
+        let hit = rect.inflate(TIMELINE_HIT_MARGIN).contains(cursor);
+        let Some(button) = bindings.clicked_button(Action::ScrubTimeline, &input, &keyboard) else {
+            return;
+        };
+        if !hit || captured.0 || clean_capture.is_hiding() {
+            return;
+        }
+        input.clear_just_pressed(button);
+        scrub.0 = true;
+        animation.playing = false;
+    } else if !bindings
+        .button(Action::ScrubTimeline)
+        .is_some_and(|button| input.pressed(button))
+    {
+        scrub.0 = false;
+        return;
+    }
+    animation.scrub((cursor.x - rect.min.x) / rect.width());
+}
+
+pub(crate) fn move_point_with_mouse(
+    mut control_points: ResMut<ControlPoints>,
+    mut drag: ResMut<DragState>,
+    mut history: ResMut<EditHistory>,
+    grid: Res<GridSettings>,
+    input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    captured: Res<PointerCaptured>,
+    pan: Res<CameraPan>,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+    pointer: Res<PointerState>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    // 手柄拥有光标时拖动由 gamepad_buttons 处理，这里不在松开的鼠标上结束它
+    if pointer.device == PointerDevice::Gamepad {
+        return;
+    }
+    let Some(button) = bindings.button(Action::DragPoint) else {
+        return;
+    };
+    if !input.pressed(button) {
+        end_drag(&mut drag, &mut control_points, &mut history, &mut session);
+        return;
+    }
+    // 在面板上按下或正在平移时不开始拖动，已经开始的拖动移到面板上也继续
+    if (captured.0 || pan.0.is_some()) && matches!(*drag, DragState::Idle) {
+        return;
+    }
+    // Convert the current mouse pos into world coords:
+    let Some(mouse_point) = cursor.position() else {
+        return;
+    };
+    let ctrl = bindings.pressed(Action::SnapToGrid, &keyboard);
+    if bindings.clicked(Action::DragPoint, &input, &keyboard) {
+        let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        *drag = begin_drag(
+            &mut control_points,
+            &point_index,
+            mouse_point,
+            shift,
+            view.0,
+        );
+        if matches!(
+            *drag,
+            DragState::Points { .. } | DragState::Handle | DragState::SymmetryAxis
+        ) {
+            history.0.begin(control_points.snapshot());
+        }
+    }
+    update_drag(
+        &mut drag,
+        &mut control_points,
+        mouse_point,
+        grid.is_snapping(ctrl),
+        &grid,
+    );
+}
+
+// 松开时框选生效，点的选择保留到下一次点击
+pub(crate) fn end_drag(
+    drag: &mut DragState,
+    control_points: &mut ControlPoints,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+) {
+    match *drag {
+        DragState::Points { moved, .. } => record_moves(control_points, session, moved),
+        DragState::Handle | DragState::EndTangent(_) | DragState::SymmetryAxis => {
+            session.record_snapshot(control_points);
+        }
+        _ => {}
+    }
+    if let DragState::Box {
+        start,
+        current,
+        additive,
+    } = *drag
+    {
+        control_points.select_in_rect(Rect::from_corners(start, current), additive);
+    }
+    // 只在确实有选中的手柄时写入，空闲的帧不触发 ControlPoints 的变化检测
+    if control_points.points.iter().any(|p| p.is_handle_selected) {
+        for point in control_points.points.iter_mut() {
+            point.is_handle_selected = false;
+        }
+    }
+    // 一次拖动只在松开时记录一条历史
+    if matches!(
+        *drag,
+        DragState::Points { .. } | DragState::Handle | DragState::SymmetryAxis
+    ) {
+        history.0.finish(&control_points.snapshot());
+    }
+    *drag = DragState::Idle;
+}
+
+// 录制时把拖动过的每个选中的点记成一条移动；对称编辑时轴上的点只沿轴移动
+fn record_moves(control_points: &ControlPoints, session: &mut SessionRecorder, moved: Vec2) {
+    if moved == Vec2::ZERO {
+        return;
+    }
+    for (index, point) in control_points.points.iter().enumerate() {
+        if !point.is_selected {
+            continue;
+        }
+        let delta = match control_points.symmetry {
+            Some(axis) if on_axis(point.position, axis) => Vec2::new(0.0, moved.y),
+            _ => moved,
+        };
+        session.record(SessionEdit::Move {
+            index,
+            from: point.position - delta,
+            to: point.position,
+        });
+    }
+}
+
+// 按住时每帧让拖动的对象跟随光标
+pub(crate) fn update_drag(
+    drag: &mut DragState,
+    control_points: &mut ControlPoints,
+    mouse_point: Vec2,
+    snapping: bool,
+    grid: &GridSettings,
+) {
+    match drag {
+        DragState::Idle => {}
+        // 按位移而不是把点移到光标处，偏离中心抓取时点不会跳动；
+        // 吸附时让被点中的点落在格点上，其余选中的点保持相对位置
+        DragState::Points {
+            start,
+            anchor,
+            moved,
+        } => {
+            let mut translation = mouse_point - *start;
+            if snapping {
+                translation = grid.snap(*anchor + translation) - *anchor;
+            }
+            let delta = translation - *moved;
+            *moved = translation;
+            for index in 0..control_points.points.len() {
+                if control_points.points[index].is_selected {
+                    control_points.move_point_by(index, delta);
+                }
+            }
+        }
+        DragState::Handle => {
+            for point in control_points.points.iter_mut() {
+                if point.is_handle_selected {
+                    point.tangent = Some((mouse_point - point.position) / TANGENT_HANDLE_SCALE);
+                }
+            }
+        }
+        DragState::EndTangent(end) => {
+            let anchor = control_points.points[control_points.end_point(*end)].position;
+            control_points.end_tangents[*end] = Some((mouse_point - anchor) / END_HANDLE_SCALE);
+        }
+        DragState::SymmetryAxis => {
+            let Some(old) = control_points.symmetry else {
+                return;
+            };
+            let axis = if snapping {
+                grid.snap(mouse_point).x
+            } else {
+                mouse_point.x
+            };
+            for point in control_points.points.iter_mut() {
+                if on_axis(point.position, old) {
+                    point.position.x = axis;
+                }
+            }
+            control_points.symmetry = Some(axis);
+        }
+        DragState::Box { current, .. } => *current = mouse_point,
+    }
+}
+
+// 按下左键时决定这次拖动的对象：点、端点条件的箭头、切线手柄、对称轴，都没点中时在空白处开始框选
+pub(crate) fn begin_drag(
+    control_points: &mut ControlPoints,
+    point_index: &PointIndex,
+    mouse_point: Vec2,
+    shift: bool,
+    scale: f32,
+) -> DragState {
+    // 点的命中优先于切线手柄，避免手柄与点重叠时点无法拖动
+    if let Some(index) = control_points.hit_unlocked_point(point_index, mouse_point, scale) {
+        // 点中已选中的点时保留整个选择，否则只选中这个点
+        if !control_points.points[index].is_selected {
+            control_points.clear_selection();
+            control_points.points[index].is_selected = true;
+        }
+        return DragState::Points {
+            start: mouse_point,
+            anchor: control_points.points[index].position,
+            moved: Vec2::ZERO,
+        };
+    }
+    // 箭头与首尾的切线手柄可能重叠，钳制时它们才影响曲线，先检查箭头
+    if control_points.has_end_handles() {
+        for end in 0..2 {
+            let point = &control_points.points[control_points.end_point(end)];
+            let handle = control_points.end_handle_position(end);
+            if !point.locked && handle.distance(mouse_point) < point.selected_size * scale {
+                return DragState::EndTangent(end);
+            }
+        }
+    }
+    if control_points.points.len() >= 2 {
+        let handles: Vec<Vec2> = (0..control_points.points.len())
+            .map(|i| control_points.handle_position(i))
+            .collect();
+        for (point, handle) in control_points.points.iter_mut().zip(handles) {
+            if !point.locked && handle.distance(mouse_point) < point.selected_size * scale {
+                point.is_handle_selected = true;
+                return DragState::Handle;
+            }
+        }
+    }
+    if let Some(axis) = control_points.symmetry {
+        if (mouse_point.x - axis).abs() < AXIS_SNAP_DISTANCE * scale {
+            return DragState::SymmetryAxis;
+        }
+    }
+    DragState::Box {
+        start: mouse_point,
+        current: mouse_point,
+        additive: shift,
+    }
+}
+
+// Shift+左键删除光标下的点；这次点击被消耗掉，不会紧接着选中别的点开始拖动
+pub(crate) fn delete_point_with_shift_click(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut input: ResMut<ButtonInput<MouseButton>>,
+    cursor: WorldCursor,
+    captured: Res<PointerCaptured>,
+    pan: Res<CameraPan>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !bindings.clicked(Action::DeletePoint, &input, &keyboard) || captured.0 || pan.0.is_some() {
+        return;
+    }
+    let Some(mouse_point) = cursor.position() else {
+        return;
+    };
+    let Some(index) = control_points.hit_point(&point_index, mouse_point, view.0) else {
+        return;
+    };
+    control_points.clear_selection();
+    remove_point(&mut control_points, &mut history, &mut session, index);
+    if let Some(button) = bindings.button(Action::DeletePoint) {
+        input.reset(button);
+    }
+}
+
+// 按住 S 点击 Bezier 附近，在离光标最近的参数处细分；这次点击被消耗掉，不会开始拖动或框选
+pub(crate) fn split_bezier_with_click(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut input: ResMut<ButtonInput<MouseButton>>,
+    cursor: WorldCursor,
+    captured: Res<PointerCaptured>,
+    control_points: Res<ControlPoints>,
+    visibility: Res<CurveVisibility>,
+    view: Res<ViewScale>,
+    mut split: ResMut<BezierSplit>,
+) {
+    if bindings.just_pressed(Action::ClearSplit, &keyboard) && split.0.is_some() {
+        split.0 = None;
+    }
+    let Some(button) = bindings.clicked_button(Action::SplitBezier, &input, &keyboard) else {
+        return;
+    };
+    if captured.0 || !visibility.is_visible(CurveKind::Bezier) {
+        return;
+    }
+    let Some(mouse_point) = cursor.position() else {
+        return;
+    };
+    let Some(curve) = build_curve(CurveKind::Bezier, &control_points) else {
+        return;
+    };
+    let (t, position) = closest_point_on_curve(curve.as_ref(), mouse_point);
+    if position.distance(mouse_point) > SPLIT_PICK_DISTANCE * view.0 {
+        return;
+    }
+    split.0 = Some(t);
+    input.clear_just_pressed(button);
+}
+
+// 按住 ` 时左键按下开始记录手绘路径，并消耗这次按下，不会同时开始拖动或框选；
+// 松开时化简路径替换所有点。记录结束后可以调整容差，从保留的原始路径重新化简
+pub(crate) fn sketch_with_mouse(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut input: ResMut<ButtonInput<MouseButton>>,
+    cursor: WorldCursor,
+    captured: Res<PointerCaptured>,
+    view: Res<ViewScale>,
+    mut sketch: ResMut<Sketch>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !sketch.recording {
+        if let Some(button) = bindings.clicked_button(Action::Sketch, &input, &keyboard) {
+            if !captured.0 {
+                sketch.recording = true;
+                sketch.raw.clear();
+                input.clear_just_pressed(button);
+            }
+        }
+    }
+    if sketch.recording {
+        let held = bindings
+            .button(Action::Sketch)
+            .is_some_and(|button| input.pressed(button));
+        if held {
+            if let Some(position) = cursor.position() {
+                let far_enough = sketch
+                    .raw
+                    .last()
+                    .is_none_or(|last| last.distance(position) >= Sketch::MIN_SPACING * view.0);
+                if far_enough {
+                    sketch.raw.push(position);
+                }
+            }
+            return;
+        }
+        sketch.recording = false;
+    } else {
+        let step = if bindings.just_pressed(Action::IncreaseSketchTolerance, &keyboard) {
+            Sketch::STEP
+        } else if bindings.just_pressed(Action::DecreaseSketchTolerance, &keyboard) {
+            1.0 / Sketch::STEP
+        } else {
+            return;
+        };
+        if sketch.raw.len() < 2 {
+            return;
+        }
+        sketch.tolerance =
+            (sketch.tolerance * step).clamp(Sketch::MIN_TOLERANCE, Sketch::MAX_TOLERANCE);
+    }
+    // 只是点了一下没有拖动时不替换
+    if sketch.raw.len() < 2 {
+        sketch.raw.clear();
+        return;
+    }
+    let simplified = ramer_douglas_peucker(&sketch.raw, sketch.tolerance);
+    history.0.record(control_points.snapshot());
+    control_points.points = simplified.into_iter().map(MovablePoint::new).collect();
+    control_points.closed = false;
+    session.record_snapshot(&control_points);
+    status.info(format!(
+        "Sketch: {} of {} points kept (tolerance {:.2})",
+        control_points.points.len(),
+        sketch.raw.len(),
+        sketch.tolerance
+    ));
+}
+
+// 探针的命中优先于控制点：按下时点中探针就开始拖动它，并消耗这次按下，
+// move_point_with_mouse 不会再选中探针下面的点或开始框选
+pub(crate) fn drag_probe(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut input: ResMut<ButtonInput<MouseButton>>,
+    cursor: WorldCursor,
+    captured: Res<PointerCaptured>,
+    pan: Res<CameraPan>,
+    drag: Res<DragState>,
+    view: Res<ViewScale>,
+    mut probe: ResMut<Probe>,
+) {
+    let Some(button) = bindings.button(Action::DragPoint) else {
+        return;
+    };
+    if !input.pressed(button) {
+        if probe.grab.is_some() {
+            probe.grab = None;
+        }
+        return;
+    }
+    let (Some(position), Some(mouse_point)) = (probe.position, cursor.position()) else {
+        return;
+    };
+    if bindings.clicked(Action::DragPoint, &input, &keyboard)
+        && !captured.0
+        && pan.0.is_none()
+        && matches!(*drag, DragState::Idle)
+        && position.distance(mouse_point) < PROBE_SIZE * view.0
+    {
+        probe.grab = Some(mouse_point - position);
+        input.clear_just_pressed(button);
+    }
+    if let Some(grab) = probe.grab {
+        let target = mouse_point - grab;
+        if position != target {
+            probe.position = Some(target);
+        }
+    }
+}
+
+// P 切换鼠标下的点的锁定状态，锁定时取消它的选择
+pub(crate) fn toggle_lock_with_p(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !bindings.just_pressed(Action::ToggleLock, &keyboard) || !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    let Some(mouse_point) = cursor.position() else {
+        return;
+    };
+    let Some(index) = control_points.hit_point(&point_index, mouse_point, view.0) else {
+        return;
+    };
+    history.0.record(control_points.snapshot());
+    let point = &mut control_points.points[index];
+    point.locked = !point.locked;
+    point.is_selected = false;
+    session.record_snapshot(&control_points);
+}
+
+// 中键删除阈值内最近的点，正在被左键拖动的点不会被删除
+pub(crate) fn remove_point_with_middle_mouse(
+    input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    drag: Res<DragState>,
+    captured: Res<PointerCaptured>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    // 拖动点或手柄时不删除
+    if !bindings.clicked(Action::RemoveNearestPoint, &input, &keyboard)
+        || captured.0
+        || matches!(
+            *drag,
+            DragState::Points { .. }
+                | DragState::Handle
+                | DragState::EndTangent(_)
+                | DragState::SymmetryAxis
+        )
+    {
+        return;
+    }
+    let Some(mouse_point) = cursor.position() else {
+        return;
+    };
+    if let Some(index) = control_points.removable_point(&point_index, mouse_point, view.0) {
+        remove_point(&mut control_points, &mut history, &mut session, index);
+    }
+}
+
+// 中键在空白处或 Space+左键拖动平移相机：每帧把光标在视口中的位移换算到世界坐标，
+// 反向移动相机，光标下的内容始终跟着光标走
+pub(crate) fn pan_camera(
+    input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mouse_position: Res<MousePosition>,
+    captured: Res<PointerCaptured>,
+    control_points: Res<ControlPoints>,
+    mut pan: ResMut<CameraPan>,
+    cursor: WorldCursor,
+    mut camera: Query<&mut Transform, With<Camera>>,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+) {
+    let Some(cursor) = mouse_position.0 else {
+        return;
+    };
+    let Ok(mut transform) = camera.get_single_mut() else {
+        return;
+    };
+    let to_world = |position| cursor.to_world(position);
+    match pan.0 {
+        None => {
+            let Some(button) = bindings.clicked_button(Action::PanCamera, &input, &keyboard) else {
+                return;
+            };
+            // 中键按在点上是删除，不开始平移
+            let on_point = button == MouseButton::Middle
+                && to_world(cursor).is_some_and(|p| {
+                    control_points
+                        .removable_point(&point_index, p, view.0)
+                        .is_some()
+                });
+            if !captured.0 && !on_point {
+                pan.0 = Some((button, cursor));
+            }
+        }
+        Some((button, _)) if !input.pressed(button) => pan.0 = None,
+        Some((button, last)) => {
+            if let (Some(from), Some(to)) = (to_world(last), to_world(cursor)) {
+                transform.translation += (from - to).extend(0.0);
+            }
+            pan.0 = Some((button, cursor));
+        }
+    }
+}
+
+// 滚轮事件换算成格数，触控板的像素滚动按 50 像素一格
+pub(crate) fn wheel_steps(event: &MouseWheel) -> f32 {
+    match event.unit {
+        MouseScrollUnit::Line => event.y,
+        MouseScrollUnit::Pixel => event.y / 50.0,
+    }
+}
+
+// 在空白处滚动滚轮或双指捏合缩放视图，光标下的世界坐标在缩放前后不变；
+// 有悬停的点时滚轮用于调整它的权重和张力
+pub(crate) fn zoom_with_scroll(
+    mut scroll_events: EventReader<MouseWheel>,
+    mut pinch_events: EventReader<PinchGesture>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    captured: Res<PointerCaptured>,
+    hovered: Res<HoveredPoint>,
+    mut view: ResMut<ViewScale>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    let mut steps: f32 = scroll_events.read().map(wheel_steps).sum();
+    // 捏合的 delta 是缩放量的近似对数，换算成等效的滚轮格数
+    steps += pinch_events.read().map(|event| event.0).sum::<f32>() / ViewScale::STEP.ln();
+    if steps == 0.0 || captured.0 || !bindings.modifiers_held(Action::Zoom, &keyboard) {
+        return;
+    }
+    let Some(anchor) = cursor.position() else {
+        return;
+    };
+    let Ok((mut transform, mut projection)) = camera.get_single_mut() else {
+        return;
+    };
+    if hovered.0.is_some() {
+        return;
+    }
+    let scale =
+        (projection.scale * ViewScale::STEP.powf(-steps)).clamp(ViewScale::MIN, ViewScale::MAX);
+    let center = view::zoom_about(
+        transform.translation.truncate(),
+        anchor,
+        scale / projection.scale,
+    );
+    transform.translation = center.extend(transform.translation.z);
+    projection.scale = scale;
+    view.0 = scale;
+}
+
+// F 让所有控制点和显示中的曲线充满窗口；少于两个点或范围退化成一点时恢复默认视图
+pub(crate) fn fit_view_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    control_points: Res<ControlPoints>,
+    visibility: Res<CurveVisibility>,
+    resolution: Res<CurveResolution>,
+    windows: Query<&Window>,
+    mut view: ResMut<ViewScale>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    if !bindings.just_pressed(Action::FitView, &keyboard) {
+        return;
+    }
+    let Ok((mut transform, mut projection)) = camera.get_single_mut() else {
+        return;
+    };
+    let viewport = windows
+        .get_single()
+        .map_or(Vec2::ZERO, |window| window.size());
+    // 样条可能鼓出控制多边形，把显示中的曲线采样点也算进去
+    let mut points = control_points.positions();
+    let inputs = CurveInputs::new(&control_points);
+    for kind in CurveKind::ALL {
+        if !visibility.is_visible(kind) {
+            continue;
+        }
+        if let Some(curve) = curve_from_inputs(kind, &inputs) {
+            points.extend(curve.sample(resolution.0).positions);
+        }
+    }
+    let fitted = (control_points.points.len() >= 2)
+        .then(|| view::bounds(points))
+        .flatten()
+        .and_then(|bounds| view::fit_view(bounds, viewport, FIT_VIEW_PADDING));
+    let (center, scale) = fitted.unwrap_or((Vec2::ZERO, 1.0));
+    let scale = scale.clamp(ViewScale::MIN, ViewScale::MAX);
+    transform.translation = center.extend(transform.translation.z);
+    projection.scale = scale;
+    view.0 = scale;
+}
+
+/// Update the current cursor position and track it in the [`MousePosition`] resource.
+/// Moving or pressing the mouse takes the virtual cursor back from the gamepad.
+pub(crate) fn handle_mouse_move(
+    mut cursor_events: EventReader<CursorMoved>,
+    input: Res<ButtonInput<MouseButton>>,
+    mut mouse_position: ResMut<MousePosition>,
+    mut pointer: ResMut<PointerState>,
+) {
+    let moved = cursor_events.read().last().map(|event| event.position);
+    if let Some(position) = moved {
+        mouse_position.0 = Some(position);
+    }
+    if (moved.is_some() || input.get_just_pressed().next().is_some())
+        && pointer.device != PointerDevice::Mouse
+    {
+        pointer.device = PointerDevice::Mouse;
+    }
+}
+
+// 摇杆推出死区时手柄接管光标；多个手柄时取偏移最大的摇杆。速度按屏幕像素计算，缩放后手感不变
+pub(crate) fn move_crosshair_with_stick(
+    gamepads: Query<&Gamepad>,
+    time: Res<Time>,
+    mouse_position: Res<MousePosition>,
+    screen: ScreenMapping,
+    view: Res<ViewScale>,
+    mut pointer: ResMut<PointerState>,
+) {
+    let Some(stick) = gamepads
+        .iter()
+        .map(Gamepad::left_stick)
+        .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+    else {
+        return;
+    };
+    let deflection = stick.length().min(1.0);
+    if deflection < STICK_DEAD_ZONE {
+        return;
+    }
+    if pointer.device != PointerDevice::Gamepad {
+        pointer.device = PointerDevice::Gamepad;
+        if let Some(position) = mouse_position.0.and_then(|p| screen.to_world(p)) {
+            pointer.crosshair = position;
+        }
+    }
+    // 死区之外从零开始加速
+    let strength = (deflection - STICK_DEAD_ZONE) / (1.0 - STICK_DEAD_ZONE);
+    let velocity = stick.normalize() * strength * strength * CROSSHAIR_SPEED * view.0;
+    pointer.crosshair += velocity * time.delta_secs();
+}
+
+// 手柄按键作用在准星上：A 和鼠标左键一样选择或拖动，B 删除准星下的点，X 在准星处加点
+pub(crate) fn gamepad_buttons(
+    gamepads: Query<&Gamepad>,
+    pointer: Res<PointerState>,
+    grid: Res<GridSettings>,
+    mut drag: ResMut<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if pointer.device != PointerDevice::Gamepad {
+        return;
+    }
+    let any = |pressed: fn(&Gamepad, GamepadButton) -> bool, button| {
+        gamepads.iter().any(|gamepad| pressed(gamepad, button))
+    };
+    let crosshair = pointer.crosshair;
+    if any(Gamepad::just_pressed, GamepadButton::South) {
+        *drag = begin_drag(&mut control_points, &point_index, crosshair, false, view.0);
+        if matches!(
+            *drag,
+            DragState::Points { .. } | DragState::Handle | DragState::SymmetryAxis
+        ) {
+            history.0.begin(control_points.snapshot());
+        }
+    }
+    if any(Gamepad::pressed, GamepadButton::South) {
+        update_drag(
+            &mut drag,
+            &mut control_points,
+            crosshair,
+            grid.is_snapping(false),
+            &grid,
+        );
+        return;
+    }
+    if !matches!(*drag, DragState::Idle) {
+        end_drag(&mut drag, &mut control_points, &mut history, &mut session);
+    }
+    if any(Gamepad::just_pressed, GamepadButton::East) {
+        if let Some(index) = control_points.hit_point(&point_index, crosshair, view.0) {
+            control_points.clear_selection();
+            remove_point(&mut control_points, &mut history, &mut session, index);
+        }
+    }
+    if any(Gamepad::just_pressed, GamepadButton::West) {
+        let position = if grid.is_snapping(false) {
+            grid.snap(crosshair)
+        } else {
+            crosshair
+        };
+        add_point(
+            &mut control_points,
+            &mut history,
+            &mut session,
+            crosshair,
+            position,
+            view.0,
+        );
+    }
+}
+
+pub(crate) fn add_point_with_right_mouse(
+    input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    grid: Res<GridSettings>,
+    captured: Res<PointerCaptured>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    view: Res<ViewScale>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if bindings.clicked(Action::AddPoint, &input, &keyboard) && !captured.0 {
+        let Some(world_position) = cursor.position() else {
+            return;
+        };
+        let position = snapped_position(world_position, &grid, &bindings, &keyboard);
+        add_point(
+            &mut control_points,
+            &mut history,
+            &mut session,
+            world_position,
+            position,
+            view.0,
+        );
+    }
+}
+
+// 右键加点的位置：开启网格吸附（或按住 Ctrl）时吸附到网格
+pub(crate) fn snapped_position(
+    world_position: Vec2,
+    grid: &GridSettings,
+    bindings: &KeyBindings,
+    keyboard: &ButtonInput<KeyCode>,
+) -> Vec2 {
+    let ctrl = bindings.pressed(Action::SnapToGrid, keyboard);
+    if grid.is_snapping(ctrl) {
+        grid.snap(world_position)
+    } else {
+        world_position
+    }
+}
+
+// 在光标处加点时的位置和插入位置，None 表示加在末尾。离控制多边形某条边足够近时插入到这条边中间，
+// 插入位置按吸附前的位置判断；对称编辑时离轴足够近的点吸附到轴上
+pub(crate) fn prospective_point(
+    control_points: &ControlPoints,
+    world_position: Vec2,
+    position: Vec2,
+    scale: f32,
+) -> (Option<usize>, Vec2) {
+    let position = match control_points.symmetry {
+        Some(axis) if (position.x - axis).abs() < AXIS_SNAP_DISTANCE * scale => {
+            Vec2::new(axis, position.y)
+        }
+        _ => position,
+    };
+    let index = insertion_index(
+        &control_points.positions(),
+        world_position,
+        control_points.closed,
+        INSERT_DISTANCE * scale,
+    );
+    (index, position)
+}
+
+fn add_point(
+    control_points: &mut ControlPoints,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+    world_position: Vec2,
+    position: Vec2,
+    scale: f32,
+) {
+    let (index, position) = prospective_point(control_points, world_position, position, scale);
+    let index = index.unwrap_or(control_points.points.len());
+    insert_point(control_points, history, session, index, position);
+}
+
+// 下面几个编辑操作由输入系统和回放共用，录制时写入日志
+fn insert_point(
+    control_points: &mut ControlPoints,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+    index: usize,
+    position: Vec2,
+) {
+    history.0.record(control_points.snapshot());
+    control_points.points.insert(
+        index,
+        MovablePoint {
+            position,
+            ..default()
+        },
+    );
+    session.record(SessionEdit::Add { index, position });
+}
+
+fn remove_point(
+    control_points: &mut ControlPoints,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+    index: usize,
+) {
+    history.0.record(control_points.snapshot());
+    control_points.points.remove(index);
+    session.record(SessionEdit::Remove { index });
+}
+
+fn move_point(
+    control_points: &mut ControlPoints,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+    index: usize,
+    to: Vec2,
+) {
+    history.0.record(control_points.snapshot());
+    let from = std::mem::replace(&mut control_points.points[index].position, to);
+    session.record(SessionEdit::Move { index, from, to });
+}
+
+fn load_preset(
+    control_points: &mut ControlPoints,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+    status: &mut StatusMessage,
+    preset: Preset,
+    center: Vec2,
+    radius: f32,
+) {
+    history.0.record(control_points.snapshot());
+    control_points.load_into_active(preset.scene(center, radius));
+    status.info(format!("Preset: {}", preset.name()));
+    session.record(SessionEdit::Preset {
+        preset,
+        center,
+        radius,
+    });
+}
+
+// 触屏的输入路径，与鼠标互不影响：点击空白处加点，拖动点移动它，长按点删除它。
+// 只有 TouchGesture 跟踪的第一根手指操作控制点，之后按下的手指留给将来的双指平移缩放
+pub(crate) fn handle_touches(
+    touches: Res<Touches>,
+    time: Res<Time>,
+    cursor: WorldCursor,
+    captured: Res<PointerCaptured>,
+    grid: Res<GridSettings>,
+    mut gesture: ResMut<TouchGesture>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    let now = time.elapsed_secs();
+    let Some(id) = gesture.finger() else {
+        // 同一帧按下多根手指时取 id 最小的
+        let Some(touch) = touches.iter_just_pressed().min_by_key(|touch| touch.id()) else {
+            return;
+        };
+        if captured.0 {
+            return;
+        }
+        let Some(world) = cursor.to_world(touch.position()) else {
+            return;
+        };
+        *gesture = TouchGesture::Pressed {
+            id: touch.id(),
+            start: touch.position(),
+            started: now,
+            point: control_points.hit_unlocked_point(&point_index, world, view.0),
+        };
+        return;
+    };
+    if let Some(touch) = touches.get_released(id) {
+        match *gesture {
+            TouchGesture::Pressed { point: None, .. } => {
+                if let Some(world) = cursor.to_world(touch.position()) {
+                    let position = if grid.is_snapping(false) {
+                        grid.snap(world)
+                    } else {
+                        world
+                    };
+                    add_point(
+                        &mut control_points,
+                        &mut history,
+                        &mut session,
+                        world,
+                        position,
+                        view.0,
+                    );
+                }
+            }
+            // 点击点只选中它，与鼠标点击相同
+            TouchGesture::Pressed {
+                point: Some(index), ..
+            } if index < control_points.points.len() => {
+                control_points.clear_selection();
+                control_points.points[index].is_selected = true;
+            }
+            TouchGesture::Dragging { moved, .. } => {
+                record_moves(&control_points, &mut session, moved);
+                history.0.finish(&control_points.snapshot());
+            }
+            _ => {}
+        }
+        *gesture = TouchGesture::Idle;
+        return;
+    }
+    let Some(touch) = touches.get_pressed(id) else {
+        // 被系统取消，或者手指在没有 just_released 的情况下消失
+        if let TouchGesture::Dragging { moved, .. } = *gesture {
+            record_moves(&control_points, &mut session, moved);
+            history.0.finish(&control_points.snapshot());
+        }
+        *gesture = TouchGesture::Idle;
+        return;
+    };
+    // 移动超过阈值的这一帧就开始拖动，下面接着施加这一帧的位移
+    if let TouchGesture::Pressed {
+        start,
+        started,
+        point,
+        ..
+    } = *gesture
+    {
+        let point = point.filter(|&index| index < control_points.points.len());
+        if touch.position().distance(start) > TOUCH_SLOP {
+            *gesture = match (point, cursor.to_world(start)) {
+                (Some(index), Some(start)) => {
+                    if !control_points.points[index].is_selected {
+                        control_points.clear_selection();
+                        control_points.points[index].is_selected = true;
+                    }
+                    history.0.begin(control_points.snapshot());
+                    TouchGesture::Dragging {
+                        id,
+                        start,
+                        anchor: control_points.points[index].position,
+                        moved: Vec2::ZERO,
+                    }
+                }
+                _ => TouchGesture::Finished { id },
+            };
+        } else if now - started >= LONG_PRESS {
+            if let Some(index) = point {
+                control_points.clear_selection();
+                remove_point(&mut control_points, &mut history, &mut session, index);
+            }
+            *gesture = TouchGesture::Finished { id };
+        }
+    }
+    if let TouchGesture::Dragging {
+        start,
+        anchor,
+        moved,
+        ..
+    } = &mut *gesture
+    {
+        let Some(world) = cursor.to_world(touch.position()) else {
+            return;
+        };
+        let mut translation = world - *start;
+        if grid.is_snapping(false) {
+            translation = grid.snap(*anchor + translation) - *anchor;
+        }
+        let delta = translation - *moved;
+        *moved = translation;
+        for index in 0..control_points.points.len() {
+            if control_points.points[index].is_selected {
+                control_points.move_point_by(index, delta);
+            }
+        }
+    }
+}
+
+// 鼠标悬停在点上时，滚轮调整该点的 NURBS 权重，按住 Ctrl 时调整 Cardinal 张力
+pub(crate) fn adjust_weight_with_scroll(
+    mut scroll_events: EventReader<MouseWheel>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    captured: Res<PointerCaptured>,
+    hovered: Res<HoveredPoint>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    let steps: f32 = scroll_events.read().map(wheel_steps).sum();
+    if steps == 0.0 || captured.0 || cursor.position().is_none() {
+        return;
+    }
+    let adjust_tension = bindings.modifiers_held(Action::AdjustTension, &keyboard);
+    if !adjust_tension && !bindings.modifiers_held(Action::AdjustWeight, &keyboard) {
+        return;
+    }
+    let Some(index) = hovered.0.filter(|&i| i < control_points.points.len()) else {
+        return;
+    };
+    history.0.record(control_points.snapshot());
+    let point = &mut control_points.points[index];
+    if adjust_tension {
+        point.tension = (point.tension + 0.05 * steps).clamp(0.0, 1.0);
+    } else {
+        point.weight = (point.weight * 1.1_f32.powf(steps)).clamp(0.1, 10.0);
+    }
+    session.record_snapshot(&control_points);
+}
+
+// 悬停高亮的点，与 begin_drag 使用同一个命中测试，高亮的就是这时点击会抓起的点；
+// 滚轮调整权重和张力也作用在它上面。拖动或框选期间不高亮
+pub(crate) fn update_hovered_point(
+    cursor: WorldCursor,
+    drag: Res<DragState>,
+    captured: Res<PointerCaptured>,
+    control_points: Res<ControlPoints>,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+    mut hovered: ResMut<HoveredPoint>,
+) {
+    let index = cursor
+        .position()
+        .filter(|_| !captured.0 && matches!(*drag, DragState::Idle))
+        .and_then(|mouse_point| {
+            control_points.hit_unlocked_point(&point_index, mouse_point, view.0)
+        });
+    if hovered.0 != index {
+        hovered.0 = index;
+    }
+}
+
+// 按住方向键的时间，用于自动重复
+#[derive(Default)]
+pub(crate) struct NudgeRepeat {
+    pub(crate) held: f32,
+    pub(crate) next: f32,
+}
+
+// 方向键移动选中的点，没有选中的点时移动鼠标下的点；按住期间只记录一条历史
+pub(crate) fn nudge_points_with_arrows(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    time: Res<Time>,
+    cursor: WorldCursor,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut repeat: Local<NudgeRepeat>,
+    view: Res<ViewScale>,
+    point_index: Res<PointIndex>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    let held: Vec<NudgeDirection> = NudgeDirection::ALL
+        .into_iter()
+        .filter(|direction| bindings.pressed(Action::Nudge(*direction), &keyboard))
+        .collect();
+    if held.is_empty() {
+        if repeat.held > 0.0 {
+            history.0.finish(&control_points.snapshot());
+        }
+        *repeat = NudgeRepeat::default();
+        return;
+    }
+    // Ctrl+方向键留给其他快捷键
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl || !matches!(*drag, DragState::Idle) {
+        return;
+    }
+
+    let pressed_now = held
+        .iter()
+        .any(|direction| bindings.just_pressed(Action::Nudge(*direction), &keyboard));
+    if repeat.held == 0.0 {
+        history.0.begin(control_points.snapshot());
+    }
+    repeat.held += time.delta_secs();
+    let step = if pressed_now {
+        repeat.next = NUDGE_REPEAT_DELAY;
+        true
+    } else if repeat.held >= repeat.next {
+        repeat.next += NUDGE_REPEAT_INTERVAL;
+        true
+    } else {
+        false
+    };
+    if !step {
+        return;
+    }
+
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let distance = if shift { NUDGE_STEP_SHIFT } else { NUDGE_STEP };
+    let direction: Vec2 = held.iter().map(|direction| direction.vector()).sum();
+    let delta = direction * distance;
+
+    let moved = control_points.arrow_targets(&point_index, cursor.position(), view.0);
+    // 录制时每一步每个点记一条移动
+    for index in moved {
+        let from = control_points.points[index].position;
+        let to = control_points.move_point_by(index, delta);
+        session.record(SessionEdit::Move { index, from, to });
+    }
+}
+
+// 只选中一个点时，PageUp/PageDown（或 , 和 .）把它与前一个/后一个点交换；
+// 在两端时不动，闭合模式下首尾相邻，可以绕到另一端
+pub(crate) fn reorder_selected_point(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    let offset: isize = if bindings.just_pressed(Action::MovePointEarlier, &keyboard) {
+        -1
+    } else if bindings.just_pressed(Action::MovePointLater, &keyboard) {
+        1
+    } else {
+        return;
+    };
+    if !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    let Some(index) = control_points.single_selected() else {
+        return;
+    };
+    let n = control_points.points.len() as isize;
+    let target = index as isize + offset;
+    let target = if control_points.closed {
+        target.rem_euclid(n)
+    } else if (0..n).contains(&target) {
+        target
+    } else {
+        return;
+    };
+    if target as usize == index {
+        return;
+    }
+    history.0.record(control_points.snapshot());
+    control_points.points.swap(index, target as usize);
+    session.record_snapshot(&control_points);
+}
+
+// Alt+H、Alt+V 把选中的点对齐到同一条水平线、竖直线上，Alt+D 把它们等距地分布在首尾两点之间
+pub(crate) fn align_selection_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    let (arrange, minimum, name): (fn(&mut [Vec2]), usize, &str) =
+        if bindings.just_pressed(Action::AlignHorizontally, &keyboard) {
+            (align_horizontally, 2, "align")
+        } else if bindings.just_pressed(Action::AlignVertically, &keyboard) {
+            (align_vertically, 2, "align")
+        } else if bindings.just_pressed(Action::DistributeEvenly, &keyboard) {
+            (distribute, 3, "distribute")
+        } else {
+            return;
+        };
+    if !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    let selected: Vec<usize> = control_points
+        .points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.is_selected)
+        .map(|(i, _)| i)
+        .collect();
+    if selected.len() < minimum {
+        status.info(format!("Select at least {minimum} points to {name}"));
+        return;
+    }
+    let mut positions: Vec<Vec2> = selected
+        .iter()
+        .map(|&i| control_points.points[i].position)
+        .collect();
+    arrange(&mut positions);
+    history.0.record(control_points.snapshot());
+    for (&i, position) in selected.iter().zip(positions) {
+        control_points.move_point_to(i, position);
+    }
+    session.record_snapshot(&control_points);
+}
+
+// F12 把当前画面保存为 PNG，Shift+F12 截图时隐藏图例、HUD 等界面元素；
+// 截图在生成 Screenshot 的这一帧渲染完成后获取，界面只在这一帧隐藏
+pub(crate) fn screenshot_keys(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut clean_capture: ResMut<CleanCapture>,
+    mut ui_roots: Query<(Entity, &mut Visibility), (With<Node>, Without<Parent>)>,
+    mut status: ResMut<StatusMessage>,
+) {
+    for (entity, visibility) in clean_capture.hidden.drain(..) {
+        if let Ok((_, mut current)) = ui_roots.get_mut(entity) {
+            *current = visibility;
+        }
+    }
+    let clean = bindings.just_pressed(Action::CleanScreenshot, &keyboard);
+    if !clean && !bindings.just_pressed(Action::Screenshot, &keyboard) {
+        return;
+    }
+    if clean {
+        for (entity, mut visibility) in ui_roots.iter_mut() {
+            clean_capture.hidden.push((entity, *visibility));
+            *visibility = Visibility::Hidden;
+        }
+        // 提示信息也属于界面，清空后截图里不会出现上一条提示
+        status.text.clear();
+    }
+    let path = output_path(&format!("screenshot_{}.png", unix_timestamp()));
+    commands.spawn(Screenshot::primary_window()).observe(
+        move |trigger: Trigger<ScreenshotCaptured>, mut pending: ResMut<PendingExports>| {
+            let image = trigger.event().0.clone();
+            let path = path.clone();
+            // PNG 编码和写文件放到 IO 线程池，结果由 poll_exports 报告
+            pending.0.push(IoTaskPool::get().spawn(async move {
+                let image = image
+                    .try_into_dynamic()
+                    .map_err(|error| error.to_string())?;
+                image
+                    .to_rgb8()
+                    .save(&path)
+                    .map(|()| path)
+                    .map_err(|error| error.to_string())
+            }));
+        },
+    );
+}
+
+// Ctrl+E 把每条可见曲线的采样点导出为一个 CSV，采样方式与渲染完全相同；
+// 格式化和写文件在 IO 线程池中进行，不阻塞当前帧
+pub(crate) fn export_csv_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    control_points: Res<ControlPoints>,
+    visibility: Res<CurveVisibility>,
+    sampling_mode: Res<SamplingMode>,
+    resolution: Res<CurveResolution>,
+    epsilon: Res<AdaptiveEpsilon>,
+    mut pending: ResMut<PendingExports>,
+    mut status: ResMut<StatusMessage>,
+) {
+    if !bindings.just_pressed(Action::ExportCsv, &keyboard) {
+        return;
+    }
+    let timestamp = unix_timestamp();
+    let pool = IoTaskPool::get();
+    let mut count = 0;
+    let inputs = CurveInputs::new(&control_points);
+    for kind in CurveKind::ALL {
+        if !visibility.is_visible(kind) {
+            continue;
+        }
+        let Some(curve) = curve_from_inputs(kind, &inputs) else {
+            continue;
+        };
+        // 控制多边形直接导出顶点，与 plot_line 一致
+        let samples = if kind == CurveKind::ControlPolygon {
+            curve.sample(resolution.0)
+        } else {
+            sample_curve(curve.as_ref(), *sampling_mode, resolution.0, epsilon.0)
+        };
+        let path = output_path(&format!(
+            "curve_{}_{timestamp}.csv",
+            export::file_slug(kind.name())
+        ));
+        pending.0.push(pool.spawn(async move {
+            std::fs::write(&path, export::samples_to_csv(&samples))
+                .map(|()| path)
+                .map_err(|error| error.to_string())
+        }));
+        count += 1;
+    }
+    if count == 0 {
+        status.error("Nothing to export");
+    } else {
+        status.info(format!("Exporting {count} curves..."));
+    }
+}
+
+pub(crate) const SVG_PADDING: f32 = 20.0;
+
+// Ctrl+Shift+E 把整个场景导出为 SVG：控制多边形为虚线，Bezier 直接写成三次路径，
+// 其他曲线写成与屏幕相同采样的折线，控制点写成圆
+pub(crate) fn export_svg_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    control_points: Res<ControlPoints>,
+    visibility: Res<CurveVisibility>,
+    sampling_mode: Res<SamplingMode>,
+    resolution: Res<CurveResolution>,
+    epsilon: Res<AdaptiveEpsilon>,
+    dash_style: Res<DashStyle>,
+    style: Res<CurveStyle>,
+    mut pending: ResMut<PendingExports>,
+    mut status: ResMut<StatusMessage>,
+) {
+    if !bindings.just_pressed(Action::ExportSvg, &keyboard) {
+        return;
+    }
+    let mut elements = Vec::new();
+    let inputs = CurveInputs::new(&control_points);
+    for kind in CurveKind::ALL {
+        if !visibility.is_visible(kind) {
+            continue;
+        }
+        let Some(curve) = curve_from_inputs(kind, &inputs) else {
+            continue;
+        };
+        let color = style.curve_color(kind, control_points.closed);
+        let width = style.line_width(kind);
+        elements.push(match kind {
+            CurveKind::ControlPolygon => SvgElement::Polyline {
+                points: curve.sample(resolution.0).positions,
+                color,
+                width,
+                dash: Some((dash_style.dash, dash_style.gap)),
+            },
+            CurveKind::Bezier => SvgElement::CubicPath {
+                segments: bezier_chain(&control_points.positions()),
+                color,
+                width,
+            },
+            _ => SvgElement::Polyline {
+                points: sample_curve(curve.as_ref(), *sampling_mode, resolution.0, epsilon.0)
+                    .positions,
+                color,
+                width,
+                dash: None,
+            },
+        });
+    }
+    for point in &control_points.points {
+        elements.push(SvgElement::Circle {
+            center: point.position,
+            radius: weighted_size(point),
+            color: point_color(point, &style.point),
+            width: style.point.line_width,
+        });
+    }
+    let path = output_path(&format!("scene_{}.svg", unix_timestamp()));
+    pending.0.push(IoTaskPool::get().spawn(async move {
+        std::fs::write(&path, svg::to_svg(&elements, SVG_PADDING))
+            .map(|()| path)
+            .map_err(|error| error.to_string())
+    }));
+    status.info("Exporting SVG...");
+}
+
+// Ctrl+S 保存控制点，Ctrl+O 读取并替换当前的控制点
+pub(crate) fn save_load_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut morph: ResMut<Morph>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    if bindings.just_pressed(Action::Save, &keyboard) {
+        save_points(&control_points, &morph, &mut status);
+    }
+    if bindings.just_pressed(Action::Open, &keyboard) {
+        load_points(
+            &mut control_points,
+            &mut morph,
+            &mut history,
+            &mut session,
+            &mut status,
+        );
+    }
+}
+
+// Ctrl+F6 开始或停止录制，停止时把日志写进 session.ron；Ctrl+F7 读取它，恢复录制开始时的所有点集，
+// 再按原来的时间间隔通过编辑操作依次执行每一条编辑。回放中按下任何键或鼠标键都会中止回放，
+// 那一次按键照常生效。在其他键盘和鼠标系统之前运行
+pub(crate) fn session_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    bindings: Res<KeyBindings>,
+    time: Res<Time>,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut session: ResMut<SessionRecorder>,
+    mut status: ResMut<StatusMessage>,
+) {
+    session.now = time.elapsed_secs();
+    if let Some(replay) = &mut session.replay {
+        // 单独按下修饰键不算
+        let modifier = |code: &&KeyCode| {
+            matches!(
+                code,
+                KeyCode::ShiftLeft
+                    | KeyCode::ShiftRight
+                    | KeyCode::ControlLeft
+                    | KeyCode::ControlRight
+                    | KeyCode::AltLeft
+                    | KeyCode::AltRight
+            )
+        };
+        if keyboard.get_just_pressed().any(|code| !modifier(&code))
+            || mouse.get_just_pressed().next().is_some()
+        {
+            session.replay = None;
+            status.info("Replay aborted");
+            return;
+        }
+        let edits = replay.advance(time.delta_secs());
+        let finished = replay.finished();
+        for edit in edits {
+            replay_edit(
+                &mut control_points,
+                &mut history,
+                &mut session,
+                &mut status,
+                edit,
+            );
+        }
+        if finished {
+            session.replay = None;
+            status.info("Replay finished");
+        }
+        return;
+    }
+    if !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    if bindings.just_pressed(Action::RecordSession, &keyboard) {
+        match session.recording.take() {
+            Some((recorded, _)) => save_session(&recorded, &mut status),
+            None => {
+                let started = session.now;
+                session.recording = Some((
+                    Session {
+                        initial: control_points.to_saved(),
+                        events: Vec::new(),
+                    },
+                    started,
+                ));
+                status.info("Recording edits");
+            }
+        }
+    }
+    if bindings.just_pressed(Action::ReplaySession, &keyboard) {
+        if session.recording.is_some() {
+            status.error("Stop recording before replaying");
+            return;
+        }
+        if let Some(replay) = load_session(&mut status) {
+            history.0.record(control_points.snapshot());
+            control_points.load_saved(replay.initial().clone());
+            session.replay = Some(replay);
+        }
+    }
+}
+
+// 录制时的下标对应当时的点，文件被改过时可能越界，越界的编辑跳过
+fn replay_edit(
+    control_points: &mut ControlPoints,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+    status: &mut StatusMessage,
+    edit: SessionEdit,
+) {
+    let len = control_points.points.len();
+    match edit {
+        SessionEdit::Add { index, position } if index <= len => {
+            insert_point(control_points, history, session, index, position);
+        }
+        SessionEdit::Move { index, to, .. } if index < len => {
+            move_point(control_points, history, session, index, to);
+        }
+        SessionEdit::Remove { index } if index < len => {
+            remove_point(control_points, history, session, index);
+        }
+        SessionEdit::Preset {
+            preset,
+            center,
+            radius,
+        } => load_preset(
+            control_points,
+            history,
+            session,
+            status,
+            preset,
+            center,
+            radius,
+        ),
+        SessionEdit::SwitchList { index } if index < control_points.list_count() => {
+            control_points.activate(index);
+        }
+        SessionEdit::Snapshot { scene } => {
+            history.0.record(control_points.snapshot());
+            control_points.load_saved(*scene);
+        }
+        _ => {}
+    }
+}
+
+// 有选中的点时 Alt+G 平移、Alt+R 旋转、Alt+S 缩放，光标移动时实时更新，左键确认、Esc 取消并恢复原来的位置。
+// 在其他键盘系统之前运行：开始、取消和确认的按键被消耗，Esc 的其他绑定这一帧不会响应
+pub(crate) fn modal_transform(
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    captured: Res<PointerCaptured>,
+    drag: Res<DragState>,
+    mut modal: ResMut<ModalTransform>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    let Some(state) = &mut modal.0 else {
+        if !matches!(*drag, DragState::Idle) || captured.0 {
+            return;
+        }
+        let Some((action, kind)) = [
+            (Action::TransformGrab, TransformKind::Grab),
+            (Action::TransformRotate, TransformKind::Rotate),
+            (Action::TransformScale, TransformKind::Scale),
+        ]
+        .into_iter()
+        .find(|(action, _)| bindings.just_pressed(*action, &keyboard)) else {
+            return;
+        };
+        let original: Vec<(usize, Vec2, Option<Vec2>)> = control_points
+            .points
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_selected)
+            .map(|(i, p)| (i, p.position, p.tangent))
+            .collect();
+        let Some(start) = cursor.position() else {
+            return;
+        };
+        if original.is_empty() {
+            return;
+        }
+        bindings.consume(action, &mut keyboard);
+        let pivot = original.iter().map(|(_, p, _)| *p).sum::<Vec2>() / original.len() as f32;
+        let transform = PointTransform {
+            kind,
+            pivot,
+            start,
+            cursor: start,
+        };
+        history.0.begin(control_points.snapshot());
+        status.info(transform.describe());
+        modal.0 = Some(ModalState {
+            transform,
+            list: control_points.active,
+            original,
+        });
+        return;
+    };
+    if control_points.active != state.list {
+        history.0.finish(&control_points.snapshot());
+        session.record_snapshot(&control_points);
+        modal.0 = None;
+        return;
+    }
+    let cancel = bindings.just_pressed(Action::CancelTransform, &keyboard);
+    if cancel {
+        bindings.consume(Action::CancelTransform, &mut keyboard);
+    } else if let Some(position) = cursor.position() {
+        state.transform.cursor = position;
+    }
+    let transform = state.transform;
+    for &(index, position, tangent) in &state.original {
+        let (position, tangent) = if cancel {
+            (position, tangent)
+        } else {
+            (
+                transform.apply(position),
+                tangent.map(|t| transform.apply_vector(t)),
+            )
+        };
+        if index < control_points.points.len() {
+            control_points.move_point_to(index, position);
+            control_points.points[index].tangent = tangent;
+        }
+    }
+    if cancel {
+        history.0.finish(&control_points.snapshot());
+        status.info("Transform cancelled");
+        modal.0 = None;
+        return;
+    }
+    status.info(transform.describe());
+    if let Some(button) = bindings.clicked_button(Action::ConfirmTransform, &mouse, &keyboard) {
+        // 确认的点击不会同时开始拖动或框选
+        mouse.clear_just_pressed(button);
+        history.0.finish(&control_points.snapshot());
+        session.record_snapshot(&control_points);
+        modal.0 = None;
+    }
+}
+
+// Ctrl+Shift+Y 开关对称编辑，打开时对称轴在 x = 0；关闭时把镜像点变成真正的点，曲线保持不变
+pub(crate) fn symmetry_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !matches!(*drag, DragState::Idle)
+        || !bindings.just_pressed(Action::ToggleSymmetry, &keyboard)
+    {
+        return;
+    }
+    history.0.record(control_points.snapshot());
+    match control_points.symmetry.take() {
+        Some(axis) => {
+            let baked = symmetric_points(&control_points.points, axis);
+            control_points.points = baked;
+            status.info(format!(
+                "Symmetry off: {} points",
+                control_points.points.len()
+            ));
+        }
+        None => {
+            control_points.symmetry = Some(0.0);
+            status.info("Symmetry on: edits are mirrored across x = 0");
+        }
+    }
+    session.record_snapshot(&control_points);
+}
+
+// Tab 切换到下一个点集，Shift+Tab 添加一个空的点集；添加点集记入撤销历史，切换不记录。
+// 录制时切换记为 SwitchList，回放的编辑落在同一个点集上
+pub(crate) fn point_list_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    if bindings.just_pressed(Action::AddPointList, &keyboard) {
+        history.0.record(control_points.snapshot());
+        control_points.add_list();
+        session.record_snapshot(&control_points);
+    } else if bindings.just_pressed(Action::CyclePointList, &keyboard) {
+        let next = (control_points.active + 1) % control_points.list_count();
+        control_points.activate(next);
+        session.record(SessionEdit::SwitchList { index: next });
+    } else {
+        return;
+    }
+    status.info(format!(
+        "Editing {} ({}/{})",
+        list_name(&control_points.name, control_points.active),
+        control_points.active + 1,
+        control_points.list_count()
+    ));
+}
+
+// Shift+8、Shift+9 把当前的点存进槽 A、B；按住 Shift+, / Shift+. 向 A 或 B 插值，
+// 一次按住只记一条历史。两组点数不同时拒绝插值并提示
+pub(crate) fn morph_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    time: Res<Time>,
+    drag: Res<DragState>,
+    mut morph: ResMut<Morph>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+    mut morphing: Local<bool>,
+) {
+    if !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    for (slot, action) in [Action::StoreMorphSlotA, Action::StoreMorphSlotB]
+        .into_iter()
+        .enumerate()
+    {
+        if bindings.just_pressed(action, &keyboard) {
+            morph.slots[slot] = Some(control_points.points.clone());
+            // 存入槽 A 时回到 A 端，存入 B 时到 B 端，与当前的点一致
+            morph.amount = slot as f32;
+            status.info(format!(
+                "Stored {} points in slot {}",
+                control_points.points.len(),
+                slot_name(slot)
+            ));
+        }
+    }
+
+    let direction = match (
+        bindings.pressed(Action::MorphTowardA, &keyboard),
+        bindings.pressed(Action::MorphTowardB, &keyboard),
+    ) {
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        _ => 0.0,
+    };
+    if direction == 0.0 {
+        if std::mem::take(&mut *morphing) {
+            history.0.finish(&control_points.snapshot());
+            session.record_snapshot(&control_points);
+        }
+        return;
+    }
+    if !*morphing {
+        // 只在按下的那一帧提示不能插值，按住时不重复
+        if let Err(error) = morph::morph(&morph.slots, morph.amount) {
+            if bindings.just_pressed(Action::MorphTowardA, &keyboard)
+                || bindings.just_pressed(Action::MorphTowardB, &keyboard)
+            {
+                status.error(format!("Cannot morph: {error}"));
+            }
+            return;
+        }
+        history.0.begin(control_points.snapshot());
+        *morphing = true;
+    }
+    let amount = (morph.amount + direction * Morph::SPEED * time.delta_secs()).clamp(0.0, 1.0);
+    morph.amount = amount;
+    if morph.apply(&mut control_points).is_ok() {
+        status.info(format!("Morph {:.0}% toward B", amount * 100.0));
+    }
+}
+
+// X 把探针放到光标处（已有探针时移过去），Shift+X 移除探针
+pub(crate) fn probe_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    mut probe: ResMut<Probe>,
+) {
+    if bindings.just_pressed(Action::RemoveProbe, &keyboard) {
+        *probe = Probe::default();
+    } else if bindings.just_pressed(Action::PlaceProbe, &keyboard) {
+        if let Some(position) = cursor.position() {
+            *probe = Probe {
+                position: Some(position),
+                grab: None,
+            };
+        }
+    }
+}
+
+// 插入的参数取 B-Spline 上离光标最近的点，不要求光标贴近曲线；闭合的 B-Spline 使用周期节点，
+// 不做节点插入
+pub(crate) fn insert_knot_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    control_points: Res<ControlPoints>,
+    visibility: Res<CurveVisibility>,
+    mut knots: ResMut<KnotInsertion>,
+    mut status: ResMut<StatusMessage>,
+) {
+    if bindings.just_pressed(Action::ClearKnots, &keyboard) {
+        if !knots.0.is_empty() {
+            knots.0.clear();
+            status.info("Inserted knots cleared");
+        }
+        return;
+    }
+    if !bindings.just_pressed(Action::InsertKnot, &keyboard)
+        || control_points.closed
+        || !visibility.is_visible(CurveKind::BSpline)
+    {
+        return;
+    }
+    let Some(mouse_point) = cursor.position() else {
+        return;
+    };
+    let Some(curve) = build_curve(CurveKind::BSpline, &control_points) else {
+        return;
+    };
+    if knots.0.len() >= KnotInsertion::MAX {
+        status.error(format!("At most {} inserted knots", KnotInsertion::MAX));
+        return;
+    }
+    let t = closest_parameter(curve.as_ref(), mouse_point);
+    knots.0.push(t);
+    status.info(format!("Knot inserted at t = {t:.3}"));
+}
+
+// handle_view_keys 的参数已经到上限，之后加的叠加层开关放在这里
+pub(crate) fn overlay_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut bounds: ResMut<BoundsOverlay>,
+    mut curvature_features: ResMut<CurvatureFeatureOverlay>,
+    mut offset: ResMut<OffsetOverlay>,
+    mut deviation_pair: ResMut<DeviationPair>,
+    mut construction: ResMut<CasteljauConstruction>,
+    mut elevation: ResMut<DegreeElevation>,
+    mut render_mode: ResMut<CurveRenderMode>,
+    mut index_labels: ResMut<IndexLabels>,
+    mut comparison: ResMut<ParameterizationComparison>,
+    mut fit: ResMut<LeastSquaresFit>,
+    mut status: ResMut<StatusMessage>,
+) {
+    let pressed = |action| bindings.just_pressed(action, &keyboard);
+    if pressed(Action::ToggleLeastSquaresFit) {
+        fit.enabled = !fit.enabled;
+        if !fit.enabled {
+            status.info("Least-squares fit off");
+        }
+    }
+    // 拟合的结果在 update_least_squares_fit 里重新计算并报告
+    if fit.enabled {
+        if pressed(Action::FewerFitControlPoints) {
+            fit.control_count = (fit.control_count - 1).max(MIN_CONTROL_POINTS);
+        }
+        if pressed(Action::MoreFitControlPoints) {
+            fit.control_count = (fit.control_count + 1).min(LeastSquaresFit::MAX);
+        }
+    }
+    if pressed(Action::ToggleIndexLabels) {
+        index_labels.0 = !index_labels.0;
+    }
+    if pressed(Action::ToggleParameterizationComparison) {
+        comparison.0 = !comparison.0;
+    }
+    if pressed(Action::CycleCurveRender) {
+        *render_mode = render_mode.next();
+        status.info(render_mode.label());
+    }
+    let levels = if pressed(Action::RaiseDegree) {
+        Some((elevation.0 + 1).min(DegreeElevation::MAX))
+    } else if pressed(Action::LowerDegree) {
+        Some(elevation.0.saturating_sub(1))
+    } else {
+        None
+    };
+    if let Some(levels) = levels {
+        elevation.0 = levels;
+        status.info(match levels {
+            0 => "Degree elevation off".to_string(),
+            n => format!("Bezier elevated to degree {}", 3 + n),
+        });
+    }
+    if pressed(Action::ToggleConstruction) {
+        construction.0 = !construction.0;
+    }
+    if pressed(Action::CycleDeviationPair) {
+        deviation_pair.cycle();
+    }
+    if pressed(Action::ToggleOffset) {
+        offset.enabled = !offset.enabled;
+    }
+    let step = if pressed(Action::IncreaseOffset) {
+        Some(OffsetOverlay::STEP)
+    } else if pressed(Action::DecreaseOffset) {
+        Some(1.0 / OffsetOverlay::STEP)
+    } else {
+        None
+    };
+    if let Some(step) = step {
+        offset.distance = (offset.distance * step).clamp(OffsetOverlay::MIN, OffsetOverlay::MAX);
+        status.info(format!("Offset distance: {:.1}", offset.distance));
+    }
+    if pressed(Action::ToggleCurvatureFeatures) {
+        curvature_features.0 = !curvature_features.0;
+    }
+    if pressed(Action::ToggleBoundingBox) {
+        bounds.bounding_box = !bounds.bounding_box;
+    }
+    if pressed(Action::ToggleConvexHull) {
+        bounds.hull = !bounds.hull;
+    }
+}
+
+// 拖放 .ron 或 .csv 文件到窗口上读取控制点；一次拖放多个文件时只读取第一个能识别的
+pub(crate) fn load_dropped_file(
+    mut events: EventReader<FileDragAndDrop>,
+    mut control_points: ResMut<ControlPoints>,
+    mut morph: ResMut<Morph>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    let mut loaded = false;
+    let mut skipped = 0;
+    for event in events.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = event else {
+            continue;
+        };
+        if loaded {
+            continue;
+        }
+        match persistence::load(path_buf) {
+            Err(PersistError::UnknownFormat | PersistError::Io(_)) => skipped += 1,
+            result => {
+                apply_loaded_points(
+                    result,
+                    path_buf,
+                    &mut control_points,
+                    &mut morph,
+                    &mut history,
+                    &mut session,
+                    &mut status,
+                );
+                loaded = true;
+            }
+        }
+    }
+    if !loaded && skipped > 0 {
+        status.error("Dropped file is not a RON or CSV points file");
+    }
+}
+
+// Ctrl+C 把控制点位置复制到剪贴板，Ctrl+V 粘贴替换当前的点，Ctrl+Shift+V 追加
+pub(crate) fn clipboard_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    if bindings.just_pressed(Action::Copy, &keyboard) {
+        let text = clipboard::format_points(&control_points.positions());
+        match clipboard::set_text(text) {
+            Ok(()) => status.info(format!("Copied {} points", control_points.points.len())),
+            Err(error) => status.error(format!("Copy failed: {error}")),
+        }
+    }
+    let append = bindings.just_pressed(Action::PasteAppend, &keyboard);
+    if append || bindings.just_pressed(Action::Paste, &keyboard) {
+        let parsed = clipboard::get_text()
+            .and_then(|text| clipboard::parse_points(&text).map_err(|error| error.to_string()));
+        match parsed {
+            Ok(positions) if positions.is_empty() => status.error("Clipboard has no points"),
+            Ok(positions) => {
+                let count = positions.len();
+                history.0.record(control_points.snapshot());
+                if !append {
+                    control_points.points.clear();
+                }
+                control_points
+                    .points
+                    .extend(positions.into_iter().map(|position| MovablePoint {
+                        position,
+                        ..default()
+                    }));
+                session.record_snapshot(&control_points);
+                status.info(format!("Pasted {count} points"));
+            }
+            Err(error) => status.error(format!("Paste failed: {error}")),
+        }
+    }
+}
+
+// 相机当前看到的世界坐标范围；没有窗口、相机或窗口大小为零时为 None
+fn visible_rect(
+    windows: &Query<&Window>,
+    camera: &Query<&Transform, With<Camera>>,
+    scale: f32,
+) -> Option<Rect> {
+    let size = windows.get_single().ok()?.size() * scale;
+    let center = camera.get_single().ok()?.translation.truncate();
+    (size.min_element() > 0.0).then(|| Rect::from_center_size(center, size))
+}
+
+// F5–F9 用内置的点集替换控制点，放在当前视图的中心，大小随视图缩放；
+// 没有窗口时按命令行预设的大小放在原点
+pub(crate) fn preset_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<DragState>,
+    windows: Query<&Window>,
+    camera: Query<&Transform, With<Camera>>,
+    view: Res<ViewScale>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    let Some(preset) = Preset::ALL
+        .into_iter()
+        .find(|&preset| bindings.just_pressed(Action::LoadPreset(preset), &keyboard))
+    else {
+        return;
+    };
+    let (center, radius) = visible_rect(&windows, &camera, view.0)
+        .map_or((Vec2::ZERO, PRESET_RADIUS), |rect| {
+            (rect.center(), rect.size().min_element() * 0.5 * PRESET_FILL)
+        });
+    load_preset(
+        &mut control_points,
+        &mut history,
+        &mut session,
+        &mut status,
+        preset,
+        center,
+        radius,
+    );
+}
+
+// F10 在视图内撒一组随机点替换控制点，种子写进日志并显示在屏幕上，用 --seed 可以重现。
+// 没有窗口时在原点附近的固定范围内撒点
+pub(crate) fn random_points_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<DragState>,
+    time: Res<Time>,
+    windows: Query<&Window>,
+    camera: Query<&Transform, With<Camera>>,
+    view: Res<ViewScale>,
+    mut random: ResMut<RandomScatter>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !matches!(*drag, DragState::Idle)
+        || !bindings.just_pressed(Action::RandomizePoints, &keyboard)
+    {
+        return;
+    }
+    let seed = random
+        .next_seed
+        .unwrap_or_else(|| SeededRng::new(time.elapsed().as_nanos() as u64).next_u64());
+    let mut rng = SeededRng::new(seed);
+    let area = visible_rect(&windows, &camera, view.0).unwrap_or(Rect::from_center_half_size(
+        Vec2::ZERO,
+        Vec2::splat(PRESET_RADIUS),
+    ));
+    let area = area.inflate(-area.size().min_element() * RandomScatter::MARGIN);
+    // 点数越多间距越小，大约是每个点分到的面积的边长的一半
+    let min_distance = (area.width() * area.height() / random.count as f32).sqrt() * 0.5;
+    let positions = scatter(&mut rng, random.count, area, min_distance);
+    random.next_seed = Some(rng.next_u64());
+    history.0.record(control_points.snapshot());
+    control_points.points = positions.into_iter().map(MovablePoint::new).collect();
+    session.record_snapshot(&control_points);
+    info!("random points seed {seed}");
+    status.info(format!("Random points, seed {seed}"));
+}
+
+// 拖动过程中不响应撤销，避免恢复的状态被正在进行的拖动覆盖
+pub(crate) fn handle_undo_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<DragState>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    if !matches!(*drag, DragState::Idle) {
+        return;
+    }
+    if bindings.just_pressed(Action::Undo, &keyboard) {
+        undo_edit(&mut control_points, &mut history, &mut session);
+    } else if bindings.just_pressed(Action::Redo, &keyboard) {
+        redo_edit(&mut control_points, &mut history, &mut session);
+    }
+}
+
+pub(crate) fn handle_keypress(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    time: Res<Time>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut clear_prompt: ResMut<ClearAllPrompt>,
+    mut split: ResMut<BezierSplit>,
+    mut knots: ResMut<KnotInsertion>,
+    mut visibility: ResMut<CurveVisibility>,
+    mut active: ResMut<ActiveCurve>,
+    mut status: ResMut<StatusMessage>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    // 除了删除最后一个点，这里的编辑录制时在最后记一次整个场景
+    let mut edited = false;
+    // 跳过末尾被锁定的点，删除最后一个未锁定的点
+    if bindings.just_pressed(Action::PopPoint, &keyboard) {
+        if let Some(index) = control_points.points.iter().rposition(|p| !p.locked) {
+            remove_point(&mut control_points, &mut history, &mut session, index);
+        }
+    }
+    // 与 C 一样保留锁定的点；细分和插入的节点跟着原来的点一起清除
+    if bindings.just_pressed(Action::ClearAllPoints, &keyboard) {
+        let removable = control_points.points.iter().filter(|p| !p.locked).count();
+        if removable == 0 {
+            status.info("No unlocked points to clear");
+        } else if clear_prompt.remaining > 0.0 {
+            clear_prompt.remaining = 0.0;
+            history.0.record(control_points.snapshot());
+            control_points.points.retain(|p| p.locked);
+            split.0 = None;
+            knots.0.clear();
+            edited = true;
+            status.info(match control_points.points.len() {
+                0 => format!("Cleared {removable} points"),
+                kept => format!("Cleared {removable} points, kept {kept} locked"),
+            });
+        } else {
+            clear_prompt.remaining = ClearAllPrompt::WINDOW;
+            status.prompt(
+                format!("Press again to clear {removable} points"),
+                ClearAllPrompt::WINDOW,
+            );
+        }
+    } else if clear_prompt.remaining > 0.0 {
+        clear_prompt.remaining = (clear_prompt.remaining - time.delta_secs()).max(0.0);
+    }
+    if bindings.just_pressed(Action::ClearSelection, &keyboard) {
+        control_points.clear_selection();
+    }
+    // 选中的点在松开鼠标后仍然保持选中，Backspace 一次删除它们
+    if bindings.just_pressed(Action::DeleteSelected, &keyboard)
+        && control_points.points.iter().any(|p| p.is_selected)
+    {
+        history.0.record(control_points.snapshot());
+        control_points.points.retain(|p| !p.is_selected);
+        edited = true;
+    }
+    // D 在选中点后面插入一个稍有偏移的副本并选中它，便于立即拖走
+    if bindings.just_pressed(Action::DuplicatePoint, &keyboard) {
+        if let Some(index) = control_points.single_selected() {
+            history.0.record(control_points.snapshot());
+            let mut copy = control_points.points[index].clone();
+            copy.position += DUPLICATE_OFFSET;
+            control_points.points[index].is_selected = false;
+            control_points.points.insert(index + 1, copy);
+            edited = true;
+        }
+    }
+    if bindings.just_pressed(Action::ToggleClosed, &keyboard) {
+        control_points.closed = !control_points.closed;
+        edited = true;
+    }
+    if bindings.just_pressed(Action::ToggleClamped, &keyboard) {
+        control_points.clamped = !control_points.clamped;
+        edited = true;
+    }
+    if bindings.just_pressed(Action::CycleParameterization, &keyboard) {
+        control_points.parameterization = control_points.parameterization.next();
+        edited = true;
+        status.info(format!(
+            "Catmull-Rom: {}",
+            control_points.parameterization.name()
+        ));
+    }
+    // 两种转换都替换所有点，细分和插入的节点失效；新的点使用默认的张力和权重
+    let replacement = if bindings.just_pressed(Action::ConvertToBezier, &keyboard) {
+        Some(convert_to_bezier(&control_points, &visibility))
+    } else if bindings.just_pressed(Action::FitCatmullRom, &keyboard) {
+        Some(fit_catmull_rom(&control_points))
+    } else {
+        None
+    };
+    match replacement {
+        Some(Ok((points, closed, kind))) => {
+            history.0.record(control_points.snapshot());
+            status.info(format!(
+                "Converted to {} ({} points)",
+                kind.name(),
+                points.len()
+            ));
+            control_points.points = points.into_iter().map(MovablePoint::new).collect();
+            control_points.closed = closed;
+            split.0 = None;
+            knots.0.clear();
+            visibility.0[kind.index()] = true;
+            active.0 = kind;
+            edited = true;
+        }
+        Some(Err(message)) => status.info(message),
+        None => {}
+    }
+    if bindings.just_pressed(Action::CycleEndCondition, &keyboard) {
+        control_points.end_condition = control_points.end_condition.next();
+        status.info(format!(
+            "End condition: {}",
+            control_points.end_condition.name()
+        ));
+        edited = true;
+    }
+    if edited {
+        session.record_snapshot(&control_points);
+    }
+}
+
+// 转换后的点、是否闭合和之后主要显示的曲线
+type Conversion = Result<(Vec<Vec2>, bool, CurveKind), &'static str>;
+
+// 画出来的 Catmull-Rom 逐段精确地写成 Bezier 控制网；闭合的曲线变成首尾重合的开放链
+fn convert_to_bezier(control_points: &ControlPoints, visibility: &CurveVisibility) -> Conversion {
+    if !visibility.0[CurveKind::CatmullRom.index()] {
+        return Err("Show the Catmull-Rom to convert it");
+    }
+    let segments = catmull_rom_bezier_segments(&CurveInputs::new(control_points));
+    if segments.is_empty() {
+        return Err("Not enough points for a Catmull-Rom");
+    }
+    Ok((bezier_net(&segments), false, CurveKind::Bezier))
+}
+
+// 经过 Bezier 链各段端点的 Catmull-Rom；链的首尾重合时拟合成闭合的曲线
+fn fit_catmull_rom(control_points: &ControlPoints) -> Conversion {
+    let (points, closed) = on_curve_points(&control_points.positions());
+    if points.len() < 2 {
+        return Err("Not enough points for a Bezier segment");
+    }
+    Ok((points, closed, CurveKind::CatmullRom))
+}
+
+// 只影响显示方式的快捷键
+pub(crate) fn handle_view_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut visibility: ResMut<CurveVisibility>,
+    mut active: ResMut<ActiveCurve>,
+    mut comb: ResMut<CurvatureComb>,
+    mut tangent_overlay: ResMut<TangentOverlay>,
+    mut closest: ResMut<ClosestPoint>,
+    mut intersections: ResMut<IntersectionOverlay>,
+    mut sampling_mode: ResMut<SamplingMode>,
+    mut epsilon: ResMut<AdaptiveEpsilon>,
+    mut resolution: ResMut<CurveResolution>,
+    mut color_mode: ResMut<ColorMode>,
+    mut grid: ResMut<GridSettings>,
+    mut coordinate_labels: ResMut<CoordinateLabels>,
+    mut diagnostics_overlay: ResMut<DiagnosticsOverlay>,
+    mut style: ResMut<CurveStyle>,
+) {
+    let pressed = |action| bindings.just_pressed(action, &keyboard);
+    if pressed(Action::ToggleMonotone) {
+        visibility.toggle(CurveKind::Monotone);
+    }
+    for kind in CurveKind::ALL {
+        if pressed(Action::ToggleCurve(kind)) {
+            visibility.toggle(kind);
+        }
+    }
+    if pressed(Action::ToggleComb) {
+        comb.enabled = !comb.enabled;
+    }
+    if pressed(Action::CycleActiveCurve) {
+        active.cycle();
+    }
+    if pressed(Action::CycleCurveColor) {
+        style.cycle_color(active.0);
+    }
+    if pressed(Action::CycleSampling) {
+        *sampling_mode = sampling_mode.next();
+    }
+    if pressed(Action::DecreaseEpsilon) {
+        epsilon.0 = (epsilon.0 / 1.5).max(0.05);
+    }
+    if pressed(Action::IncreaseEpsilon) {
+        epsilon.0 = (epsilon.0 * 1.5).min(50.0);
+    }
+    if pressed(Action::DecreaseResolution) {
+        let step = resolution.step();
+        resolution.0 = resolution.0.saturating_sub(step).max(CurveResolution::MIN);
+    }
+    if pressed(Action::IncreaseResolution) {
+        let step = resolution.step();
+        resolution.0 = (resolution.0 + step).min(CurveResolution::MAX);
+    }
+    if pressed(Action::ToggleTangents) {
+        tangent_overlay.0 = !tangent_overlay.0;
+    }
+    if pressed(Action::ToggleClosestPoint) {
+        closest.enabled = !closest.enabled;
+    }
+    if pressed(Action::ToggleIntersections) {
+        intersections.crossings = !intersections.crossings;
+    }
+    // 自身交点跟着两两交点一起显示，打开它时也打开两两交点
+    if pressed(Action::ToggleSelfIntersections) {
+        intersections.self_crossings = !intersections.self_crossings;
+        intersections.crossings |= intersections.self_crossings;
+    }
+    if pressed(Action::ToggleCoordinates) {
+        coordinate_labels.0 = !coordinate_labels.0;
+    }
+    if pressed(Action::ToggleDiagnostics) {
+        diagnostics_overlay.0 = !diagnostics_overlay.0;
+    }
+    if pressed(Action::ToggleGrid) {
+        grid.enabled = !grid.enabled;
+    }
+    if pressed(Action::CycleColorMode) {
+        *color_mode = color_mode.next();
+    }
+    if pressed(Action::CombScaleUp) {
+        comb.scale *= 1.25;
+    }
+    if pressed(Action::CombScaleDown) {
+        comb.scale /= 1.25;
+    }
+}
+
+// H 或 F1 开关帮助，Esc 也可以关闭；帮助打开时其他快捷键和鼠标编辑都暂停
+pub(crate) fn toggle_help(
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut help: ResMut<HelpOverlay>,
+    mut overlay: Query<&mut Visibility, With<HelpPanel>>,
+) {
+    let open = if bindings.just_pressed(Action::ToggleHelp, &keyboard) {
+        !help.0
+    } else if help.0 && bindings.just_pressed(Action::CloseHelp, &keyboard) {
+        // Esc 同时是取消选择，关闭帮助时不再传给编辑系统
+        bindings.consume(Action::CloseHelp, &mut keyboard);
+        false
+    } else {
+        return;
+    };
+    help.0 = open;
+    for mut visibility in overlay.iter_mut() {
+        *visibility = if open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+pub(crate) fn help_closed(help: Res<HelpOverlay>) -> bool {
+    !help.0
+}
@@ -0,0 +1,253 @@
+//! 折线之间和折线自身的交点。
+//!
+//! 把第二条折线的每一段按包围盒放进格子，第一条折线的每一段只与同一格子里的段求交，
+//! 采样点很多时也不会退化成逐对检查。相距不到 `epsilon` 的交点合并成一个，
+//! 几乎平行地重叠的两段曲线不会报出一串重复的交点。
+
+use std::collections::HashMap;
+
+use bevy::math::{IVec2, Rect, Vec2};
+
+/// 两条线段的交点，端点相接也算；平行（包括共线重叠）时返回 None。
+pub fn segment_intersection(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> Option<Vec2> {
+    let r = a1 - a0;
+    let s = b1 - b0;
+    let denominator = r.perp_dot(s);
+    if denominator.abs() <= f32::EPSILON * r.length() * s.length() {
+        return None;
+    }
+    let offset = b0 - a0;
+    let t = offset.perp_dot(s) / denominator;
+    let u = offset.perp_dot(r) / denominator;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| a0 + r * t)
+}
+
+// 按包围盒把线段放进格子，一段可能占多个格子
+struct SegmentGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<usize>>,
+}
+
+impl SegmentGrid {
+    fn new(points: &[Vec2]) -> Self {
+        // 格子边长取平均段长，每段大约占一到四个格子
+        let segments = points.len().saturating_sub(1).max(1);
+        let length: f32 = points.windows(2).map(|w| w[0].distance(w[1])).sum();
+        let cell_size = (length / segments as f32).max(1e-3);
+        let mut grid = Self {
+            cell_size,
+            cells: HashMap::new(),
+        };
+        for (i, w) in points.windows(2).enumerate() {
+            for cell in grid.cells_of(w[0], w[1]) {
+                grid.cells.entry(cell).or_default().push(i);
+            }
+        }
+        grid
+    }
+
+    fn cells_of(&self, a: Vec2, b: Vec2) -> impl Iterator<Item = IVec2> {
+        let bounds = Rect::from_corners(a, b);
+        let min = (bounds.min / self.cell_size).floor().as_ivec2();
+        let max = (bounds.max / self.cell_size).floor().as_ivec2();
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+    }
+
+    // 可能与 a-b 相交的段，按下标排序且不重复
+    fn candidates(&self, a: Vec2, b: Vec2) -> Vec<usize> {
+        let mut found: Vec<usize> = self
+            .cells_of(a, b)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
+
+/// 两条折线的所有交点，按第一条折线上的顺序排列。
+pub fn polyline_intersections(a: &[Vec2], b: &[Vec2], epsilon: f32) -> Vec<Vec2> {
+    if a.len() < 2 || b.len() < 2 {
+        return Vec::new();
+    }
+    let grid = SegmentGrid::new(b);
+    let mut hits = Vec::new();
+    for w in a.windows(2) {
+        for j in grid.candidates(w[0], w[1]) {
+            if let Some(hit) = segment_intersection(w[0], w[1], b[j], b[j + 1]) {
+                hits.push(hit);
+            }
+        }
+    }
+    dedup_points(hits, epsilon)
+}
+
+/// 折线自身的交点。相邻的段共用端点，不算相交；首尾相接的闭合折线，首段和末段也是相邻的。
+pub fn self_intersections(points: &[Vec2], epsilon: f32) -> Vec<Vec2> {
+    let segments = points.len().saturating_sub(1);
+    if segments < 3 {
+        return Vec::new();
+    }
+    let closed = points[0].distance(points[segments]) <= epsilon;
+    let grid = SegmentGrid::new(points);
+    let mut hits = Vec::new();
+    for i in 0..segments {
+        for j in grid.candidates(points[i], points[i + 1]) {
+            // 每一对只检查一次，跳过自身和相邻的段
+            if j <= i + 1 || (closed && i == 0 && j == segments - 1) {
+                continue;
+            }
+            let hit = segment_intersection(points[i], points[i + 1], points[j], points[j + 1]);
+            hits.extend(hit);
+        }
+    }
+    dedup_points(hits, epsilon)
+}
+
+/// 依次保留与已保留的点距离都不小于 `epsilon` 的点。
+pub fn dedup_points(points: Vec<Vec2>, epsilon: f32) -> Vec<Vec2> {
+    let mut kept: Vec<Vec2> = Vec::with_capacity(points.len());
+    for point in points {
+        if kept.iter().all(|k| k.distance(point) >= epsilon) {
+            kept.push(point);
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(actual.distance(expected) < 1e-4, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn crossing_and_touching_segments() {
+        let hit = segment_intersection(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+            Vec2::new(10.0, 0.0),
+        );
+        assert_near(hit.unwrap(), Vec2::new(5.0, 5.0));
+        // 端点落在另一段上
+        let hit = segment_intersection(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, -5.0),
+            Vec2::new(10.0, 5.0),
+        );
+        assert_near(hit.unwrap(), Vec2::new(10.0, 0.0));
+        // 延长线相交不算
+        assert_eq!(
+            segment_intersection(
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(5.0, -1.0),
+                Vec2::new(5.0, 1.0),
+            ),
+            None
+        );
+        // 共线
+        assert_eq!(
+            segment_intersection(
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 0.0),
+                Vec2::new(5.0, 0.0),
+                Vec2::new(15.0, 0.0),
+            ),
+            None
+        );
+    }
+
+    // 正弦曲线和水平线交于每个 π
+    #[test]
+    fn sine_crosses_the_axis_at_every_half_period() {
+        let sine: Vec<Vec2> = (0..=1000)
+            .map(|i| {
+                let x = i as f32 * 0.01;
+                Vec2::new(x, x.sin())
+            })
+            .collect();
+        let axis = [Vec2::new(-1.0, 0.0), Vec2::new(11.0, 0.0)];
+        let hits = polyline_intersections(&sine, &axis, 1e-3);
+        let xs: Vec<f32> = hits.iter().map(|p| p.x).collect();
+        assert_eq!(xs.len(), 4, "{xs:?}");
+        for (x, k) in xs.iter().zip(0..) {
+            assert!((x - k as f32 * std::f32::consts::PI).abs() < 1e-3, "{xs:?}");
+        }
+    }
+
+    #[test]
+    fn grid_matches_pairwise_search() {
+        let a: Vec<Vec2> = (0..=300)
+            .map(|i| {
+                let t = i as f32 * 0.05;
+                Vec2::new(t.cos() * (50.0 + 3.0 * t), t.sin() * 40.0)
+            })
+            .collect();
+        let b: Vec<Vec2> = (0..=200)
+            .map(|i| {
+                let t = i as f32 * 0.1;
+                Vec2::new(t * 6.0 - 60.0, (t * 1.7).sin() * 45.0)
+            })
+            .collect();
+        let mut brute = Vec::new();
+        for wa in a.windows(2) {
+            for wb in b.windows(2) {
+                brute.extend(segment_intersection(wa[0], wa[1], wb[0], wb[1]));
+            }
+        }
+        let brute = dedup_points(brute, 1e-3);
+        let fast = polyline_intersections(&a, &b, 1e-3);
+        assert!(!fast.is_empty());
+        assert_eq!(fast.len(), brute.len());
+        for (f, b) in fast.iter().zip(&brute) {
+            assert_near(*f, *b);
+        }
+    }
+
+    // 两条几乎重合的折线在每个采样点附近都可能相交，合并后只剩相距足够远的点
+    #[test]
+    fn nearly_parallel_overlap_is_deduplicated() {
+        let a: Vec<Vec2> = (0..=500).map(|i| Vec2::new(i as f32 * 0.01, 0.0)).collect();
+        let b: Vec<Vec2> = (0..=500)
+            .map(|i| {
+                let wobble = if i % 2 == 0 { 1e-4 } else { -1e-4 };
+                Vec2::new(i as f32 * 0.01, wobble)
+            })
+            .collect();
+        let raw = polyline_intersections(&a, &b, 0.0);
+        assert!(raw.len() > 400);
+        let merged = polyline_intersections(&a, &b, 1.0);
+        assert_eq!(merged.len(), 5);
+    }
+
+    // 从 (100, 0) 开始，两次经过原点都落在折线中间
+    #[test]
+    fn figure_eight_crosses_itself_once() {
+        let eight: Vec<Vec2> = (0..=400)
+            .map(|i| {
+                let t = std::f32::consts::FRAC_PI_2 + i as f32 / 400.0 * std::f32::consts::TAU;
+                Vec2::new(t.sin() * 100.0, (2.0 * t).sin() * 50.0)
+            })
+            .collect();
+        let hits = self_intersections(&eight, 1e-2);
+        assert_eq!(hits.len(), 1, "{hits:?}");
+        assert_near(hits[0], Vec2::ZERO);
+    }
+
+    #[test]
+    fn simple_loop_has_no_self_intersections() {
+        let circle: Vec<Vec2> = (0..=64)
+            .map(|i| Vec2::from_angle(i as f32 / 64.0 * std::f32::consts::TAU) * 10.0)
+            .collect();
+        assert!(self_intersections(&circle, 1e-3).is_empty());
+        let open = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y];
+        assert!(self_intersections(&open, 1e-3).is_empty());
+    }
+}
@@ -0,0 +1,1220 @@
+//! 所有键盘和鼠标操作的唯一定义。输入系统通过 [`KeyBindings`] 判断操作是否触发，
+//! 帮助界面也由同一张表生成，两边不会不一致。
+//!
+//! 启动时可以用 `keybindings.ron` 覆盖默认绑定。文件是操作名到绑定列表的映射，
+//! 绑定的写法与帮助界面显示的相同，没有列出的操作保留默认绑定，空列表取消绑定：
+//!
+//! ```ron
+//! {
+//!     "Undo": ["Ctrl+U"],
+//!     "ToggleCurve(BSpline)": ["F2"],
+//!     "PanCamera": ["Any+Middle mouse", "Any+Space+Left mouse"],
+//! }
+//! ```
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use bevy::prelude::*;
+
+use crate::{presets::Preset, CurveKind};
+
+/// 触发操作时必须按住的修饰键；`Any` 表示不关心修饰键。
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Modifiers {
+    None,
+    Shift,
+    Ctrl,
+    CtrlShift,
+    Alt,
+    Any,
+}
+
+impl Modifiers {
+    pub fn matches(self, keyboard: &ButtonInput<KeyCode>) -> bool {
+        let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+        let alt = keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+        match self {
+            Modifiers::None => !shift && !ctrl && !alt,
+            Modifiers::Shift => shift && !ctrl && !alt,
+            Modifiers::Ctrl => ctrl && !shift && !alt,
+            Modifiers::CtrlShift => ctrl && shift && !alt,
+            Modifiers::Alt => alt && !shift && !ctrl,
+            Modifiers::Any => true,
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Modifiers::None | Modifiers::Any => "",
+            Modifiers::Shift => "Shift+",
+            Modifiers::Ctrl => "Ctrl+",
+            Modifiers::CtrlShift => "Ctrl+Shift+",
+            Modifiers::Alt => "Alt+",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Gesture {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    // 按住一个键的同时按下鼠标键
+    KeyAndMouse(KeyCode, MouseButton),
+    Wheel,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NudgeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl NudgeDirection {
+    pub const ALL: [NudgeDirection; 4] = [
+        NudgeDirection::Left,
+        NudgeDirection::Right,
+        NudgeDirection::Up,
+        NudgeDirection::Down,
+    ];
+
+    pub fn vector(self) -> Vec2 {
+        match self {
+            NudgeDirection::Left => Vec2::NEG_X,
+            NudgeDirection::Right => Vec2::X,
+            NudgeDirection::Up => Vec2::Y,
+            NudgeDirection::Down => Vec2::NEG_Y,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    // 鼠标
+    DragPoint,
+    SnapToGrid,
+    DeletePoint,
+    AdjustHeight,
+    AddPoint,
+    RemoveNearestPoint,
+    ScrubTimeline,
+    AdjustWeight,
+    AdjustTension,
+    PanCamera,
+    Zoom,
+    FitView,
+    SplitBezier,
+    // 编辑
+    PopPoint,
+    ClearAllPoints,
+    ClearSelection,
+    DeleteSelected,
+    DuplicatePoint,
+    ToggleLock,
+    Nudge(NudgeDirection),
+    MovePointEarlier,
+    MovePointLater,
+    ToggleClosed,
+    ToggleClamped,
+    CycleParameterization,
+    CycleEndCondition,
+    ConvertToBezier,
+    FitCatmullRom,
+    ToggleLeastSquaresFit,
+    FewerFitControlPoints,
+    MoreFitControlPoints,
+    Sketch,
+    DecreaseSketchTolerance,
+    IncreaseSketchTolerance,
+    StoreMorphSlotA,
+    StoreMorphSlotB,
+    CyclePointList,
+    AddPointList,
+    ToggleSymmetry,
+    TransformGrab,
+    AlignHorizontally,
+    AlignVertically,
+    DistributeEvenly,
+    TransformRotate,
+    TransformScale,
+    ConfirmTransform,
+    CancelTransform,
+    MorphTowardA,
+    MorphTowardB,
+    ToggleParameterizationComparison,
+    Undo,
+    Redo,
+    // 显示
+    ToggleCurve(CurveKind),
+    ToggleMonotone,
+    ToggleComb,
+    CycleActiveCurve,
+    CycleCurveColor,
+    CombScaleUp,
+    CombScaleDown,
+    ToggleTangents,
+    ToggleClosestPoint,
+    ToggleIntersections,
+    ToggleSelfIntersections,
+    ToggleCurvatureFeatures,
+    ClearSplit,
+    ToggleOffset,
+    CycleDeviationPair,
+    ToggleConstruction,
+    RaiseDegree,
+    LowerDegree,
+    InsertKnot,
+    ClearKnots,
+    DecreaseOffset,
+    IncreaseOffset,
+    ToggleBoundingBox,
+    ToggleConvexHull,
+    PlaceProbe,
+    RemoveProbe,
+    CycleSampling,
+    DecreaseEpsilon,
+    IncreaseEpsilon,
+    DecreaseResolution,
+    IncreaseResolution,
+    CycleColorMode,
+    CycleCurveRender,
+    ToggleGrid,
+    ToggleCoordinates,
+    ToggleIndexLabels,
+    ToggleDiagnostics,
+    ToggleAnimation,
+    ResetAnimation,
+    CyclePlaybackMode,
+    CycleEndBehavior,
+    PlaybackFaster,
+    PlaybackSlower,
+    // 文件
+    Save,
+    Open,
+    RecordSession,
+    ReplaySession,
+    LoadPreset(Preset),
+    RandomizePoints,
+    Copy,
+    Paste,
+    PasteAppend,
+    ExportCsv,
+    ExportSvg,
+    Screenshot,
+    CleanScreenshot,
+    ToggleHelp,
+    CloseHelp,
+}
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::DragPoint => "Select / drag points, drag empty space to box-select",
+            Action::SnapToGrid => "Hold to snap to grid while dragging or adding",
+            Action::DeletePoint => "Delete point under cursor",
+            Action::AdjustHeight => "Drag point height (3D mode)",
+            Action::AddPoint => "Add point (inserted on a nearby edge)",
+            Action::RemoveNearestPoint => "Remove point near cursor",
+            Action::ScrubTimeline => "Drag the timeline to scrub the marker",
+            Action::AdjustWeight => "NURBS weight of hovered point",
+            Action::AdjustTension => "Tension of hovered point",
+            Action::PanCamera => "Pan view (drag)",
+            Action::Zoom => "Zoom at cursor (over empty space)",
+            Action::FitView => "Fit all points in view",
+            Action::SplitBezier => "Split the Bezier at the clicked point",
+            Action::PopPoint => "Remove last unlocked point",
+            Action::ClearAllPoints => "Clear all unlocked points (press twice)",
+            Action::ClearSelection => "Clear selection",
+            Action::DeleteSelected => "Delete selected points",
+            Action::DuplicatePoint => "Duplicate selected point",
+            Action::ToggleLock => "Lock / unlock hovered point",
+            Action::Nudge(_) => "Nudge selected or hovered point (Shift x10)",
+            Action::MovePointEarlier => "Move selected point earlier",
+            Action::MovePointLater => "Move selected point later",
+            Action::ToggleClosed => "Open / closed curves",
+            Action::ToggleClamped => "Clamp the B-Spline to the end points",
+            Action::CycleParameterization => "Catmull-Rom uniform / centripetal / chord length",
+            Action::CycleEndCondition => "End condition auto / natural / clamped / not-a-knot",
+            Action::ConvertToBezier => "Replace the points with the Catmull-Rom's Bezier net",
+            Action::FitCatmullRom => "Fit a Catmull-Rom through the Bezier chain",
+            Action::ToggleLeastSquaresFit => "Least-squares B-Spline fit to the points",
+            Action::FewerFitControlPoints => "Fewer control points in the fit",
+            Action::MoreFitControlPoints => "More control points in the fit",
+            Action::Sketch => "Draw freehand, simplified into new points",
+            Action::DecreaseSketchTolerance => "Keep more points of the sketch",
+            Action::IncreaseSketchTolerance => "Keep fewer points of the sketch",
+            Action::ToggleSymmetry => "Mirror edits across a vertical axis",
+            Action::TransformGrab => "Move the selection with the cursor",
+            Action::AlignHorizontally => "Align the selection on a horizontal line",
+            Action::AlignVertically => "Align the selection on a vertical line",
+            Action::DistributeEvenly => "Space the selection evenly between its ends",
+            Action::TransformRotate => "Rotate the selection about its centroid",
+            Action::TransformScale => "Scale the selection about its centroid",
+            Action::ConfirmTransform => "Apply the transform",
+            Action::CancelTransform => "Cancel the transform",
+            Action::CyclePointList => "Edit the next point list",
+            Action::AddPointList => "Add an empty point list and edit it",
+            Action::StoreMorphSlotA => "Store the points in morph slot A",
+            Action::StoreMorphSlotB => "Store the points in morph slot B",
+            Action::MorphTowardA => "Morph the points toward slot A (hold)",
+            Action::MorphTowardB => "Morph the points toward slot B (hold)",
+            Action::ToggleParameterizationComparison => "Compare with uniform Catmull-Rom",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::ToggleCurve(_) => "Toggle curve (see legend)",
+            Action::ToggleMonotone => "Toggle monotone cubic",
+            Action::ToggleComb => "Curvature comb",
+            Action::CycleActiveCurve => "Next analysed curve",
+            Action::CycleCurveColor => "Next color for analysed curve",
+            Action::CombScaleUp => "Longer comb teeth",
+            Action::CombScaleDown => "Shorter comb teeth",
+            Action::ToggleTangents => "Tangent and normal overlay",
+            Action::ToggleClosestPoint => "Closest point on active curve to the cursor",
+            Action::ToggleIntersections => "Mark where displayed curves cross",
+            Action::ToggleSelfIntersections => "Also mark self-intersections",
+            Action::ToggleCurvatureFeatures => "Inflections and curvature maxima",
+            Action::ClearSplit => "Clear the Bezier split",
+            Action::ToggleOffset => "Offset curves on both sides",
+            Action::CycleDeviationPair => "Next pair of curves to compare",
+            Action::ToggleConstruction => "de Casteljau construction on the Bezier",
+            Action::RaiseDegree => "Elevate the Bezier control polygon one more degree",
+            Action::LowerDegree => "Remove one degree elevation",
+            Action::InsertKnot => "Insert a B-Spline knot nearest the cursor",
+            Action::ClearKnots => "Clear inserted knots",
+            Action::DecreaseOffset => "Smaller offset distance",
+            Action::IncreaseOffset => "Larger offset distance",
+            Action::ToggleBoundingBox => "Bounding box of the analysed curve",
+            Action::ToggleConvexHull => "Convex hull of the control points",
+            Action::PlaceProbe => "Drop the distance probe at the cursor",
+            Action::RemoveProbe => "Remove the distance probe",
+            Action::CycleSampling => "Next sampling mode",
+            Action::DecreaseEpsilon => "Finer adaptive sampling",
+            Action::IncreaseEpsilon => "Coarser adaptive sampling",
+            Action::DecreaseResolution => "Fewer samples per segment",
+            Action::IncreaseResolution => "More samples per segment",
+            Action::CycleColorMode => "Next color mode",
+            Action::CycleCurveRender => "Curves as gizmo lines / mesh with miter / bevel joins",
+            Action::ToggleGrid => "Grid snapping",
+            Action::ToggleCoordinates => "Coordinate labels",
+            Action::ToggleIndexLabels => "Point index labels",
+            Action::ToggleDiagnostics => "FPS and timing",
+            Action::ToggleAnimation => "Play / pause marker on active curve",
+            Action::ResetAnimation => "Move marker back to the start",
+            Action::CyclePlaybackMode => "Marker: uniform t / constant speed / both",
+            Action::CycleEndBehavior => "Marker at the end: stop / loop / ping-pong",
+            Action::PlaybackFaster => "Faster playback (no point selected or hovered)",
+            Action::PlaybackSlower => "Slower playback (no point selected or hovered)",
+            Action::Save => "Save points",
+            Action::Open => "Open points",
+            Action::RecordSession => "Start / stop recording edits",
+            Action::ReplaySession => "Replay recorded edits (any key aborts)",
+            Action::LoadPreset(_) => "Preset: circle, sine, zigzag, S-curve, star",
+            Action::RandomizePoints => "Random points (seed shown, --seed to repeat)",
+            Action::Copy => "Copy points",
+            Action::Paste => "Paste points (replace)",
+            Action::PasteAppend => "Paste points (append)",
+            Action::ExportCsv => "Export samples to CSV",
+            Action::ExportSvg => "Export scene to SVG",
+            Action::Screenshot => "Screenshot",
+            Action::CleanScreenshot => "Screenshot without UI",
+            Action::ToggleHelp => "Show / hide this help",
+            Action::CloseHelp => "Close help",
+        }
+    }
+}
+
+pub struct Binding {
+    pub action: Action,
+    pub gesture: Gesture,
+    pub modifiers: Modifiers,
+}
+
+impl Binding {
+    /// 不带修饰键的按键绑定。
+    pub fn key(action: Action, key: KeyCode) -> Self {
+        Self::new(action, Gesture::Key(key))
+    }
+
+    pub fn mouse(action: Action, button: MouseButton) -> Self {
+        Self::new(action, Gesture::Mouse(button))
+    }
+
+    pub fn wheel(action: Action) -> Self {
+        Self::new(action, Gesture::Wheel)
+    }
+
+    fn new(action: Action, gesture: Gesture) -> Self {
+        Self {
+            action,
+            gesture,
+            modifiers: Modifiers::None,
+        }
+    }
+
+    pub fn with(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+}
+
+const CURVE_TOGGLE_KEYS: [KeyCode; 10] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Digit0,
+];
+
+const PRESET_KEYS: [KeyCode; 5] = [
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+];
+
+const NUDGE_KEYS: [KeyCode; 4] = [
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+];
+
+// 常用的按键已经被别的操作占用而改用其他绑定的操作：(操作, 常用的按键, 占用它的操作)，
+// 帮助界面末尾逐条说明
+const REMAPPED: [(Action, &str, Action); 16] = [
+    (Action::ToggleGrid, "G", Action::CycleColorMode),
+    (Action::AdjustTension, "Wheel", Action::AdjustWeight),
+    (Action::TransformGrab, "G", Action::CycleColorMode),
+    (Action::TransformRotate, "R", Action::ResetAnimation),
+    (Action::TransformScale, "S", Action::SplitBezier),
+    (Action::ToggleBoundingBox, "B", Action::CycleEndBehavior),
+    (Action::ToggleConvexHull, "B", Action::CycleEndBehavior),
+    (Action::InsertKnot, "I", Action::ToggleIntersections),
+    (
+        Action::ClearKnots,
+        "Shift+I",
+        Action::ToggleSelfIntersections,
+    ),
+    (Action::ConvertToBezier, "V", Action::CycleDeviationPair),
+    (Action::Sketch, "F+Left mouse", Action::FitView),
+    (
+        Action::StoreMorphSlotA,
+        "8",
+        Action::ToggleCurve(CurveKind::Akima),
+    ),
+    (
+        Action::StoreMorphSlotB,
+        "9",
+        Action::ToggleCurve(CurveKind::Monotone),
+    ),
+    (Action::ToggleSymmetry, "Y", Action::CycleParameterization),
+    (
+        Action::RecordSession,
+        "F6",
+        Action::LoadPreset(Preset::Sine),
+    ),
+    (
+        Action::ReplaySession,
+        "F7",
+        Action::LoadPreset(Preset::Zigzag),
+    ),
+];
+
+/// 按帮助界面的显示顺序排列的所有绑定，一个操作可以有多个绑定。
+#[derive(Resource)]
+pub struct KeyBindings(Vec<Binding>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        use Modifiers::{Alt, Any, Ctrl, CtrlShift, Shift};
+        let key = Binding::key;
+        let mut bindings = vec![
+            Binding::mouse(Action::DragPoint, MouseButton::Left).with(Any),
+            key(Action::SnapToGrid, KeyCode::ControlLeft).with(Any),
+            key(Action::SnapToGrid, KeyCode::ControlRight).with(Any),
+            Binding::mouse(Action::DeletePoint, MouseButton::Left).with(Shift),
+            Binding::mouse(Action::AdjustHeight, MouseButton::Left).with(Shift),
+            Binding::mouse(Action::AddPoint, MouseButton::Right).with(Any),
+            Binding::mouse(Action::RemoveNearestPoint, MouseButton::Middle).with(Any),
+            Binding::mouse(Action::ScrubTimeline, MouseButton::Left).with(Any),
+            Binding::wheel(Action::AdjustWeight),
+            Binding::wheel(Action::AdjustTension).with(Ctrl),
+            Binding::wheel(Action::Zoom).with(Any),
+            key(Action::FitView, KeyCode::KeyF),
+            Binding::mouse(Action::PanCamera, MouseButton::Middle).with(Any),
+            Binding::new(
+                Action::PanCamera,
+                Gesture::KeyAndMouse(KeyCode::Space, MouseButton::Left),
+            )
+            .with(Any),
+            Binding::new(
+                Action::SplitBezier,
+                Gesture::KeyAndMouse(KeyCode::KeyS, MouseButton::Left),
+            ),
+            Binding::new(
+                Action::Sketch,
+                Gesture::KeyAndMouse(KeyCode::Backquote, MouseButton::Left),
+            ),
+            key(Action::PopPoint, KeyCode::KeyC),
+            key(Action::ClearAllPoints, KeyCode::KeyC).with(Shift),
+            key(Action::ClearAllPoints, KeyCode::Delete),
+            key(Action::ClearSelection, KeyCode::Escape),
+            key(Action::DeleteSelected, KeyCode::Backspace),
+            key(Action::DuplicatePoint, KeyCode::KeyD),
+            key(Action::ToggleLock, KeyCode::KeyP),
+        ];
+        // Shift 加大步长，在系统里判断
+        for (direction, code) in NudgeDirection::ALL.into_iter().zip(NUDGE_KEYS) {
+            bindings.push(key(Action::Nudge(direction), code).with(Any));
+        }
+        bindings.extend([
+            key(Action::MovePointEarlier, KeyCode::PageUp),
+            key(Action::MovePointEarlier, KeyCode::Comma),
+            key(Action::MovePointLater, KeyCode::PageDown),
+            key(Action::MovePointLater, KeyCode::Period),
+            key(Action::ToggleClosed, KeyCode::KeyL),
+            key(Action::ToggleClamped, KeyCode::KeyL).with(Shift),
+            key(Action::CycleParameterization, KeyCode::KeyY),
+            key(Action::ToggleParameterizationComparison, KeyCode::KeyY).with(Shift),
+            key(Action::CycleEndCondition, KeyCode::KeyT).with(Shift),
+            key(Action::ConvertToBezier, KeyCode::KeyV).with(Shift),
+            key(Action::FitCatmullRom, KeyCode::KeyH).with(Shift),
+            key(Action::ToggleLeastSquaresFit, KeyCode::KeyF).with(Shift),
+            key(Action::FewerFitControlPoints, KeyCode::BracketLeft).with(Shift),
+            key(Action::MoreFitControlPoints, KeyCode::BracketRight).with(Shift),
+            key(Action::DecreaseSketchTolerance, KeyCode::Semicolon).with(Shift),
+            key(Action::IncreaseSketchTolerance, KeyCode::Quote).with(Shift),
+            key(Action::ToggleSymmetry, KeyCode::KeyY).with(CtrlShift),
+            // G、R、S 已被颜色模式、重置动画和 S+左键拆分 Bezier 占用，变换改用 Alt
+            key(Action::TransformGrab, KeyCode::KeyG).with(Alt),
+            key(Action::TransformRotate, KeyCode::KeyR).with(Alt),
+            key(Action::TransformScale, KeyCode::KeyS).with(Alt),
+            key(Action::AlignHorizontally, KeyCode::KeyH).with(Alt),
+            key(Action::AlignVertically, KeyCode::KeyV).with(Alt),
+            key(Action::DistributeEvenly, KeyCode::KeyD).with(Alt),
+            Binding::mouse(Action::ConfirmTransform, MouseButton::Left).with(Any),
+            key(Action::CancelTransform, KeyCode::Escape),
+            key(Action::CyclePointList, KeyCode::Tab),
+            key(Action::AddPointList, KeyCode::Tab).with(Shift),
+            key(Action::StoreMorphSlotA, KeyCode::Digit8).with(Shift),
+            key(Action::StoreMorphSlotB, KeyCode::Digit9).with(Shift),
+            key(Action::MorphTowardA, KeyCode::Comma).with(Shift),
+            key(Action::MorphTowardB, KeyCode::Period).with(Shift),
+            key(Action::Undo, KeyCode::KeyZ).with(Ctrl),
+            key(Action::Redo, KeyCode::KeyZ).with(CtrlShift),
+            key(Action::Redo, KeyCode::KeyY).with(Ctrl),
+        ]);
+        for (kind, code) in CurveKind::ALL.into_iter().zip(CURVE_TOGGLE_KEYS) {
+            bindings.push(key(Action::ToggleCurve(kind), code));
+        }
+        bindings.extend([
+            key(Action::ToggleMonotone, KeyCode::KeyM),
+            key(Action::ToggleComb, KeyCode::KeyK),
+            key(Action::CycleActiveCurve, KeyCode::KeyK).with(Shift),
+            key(Action::CycleCurveColor, KeyCode::KeyU),
+            // + 在多数键盘上需要 Shift，这几个键不限制修饰键
+            key(Action::CombScaleUp, KeyCode::Equal).with(Any),
+            key(Action::CombScaleUp, KeyCode::NumpadAdd).with(Any),
+            key(Action::CombScaleDown, KeyCode::Minus).with(Any),
+            key(Action::CombScaleDown, KeyCode::NumpadSubtract).with(Any),
+            key(Action::ToggleTangents, KeyCode::KeyT),
+            key(Action::ToggleClosestPoint, KeyCode::KeyQ),
+            key(Action::ToggleIntersections, KeyCode::KeyI),
+            key(Action::ToggleSelfIntersections, KeyCode::KeyI).with(Shift),
+            key(Action::ToggleCurvatureFeatures, KeyCode::KeyE),
+            key(Action::ClearSplit, KeyCode::Escape),
+            key(Action::ToggleOffset, KeyCode::KeyO),
+            key(Action::CycleDeviationPair, KeyCode::KeyV),
+            key(Action::ToggleConstruction, KeyCode::KeyJ),
+            key(Action::RaiseDegree, KeyCode::KeyW),
+            key(Action::LowerDegree, KeyCode::KeyW).with(Shift),
+            key(Action::InsertKnot, KeyCode::KeyI).with(Ctrl),
+            key(Action::ClearKnots, KeyCode::KeyI).with(CtrlShift),
+            key(Action::DecreaseOffset, KeyCode::BracketLeft).with(Ctrl),
+            key(Action::IncreaseOffset, KeyCode::BracketRight).with(Ctrl),
+            key(Action::ToggleBoundingBox, KeyCode::KeyB).with(Shift),
+            key(Action::ToggleConvexHull, KeyCode::KeyB).with(Ctrl),
+            key(Action::PlaceProbe, KeyCode::KeyX),
+            key(Action::RemoveProbe, KeyCode::KeyX).with(Shift),
+            key(Action::CycleSampling, KeyCode::KeyA),
+            key(Action::DecreaseEpsilon, KeyCode::Semicolon),
+            key(Action::IncreaseEpsilon, KeyCode::Quote),
+            key(Action::DecreaseResolution, KeyCode::BracketLeft),
+            key(Action::IncreaseResolution, KeyCode::BracketRight),
+            key(Action::CycleColorMode, KeyCode::KeyG),
+            key(Action::CycleCurveRender, KeyCode::F4),
+            key(Action::ToggleGrid, KeyCode::KeyG).with(Shift),
+            key(Action::ToggleCoordinates, KeyCode::KeyN),
+            key(Action::ToggleIndexLabels, KeyCode::KeyN).with(Shift),
+            key(Action::ToggleDiagnostics, KeyCode::F3),
+            // Space 同时用于 Space+左键平移，松开时才切换播放
+            key(Action::ToggleAnimation, KeyCode::Space),
+            key(Action::ResetAnimation, KeyCode::KeyR),
+            key(Action::CyclePlaybackMode, KeyCode::KeyR).with(Shift),
+            key(Action::CycleEndBehavior, KeyCode::KeyB),
+            key(Action::PlaybackFaster, KeyCode::ArrowUp),
+            key(Action::PlaybackSlower, KeyCode::ArrowDown),
+            key(Action::Save, KeyCode::KeyS).with(Ctrl),
+            key(Action::Open, KeyCode::KeyO).with(Ctrl),
+            // 不带修饰键的 F6、F7 是预设
+            key(Action::RecordSession, KeyCode::F6).with(Ctrl),
+            key(Action::ReplaySession, KeyCode::F7).with(Ctrl),
+        ]);
+        for (preset, code) in Preset::ALL.into_iter().zip(PRESET_KEYS) {
+            bindings.push(key(Action::LoadPreset(preset), code));
+        }
+        bindings.extend([
+            key(Action::RandomizePoints, KeyCode::F10),
+            key(Action::Copy, KeyCode::KeyC).with(Ctrl),
+            key(Action::Paste, KeyCode::KeyV).with(Ctrl),
+            key(Action::PasteAppend, KeyCode::KeyV).with(CtrlShift),
+            key(Action::ExportCsv, KeyCode::KeyE).with(Ctrl),
+            key(Action::ExportSvg, KeyCode::KeyE).with(CtrlShift),
+            key(Action::Screenshot, KeyCode::F12),
+            key(Action::CleanScreenshot, KeyCode::F12).with(Shift),
+            key(Action::ToggleHelp, KeyCode::KeyH),
+            key(Action::ToggleHelp, KeyCode::F1),
+            key(Action::CloseHelp, KeyCode::Escape),
+        ]);
+        Self(bindings)
+    }
+}
+
+impl KeyBindings {
+    /// 读取 `path` 中的绑定并覆盖默认值，见模块文档；文件不存在时直接使用默认绑定。
+    /// 同时返回警告：读取失败、无法识别的条目，以及文件中的绑定与其他操作的冲突。
+    pub fn load(path: &Path) -> (Self, Vec<String>) {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::from_ron(&text),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (Self::default(), Vec::new()),
+            Err(error) => (Self::default(), vec![error.to_string()]),
+        }
+    }
+
+    pub fn from_ron(text: &str) -> (Self, Vec<String>) {
+        let overrides: BTreeMap<String, Vec<String>> = match ron::from_str(text) {
+            Ok(overrides) => overrides,
+            Err(error) => return (Self::default(), vec![format!("invalid RON: {error}")]),
+        };
+        let mut bindings = Self::default();
+        let actions = bindings.actions();
+        let mut warnings = Vec::new();
+        let mut overridden = Vec::new();
+        for (name, specs) in &overrides {
+            let Some(&action) = actions.iter().find(|a| format!("{a:?}") == *name) else {
+                warnings.push(format!("unknown action \"{name}\""));
+                continue;
+            };
+            let mut parsed = Vec::with_capacity(specs.len());
+            for spec in specs {
+                match parse_binding(action, spec) {
+                    Some(binding) => parsed.push(binding),
+                    None => warnings.push(format!("{name}: cannot read binding \"{spec}\"")),
+                }
+            }
+            bindings.replace(action, parsed);
+            overridden.push(action);
+        }
+        warnings.extend(bindings.conflicts(&overridden));
+        (bindings, warnings)
+    }
+
+    // 按第一次出现的顺序列出所有操作，每个操作都有默认绑定
+    fn actions(&self) -> Vec<Action> {
+        let mut actions: Vec<Action> = Vec::new();
+        for binding in &self.0 {
+            if !actions.contains(&binding.action) {
+                actions.push(binding.action);
+            }
+        }
+        actions
+    }
+
+    // 新的绑定放在原来第一个绑定的位置，帮助界面的顺序不变
+    fn replace(&mut self, action: Action, bindings: Vec<Binding>) {
+        let Some(index) = self.0.iter().position(|b| b.action == action) else {
+            return;
+        };
+        self.0.retain(|b| b.action != action);
+        self.0.splice(index..index, bindings);
+    }
+
+    // 手势和修饰键完全相同、属于不同操作的绑定。默认绑定里有意共用的按键（例如 Esc）
+    // 只在其中一方被文件修改时才报告
+    fn conflicts(&self, overridden: &[Action]) -> Vec<String> {
+        let mut found = Vec::new();
+        for (i, a) in self.0.iter().enumerate() {
+            for b in &self.0[i + 1..] {
+                let clash = a.action != b.action
+                    && a.gesture == b.gesture
+                    && a.modifiers == b.modifiers
+                    && (overridden.contains(&a.action) || overridden.contains(&b.action));
+                if clash {
+                    found.push(format!(
+                        "{}{} is bound to both {:?} and {:?}",
+                        a.modifiers.prefix(),
+                        gesture_name(a.gesture),
+                        a.action,
+                        b.action
+                    ));
+                }
+            }
+        }
+        found
+    }
+
+    fn bindings(&self, action: Action) -> impl Iterator<Item = &Binding> {
+        self.0
+            .iter()
+            .filter(move |binding| binding.action == action)
+    }
+
+    fn keys(&self, action: Action) -> impl Iterator<Item = (KeyCode, Modifiers)> + '_ {
+        self.bindings(action)
+            .filter_map(|binding| match binding.gesture {
+                Gesture::Key(key) => Some((key, binding.modifiers)),
+                _ => None,
+            })
+    }
+
+    /// 这一帧按下了操作的某个按键，并且修饰键符合。
+    pub fn just_pressed(&self, action: Action, keyboard: &ButtonInput<KeyCode>) -> bool {
+        self.keys(action)
+            .any(|(key, modifiers)| keyboard.just_pressed(key) && modifiers.matches(keyboard))
+    }
+
+    /// 这一帧松开了操作的某个按键，并且修饰键符合。
+    pub fn just_released(&self, action: Action, keyboard: &ButtonInput<KeyCode>) -> bool {
+        self.keys(action)
+            .any(|(key, modifiers)| keyboard.just_released(key) && modifiers.matches(keyboard))
+    }
+
+    /// 操作的某个按键正被按住，并且修饰键符合。
+    pub fn pressed(&self, action: Action, keyboard: &ButtonInput<KeyCode>) -> bool {
+        self.keys(action)
+            .any(|(key, modifiers)| keyboard.pressed(key) && modifiers.matches(keyboard))
+    }
+
+    /// 清除操作按键这一帧的按下状态，后面运行的系统不会再响应同一次按键。
+    pub fn consume(&self, action: Action, keyboard: &mut ButtonInput<KeyCode>) {
+        let keys: Vec<KeyCode> = self.keys(action).map(|(key, _)| key).collect();
+        for key in keys {
+            keyboard.clear_just_pressed(key);
+        }
+    }
+
+    /// 这一帧按下了操作的鼠标按键，并且修饰键符合。
+    pub fn clicked(
+        &self,
+        action: Action,
+        mouse: &ButtonInput<MouseButton>,
+        keyboard: &ButtonInput<KeyCode>,
+    ) -> bool {
+        self.clicked_button(action, mouse, keyboard).is_some()
+    }
+
+    /// 与 [`KeyBindings::clicked`] 相同，同时返回按下的是哪个鼠标键。
+    pub fn clicked_button(
+        &self,
+        action: Action,
+        mouse: &ButtonInput<MouseButton>,
+        keyboard: &ButtonInput<KeyCode>,
+    ) -> Option<MouseButton> {
+        self.bindings(action)
+            .filter(|binding| binding.modifiers.matches(keyboard))
+            .find_map(|binding| match binding.gesture {
+                Gesture::Mouse(button) if mouse.just_pressed(button) => Some(button),
+                Gesture::KeyAndMouse(key, button)
+                    if keyboard.pressed(key) && mouse.just_pressed(button) =>
+                {
+                    Some(button)
+                }
+                _ => None,
+            })
+    }
+
+    /// 操作绑定的第一个鼠标按键，拖动时用来判断按键是否仍然按着。
+    pub fn button(&self, action: Action) -> Option<MouseButton> {
+        self.bindings(action)
+            .find_map(|binding| match binding.gesture {
+                Gesture::Mouse(button) | Gesture::KeyAndMouse(_, button) => Some(button),
+                _ => None,
+            })
+    }
+
+    /// 滚轮等没有按下瞬间的操作：当前的修饰键符合操作的某个绑定。
+    pub fn modifiers_held(&self, action: Action, keyboard: &ButtonInput<KeyCode>) -> bool {
+        self.bindings(action)
+            .any(|binding| binding.modifiers.matches(keyboard))
+    }
+
+    /// 帮助界面的行：按键和说明；相邻的说明相同的绑定合并成一行。
+    pub fn help_rows(&self) -> Vec<(String, &'static str)> {
+        let mut rows: Vec<(String, &'static str)> = Vec::new();
+        for binding in &self.0 {
+            let description = binding.action.description();
+            let label = binding_label(binding);
+            match rows.last_mut() {
+                Some((keys, last)) if *last == description => {
+                    // 左右 Ctrl 之类显示相同的按键只列一次
+                    if !keys.split(" / ").any(|key| key == label) {
+                        keys.push_str(" / ");
+                        keys.push_str(&label);
+                    }
+                }
+                _ => rows.push((label, description)),
+            }
+        }
+        rows
+    }
+
+    /// 帮助界面末尾的说明：哪些操作没有用常用的按键，那个按键被什么占用了。
+    /// 操作没有绑定，或者绑定文件把它改回了常用的按键时不说明。
+    pub fn remap_notes(&self) -> Vec<String> {
+        REMAPPED
+            .iter()
+            .filter_map(|&(action, usual, holder)| {
+                let keys: Vec<String> = self.bindings(action).map(binding_label).collect();
+                if keys.is_empty() || keys.iter().any(|key| key == usual) {
+                    return None;
+                }
+                Some(format!(
+                    "{usual} is \"{}\", so \"{}\" uses {}",
+                    holder.description(),
+                    action.description(),
+                    keys.join(" / ")
+                ))
+            })
+            .collect()
+    }
+}
+
+fn binding_label(binding: &Binding) -> String {
+    format!(
+        "{}{}",
+        binding.modifiers.prefix(),
+        gesture_name(binding.gesture)
+    )
+}
+
+fn gesture_name(gesture: Gesture) -> String {
+    match gesture {
+        Gesture::Key(key) => key_name(key),
+        Gesture::Mouse(MouseButton::Left) => "Left mouse".to_string(),
+        Gesture::Mouse(MouseButton::Right) => "Right mouse".to_string(),
+        Gesture::Mouse(MouseButton::Middle) => "Middle mouse".to_string(),
+        Gesture::Mouse(button) => format!("{button:?} mouse"),
+        Gesture::KeyAndMouse(key, button) => {
+            format!("{}+{}", key_name(key), gesture_name(Gesture::Mouse(button)))
+        }
+        Gesture::Wheel => "Wheel".to_string(),
+    }
+}
+
+// 修饰键前缀，后面是帮助界面里的按键名（或者 KeyCode 的枚举名）、鼠标键、
+// 按键+鼠标键或者 Wheel
+fn parse_binding(action: Action, spec: &str) -> Option<Binding> {
+    let (mut ctrl, mut shift, mut alt, mut any) = (false, false, false, false);
+    let mut rest = spec.trim();
+    loop {
+        if let Some(tail) = rest.strip_prefix("Ctrl+").filter(|tail| !tail.is_empty()) {
+            ctrl = true;
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("Shift+").filter(|tail| !tail.is_empty()) {
+            shift = true;
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("Alt+").filter(|tail| !tail.is_empty()) {
+            alt = true;
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("Any+") {
+            any = true;
+            rest = tail;
+        } else {
+            break;
+        }
+    }
+    let modifiers = match (any, ctrl, shift) {
+        _ if alt && (any || ctrl || shift) => return None,
+        _ if alt => Modifiers::Alt,
+        (true, false, false) => Modifiers::Any,
+        (true, _, _) => return None,
+        (false, true, true) => Modifiers::CtrlShift,
+        (false, true, false) => Modifiers::Ctrl,
+        (false, false, true) => Modifiers::Shift,
+        (false, false, false) => Modifiers::None,
+    };
+    let gesture = if rest == "Wheel" {
+        Gesture::Wheel
+    } else if let Some(button) = parse_mouse(rest) {
+        Gesture::Mouse(button)
+    } else if let Some((key, button)) = rest
+        .rsplit_once('+')
+        .and_then(|(key, button)| Some((parse_key(key)?, parse_mouse(button)?)))
+    {
+        Gesture::KeyAndMouse(key, button)
+    } else {
+        Gesture::Key(parse_key(rest)?)
+    };
+    Some(Binding::new(action, gesture).with(modifiers))
+}
+
+fn parse_mouse(name: &str) -> Option<MouseButton> {
+    [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+        .into_iter()
+        .find(|&button| gesture_name(Gesture::Mouse(button)) == name)
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    NAMED_KEYS
+        .iter()
+        .copied()
+        .find(|&key| key_name(key) == name || format!("{key:?}") == name)
+}
+
+// 绑定文件里可以使用的按键
+const NAMED_KEYS: &[KeyCode] = &[
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Backspace,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Space,
+    KeyCode::Escape,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Equal,
+    KeyCode::Minus,
+    KeyCode::Slash,
+    KeyCode::Backslash,
+    KeyCode::Backquote,
+    KeyCode::NumpadAdd,
+    KeyCode::NumpadSubtract,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+];
+
+fn key_name(key: KeyCode) -> String {
+    let name = match key {
+        KeyCode::ControlLeft | KeyCode::ControlRight => "Ctrl",
+        KeyCode::ArrowLeft => "Left",
+        KeyCode::ArrowRight => "Right",
+        KeyCode::ArrowUp => "Up",
+        KeyCode::ArrowDown => "Down",
+        KeyCode::Escape => "Esc",
+        KeyCode::Comma => ",",
+        KeyCode::Period => ".",
+        KeyCode::Semicolon => ";",
+        KeyCode::Quote => "'",
+        KeyCode::BracketLeft => "[",
+        KeyCode::BracketRight => "]",
+        KeyCode::Equal => "=",
+        KeyCode::Minus => "-",
+        KeyCode::NumpadAdd => "Num+",
+        KeyCode::NumpadSubtract => "Num-",
+        _ => {
+            // KeyA -> A，Digit1 -> 1，其余按键直接使用枚举名
+            let name = format!("{key:?}");
+            return name
+                .strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Digit"))
+                .unwrap_or(&name)
+                .to_string();
+        }
+    };
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyboard(pressed: &[KeyCode]) -> ButtonInput<KeyCode> {
+        let mut keyboard = ButtonInput::default();
+        for key in pressed {
+            keyboard.press(*key);
+        }
+        keyboard
+    }
+
+    #[test]
+    fn modifiers_must_match_exactly() {
+        let bindings = KeyBindings::default();
+        let undo = keyboard(&[KeyCode::ControlLeft, KeyCode::KeyZ]);
+        assert!(bindings.just_pressed(Action::Undo, &undo));
+        assert!(!bindings.just_pressed(Action::Redo, &undo));
+
+        let redo = keyboard(&[KeyCode::ControlRight, KeyCode::ShiftLeft, KeyCode::KeyZ]);
+        assert!(bindings.just_pressed(Action::Redo, &redo));
+        assert!(!bindings.just_pressed(Action::Undo, &redo));
+
+        // Ctrl+C 是复制，不会删除点
+        let copy = keyboard(&[KeyCode::ControlLeft, KeyCode::KeyC]);
+        assert!(bindings.just_pressed(Action::Copy, &copy));
+        assert!(!bindings.just_pressed(Action::PopPoint, &copy));
+
+        // Alt+D 只分布选中的点，不会复制点
+        let distribute = keyboard(&[KeyCode::AltLeft, KeyCode::KeyD]);
+        assert!(bindings.just_pressed(Action::DistributeEvenly, &distribute));
+        assert!(!bindings.just_pressed(Action::DuplicatePoint, &distribute));
+    }
+
+    #[test]
+    fn any_modifier_ignores_shift_and_ctrl() {
+        let bindings = KeyBindings::default();
+        let held = keyboard(&[KeyCode::ShiftLeft, KeyCode::ArrowUp]);
+        assert!(bindings.pressed(Action::Nudge(NudgeDirection::Up), &held));
+        assert!(!bindings.pressed(Action::Nudge(NudgeDirection::Down), &held));
+
+        let mut mouse = ButtonInput::default();
+        mouse.press(MouseButton::Left);
+        let shift = keyboard(&[KeyCode::ShiftLeft]);
+        assert!(bindings.clicked(Action::DragPoint, &mouse, &shift));
+        assert!(bindings.clicked(Action::DeletePoint, &mouse, &shift));
+        assert!(!bindings.clicked(Action::DeletePoint, &mouse, &keyboard(&[])));
+    }
+
+    #[test]
+    fn release_checks_modifiers_still_held() {
+        let bindings = KeyBindings::default();
+        let mut released = keyboard(&[KeyCode::Space]);
+        released.clear();
+        released.release(KeyCode::Space);
+        assert!(bindings.just_released(Action::ToggleAnimation, &released));
+        assert!(!bindings.just_pressed(Action::ToggleAnimation, &released));
+
+        released.press(KeyCode::ShiftLeft);
+        assert!(!bindings.just_released(Action::ToggleAnimation, &released));
+    }
+
+    #[test]
+    fn key_and_mouse_needs_the_key_held() {
+        let bindings = KeyBindings::default();
+        let mut mouse = ButtonInput::default();
+        mouse.press(MouseButton::Left);
+        assert_eq!(
+            bindings.clicked_button(Action::PanCamera, &mouse, &keyboard(&[])),
+            None
+        );
+        assert_eq!(
+            bindings.clicked_button(Action::PanCamera, &mouse, &keyboard(&[KeyCode::Space])),
+            Some(MouseButton::Left)
+        );
+
+        let mut middle = ButtonInput::default();
+        middle.press(MouseButton::Middle);
+        assert_eq!(
+            bindings.clicked_button(Action::PanCamera, &middle, &keyboard(&[])),
+            Some(MouseButton::Middle)
+        );
+    }
+
+    #[test]
+    fn file_overrides_only_the_listed_actions() {
+        let (bindings, warnings) = KeyBindings::from_ron(
+            r#"{
+                "Undo": ["Ctrl+U", "Insert"],
+                "ToggleCurve(BSpline)": ["F2"],
+                "PanCamera": ["Any+Space+Right mouse"],
+                "ToggleGrid": [],
+            }"#,
+        );
+        assert!(warnings.is_empty(), "{warnings:?}");
+        let pressed = |action, keys: &[KeyCode]| bindings.just_pressed(action, &keyboard(keys));
+        assert!(pressed(
+            Action::Undo,
+            &[KeyCode::ControlLeft, KeyCode::KeyU]
+        ));
+        assert!(pressed(Action::Undo, &[KeyCode::Insert]));
+        assert!(!pressed(
+            Action::Undo,
+            &[KeyCode::ControlLeft, KeyCode::KeyZ]
+        ));
+        assert!(pressed(
+            Action::ToggleCurve(CurveKind::BSpline),
+            &[KeyCode::F2]
+        ));
+        assert!(!pressed(
+            Action::ToggleGrid,
+            &[KeyCode::ShiftLeft, KeyCode::KeyG]
+        ));
+        // 没有列出的操作保留默认绑定
+        assert!(pressed(
+            Action::Redo,
+            &[KeyCode::ControlLeft, KeyCode::ShiftLeft, KeyCode::KeyZ]
+        ));
+        let mut mouse = ButtonInput::default();
+        mouse.press(MouseButton::Right);
+        assert_eq!(
+            bindings.clicked_button(Action::PanCamera, &mouse, &keyboard(&[KeyCode::Space])),
+            Some(MouseButton::Right)
+        );
+        // 帮助界面的位置不变
+        let rows = bindings.help_rows();
+        let default_rows = KeyBindings::default().help_rows();
+        let position = |rows: &[(String, &str)]| rows.iter().position(|(_, d)| *d == "Undo");
+        assert_eq!(position(&rows), position(&default_rows));
+    }
+
+    #[test]
+    fn bad_entries_and_conflicts_are_reported() {
+        let (bindings, warnings) = KeyBindings::from_ron(
+            r#"{
+                "Undo": ["Ctrl+S", "Hyper+Z"],
+                "Teleport": ["T"],
+            }"#,
+        );
+        assert_eq!(warnings.len(), 3, "{warnings:?}");
+        assert!(warnings.iter().any(|w| w.contains("Teleport")));
+        assert!(warnings.iter().any(|w| w.contains("Hyper+Z")));
+        assert!(warnings
+            .iter()
+            .any(|w| w == "Ctrl+S is bound to both Undo and Save"));
+        // 能读出来的绑定仍然生效
+        let save = keyboard(&[KeyCode::ControlLeft, KeyCode::KeyS]);
+        assert!(bindings.just_pressed(Action::Undo, &save));
+
+        let (bindings, warnings) = KeyBindings::from_ron("not ron");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(bindings.help_rows(), KeyBindings::default().help_rows());
+        // 默认绑定之间有意共用的按键不算冲突
+        assert!(KeyBindings::from_ron("{}").1.is_empty());
+    }
+
+    // 左右 Ctrl 显示成同一个名字，读回来比较显示出来的名字
+    #[test]
+    fn help_labels_read_back_as_the_same_binding() {
+        for binding in &KeyBindings::default().0 {
+            let label = format!(
+                "{}{}",
+                match binding.modifiers {
+                    Modifiers::Any => "Any+",
+                    modifiers => modifiers.prefix(),
+                },
+                gesture_name(binding.gesture)
+            );
+            let parsed = parse_binding(binding.action, &label).expect(&label);
+            assert_eq!(
+                gesture_name(parsed.gesture),
+                gesture_name(binding.gesture),
+                "{label}"
+            );
+            assert_eq!(parsed.modifiers, binding.modifiers, "{label}");
+        }
+    }
+
+    #[test]
+    fn remap_notes_name_the_binding_that_took_the_usual_key() {
+        let notes = KeyBindings::default().remap_notes();
+        assert_eq!(notes.len(), REMAPPED.len());
+        assert!(notes.contains(
+            &"B is \"Marker at the end: stop / loop / ping-pong\", so \"Bounding box of the \
+              analysed curve\" uses Shift+B"
+                .to_string()
+        ));
+        // 每个常用的按键确实被说明里的操作占用；F+左键的 F 被单独占用
+        let bindings = KeyBindings::default();
+        for (_, usual, holder) in REMAPPED {
+            let held = bindings.bindings(holder).any(|binding| {
+                let label = binding_label(binding);
+                label == usual || usual.starts_with(&format!("{label}+"))
+            });
+            assert!(held, "{usual}");
+        }
+    }
+
+    #[test]
+    fn help_rows_merge_bindings_with_the_same_description() {
+        let rows = KeyBindings::default().help_rows();
+        let row = |description: &str| {
+            rows.iter()
+                .find(|(_, d)| *d == description)
+                .map(|(keys, _)| keys.as_str())
+        };
+        assert_eq!(row("Redo"), Some("Ctrl+Shift+Z / Ctrl+Y"));
+        assert_eq!(row("Show / hide this help"), Some("H / F1"));
+        assert_eq!(
+            row("Pan view (drag)"),
+            Some("Middle mouse / Space+Left mouse")
+        );
+        assert_eq!(
+            row("Hold to snap to grid while dragging or adding"),
+            Some("Ctrl")
+        );
+        assert_eq!(
+            row("Toggle curve (see legend)"),
+            Some("1 / 2 / 3 / 4 / 5 / 6 / 7 / 8 / 9 / 0")
+        );
+        // 每种说明只出现一次
+        for (i, (_, description)) in rows.iter().enumerate() {
+            assert!(rows[i + 1..].iter().all(|(_, d)| d != description));
+        }
+    }
+}
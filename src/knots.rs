@@ -0,0 +1,157 @@
+//! 任意节点向量的三次 B-Spline 和 Boehm 节点插入。
+//!
+//! 均匀节点 j - 3 的参数范围与 `CubicBSpline` 相同：n 个控制点的曲线在 0..=n-3 上，
+//! 每段长度为 1。插入节点后节点向量不再均匀，控制点多一个，曲线不变。
+
+use bevy::math::Vec2;
+
+const DEGREE: usize = 3;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KnotVectorBSpline {
+    pub knots: Vec<f32>,
+    pub points: Vec<Vec2>,
+}
+
+impl KnotVectorBSpline {
+    /// 与 `CubicBSpline` 相同的均匀节点，至少需要 4 个点。
+    pub fn uniform(points: &[Vec2]) -> Option<Self> {
+        if points.len() <= DEGREE {
+            return None;
+        }
+        let knots = (0..points.len() + DEGREE + 1)
+            .map(|j| j as f32 - DEGREE as f32)
+            .collect();
+        Some(Self {
+            knots,
+            points: points.to_vec(),
+        })
+    }
+
+    pub fn domain(&self) -> (f32, f32) {
+        (self.knots[DEGREE], self.knots[self.points.len()])
+    }
+
+    // 包含 t 的非空区间 knots[s]..knots[s + 1]，限制在有效的段内；t 在末端时取最后一个非空区间
+    fn span(&self, t: f32) -> usize {
+        (DEGREE..self.points.len())
+            .rev()
+            .find(|&s| self.knots[s] <= t && self.knots[s] < self.knots[s + 1])
+            .unwrap_or(DEGREE)
+    }
+
+    /// de Boor 算法求值，`t` 超出参数范围时截断到端点。
+    pub fn position(&self, t: f32) -> Vec2 {
+        let (start, end) = self.domain();
+        let t = t.clamp(start, end);
+        let s = self.span(t);
+        let mut d: [Vec2; DEGREE + 1] = std::array::from_fn(|j| self.points[j + s - DEGREE]);
+        for r in 1..=DEGREE {
+            for j in (r..=DEGREE).rev() {
+                let i = j + s - DEGREE;
+                let alpha = (t - self.knots[i]) / (self.knots[i + DEGREE + 1 - r] - self.knots[i]);
+                d[j] = d[j - 1].lerp(d[j], alpha);
+            }
+        }
+        d[DEGREE]
+    }
+
+    /// Boehm 算法在 `t` 处插入一个节点。`t` 在参数范围外或者那里的节点已经重复了
+    /// 三次（曲线在这里只剩 C0 连续，再插入也不会改变控制多边形）时不插入，返回 false。
+    pub fn insert(&mut self, t: f32) -> bool {
+        let (start, end) = self.domain();
+        if !(start..=end).contains(&t) {
+            return false;
+        }
+        let multiplicity = self.knots.iter().filter(|&&k| k == t).count();
+        if multiplicity >= DEGREE {
+            return false;
+        }
+        let s = self.span(t);
+        let n = self.points.len();
+        let mut refined = Vec::with_capacity(n + 1);
+        refined.extend_from_slice(&self.points[..=s - DEGREE]);
+        for i in s - DEGREE + 1..=s {
+            let a = (t - self.knots[i]) / (self.knots[i + DEGREE] - self.knots[i]);
+            refined.push(self.points[i - 1].lerp(self.points[i], a));
+        }
+        refined.extend_from_slice(&self.points[s..]);
+        self.points = refined;
+        self.knots.insert(s + 1, t);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::cubic_splines::{CubicBSpline, CubicGenerator};
+
+    fn points() -> Vec<Vec2> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(60.0, 150.0),
+            Vec2::new(180.0, -40.0),
+            Vec2::new(260.0, 120.0),
+            Vec2::new(330.0, 10.0),
+            Vec2::new(400.0, 90.0),
+        ]
+    }
+
+    fn samples(spline: &KnotVectorBSpline) -> Vec<Vec2> {
+        let (start, end) = spline.domain();
+        (0..=60)
+            .map(|i| spline.position(start + (end - start) * i as f32 / 60.0))
+            .collect()
+    }
+
+    fn assert_same(a: &[Vec2], b: &[Vec2]) {
+        for (p, q) in a.iter().zip(b) {
+            assert!(p.distance(*q) < 1e-3, "{p} != {q}");
+        }
+    }
+
+    #[test]
+    fn uniform_knots_match_cubic_b_spline() {
+        let spline = KnotVectorBSpline::uniform(&points()).unwrap();
+        assert_eq!(spline.domain(), (0.0, 3.0));
+        let curve = CubicBSpline::new(points()).to_curve().unwrap();
+        for i in 0..=30 {
+            let t = i as f32 / 10.0;
+            let (p, q) = (spline.position(t), curve.position(t));
+            assert!(p.distance(q) < 1e-3, "{t}: {p} != {q}");
+        }
+        assert!(KnotVectorBSpline::uniform(&points()[..3]).is_none());
+    }
+
+    #[test]
+    fn insertion_keeps_the_curve() {
+        let mut spline = KnotVectorBSpline::uniform(&points()).unwrap();
+        let before = samples(&spline);
+        for t in [0.4, 1.0, 2.75, 0.4, 0.0, 3.0] {
+            let count = spline.points.len();
+            assert!(spline.insert(t), "{t}");
+            assert_eq!(spline.points.len(), count + 1);
+            assert_eq!(spline.knots.len(), spline.points.len() + DEGREE + 1);
+            assert_same(&samples(&spline), &before);
+        }
+        assert!(spline.knots.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(spline.domain(), (0.0, 3.0));
+    }
+
+    // 同一处最多插入三次，第三次之后曲线经过那里的控制点
+    #[test]
+    fn triple_knot_interpolates_a_control_point() {
+        let mut spline = KnotVectorBSpline::uniform(&points()).unwrap();
+        let before = samples(&spline);
+        let on_curve = spline.position(1.5);
+        for _ in 0..DEGREE {
+            assert!(spline.insert(1.5));
+        }
+        assert!(!spline.insert(1.5));
+        assert_same(&samples(&spline), &before);
+        assert!(spline.points.iter().any(|p| p.distance(on_curve) < 1e-3));
+        assert!(!spline.insert(-0.5));
+        assert!(!spline.insert(3.5));
+    }
+}
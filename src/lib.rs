@@ -0,0 +1,3147 @@
+//! 这个示例演示了如何使用 `bevy_gizmos` 包在 2D 中绘制线条和点。
+
+// bevy 系统的参数经常超过 clippy 的默认上限
+#![allow(clippy::too_many_arguments)]
+
+mod akima;
+mod align;
+mod catmull_rom;
+pub mod cli;
+mod clipboard;
+mod coincident;
+mod convert;
+mod curve;
+mod curves;
+mod elevation;
+mod end_condition;
+mod export;
+mod extrema;
+mod fitting;
+mod heading;
+mod heatmap;
+mod history;
+mod hull;
+mod input;
+mod intersect;
+mod keybindings;
+mod knots;
+mod modal;
+mod monotone;
+mod morph;
+mod natural_spline;
+mod nurbs;
+mod offset;
+#[cfg(feature = "egui")]
+mod panel;
+mod persistence;
+mod playback;
+mod points;
+mod presets;
+mod quadratic;
+mod random;
+mod render;
+mod session;
+mod settings;
+mod simplify;
+mod space;
+mod spatial;
+mod stroke;
+mod subdivision;
+mod svg;
+mod symmetry;
+mod transition;
+mod view;
+
+pub use catmull_rom::Parameterization;
+pub use end_condition::EndCondition;
+pub use points::{ControlPoints, MovablePoint, PointList};
+pub use space::SpaceModePlugin;
+
+use akima::AkimaSpline;
+use align::{align_horizontally, align_vertically, distribute};
+use bevy::{
+    color::palettes::css::*,
+    diagnostic::{
+        Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin,
+        RegisterDiagnostic,
+    },
+    ecs::system::SystemParam,
+    input::{
+        gestures::PinchGesture,
+        mouse::{MouseScrollUnit, MouseWheel},
+    },
+    math::{cubic_splines::CyclicCubicGenerator, Vec2, VectorSpace},
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+        view::screenshot::{Screenshot, ScreenshotCaptured},
+    },
+    sprite::Anchor,
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+    utils::Instant,
+};
+use catmull_rom::{catmull_rom_curve, catmull_rom_segments};
+use coincident::{distinct_indices, COINCIDENT_EPSILON};
+use convert::{bezier_net, on_curve_points};
+use curve::{
+    closest_parameter, closest_point_on_curve, deviation, distance_to_segment, polyline_length,
+    sample_adaptive, sample_arc_length, signed_curvature, split_parameter, ArcLengthTable,
+    Deviation, PlanarCurve, Polyline, Samples,
+};
+use curves::*;
+use elevation::elevations;
+use end_condition::apply_end_condition;
+use extrema::{curvature_features, CurvatureFeature, FeatureKind};
+use fitting::{fit_b_spline, BSplineFit, MIN_CONTROL_POINTS};
+use heading::Heading;
+use history::History;
+use hull::convex_hull;
+use input::*;
+use intersect::{polyline_intersections, self_intersections};
+use keybindings::{Action, KeyBindings, NudgeDirection};
+use knots::KnotVectorBSpline;
+use modal::{PointTransform, TransformKind};
+use monotone::MonotoneCubic;
+use morph::{morph, slot_name};
+use natural_spline::NaturalCubicSpline;
+use nurbs::RationalBSpline;
+use offset::{offset_samples, OffsetSample};
+use persistence::{PersistError, SavedList, SavedPoint, SavedScene};
+use playback::{EndBehavior, Playhead};
+use points::*;
+use presets::Preset;
+use quadratic::QuadraticBezierChain;
+use random::{scatter, SeededRng};
+use render::*;
+use serde::{Deserialize, Serialize};
+use session::{Replay, Session, SessionEdit, SessionEvent};
+use settings::{SavedCamera, Settings, SettingsError, SettingsResources};
+use simplify::ramer_douglas_peucker;
+use spatial::{nearest_by_scan, SpatialHash};
+use std::{
+    any::TypeId,
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use stroke::{stroke_polyline, Join};
+use subdivision::{casteljau_levels, split_chain};
+use svg::SvgElement;
+use symmetry::{mirror, on_axis, symmetric_points};
+use transition::PointTransition;
+
+/// 光标在视口中的位置，由 `CursorMoved` 事件更新；没有窗口时可以直接写入。
+#[derive(Default, Resource)]
+pub struct MousePosition(pub Option<Vec2>);
+
+/// 视口坐标到世界坐标的换算。默认经过唯一的 2D 相机；没有窗口和相机的 App
+/// （例如测试）可以换成普通函数，比如 [`ScreenToWorld::IDENTITY`]。
+#[derive(Default, Resource, Clone, Copy)]
+pub enum ScreenToWorld {
+    #[default]
+    Camera,
+    Function(fn(Vec2) -> Vec2),
+}
+
+impl ScreenToWorld {
+    pub const IDENTITY: Self = Self::Function(std::convert::identity);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+enum PointerDevice {
+    #[default]
+    Mouse,
+    Gamepad,
+}
+
+// 虚拟光标：最后移动的设备拥有它。鼠标移动或按下时交给鼠标，手柄左摇杆推出死区时交给手柄，
+// crosshair 是手柄准星的世界坐标，接管时从鼠标所在的位置开始
+#[derive(Default, Resource)]
+struct PointerState {
+    device: PointerDevice,
+    crosshair: Vec2,
+}
+
+// 视口坐标到世界坐标的换算，鼠标、触摸和手柄共用
+#[derive(SystemParam)]
+struct ScreenMapping<'w, 's> {
+    mapping: Res<'w, ScreenToWorld>,
+    camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+}
+
+impl ScreenMapping<'_, '_> {
+    fn to_world(&self, position: Vec2) -> Option<Vec2> {
+        match *self.mapping {
+            ScreenToWorld::Camera => {
+                let (camera, camera_transform) = self.camera.get_single().ok()?;
+                camera.viewport_to_world_2d(camera_transform, position).ok()
+            }
+            ScreenToWorld::Function(to_world) => Some(to_world(position)),
+        }
+    }
+}
+
+// 输入系统通过它取得虚拟光标的世界坐标，不直接依赖相机，也不关心光标属于哪个设备
+#[derive(SystemParam)]
+struct WorldCursor<'w, 's> {
+    mouse_position: Res<'w, MousePosition>,
+    pointer: Res<'w, PointerState>,
+    screen: ScreenMapping<'w, 's>,
+}
+
+impl WorldCursor<'_, '_> {
+    fn position(&self) -> Option<Vec2> {
+        match self.pointer.device {
+            PointerDevice::Gamepad => Some(self.pointer.crosshair),
+            PointerDevice::Mouse => self
+                .mouse_position
+                .0
+                .and_then(|position| self.to_world(position)),
+        }
+    }
+
+    fn to_world(&self, position: Vec2) -> Option<Vec2> {
+        self.screen.to_world(position)
+    }
+}
+
+// 左摇杆的死区，以及推到底时准星每秒移动的屏幕像素；速度与偏移量的平方成正比，
+// 轻推时可以精确对准，推到底时快速移动
+const STICK_DEAD_ZONE: f32 = 0.15;
+const CROSSHAIR_SPEED: f32 = 900.0;
+const CROSSHAIR_SIZE: f32 = 10.0;
+
+#[derive(Component)]
+struct MonotoneHint;
+
+/// 所有可以绘制的曲线类型，顺序与数字键 1..9、0 对应。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum CurveKind {
+    ControlPolygon,
+    BSpline,
+    CatmullRom,
+    Bezier,
+    Hermite,
+    Natural,
+    Nurbs,
+    Akima,
+    Monotone,
+    Quadratic,
+}
+
+impl CurveKind {
+    pub const ALL: [CurveKind; 10] = [
+        CurveKind::ControlPolygon,
+        CurveKind::BSpline,
+        CurveKind::CatmullRom,
+        CurveKind::Bezier,
+        CurveKind::Hermite,
+        CurveKind::Natural,
+        CurveKind::Nurbs,
+        CurveKind::Akima,
+        CurveKind::Monotone,
+        CurveKind::Quadratic,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            CurveKind::ControlPolygon => "Control polygon",
+            CurveKind::BSpline => "B-Spline",
+            CurveKind::CatmullRom => "Catmull-Rom",
+            CurveKind::Bezier => "Bezier",
+            CurveKind::Hermite => "Hermite",
+            CurveKind::Natural => "Natural cubic",
+            CurveKind::Nurbs => "NURBS",
+            CurveKind::Akima => "Akima",
+            CurveKind::Monotone => "Monotone cubic",
+            CurveKind::Quadratic => "Quadratic Bezier",
+        }
+    }
+
+    // 曲线是否经过每个控制点；这些曲线构造前合并重合的相邻点
+    fn interpolates(self) -> bool {
+        matches!(
+            self,
+            CurveKind::CatmullRom
+                | CurveKind::Hermite
+                | CurveKind::Natural
+                | CurveKind::Akima
+                | CurveKind::Monotone
+        )
+    }
+
+    // 当前控制点能否构造出这种曲线
+    fn can_draw(self, points: &[Vec2]) -> bool {
+        let min_points = match self {
+            CurveKind::BSpline | CurveKind::Bezier | CurveKind::Nurbs => 4,
+            CurveKind::Quadratic => 3,
+            _ => 2,
+        };
+        // 重合的点合并后再判断是否单调
+        points.len() >= min_points
+            && (self != CurveKind::Monotone || {
+                let kept = distinct_indices(points, false, COINCIDENT_EPSILON);
+                monotone::is_x_monotone(&kept.iter().map(|&i| points[i]).collect::<Vec<_>>())
+            })
+    }
+
+    // 启动时的颜色，运行时的颜色在 CurveStyle 中
+    fn default_color(self) -> Srgba {
+        match self {
+            CurveKind::ControlPolygon => WHITE,
+            CurveKind::BSpline => PINK,
+            CurveKind::CatmullRom => YELLOW,
+            CurveKind::Bezier => GREEN,
+            CurveKind::Hermite => ORANGE,
+            CurveKind::Natural => AQUA,
+            CurveKind::Nurbs => VIOLET,
+            CurveKind::Akima => TOMATO,
+            CurveKind::Monotone => CHARTREUSE,
+            CurveKind::Quadratic => CORNFLOWER_BLUE,
+        }
+    }
+}
+
+// 每种曲线是否显示
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct CurveVisibility([bool; CurveKind::ALL.len()]);
+
+impl Default for CurveVisibility {
+    fn default() -> Self {
+        let mut visible = [true; CurveKind::ALL.len()];
+        // 单调三次插值只对按 x 排序的点有意义，默认关闭
+        visible[CurveKind::Monotone.index()] = false;
+        Self(visible)
+    }
+}
+
+impl CurveVisibility {
+    fn is_visible(&self, kind: CurveKind) -> bool {
+        self.0[kind.index()]
+    }
+
+    fn toggle(&mut self, kind: CurveKind) {
+        self.0[kind.index()] = !self.0[kind.index()];
+    }
+}
+
+// 曲率梳等分析叠加层作用的曲线，Shift+K 切换
+#[derive(Resource)]
+struct ActiveCurve(CurveKind);
+
+impl Default for ActiveCurve {
+    fn default() -> Self {
+        Self(CurveKind::CatmullRom)
+    }
+}
+
+impl ActiveCurve {
+    // 控制多边形没有曲率可言，切换时跳过
+    fn cycle(&mut self) {
+        let next = (self.0.index() + 1) % CurveKind::ALL.len();
+        self.0 = CurveKind::ALL[next];
+        if self.0 == CurveKind::ControlPolygon {
+            self.cycle();
+        }
+    }
+}
+
+// 曲率梳：梳齿长度 = 曲率 * scale
+#[derive(Resource)]
+struct CurvatureComb {
+    enabled: bool,
+    scale: f32,
+}
+
+impl Default for CurvatureComb {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scale: 2000.0,
+        }
+    }
+}
+
+// 曲率梳每段的采样数
+const COMB_SAMPLES_PER_SEGMENT: usize = 20;
+
+// 是否在当前曲线上显示切线箭头和法线刻度，T 切换
+#[derive(Default, Resource)]
+struct TangentOverlay(bool);
+
+// 是否标出当前曲线上离光标最近的点，Q 切换；hit 是最近点的参数和位置
+#[derive(Default, Resource)]
+struct ClosestPoint {
+    enabled: bool,
+    hit: Option<(f32, Vec2)>,
+}
+
+#[derive(Component)]
+struct ClosestPointText;
+
+// 标出显示中的曲线两两之间的交点，I 切换；Shift+I 同时标出每条曲线自身的交点
+#[derive(Default, Resource)]
+struct IntersectionOverlay {
+    crossings: bool,
+    self_crossings: bool,
+}
+
+// 插值样条构造前合并掉的重合点，由 update_curve_cache 更新，plot_coincident_points 画警告标记
+#[derive(Default, Resource)]
+struct CoincidentPoints(Vec<Vec2>);
+
+// 由 update_intersections 在采样缓存或开关变化时重新计算，关闭时为空
+#[derive(Default, Resource)]
+struct CurveIntersections {
+    crossings: Vec<Vec2>,
+    self_crossings: Vec<Vec2>,
+}
+
+#[derive(Component)]
+struct IntersectionText;
+
+// 距离小于它的交点合并为一个，世界坐标
+const INTERSECTION_EPSILON: f32 = 0.5;
+const INTERSECTION_MARK_SIZE: f32 = 5.0;
+// 重合点警告三角形的外接圆半径，屏幕像素
+const COINCIDENT_MARK_SIZE: f32 = 9.0;
+
+// Shift+Y 在非均匀参数化的 Catmull-Rom 下面用暗一些的颜色画出均匀参数化的版本
+#[derive(Default, Resource)]
+struct ParameterizationComparison(bool);
+
+// Shift+F 把点当作数据，用最小二乘拟合一条 B-Spline；Shift+[ 和 Shift+] 调整它的控制点个数。
+// 点或设置改变时重新拟合，结果保存在这里
+#[derive(Resource)]
+struct LeastSquaresFit {
+    enabled: bool,
+    control_count: usize,
+    result: Option<BSplineFit>,
+}
+
+impl LeastSquaresFit {
+    const MAX: usize = 64;
+}
+
+impl Default for LeastSquaresFit {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            control_count: 8,
+            result: None,
+        }
+    }
+}
+
+const FIT_CURVE_COLOR: Srgba = GOLD;
+const FIT_POLYGON_COLOR: Srgba = VIOLET;
+// 拟合模式下数据点的半径，屏幕像素
+const DATA_POINT_SIZE: f32 = 2.0;
+
+// 按住 ` 用左键手绘：拖动时记录光标经过的原始路径，松开后用 RDP 化简成新的控制点。
+// 原始路径一直保留到下一次手绘，Shift+; 和 Shift+' 调整容差后从它重新化简
+#[derive(Resource)]
+struct Sketch {
+    recording: bool,
+    raw: Vec<Vec2>,
+    // 世界坐标
+    tolerance: f32,
+}
+
+impl Sketch {
+    const STEP: f32 = 1.25;
+    const MIN_TOLERANCE: f32 = 0.25;
+    const MAX_TOLERANCE: f32 = 100.0;
+    // 光标移动不到这个距离时不记录新的点，屏幕像素
+    const MIN_SPACING: f32 = 1.0;
+}
+
+impl Default for Sketch {
+    fn default() -> Self {
+        Self {
+            recording: false,
+            raw: Vec::new(),
+            tolerance: 4.0,
+        }
+    }
+}
+
+const SKETCH_COLOR: Srgba = Srgba::new(1.0, 1.0, 1.0, 0.3);
+
+// 第二个及之后的点集的点的颜色，第一个点集使用 PointStyle 的颜色
+const LIST_POINT_COLORS: [Srgba; 4] = [DEEP_SKY_BLUE, ORANGE, ORCHID, YELLOW];
+// G、R、S 开始的模态变换，变换期间不为 None。original 是选中的点的索引、开始时的位置和手动设置的切线；
+// 开始后切换了点集时放弃这次变换
+#[derive(Default, Resource)]
+struct ModalTransform(Option<ModalState>);
+
+struct ModalState {
+    transform: PointTransform,
+    list: usize,
+    original: Vec<(usize, Vec2, Option<Vec2>)>,
+}
+
+// 对称编辑的轴
+const SYMMETRY_AXIS_COLOR: Srgba = Srgba::new(0.8, 0.8, 0.8, 0.6);
+// 没有在编辑的点集的曲线和点的透明度
+const INACTIVE_LIST_ALPHA: f32 = 0.35;
+
+// 右键加点的预览，由 update_ghost_preview 维护。point 是右键会加上的点的插入位置（None 表示加在末尾）
+// 和坐标，curves 是加上它之后的曲线；只在它或控制点、采样设置变化时重建
+#[derive(Default, Resource)]
+struct GhostPreview {
+    point: Option<(Option<usize>, Vec2)>,
+    curves: [Option<CachedCurve>; CurveKind::ALL.len()],
+}
+
+// 预览的点和曲线的透明度
+const GHOST_ALPHA: f32 = 0.25;
+
+// Shift+8、Shift+9 保存的两组点和当前的插值位置（0 是 A，1 是 B）。按住 Shift+, / Shift+.
+// 或拖动面板的滑块时逐帧把插值结果写回控制点，曲线缓存随之每帧重建
+#[derive(Default, Resource)]
+struct Morph {
+    slots: [Option<Vec<MovablePoint>>; 2],
+    amount: f32,
+}
+
+impl Morph {
+    // 按住按键时每秒变化的插值量
+    const SPEED: f32 = 0.5;
+
+    // 把插值位置处的点写入控制点，两组点不能插值时返回原因
+    fn apply(&self, control_points: &mut ControlPoints) -> Result<(), morph::MorphError> {
+        control_points.points = morph(&self.slots, self.amount)?;
+        Ok(())
+    }
+}
+
+// Shift+B 显示当前曲线采样点的包围盒，Ctrl+B 显示控制点的凸包；B 已用于播放结束后的行为
+#[derive(Default, Resource)]
+struct BoundsOverlay {
+    bounding_box: bool,
+    hull: bool,
+}
+
+// E 在当前曲线上标出拐点（空心菱形）和曲率最大值（实心菱形）
+#[derive(Default, Resource)]
+struct CurvatureFeatureOverlay(bool);
+
+// 只在控制点、当前曲线或开关变化时由 update_curvature_features 重新计算，关闭时为空
+#[derive(Default, Resource)]
+struct CurvatureFeatures(Vec<CurvatureFeature>);
+
+#[derive(Component)]
+struct CurvatureFeatureText;
+
+const FEATURE_MARK_SIZE: f32 = 6.0;
+// HUD 里每种特征点最多列出的参数个数
+const FEATURE_TEXT_LIMIT: usize = 8;
+
+// O 在当前曲线两侧画出 ±distance 的偏移曲线，Ctrl+[ / Ctrl+] 调整距离（世界坐标）
+#[derive(Resource)]
+struct OffsetOverlay {
+    enabled: bool,
+    distance: f32,
+}
+
+impl Default for OffsetOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            distance: 30.0,
+        }
+    }
+}
+
+impl OffsetOverlay {
+    const MIN: f32 = 1.0;
+    const MAX: f32 = 500.0;
+    const STEP: f32 = 1.25;
+}
+
+// 左右两侧的偏移采样和偏移曲线的自交点，由 update_offset_curves 在缓存或设置变化时重建
+#[derive(Default, Resource)]
+struct OffsetCurves {
+    sides: [Vec<OffsetSample>; 2],
+    crossings: Vec<Vec2>,
+}
+
+// 比较的曲线对：第一条的采样点投影到第二条上，V 切换
+const DEVIATION_PAIRS: [(CurveKind, CurveKind); 5] = [
+    (CurveKind::CatmullRom, CurveKind::BSpline),
+    (CurveKind::Natural, CurveKind::BSpline),
+    (CurveKind::CatmullRom, CurveKind::Natural),
+    (CurveKind::Akima, CurveKind::CatmullRom),
+    (CurveKind::Nurbs, CurveKind::BSpline),
+];
+
+#[derive(Default, Resource)]
+struct DeviationPair(usize);
+
+impl DeviationPair {
+    fn kinds(&self) -> (CurveKind, CurveKind) {
+        DEVIATION_PAIRS[self.0]
+    }
+
+    fn cycle(&mut self) {
+        self.0 = (self.0 + 1) % DEVIATION_PAIRS.len();
+    }
+}
+
+// 两条曲线都显示时由 update_curve_deviation 在采样缓存变化时重新计算
+#[derive(Default, Resource)]
+struct CurveDeviation(Option<Deviation>);
+
+#[derive(Component)]
+struct DeviationText;
+
+// J 在 Bezier 上画出 de Casteljau 构造，参数跟随 uniform t 的动画标记
+#[derive(Default, Resource)]
+struct CasteljauConstruction(bool);
+
+// W / Shift+W 逐级升高或降低 Bezier 控制多边形的阶数，0 表示不显示；
+// 每一级都从当前控制点重新计算，拖动控制点时跟着更新
+#[derive(Default, Resource)]
+struct DegreeElevation(usize);
+
+impl DegreeElevation {
+    const MAX: usize = 8;
+}
+
+// Ctrl+I 在 B-Spline 离光标最近处插入的节点参数，按插入顺序保存，Ctrl+Shift+I 清除；
+// 细化后的控制多边形每一帧从当前控制点重新插入得到
+#[derive(Default, Resource)]
+struct KnotInsertion(Vec<f32>);
+
+impl KnotInsertion {
+    const MAX: usize = 12;
+}
+
+const REFINED_POLYGON_COLOR: Srgba = AQUA;
+
+// 按住 S 点击 Bezier 得到的细分参数，Esc 清除；控制点移动后在同一参数处重新细分
+#[derive(Default, Resource)]
+struct BezierSplit(Option<f32>);
+
+// 点击处离 Bezier 多近（屏幕像素）才细分
+const SPLIT_PICK_DISTANCE: f32 = 20.0;
+const SPLIT_COLORS: [Srgba; 2] = [ORANGE, DEEP_PINK];
+
+// 探针：X 放在光标处，可以像控制点一样拖动，但不属于 ControlPoints；Shift+X 移除。
+// grab 是拖动时光标相对探针的偏移
+#[derive(Default, Resource)]
+struct Probe {
+    position: Option<Vec2>,
+    grab: Option<Vec2>,
+}
+
+// 探针到每条显示中的曲线的垂足，只在探针、控制点或曲线显示变化时重新计算
+#[derive(Default, Resource)]
+struct ProbeFeet(Vec<ProbeFoot>);
+
+struct ProbeFoot {
+    kind: CurveKind,
+    position: Vec2,
+    distance: f32,
+}
+
+// 距离标签，值是 ProbeFeet 中的索引
+#[derive(Component)]
+struct ProbeLabel(usize);
+
+// 探针的菱形半径和命中半径，屏幕像素
+const PROBE_SIZE: f32 = 7.0;
+
+// 每隔多少个渲染采样点画一组切线和法线
+const TANGENT_SAMPLE_STRIDE: usize = 10;
+const TANGENT_LENGTH: f32 = 30.0;
+const NORMAL_LENGTH: f32 = 12.0;
+
+// 是否在每个控制点旁显示世界坐标，N 切换
+#[derive(Default, Resource, Clone, Serialize, Deserialize)]
+struct CoordinateLabels(bool);
+
+// 坐标标签，值是对应控制点的索引
+#[derive(Component)]
+struct CoordinateLabel(usize);
+
+// 是否在每个控制点旁显示它的索引，Shift+N 切换；与坐标标签分别放在点的两侧
+#[derive(Default, Resource, Clone, Serialize, Deserialize)]
+struct IndexLabels(bool);
+
+#[derive(Component)]
+struct IndexLabel(usize);
+
+// 屏幕上方的点数和各曲线段数
+#[derive(Component)]
+struct PointCountText;
+
+// 标签相对点圆周的额外间距，避免文字压在圆上
+const COORDINATE_LABEL_GAP: f32 = 4.0;
+const COORDINATE_LABEL_FONT_SIZE: f32 = 12.0;
+
+// 鼠标离当前曲线多近（屏幕像素）时显示密切圆；半径超过上限时曲线接近直线，不画圆
+const OSCULATING_HOVER_DISTANCE: f32 = 40.0;
+const OSCULATING_MAX_RADIUS: f32 = 2000.0;
+
+// 屏幕底部速度曲线图的位置（像素），底边留出提示文字的空间
+const VELOCITY_PLOT_MARGIN: f32 = 12.0;
+const VELOCITY_PLOT_BOTTOM: f32 = 36.0;
+const VELOCITY_PLOT_HEIGHT: f32 = 80.0;
+const VELOCITY_PLOT_SAMPLES: usize = 200;
+
+// 渲染时的采样方式，A 切换
+#[derive(Default, Resource, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+enum SamplingMode {
+    // 按参数 t 均匀采样，段长不同时采样点会聚集
+    #[default]
+    Uniform,
+    // 按弧长均匀采样
+    ArcLength,
+    // 按局部平直度自适应细分
+    Adaptive,
+}
+
+impl SamplingMode {
+    #[cfg_attr(not(feature = "egui"), allow(dead_code))]
+    const ALL: [SamplingMode; 3] = [
+        SamplingMode::Uniform,
+        SamplingMode::ArcLength,
+        SamplingMode::Adaptive,
+    ];
+
+    fn next(self) -> Self {
+        match self {
+            SamplingMode::Uniform => SamplingMode::ArcLength,
+            SamplingMode::ArcLength => SamplingMode::Adaptive,
+            SamplingMode::Adaptive => SamplingMode::Uniform,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SamplingMode::Uniform => "uniform t",
+            SamplingMode::ArcLength => "arc length",
+            SamplingMode::Adaptive => "adaptive",
+        }
+    }
+}
+
+// 自适应细分的世界空间误差阈值，; 和 ' 调整
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct AdaptiveEpsilon(f32);
+
+impl Default for AdaptiveEpsilon {
+    fn default() -> Self {
+        Self(0.5)
+    }
+}
+
+// 每段曲线的采样数，[ 和 ] 调整
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct CurveResolution(usize);
+
+impl Default for CurveResolution {
+    fn default() -> Self {
+        Self(SAMPLES_PER_SEGMENT)
+    }
+}
+
+impl CurveResolution {
+    const MIN: usize = 2;
+    const MAX: usize = 500;
+    // 低于这个分辨率时把采样点画出来，方便观察折线近似
+    const SHOW_SAMPLES_BELOW: usize = 20;
+
+    // 分辨率越小步长越小，低分辨率区间可以逐个调整
+    fn step(&self) -> usize {
+        (self.0 / 10).max(1)
+    }
+}
+
+// 虚线的实线段和间隔长度（世界单位），控制多边形和其他叠加层共用
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct DashStyle {
+    dash: f32,
+    gap: f32,
+}
+
+impl Default for DashStyle {
+    fn default() -> Self {
+        Self {
+            dash: 8.0,
+            gap: 6.0,
+        }
+    }
+}
+
+// 曲线的着色方式，G 切换
+#[derive(Default, Resource, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+enum ColorMode {
+    #[default]
+    Solid,
+    // 颜色随参数 t 从起点色渐变到终点色，配合不同采样方式观察参数化速度
+    Gradient,
+    // 按局部曲率大小着色
+    Heatmap,
+}
+
+impl ColorMode {
+    fn next(self) -> Self {
+        match self {
+            ColorMode::Solid => ColorMode::Gradient,
+            ColorMode::Gradient => ColorMode::Heatmap,
+            ColorMode::Heatmap => ColorMode::Solid,
+        }
+    }
+}
+
+// 每种曲线渐变的起点色和终点色，默认从曲线本身的颜色旋转色相 120°
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct GradientSettings([(Srgba, Srgba); CurveKind::ALL.len()]);
+
+impl Default for GradientSettings {
+    fn default() -> Self {
+        Self(CurveKind::ALL.map(|kind| {
+            let start = kind.default_color();
+            (start, Hsla::from(start).rotate_hue(120.0).into())
+        }))
+    }
+}
+
+impl GradientSettings {
+    fn endpoints(&self, kind: CurveKind) -> (Srgba, Srgba) {
+        self.0[kind.index()]
+    }
+}
+
+// 热力图色带的上限取曲率的 95 分位数，避免一个尖角把其余部分都压成蓝色
+const HEATMAP_PERCENTILE: f32 = 0.95;
+// 上限每秒向目标靠近的速率，拖动时颜色不会闪烁
+const HEATMAP_SCALE_RATE: f32 = 4.0;
+
+// 每种曲线平滑后的热力图上限，None 表示还没有绘制过，第一次直接取目标值
+#[derive(Default, Resource)]
+struct HeatmapScale([Option<f32>; CurveKind::ALL.len()]);
+
+// 渲染一条曲线时每一小段的颜色来源
+enum CurvePaint {
+    Solid(Srgba),
+    Gradient(Srgba, Srgba),
+    // 每一小段的曲率大小和色带上限
+    Heatmap { curvatures: Vec<f32>, scale: f32 },
+}
+
+// 曲线的绘制方式，F4 循环切换。gizmo 线宽有上限，网格模式把采样折线扩成三角形，
+// 转角处斜接或斜切；控制多边形始终用 gizmo 虚线
+#[derive(Default, Resource, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+enum CurveRenderMode {
+    #[default]
+    Gizmos,
+    Mesh(Join),
+}
+
+impl CurveRenderMode {
+    fn next(self) -> Self {
+        match self {
+            CurveRenderMode::Gizmos => CurveRenderMode::Mesh(Join::Miter),
+            CurveRenderMode::Mesh(Join::Miter) => CurveRenderMode::Mesh(Join::Bevel),
+            CurveRenderMode::Mesh(Join::Bevel) => CurveRenderMode::Gizmos,
+        }
+    }
+
+    fn join(self) -> Option<Join> {
+        match self {
+            CurveRenderMode::Gizmos => None,
+            CurveRenderMode::Mesh(join) => Some(join),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            CurveRenderMode::Gizmos => "Curves: gizmo lines",
+            CurveRenderMode::Mesh(Join::Miter) => "Curves: mesh, miter joins",
+            CurveRenderMode::Mesh(Join::Bevel) => "Curves: mesh, bevel joins",
+        }
+    }
+}
+
+// 每条已绘制曲线的统计，由 plot_line 用绘制时的采样点计算
+#[derive(Clone, Copy)]
+struct CurveStat {
+    length: f32,
+    samples: usize,
+    segments: usize,
+}
+
+#[derive(Default, Resource)]
+struct CurveStats([Option<CurveStat>; CurveKind::ALL.len()]);
+
+// 一条曲线的采样结果
+struct CachedCurve {
+    samples: Samples,
+    domain_end: f32,
+    segments: usize,
+    // 只在热力图模式下计算
+    curvatures: Vec<f32>,
+}
+
+// 显示中的曲线的采样缓存，只在控制点或采样设置变化时由 update_curve_cache 重建
+#[derive(Default, Resource)]
+struct CachedCurves([Option<CachedCurve>; CurveKind::ALL.len()]);
+
+// 没有在编辑的点集的一条显示中的曲线
+struct InactiveListCurve {
+    kind: CurveKind,
+    closed: bool,
+    samples: Samples,
+    domain_end: f32,
+}
+
+// 没有在编辑的点集的曲线采样，只在控制点或采样设置变化时由 update_inactive_list_curves 重建
+#[derive(Default, Resource)]
+struct InactiveListCurves(Vec<InactiveListCurve>);
+
+/// 正在编辑的点集的各条曲线最新的采样点，世界坐标。隐藏的或点数不够、画不出来的曲线为空。
+#[derive(Default, Resource)]
+pub struct SampledCurves([Arc<Vec<Vec2>>; CurveKind::ALL.len()]);
+
+impl SampledCurves {
+    pub fn get(&self, curve: CurveKind) -> &[Vec2] {
+        &self.0[curve.index()]
+    }
+
+    /// 与 [`CurveChanged::samples`] 共享的同一份采样点。
+    pub fn shared(&self, curve: CurveKind) -> Arc<Vec<Vec2>> {
+        Arc::clone(&self.0[curve.index()])
+    }
+}
+
+/// 正在编辑的点集构造出的三次曲线，可以在任意参数处求位置、速度等，参数范围是 0 到段数。
+///
+/// 与 [`SampledCurves`] 由同一个系统在控制点或设置变化时重建，这里的曲线就是被采样绘制的曲线；
+/// 点数不足或无法构造时为 None。不受曲线开关影响，增删点的过渡动画期间是过渡中的曲线。
+#[derive(Default, Resource)]
+pub struct BuiltCurves {
+    pub b_spline: Option<CubicCurve<Vec2>>,
+    pub catmull_rom: Option<CubicCurve<Vec2>>,
+    pub bezier: Option<CubicCurve<Vec2>>,
+    pub hermite: Option<CubicCurve<Vec2>>,
+    pub natural: Option<CubicCurve<Vec2>>,
+    pub akima: Option<CubicCurve<Vec2>>,
+    // 其余类型的曲线，按 CurveKind::index 存放，三次曲线的位置总是 None
+    others: [Option<Box<dyn PlanarCurve>>; CurveKind::ALL.len()],
+}
+
+impl BuiltCurves {
+    /// 不是三次曲线的类型（控制多边形、NURBS 等）总是 None。
+    pub fn get(&self, curve: CurveKind) -> Option<&CubicCurve<Vec2>> {
+        match curve {
+            CurveKind::BSpline => self.b_spline.as_ref(),
+            CurveKind::CatmullRom => self.catmull_rom.as_ref(),
+            CurveKind::Bezier => self.bezier.as_ref(),
+            CurveKind::Hermite => self.hermite.as_ref(),
+            CurveKind::Natural => self.natural.as_ref(),
+            CurveKind::Akima => self.akima.as_ref(),
+            _ => None,
+        }
+    }
+
+    // 各个分析系统读这里，与绘制的是同一条曲线
+    pub(crate) fn curve(&self, curve: CurveKind) -> Option<&dyn PlanarCurve> {
+        match self.get(curve) {
+            Some(cubic) => Some(cubic),
+            None => self.others[curve.index()].as_deref(),
+        }
+    }
+
+    fn slot_mut(&mut self, curve: CurveKind) -> Option<&mut Option<CubicCurve<Vec2>>> {
+        match curve {
+            CurveKind::BSpline => Some(&mut self.b_spline),
+            CurveKind::CatmullRom => Some(&mut self.catmull_rom),
+            CurveKind::Bezier => Some(&mut self.bezier),
+            CurveKind::Hermite => Some(&mut self.hermite),
+            CurveKind::Natural => Some(&mut self.natural),
+            CurveKind::Akima => Some(&mut self.akima),
+            _ => None,
+        }
+    }
+}
+
+/// 一条曲线的采样点重新生成并且与之前不同时发送，`samples` 与 [`SampledCurves`] 中的相同。
+///
+/// 只在控制点、曲线开关或采样设置变化，并且这条曲线的采样点确实改变时发送，静止的帧不会发送；
+/// 拖动点时每个移动了的帧发送一次，增删点的过渡动画期间也是每帧一次。
+/// 曲线被隐藏或画不出来时发送一次空的 `samples`。
+#[derive(Event, Clone, Debug)]
+pub struct CurveChanged {
+    pub curve: CurveKind,
+    pub samples: Arc<Vec<Vec2>>,
+}
+
+// 增删点时的过渡动画，由 ease_display_points 维护；previous 是上一次看到的控制点。
+// 过渡期间 update_curve_cache 用缓动中的点构造曲线，拖动点时直接用真实位置
+#[derive(Default, Resource)]
+struct DisplayPoints {
+    previous: Vec<MovablePoint>,
+    // previous 属于哪个点集
+    list: usize,
+    transition: Option<PointTransition>,
+}
+
+#[derive(Component)]
+struct SamplingText;
+
+// 屏幕上方中间的提示信息，例如保存、读取的结果，几秒后消失
+#[derive(Default, Resource)]
+struct StatusMessage {
+    text: String,
+    is_error: bool,
+    remaining: f32,
+}
+
+impl StatusMessage {
+    const DURATION: f32 = 4.0;
+
+    fn info(&mut self, text: impl Into<String>) {
+        self.set(text.into(), false);
+    }
+
+    fn error(&mut self, text: impl Into<String>) {
+        self.set(text.into(), true);
+    }
+
+    // 只显示 `seconds` 秒的提示，与它对应的操作同时过期
+    fn prompt(&mut self, text: impl Into<String>, seconds: f32) {
+        self.set(text.into(), false);
+        self.remaining = seconds;
+    }
+
+    fn set(&mut self, text: String, is_error: bool) {
+        self.text = text;
+        self.is_error = is_error;
+        self.remaining = Self::DURATION;
+    }
+}
+
+// 光标下、点击会抓起的未锁定控制点，由 update_hovered_point 每帧更新；拖动或框选期间为 None
+#[derive(Default, Resource)]
+struct HoveredPoint(Option<usize>);
+
+// Shift+C 或 Delete 清空所有点要按两次：第一次按下后在 remaining 秒内再按一次才清空
+#[derive(Default, Resource)]
+struct ClearAllPrompt {
+    remaining: f32,
+}
+
+impl ClearAllPrompt {
+    const WINDOW: f32 = 2.0;
+}
+
+#[derive(Component)]
+struct StatusText;
+
+const HELP_KEY_COLUMN_WIDTH: f32 = 220.0;
+
+// F3 切换的性能读数：帧率和每帧构造、采样所有曲线的耗时
+#[derive(Default, Resource)]
+struct DiagnosticsOverlay(bool);
+
+#[derive(Component)]
+struct DiagnosticsText;
+
+// 帮助界面是否打开，H 或 F1 切换
+#[derive(Default, Resource)]
+struct HelpOverlay(bool);
+
+#[derive(Component)]
+struct HelpPanel;
+
+const CURVE_BUILD_TIME: DiagnosticPath = DiagnosticPath::const_new("curve_build_time");
+// 曲线耗时取最近这么多帧的平均
+const CURVE_BUILD_TIME_HISTORY: usize = 60;
+
+// 右下角显示选中点的索引
+#[derive(Component)]
+struct SelectionText;
+
+#[derive(Component)]
+struct ArcLengthRow(CurveKind);
+
+#[derive(Component)]
+struct ArcLengthSwatch(CurveKind);
+
+#[derive(Component)]
+struct ArcLengthText(CurveKind);
+
+// 图例中每种曲线的色条和文字
+#[derive(Component)]
+struct LegendSwatch(CurveKind);
+
+#[derive(Component)]
+struct LegendLabel(CurveKind);
+
+// 控制点和切线手柄，以及曲率梳、探针、网格等叠加层各自的 gizmo 配置组；
+// 与每种曲线的配置组一样，线宽由 apply_curve_style 按 CurveStyle 写入
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct PointGizmos;
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct OverlayGizmos;
+
+// 每种曲线一个 gizmo 配置组，线宽可以按曲线单独设置
+macro_rules! curve_gizmo_groups {
+    ($(($kind:ident, $group:ident, $field:ident)),* $(,)?) => {
+        $(
+            #[derive(Default, Reflect, GizmoConfigGroup)]
+            struct $group;
+        )*
+
+        #[derive(SystemParam)]
+        struct CurveGizmos<'w, 's> {
+            $($field: Gizmos<'w, 's, $group>,)*
+        }
+
+        impl CurveGizmos<'_, '_> {
+            fn draw(&mut self, kind: CurveKind, drawing: &CurveDrawing) {
+                match kind {
+                    $(CurveKind::$kind => drawing.draw(&mut self.$field),)*
+                }
+            }
+
+            // 3D 模式直接画折线
+            fn linestrip(
+                &mut self,
+                kind: CurveKind,
+                positions: impl IntoIterator<Item = Vec3>,
+                color: Srgba,
+            ) {
+                match kind {
+                    $(CurveKind::$kind => self.$field.linestrip(positions, color),)*
+                }
+            }
+        }
+
+        fn curve_gizmo_group(kind: CurveKind) -> TypeId {
+            match kind {
+                $(CurveKind::$kind => TypeId::of::<$group>(),)*
+            }
+        }
+
+        struct CurveGizmosPlugin;
+
+        impl Plugin for CurveGizmosPlugin {
+            fn build(&self, app: &mut App) {
+                $(app.init_gizmo_group::<$group>();)*
+            }
+        }
+    };
+}
+
+curve_gizmo_groups!(
+    (ControlPolygon, ControlPolygonGizmos, control_polygon),
+    (BSpline, BSplineGizmos, b_spline),
+    (CatmullRom, CatmullRomGizmos, catmull_rom),
+    (Bezier, BezierGizmos, bezier),
+    (Hermite, HermiteGizmos, hermite),
+    (Natural, NaturalGizmos, natural),
+    (Nurbs, NurbsGizmos, nurbs),
+    (Akima, AkimaGizmos, akima),
+    (Monotone, MonotoneGizmos, monotone),
+    (Quadratic, QuadraticGizmos, quadratic),
+);
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct CurveStyleEntry {
+    color: Srgba,
+    line_width: f32,
+}
+
+// 控制点和切线手柄的颜色和线宽
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct PointStyle {
+    default_color: Srgba,
+    selected_color: Srgba,
+    // 光标悬停在未选中的点上时的颜色
+    hover_color: Srgba,
+    handle_color: Srgba,
+    locked_color: Srgba,
+    line_width: f32,
+}
+
+impl Default for PointStyle {
+    fn default() -> Self {
+        Self {
+            default_color: GREEN,
+            selected_color: RED,
+            hover_color: PALE_GREEN,
+            handle_color: ORANGE,
+            locked_color: LIGHT_SLATE_GRAY,
+            line_width: 5.0,
+        }
+    }
+}
+
+// 每种曲线的颜色和线宽，控制点的样式，以及所有叠加层共用的线宽；
+// 默认控制多边形最细，曲线次之，控制点最粗
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct CurveStyle {
+    curves: [CurveStyleEntry; CurveKind::ALL.len()],
+    point: PointStyle,
+    overlay_line_width: f32,
+}
+
+impl Default for CurveStyle {
+    fn default() -> Self {
+        Self {
+            curves: CurveKind::ALL.map(|kind| CurveStyleEntry {
+                color: kind.default_color(),
+                line_width: if kind == CurveKind::ControlPolygon {
+                    1.0
+                } else {
+                    3.0
+                },
+            }),
+            point: PointStyle::default(),
+            overlay_line_width: 2.0,
+        }
+    }
+}
+
+// U 键在这些颜色之间循环切换当前分析的曲线的颜色
+const CURVE_PALETTE: [Srgba; 12] = [
+    WHITE,
+    PINK,
+    YELLOW,
+    GREEN,
+    ORANGE,
+    AQUA,
+    VIOLET,
+    TOMATO,
+    CHARTREUSE,
+    CORNFLOWER_BLUE,
+    GOLD,
+    DEEP_SKY_BLUE,
+];
+
+impl CurveStyle {
+    #[cfg_attr(not(feature = "egui"), allow(dead_code))]
+    const MIN_LINE_WIDTH: f32 = 0.5;
+    #[cfg_attr(not(feature = "egui"), allow(dead_code))]
+    const MAX_LINE_WIDTH: f32 = 10.0;
+
+    fn color(&self, kind: CurveKind) -> Srgba {
+        self.curves[kind.index()].color
+    }
+
+    fn line_width(&self, kind: CurveKind) -> f32 {
+        self.curves[kind.index()].line_width
+    }
+
+    // 闭合时控制多边形换一种颜色提示当前模式
+    fn curve_color(&self, kind: CurveKind, closed: bool) -> Srgba {
+        if kind == CurveKind::ControlPolygon && closed {
+            LIGHT_SKY_BLUE
+        } else {
+            self.color(kind)
+        }
+    }
+
+    // 换成调色板中的下一个颜色；当前颜色不在调色板中时从第一个开始
+    fn cycle_color(&mut self, kind: CurveKind) {
+        let color = &mut self.curves[kind.index()].color;
+        let next = CURVE_PALETTE
+            .iter()
+            .position(|c| c == color)
+            .map_or(0, |i| (i + 1) % CURVE_PALETTE.len());
+        *color = CURVE_PALETTE[next];
+    }
+}
+
+// 相机正交投影的缩放，即每个屏幕像素对应的世界单位；只由缩放和适应视图的系统修改。
+// 点的命中半径和绘制大小都乘上它，缩放后点在屏幕上的大小不变
+#[derive(Resource)]
+struct ViewScale(f32);
+
+impl Default for ViewScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl ViewScale {
+    const MIN: f32 = 0.1;
+    const MAX: f32 = 10.0;
+    // 每格滚轮缩放的倍数
+    const STEP: f32 = 1.1;
+}
+
+// F 适应视图时四周留出的边距，占控制点和曲线范围的比例
+const FIT_VIEW_PADDING: f32 = 0.05;
+// F5–F9 的预设点集占视图短边的比例；命令行启动或没有窗口时的大小（世界单位）
+const PRESET_FILL: f32 = 0.7;
+const PRESET_RADIUS: f32 = 250.0;
+
+// F10 随机撒点的设置。next_seed 是下一次使用的种子，命令行 --seed 给定第一次的，
+// 之后每次的种子由上一次的发生器接着产生；没有给定时第一次按下时取当时的运行时间
+#[derive(Resource)]
+struct RandomScatter {
+    next_seed: Option<u64>,
+    count: usize,
+}
+
+impl Default for RandomScatter {
+    fn default() -> Self {
+        Self {
+            next_seed: None,
+            count: Self::DEFAULT_COUNT,
+        }
+    }
+}
+
+impl RandomScatter {
+    const DEFAULT_COUNT: usize = 8;
+    const MIN_COUNT: usize = 2;
+    const MAX_COUNT: usize = 100;
+    // 撒点范围离视图边缘留出的比例
+    const MARGIN: f32 = 0.1;
+}
+
+// 标记的两种走法：按参数 t 均匀前进，或按弧长匀速前进；Both 同时显示两个标记
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum PlaybackMode {
+    #[default]
+    UniformT,
+    ConstantSpeed,
+    Both,
+}
+
+impl PlaybackMode {
+    fn next(self) -> Self {
+        match self {
+            PlaybackMode::UniformT => PlaybackMode::ConstantSpeed,
+            PlaybackMode::ConstantSpeed => PlaybackMode::Both,
+            PlaybackMode::Both => PlaybackMode::UniformT,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            PlaybackMode::UniformT => "uniform t",
+            PlaybackMode::ConstantSpeed => "constant speed",
+            PlaybackMode::Both => "uniform t and constant speed",
+        }
+    }
+
+    // 这种模式下是否显示某个标记，marker 只会是前两种
+    fn shows(self, marker: PlaybackMode) -> bool {
+        self == marker || self == PlaybackMode::Both
+    }
+}
+
+// 沿当前曲线移动的标记；t 是归一化参数（0..1），每秒前进 speed，到终点的行为由 end 决定。
+// 绘制时才换算到曲线的参数域，控制点变化后标记停在新曲线上相同的归一化位置。
+// 匀速标记的位置 s 是归一化弧长，每秒前进 units_per_second 个世界单位。
+// rate 是播放倍速，只乘在每帧的增量上，改变倍速时标记不会跳动
+#[derive(Resource)]
+struct CurveAnimation {
+    t: Playhead,
+    speed: f32,
+    s: Playhead,
+    units_per_second: f32,
+    rate: f32,
+    mode: PlaybackMode,
+    end: EndBehavior,
+    playing: bool,
+}
+
+impl Default for CurveAnimation {
+    fn default() -> Self {
+        Self {
+            t: Playhead::default(),
+            speed: 0.2,
+            s: Playhead::default(),
+            units_per_second: 200.0,
+            rate: 1.0,
+            mode: PlaybackMode::default(),
+            end: EndBehavior::default(),
+            playing: false,
+        }
+    }
+}
+
+impl CurveAnimation {
+    const MIN_RATE: f32 = 0.1;
+    const MAX_RATE: f32 = 5.0;
+    // 每按一次方向键倍速的变化倍数
+    const RATE_STEP: f32 = 1.25;
+
+    fn set_rate(&mut self, rate: f32) {
+        self.rate = rate.clamp(Self::MIN_RATE, Self::MAX_RATE);
+    }
+
+    // 拖动时间轴时两种标记都放到同一个归一化位置
+    fn scrub(&mut self, u: f32) {
+        let u = u.clamp(0.0, 1.0);
+        self.t = Playhead::at(u);
+        self.s = Playhead::at(u);
+    }
+
+    fn reset(&mut self) {
+        self.t = Playhead::default();
+        self.s = Playhead::default();
+    }
+
+    // 停在终点模式下，显示的标记都走到终点后停止播放
+    fn finished(&self) -> bool {
+        self.end == EndBehavior::Stop
+            && (!self.mode.shows(PlaybackMode::UniformT) || self.t.at_end())
+            && (!self.mode.shows(PlaybackMode::ConstantSpeed) || self.s.at_end())
+    }
+}
+
+// 屏幕底部中间的时间轴（屏幕像素），拖动它直接设置标记的位置
+const TIMELINE_BOTTOM: f32 = 10.0;
+const TIMELINE_HEIGHT: f32 = 8.0;
+// 时间轴的宽度占窗口宽度的比例，两侧留给左下角和右下角的文字
+const TIMELINE_WIDTH_FRACTION: f32 = 0.4;
+// 时间轴的命中范围比绘制的范围向外扩展的像素
+const TIMELINE_HIT_MARGIN: f32 = 6.0;
+
+// 为 true 时正在拖动时间轴，这次按下的鼠标键不会落到时间轴下面的点上
+#[derive(Default, Resource)]
+struct TimelineScrub(bool);
+
+// 时间轴在视口坐标（y 轴向下）中的矩形
+fn timeline_rect(window: &Window) -> Rect {
+    let width = window.width() * TIMELINE_WIDTH_FRACTION;
+    let bottom = window.height() - TIMELINE_BOTTOM;
+    let left = (window.width() - width) * 0.5;
+    Rect::new(left, bottom - TIMELINE_HEIGHT, left + width, bottom)
+}
+
+// 匀速播放用的当前曲线弧长表，BuiltCurves 重建或切换曲线时跟着重建
+#[derive(Default, Resource)]
+struct MarkerArcLength {
+    table: Option<ArcLengthTable>,
+}
+
+// 标记按哪种方式前进，只会是 UniformT 或 ConstantSpeed
+#[derive(Component)]
+struct AnimationMarker(PlaybackMode);
+
+// 网格模式下一种曲线的网格实体，网格资源原地更新
+#[derive(Component)]
+struct CurveMesh(CurveKind);
+
+// 跟随标记的箭头，像赛道上的车一样朝向速度方向
+#[derive(Component, Default)]
+struct DirectionArrow(Heading);
+
+// 标记的半径和箭头的长度（屏幕像素）
+const ANIMATION_MARKER_RADIUS: f32 = 6.0;
+const DIRECTION_ARROW_LENGTH: f32 = 18.0;
+// 匀速标记的颜色，与按参数前进的标记（曲线颜色）区分
+const CONSTANT_SPEED_MARKER_COLOR: Srgba = WHITE;
+
+// 正在平移相机时是按下的鼠标键和上一帧的光标位置（视口坐标）
+#[derive(Default, Resource)]
+struct CameraPan(Option<(MouseButton, Vec2)>);
+
+// 为 true 时鼠标在设置面板上，世界中的点击、拖动和滚轮都不响应
+#[derive(Default, Resource)]
+struct PointerCaptured(bool);
+
+// 每段曲线的默认采样数
+const SAMPLES_PER_SEGMENT: usize = 100;
+
+const POINTS_FILE_NAME: &str = "points.ron";
+// 在工作目录下查找，不存在时使用默认绑定
+const KEY_BINDINGS_FILE_NAME: &str = "keybindings.ron";
+// 与绑定文件一样放在工作目录下
+const SETTINGS_FILE_NAME: &str = "showcase_settings.ron";
+// 设置最后一次改动之后等这么久再写入文件，连续调整时只写一次
+const SETTINGS_SAVE_DELAY: f32 = 1.0;
+// Ctrl+F6 录制的编辑过程，与 points.ron 放在一起
+const SESSION_FILE_NAME: &str = "session.ron";
+
+// 编辑过程的录制和回放。recording 是录制中的日志和开始录制的时间，now 由 session_keys 每帧更新；
+// 编辑操作通过 record 写入日志，没有在录制时什么也不做
+#[derive(Default, Resource)]
+struct SessionRecorder {
+    recording: Option<(Session, f32)>,
+    replay: Option<Replay>,
+    now: f32,
+}
+
+impl SessionRecorder {
+    fn record(&mut self, edit: SessionEdit) {
+        if let Some((session, started)) = &mut self.recording {
+            session.events.push(SessionEvent {
+                time: self.now - *started,
+                edit,
+            });
+        }
+    }
+
+    // 没有专门记录方式的编辑之后调用，记下整个场景；不在录制时不复制点
+    fn record_snapshot(&mut self, control_points: &ControlPoints) {
+        if self.recording.is_some() {
+            self.record(SessionEdit::Snapshot {
+                scene: Box::new(control_points.to_saved()),
+            });
+        }
+    }
+}
+
+// 网格吸附，Shift+G 开关，拖动时按住 Ctrl 临时启用
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct GridSettings {
+    spacing: f32,
+    enabled: bool,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            spacing: 25.0,
+            enabled: false,
+        }
+    }
+}
+
+impl GridSettings {
+    fn is_snapping(&self, ctrl: bool) -> bool {
+        self.enabled || ctrl
+    }
+
+    fn snap(&self, position: Vec2) -> Vec2 {
+        (position / self.spacing).round() * self.spacing
+    }
+}
+
+// 控制点的撤销历史，Ctrl+Z 撤销，Ctrl+Shift+Z 或 Ctrl+Y 重做
+#[derive(Resource)]
+struct EditHistory(History<Snapshot>);
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self(History::new(100))
+    }
+}
+
+fn setup(mut commands: Commands, bindings: Res<KeyBindings>, style: Res<CurveStyle>) {
+    commands.spawn(Camera2dBundle::default());
+
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        MonotoneHint,
+    ));
+
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            right: Val::Px(12.0),
+            ..default()
+        },
+        SelectionText,
+    ));
+
+    // 右下角、速度曲线图上方
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(VELOCITY_PLOT_BOTTOM + VELOCITY_PLOT_HEIGHT + 8.0),
+            right: Val::Px(12.0),
+            ..default()
+        },
+        DiagnosticsText,
+    ));
+
+    // 提示信息横跨顶部并居中显示
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                StatusText,
+            ));
+        });
+
+    // 右上角的弧长读数，未绘制的曲线隐藏对应的行
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            right: Val::Px(12.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(2.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            for kind in CurveKind::ALL {
+                parent
+                    .spawn((
+                        Node {
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(6.0),
+                            ..default()
+                        },
+                        ArcLengthRow(kind),
+                    ))
+                    .with_children(|row| {
+                        row.spawn((
+                            Node {
+                                width: Val::Px(10.0),
+                                height: Val::Px(10.0),
+                                ..default()
+                            },
+                            BackgroundColor(style.color(kind).into()),
+                            ArcLengthSwatch(kind),
+                        ));
+                        row.spawn((
+                            Text::new(""),
+                            TextFont {
+                                font_size: 14.0,
+                                ..default()
+                            },
+                            ArcLengthText(kind),
+                        ));
+                    });
+            }
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                PointCountText,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                SamplingText,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                ClosestPointText,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                IntersectionText,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                CurvatureFeatureText,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                DeviationText,
+            ));
+        });
+
+    // 左上角的图例，每行一个色条加曲线名称
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(2.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            for (i, kind) in CurveKind::ALL.into_iter().enumerate() {
+                parent
+                    .spawn(Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(6.0),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            Node {
+                                width: Val::Px(20.0),
+                                height: Val::Px(3.0),
+                                ..default()
+                            },
+                            BackgroundColor(style.color(kind).into()),
+                            LegendSwatch(kind),
+                        ));
+                        row.spawn((
+                            Text::new(legend_text(
+                                i,
+                                kind,
+                                ActiveCurve::default().0,
+                                &ControlPoints::default(),
+                            )),
+                            TextFont {
+                                font_size: 14.0,
+                                ..default()
+                            },
+                            LegendLabel(kind),
+                        ));
+                    });
+            }
+        });
+
+    spawn_help_overlay(&mut commands, &bindings);
+}
+
+// 覆盖整个窗口的半透明帮助，内容来自 KeyBindings，行太多时换到下一列
+fn spawn_help_overlay(commands: &mut Commands, bindings: &KeyBindings) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(24.0)),
+                flex_direction: FlexDirection::Column,
+                flex_wrap: FlexWrap::Wrap,
+                align_content: AlignContent::FlexStart,
+                row_gap: Val::Px(2.0),
+                column_gap: Val::Px(32.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            // 盖在其他界面之上
+            GlobalZIndex(1),
+            Visibility::Hidden,
+            HelpPanel,
+        ))
+        .with_children(|parent| {
+            for (keys, description) in bindings.help_rows() {
+                parent
+                    .spawn(Node {
+                        column_gap: Val::Px(12.0),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            Node {
+                                width: Val::Px(HELP_KEY_COLUMN_WIDTH),
+                                ..default()
+                            },
+                            Text::new(keys),
+                            TextFont {
+                                font_size: 14.0,
+                                ..default()
+                            },
+                            TextColor(YELLOW.into()),
+                        ));
+                        row.spawn((
+                            Text::new(description),
+                            TextFont {
+                                font_size: 14.0,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+            // 没有用常用按键的操作放在最后说明原因
+            for note in bindings.remap_notes() {
+                parent.spawn((
+                    Text::new(note),
+                    TextFont {
+                        font_size: 12.0,
+                        ..default()
+                    },
+                    TextColor(LIGHT_GRAY.into()),
+                ));
+            }
+        });
+}
+
+// 图例文字：切换键、曲线名称，当前分析的曲线后面加上标记
+fn legend_text(
+    index: usize,
+    kind: CurveKind,
+    active: CurveKind,
+    control_points: &ControlPoints,
+) -> String {
+    let marker = if kind == active { " <" } else { "" };
+    let variant = match curve_variant(kind, control_points) {
+        Some(variant) => format!(" ({variant})"),
+        None => String::new(),
+    };
+    format!("{} {}{}{}", (index + 1) % 10, kind.name(), variant, marker)
+}
+
+// 同一种曲线的不同构造方式；闭合的曲线是周期的，没有钳制与否和端点条件之分
+fn curve_variant(kind: CurveKind, control_points: &ControlPoints) -> Option<&'static str> {
+    let end_condition = control_points.end_condition;
+    match kind {
+        CurveKind::CatmullRom | CurveKind::Hermite | CurveKind::Natural
+            if !control_points.closed && end_condition != EndCondition::Auto =>
+        {
+            Some(end_condition.name())
+        }
+        CurveKind::BSpline if !control_points.closed => Some(if control_points.clamped {
+            "clamped"
+        } else {
+            "unclamped"
+        }),
+        _ => None,
+    }
+}
+
+// 隐藏或点数不足无法绘制的曲线在图例中变灰
+fn update_legend(
+    visibility: Res<CurveVisibility>,
+    control_points: Res<ControlPoints>,
+    active: Res<ActiveCurve>,
+    style: Res<CurveStyle>,
+    mut swatches: Query<(&LegendSwatch, &mut BackgroundColor)>,
+    mut arc_length_swatches: Query<(&ArcLengthSwatch, &mut BackgroundColor), Without<LegendSwatch>>,
+    mut labels: Query<(&LegendLabel, &mut Text, &mut TextColor)>,
+) {
+    if style.is_changed() {
+        for (swatch, mut background) in arc_length_swatches.iter_mut() {
+            background.0 = style.color(swatch.0).into();
+        }
+    }
+    let positions = control_points.positions();
+    let is_drawn = |kind: CurveKind| visibility.is_visible(kind) && kind.can_draw(&positions);
+    for (swatch, mut background) in swatches.iter_mut() {
+        let color = if is_drawn(swatch.0) {
+            style.color(swatch.0)
+        } else {
+            GRAY.with_alpha(0.4)
+        };
+        background.0 = color.into();
+    }
+    for (label, mut text, mut text_color) in labels.iter_mut() {
+        let content = legend_text(label.0.index(), label.0, active.0, &control_points);
+        if text.0 != content {
+            **text = content;
+        }
+        let color = if is_drawn(label.0) {
+            WHITE
+        } else {
+            GRAY.with_alpha(0.6)
+        };
+        text_color.0 = color.into();
+    }
+}
+
+// 点或拟合设置改变时重新拟合，并在状态栏报告最大残差
+fn update_least_squares_fit(
+    control_points: Res<ControlPoints>,
+    mut fit: ResMut<LeastSquaresFit>,
+    mut status: ResMut<StatusMessage>,
+) {
+    // 写入结果不算设置的改变，否则下一帧又会重新拟合
+    let settings_changed = fit.is_changed();
+    let fit = fit.bypass_change_detection();
+    if !fit.enabled {
+        fit.result = None;
+        return;
+    }
+    if !settings_changed && !control_points.is_changed() {
+        return;
+    }
+    let count = fit.control_count;
+    fit.result = fit_b_spline(&control_points.positions(), count);
+    // 拖动数据点时每帧都会重新拟合，只在设置改变时报告
+    if settings_changed {
+        status.info(match &fit.result {
+            Some(result) => format!(
+                "Least-squares fit: {count} control points, max residual {:.2}",
+                result.max_residual
+            ),
+            None => format!("Least-squares fit needs at least {count} points"),
+        });
+    }
+}
+
+// 段数取自采样缓存里构造出的曲线，与画出来的一致
+fn update_point_count_text(
+    control_points: Res<ControlPoints>,
+    stats: Res<CurveStats>,
+    mut text: Query<&mut Text, With<PointCountText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    let content = point_count_text(control_points.points.len(), &stats);
+    if text.0 != content {
+        **text = content;
+    }
+}
+
+fn point_count_text(points: usize, stats: &CurveStats) -> String {
+    let mut content = match points {
+        1 => "1 point".to_string(),
+        n => format!("{n} points"),
+    };
+    for kind in CurveKind::ALL {
+        if kind == CurveKind::ControlPolygon {
+            continue;
+        }
+        if let Some(stat) = stats.0[kind.index()] {
+            content.push_str(&format!(" · {} {} segs", kind.name(), stat.segments));
+        }
+    }
+    content
+}
+
+fn update_arc_length_hud(
+    stats: Res<CurveStats>,
+    sampling_mode: Res<SamplingMode>,
+    resolution: Res<CurveResolution>,
+    epsilon: Res<AdaptiveEpsilon>,
+    mut rows: Query<(&ArcLengthRow, &mut Node)>,
+    mut texts: Query<(&ArcLengthText, &mut Text), Without<SamplingText>>,
+    mut sampling_text: Query<&mut Text, With<SamplingText>>,
+) {
+    for (row, mut node) in rows.iter_mut() {
+        let display = if stats.0[row.0.index()].is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    for (label, mut text) in texts.iter_mut() {
+        let Some(stat) = stats.0[label.0.index()] else {
+            continue;
+        };
+        let content = format!(
+            "{} {:.1} ({} pts)",
+            label.0.name(),
+            stat.length,
+            stat.samples
+        );
+        if text.0 != content {
+            **text = content;
+        }
+    }
+    if let Ok(mut text) = sampling_text.get_single_mut() {
+        let content = match *sampling_mode {
+            SamplingMode::Adaptive => format!("Sampling: adaptive (eps {:.2})", epsilon.0),
+            mode => format!("Sampling: {}, {} / segment", mode.name(), resolution.0),
+        };
+        if text.0 != content {
+            **text = content;
+        }
+    }
+}
+
+// 推进标记；曲线变化时重建匀速播放用的弧长表
+fn advance_animation(
+    time: Res<Time>,
+    mut animation: ResMut<CurveAnimation>,
+    mut arc_length: ResMut<MarkerArcLength>,
+    built: Res<BuiltCurves>,
+    active: Res<ActiveCurve>,
+) {
+    if built.is_changed() || active.is_changed() {
+        arc_length.table = built.curve(active.0).map(ArcLengthTable::new);
+    }
+    let total_length = arc_length
+        .table
+        .as_ref()
+        .map_or(0.0, ArcLengthTable::total_length);
+    if animation.playing {
+        let dt = time.delta_secs() * animation.rate;
+        let end = animation.end;
+        let delta = animation.speed * dt;
+        animation.t.advance(delta, end);
+        if total_length > 0.0 {
+            let delta = animation.units_per_second * dt / total_length;
+            animation.s.advance(delta, end);
+        }
+        if animation.finished() {
+            animation.playing = false;
+        }
+    }
+}
+
+// 标记在曲线参数域中的位置，曲线不存在时返回 None
+fn marker_parameter(
+    marker: PlaybackMode,
+    animation: &CurveAnimation,
+    curve: Option<&dyn PlanarCurve>,
+    arc_length: &MarkerArcLength,
+) -> Option<f32> {
+    let curve = curve?;
+    match (marker, &arc_length.table) {
+        (PlaybackMode::ConstantSpeed, Some(table)) => {
+            Some(table.parameter_at(animation.s.position * table.total_length()))
+        }
+        (PlaybackMode::ConstantSpeed, None) => None,
+        _ => Some(animation.t.position * curve.domain_end()),
+    }
+}
+
+fn update_monotone_hint(
+    visibility: Res<CurveVisibility>,
+    control_points: Res<ControlPoints>,
+    mut hint: Query<&mut Text, With<MonotoneHint>>,
+) {
+    let Ok(mut text) = hint.get_single_mut() else {
+        return;
+    };
+    let positions: Vec<Vec2> = control_points.points.iter().map(|p| p.position).collect();
+    let message = if visibility.is_visible(CurveKind::Monotone)
+        && positions.len() >= 2
+        && !monotone::is_x_monotone(&positions)
+    {
+        "Monotone cubic hidden: control points are not sorted by x"
+    } else {
+        ""
+    };
+    if text.0 != message {
+        **text = message.to_string();
+    }
+}
+
+// 按索引让标签实体与控制点一一对应：多出的点生成标签，多出的标签删除。
+// 在所有编辑和拖动系统之后运行，拖动时标签与点在同一帧移动
+fn sync_coordinate_labels(
+    mut commands: Commands,
+    enabled: Res<CoordinateLabels>,
+    control_points: Res<ControlPoints>,
+    mut labels: Query<(
+        Entity,
+        &CoordinateLabel,
+        &mut Text2d,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    view: Res<ViewScale>,
+    clean_capture: Res<CleanCapture>,
+) {
+    let points = &control_points.points;
+    let count = if enabled.0 { points.len() } else { 0 };
+    let shown = clean_capture.label_visibility();
+    let mut existing = 0;
+    for (entity, label, mut text, mut transform, mut visibility) in labels.iter_mut() {
+        if label.0 >= count {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        existing += 1;
+        let point = &points[label.0];
+        let content = coordinate_label_text(point.position);
+        if text.0 != content {
+            text.0 = content;
+        }
+        let target = coordinate_label_transform(point, view.0);
+        if *transform != target {
+            *transform = target;
+        }
+        visibility.set_if_neq(shown);
+    }
+    // 标签总是覆盖 0..existing，直接补上后面的索引
+    for (index, point) in points.iter().enumerate().take(count).skip(existing) {
+        commands.spawn((
+            CoordinateLabel(index),
+            Text2d::new(coordinate_label_text(point.position)),
+            TextFont {
+                font_size: COORDINATE_LABEL_FONT_SIZE,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Anchor::BottomLeft,
+            coordinate_label_transform(point, view.0),
+            shown,
+        ));
+    }
+}
+
+// 与 sync_coordinate_labels 相同，标签放在点的左上方
+fn sync_index_labels(
+    mut commands: Commands,
+    enabled: Res<IndexLabels>,
+    control_points: Res<ControlPoints>,
+    mut labels: Query<(Entity, &IndexLabel, &mut Transform, &mut Visibility)>,
+    view: Res<ViewScale>,
+    clean_capture: Res<CleanCapture>,
+) {
+    let points = &control_points.points;
+    let count = if enabled.0 { points.len() } else { 0 };
+    let shown = clean_capture.label_visibility();
+    let mut existing = 0;
+    for (entity, label, mut transform, mut visibility) in labels.iter_mut() {
+        if label.0 >= count {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        existing += 1;
+        let target = index_label_transform(&points[label.0], view.0);
+        if *transform != target {
+            *transform = target;
+        }
+        visibility.set_if_neq(shown);
+    }
+    for (index, point) in points.iter().enumerate().take(count).skip(existing) {
+        commands.spawn((
+            IndexLabel(index),
+            Text2d::new(index.to_string()),
+            TextFont {
+                font_size: COORDINATE_LABEL_FONT_SIZE,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Anchor::BottomRight,
+            index_label_transform(point, view.0),
+            shown,
+        ));
+    }
+}
+
+fn update_probe_feet(
+    probe: Res<Probe>,
+    built: Res<BuiltCurves>,
+    visibility: Res<CurveVisibility>,
+    mut feet: ResMut<ProbeFeet>,
+) {
+    if !probe.is_changed() && !built.is_changed() && !visibility.is_changed() {
+        return;
+    }
+    feet.0.clear();
+    let Some(position) = probe.position else {
+        return;
+    };
+    // 控制多边形不是曲线，不求垂足
+    for kind in CurveKind::ALL {
+        if kind == CurveKind::ControlPolygon || !visibility.is_visible(kind) {
+            continue;
+        }
+        let Some(curve) = built.curve(kind) else {
+            continue;
+        };
+        let (_, foot) = closest_point_on_curve(curve, position);
+        feet.0.push(ProbeFoot {
+            kind,
+            position: foot,
+            distance: foot.distance(position),
+        });
+    }
+}
+
+// 标签放在探针与垂足连线的中点，和坐标标签一样随缩放保持屏幕大小
+fn sync_probe_labels(
+    mut commands: Commands,
+    probe: Res<Probe>,
+    feet: Res<ProbeFeet>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+    clean_capture: Res<CleanCapture>,
+    mut labels: Query<(
+        Entity,
+        &ProbeLabel,
+        &mut Text2d,
+        &mut TextColor,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    let Some(probe_position) = probe.position else {
+        for (entity, ..) in labels.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+    let label = |foot: &ProbeFoot| {
+        let text = format!("{}: {:.1}", foot.kind.name(), foot.distance);
+        let color = TextColor(style.color(foot.kind).into());
+        let anchor =
+            probe_position.lerp(foot.position, 0.5) + Vec2::splat(COORDINATE_LABEL_GAP * view.0);
+        let transform =
+            Transform::from_translation(anchor.extend(0.0)).with_scale(Vec3::splat(view.0));
+        (text, color, transform)
+    };
+    let shown = clean_capture.label_visibility();
+    let mut existing = 0;
+    for (entity, probe_label, mut text, mut color, mut transform, mut visibility) in
+        labels.iter_mut()
+    {
+        let Some(foot) = feet.0.get(probe_label.0) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        existing += 1;
+        let (content, target_color, target) = label(foot);
+        if text.0 != content {
+            text.0 = content;
+        }
+        if *color != target_color {
+            *color = target_color;
+        }
+        if *transform != target {
+            *transform = target;
+        }
+        visibility.set_if_neq(shown);
+    }
+    for (index, foot) in feet.0.iter().enumerate().skip(existing) {
+        let (content, color, transform) = label(foot);
+        commands.spawn((
+            ProbeLabel(index),
+            Text2d::new(content),
+            TextFont {
+                font_size: COORDINATE_LABEL_FONT_SIZE,
+                ..default()
+            },
+            color,
+            Anchor::BottomLeft,
+            transform,
+            shown,
+        ));
+    }
+}
+
+fn coordinate_label_text(position: Vec2) -> String {
+    format!("({:.1}, {:.1})", position.x, position.y)
+}
+
+// 放在点的右上方，圆的外切正方形之外
+// 缩放时文字和间距跟着缩放，在屏幕上大小不变
+fn coordinate_label_transform(point: &MovablePoint, scale: f32) -> Transform {
+    let offset = Vec2::splat((point.selected_size + COORDINATE_LABEL_GAP) * scale);
+    Transform::from_translation((point.position + offset).extend(0.0))
+        .with_scale(Vec3::splat(scale))
+}
+
+fn index_label_transform(point: &MovablePoint, scale: f32) -> Transform {
+    let offset = Vec2::new(-1.0, 1.0) * (point.selected_size + COORDINATE_LABEL_GAP) * scale;
+    Transform::from_translation((point.position + offset).extend(0.0))
+        .with_scale(Vec3::splat(scale))
+}
+
+// 光标移动时每帧重新计算；关闭时清空，绘制和 HUD 都不再显示
+fn update_closest_point(
+    cursor: WorldCursor,
+    built: Res<BuiltCurves>,
+    active: Res<ActiveCurve>,
+    mut closest: ResMut<ClosestPoint>,
+) {
+    let hit = closest
+        .enabled
+        .then(|| {
+            let point = cursor.position()?;
+            let curve = built.curve(active.0)?;
+            Some(closest_point_on_curve(curve, point))
+        })
+        .flatten();
+    if closest.hit != hit {
+        closest.hit = hit;
+    }
+}
+
+fn update_curvature_features(
+    overlay: Res<CurvatureFeatureOverlay>,
+    built: Res<BuiltCurves>,
+    active: Res<ActiveCurve>,
+    mut features: ResMut<CurvatureFeatures>,
+) {
+    if !overlay.is_changed() && !built.is_changed() && !active.is_changed() {
+        return;
+    }
+    features.0 = if overlay.0 {
+        built
+            .curve(active.0)
+            .map(curvature_features)
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+}
+
+// 例如 "Inflections: t = 0.50 | Curvature maxima: t = 0.19, 0.81"
+fn curvature_feature_text(features: &[CurvatureFeature]) -> String {
+    let list = |kind: FeatureKind| {
+        let params: Vec<f32> = features
+            .iter()
+            .filter(|f| f.kind == kind)
+            .map(|f| f.t)
+            .collect();
+        if params.is_empty() {
+            return "none".to_string();
+        }
+        let mut text = params
+            .iter()
+            .take(FEATURE_TEXT_LIMIT)
+            .map(|t| format!("{t:.2}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if params.len() > FEATURE_TEXT_LIMIT {
+            text += &format!(" (+{} more)", params.len() - FEATURE_TEXT_LIMIT);
+        }
+        format!("t = {text}")
+    };
+    format!(
+        "Inflections: {} | Curvature maxima: {}",
+        list(FeatureKind::Inflection),
+        list(FeatureKind::Maximum)
+    )
+}
+
+fn update_curvature_feature_text(
+    overlay: Res<CurvatureFeatureOverlay>,
+    features: Res<CurvatureFeatures>,
+    mut text: Query<&mut Text, With<CurvatureFeatureText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    let content = if overlay.0 {
+        curvature_feature_text(&features.0)
+    } else {
+        String::new()
+    };
+    if text.0 != content {
+        **text = content;
+    }
+}
+
+fn update_deviation_text(
+    pair: Res<DeviationPair>,
+    deviation: Res<CurveDeviation>,
+    mut text: Query<&mut Text, With<DeviationText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    let (from, to) = pair.kinds();
+    let content = match deviation.0 {
+        Some(deviation) => format!(
+            "{} vs {}: max {:.2}, mean {:.2}",
+            from.name(),
+            to.name(),
+            deviation.max,
+            deviation.mean
+        ),
+        None => format!("{} vs {}: show both to compare", from.name(), to.name()),
+    };
+    if text.0 != content {
+        **text = content;
+    }
+}
+
+fn update_intersection_text(
+    overlay: Res<IntersectionOverlay>,
+    intersections: Res<CurveIntersections>,
+    mut text: Query<&mut Text, With<IntersectionText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    let content = match (overlay.crossings, overlay.self_crossings) {
+        (false, _) => String::new(),
+        (true, false) => format!("Intersections: {}", intersections.crossings.len()),
+        (true, true) => format!(
+            "Intersections: {}, self-intersections: {}",
+            intersections.crossings.len(),
+            intersections.self_crossings.len()
+        ),
+    };
+    if text.0 != content {
+        **text = content;
+    }
+}
+
+fn update_closest_point_text(
+    closest: Res<ClosestPoint>,
+    active: Res<ActiveCurve>,
+    mut text: Query<&mut Text, With<ClosestPointText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    let content = match closest.hit {
+        Some((t, position)) => format!(
+            "Closest on {}: t = {t:.3} ({:.1}, {:.1})",
+            active.0.name(),
+            position.x,
+            position.y
+        ),
+        None => String::new(),
+    };
+    if text.0 != content {
+        **text = content;
+    }
+}
+
+fn update_selection_text(
+    control_points: Res<ControlPoints>,
+    mut text: Query<&mut Text, With<SelectionText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    let selected = control_points
+        .points
+        .iter()
+        .filter(|p| p.is_selected)
+        .count();
+    let content = match control_points.single_selected() {
+        Some(index) => format!("Point {} / {}", index + 1, control_points.points.len()),
+        None if selected > 1 => format!("{selected} points selected"),
+        None => String::new(),
+    };
+    if text.0 != content {
+        **text = content;
+    }
+}
+
+// 保存和导出的文件放在可执行文件旁边，取不到可执行文件路径时使用当前目录
+// 读取 keybindings.ron，问题逐条写进日志；2D 和 3D 模式共用
+fn load_key_bindings() -> (KeyBindings, Vec<String>) {
+    let (bindings, warnings) = KeyBindings::load(Path::new(KEY_BINDINGS_FILE_NAME));
+    for warning in &warnings {
+        warn!("{KEY_BINDINGS_FILE_NAME}: {warning}");
+    }
+    (bindings, warnings)
+}
+
+// 读取 showcase_settings.ron；文件不存在时使用默认设置，损坏或版本不符时同样使用默认设置并写进日志
+fn load_settings() -> Settings {
+    let mut settings = match settings::load(Path::new(SETTINGS_FILE_NAME)) {
+        Ok(settings) => settings,
+        Err(SettingsError::Io(error)) if error.kind() == std::io::ErrorKind::NotFound => {
+            Settings::default()
+        }
+        Err(error) => {
+            warn!("{SETTINGS_FILE_NAME}: {error}; using the default settings");
+            Settings::default()
+        }
+    };
+    // 手动改过的文件里可能有超出范围的值
+    settings.resolution.0 = settings
+        .resolution
+        .0
+        .clamp(CurveResolution::MIN, CurveResolution::MAX);
+    if settings.active_curve == CurveKind::ControlPolygon {
+        settings.active_curve = ActiveCurve::default().0;
+    }
+    if !(settings.comb_scale.is_finite() && settings.comb_scale > 0.0) {
+        settings.comb_scale = CurvatureComb::default().scale;
+    }
+    settings.playback_rate = settings
+        .playback_rate
+        .clamp(CurveAnimation::MIN_RATE, CurveAnimation::MAX_RATE);
+    settings.offset_distance = settings
+        .offset_distance
+        .clamp(OffsetOverlay::MIN, OffsetOverlay::MAX);
+    settings.random_count = settings
+        .random_count
+        .clamp(RandomScatter::MIN_COUNT, RandomScatter::MAX_COUNT);
+    settings.sketch_tolerance = settings
+        .sketch_tolerance
+        .clamp(Sketch::MIN_TOLERANCE, Sketch::MAX_TOLERANCE);
+    settings.fit_control_count = settings
+        .fit_control_count
+        .clamp(MIN_CONTROL_POINTS, LeastSquaresFit::MAX);
+    settings
+}
+
+// 设置文件的写入状态。written 是文件中现在的内容，收集出来的设置与它相同时不写；
+// camera 是读出的相机位置，启动时由 restore_camera 使用；
+// last 是上一次看到的设置，只保存部分字段的资源与它按值比较
+#[derive(Resource)]
+struct SettingsPersistence {
+    written: String,
+    pending: Option<f32>,
+    camera: Option<SavedCamera>,
+    last: Settings,
+}
+
+impl SettingsPersistence {
+    fn new(settings: &Settings) -> Self {
+        Self {
+            written: settings::to_ron(settings).unwrap_or_default(),
+            pending: None,
+            camera: settings.camera,
+            last: settings.clone(),
+        }
+    }
+}
+
+fn restore_camera(
+    persistence: Res<SettingsPersistence>,
+    mut view: ResMut<ViewScale>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    let (Some(saved), Ok((mut transform, mut projection))) =
+        (persistence.camera, camera.get_single_mut())
+    else {
+        return;
+    };
+    let scale = saved.scale.clamp(ViewScale::MIN, ViewScale::MAX);
+    transform.translation = saved.center.extend(transform.translation.z);
+    projection.scale = scale;
+    view.0 = scale;
+}
+
+// 设置或相机改动后等 SETTINGS_SAVE_DELAY 秒写入；退出的那一帧把还没写入的改动立即写入
+fn persist_settings(
+    resources: SettingsResources,
+    camera: Query<(Ref<Transform>, Ref<OrthographicProjection>), With<Camera>>,
+    time: Res<Time>,
+    mut exit: EventReader<AppExit>,
+    mut persistence: ResMut<SettingsPersistence>,
+) {
+    let camera = camera.get_single().ok();
+    let camera_changed = camera
+        .as_ref()
+        .is_some_and(|(transform, projection)| transform.is_changed() || projection.is_changed());
+    let fields_changed = resources.fields_differ(&persistence.last);
+    if fields_changed {
+        persistence.last = resources.to_settings(None);
+    }
+    if resources.is_changed() || fields_changed || camera_changed {
+        persistence.pending = Some(SETTINGS_SAVE_DELAY);
+    }
+    let exiting = exit.read().count() > 0;
+    let Some(remaining) = &mut persistence.pending else {
+        return;
+    };
+    *remaining -= time.delta_secs();
+    if *remaining > 0.0 && !exiting {
+        return;
+    }
+    persistence.pending = None;
+    let camera = camera.map(|(transform, projection)| SavedCamera {
+        center: transform.translation.truncate(),
+        scale: projection.scale,
+    });
+    let text = match settings::to_ron(&resources.to_settings(camera)) {
+        Ok(text) => text,
+        Err(error) => {
+            warn!("{SETTINGS_FILE_NAME}: {error}");
+            return;
+        }
+    };
+    if text == persistence.written {
+        return;
+    }
+    match std::fs::write(SETTINGS_FILE_NAME, &text) {
+        Ok(()) => persistence.written = text,
+        Err(error) => warn!("{SETTINGS_FILE_NAME}: {error}"),
+    }
+}
+
+fn output_path(file_name: &str) -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(file_name)))
+        .unwrap_or_else(|| PathBuf::from(file_name))
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+// Shift+F12 截图时隐藏的界面节点及其原来的可见性，下一帧恢复
+#[derive(Default, Resource)]
+struct CleanCapture {
+    hidden: Vec<(Entity, Visibility)>,
+}
+
+impl CleanCapture {
+    // HUD 类的 gizmo 在隐藏界面的这一帧也不绘制
+    fn is_hiding(&self) -> bool {
+        !self.hidden.is_empty()
+    }
+
+    // 点旁边的 Text2d 标签不是界面节点，由各自的同步系统按这个可见性隐藏
+    fn label_visibility(&self) -> Visibility {
+        if self.is_hiding() {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        }
+    }
+}
+
+// 正在后台写入的导出文件，完成后在提示信息中报告结果
+#[derive(Default, Resource)]
+struct PendingExports(Vec<Task<Result<PathBuf, String>>>);
+
+fn poll_exports(mut pending: ResMut<PendingExports>, mut status: ResMut<StatusMessage>) {
+    pending
+        .0
+        .retain_mut(|task| match block_on(future::poll_once(task)) {
+            Some(Ok(path)) => {
+                status.info(format!("Exported {}", path.display()));
+                false
+            }
+            Some(Err(error)) => {
+                status.error(format!("Export failed: {error}"));
+                false
+            }
+            None => true,
+        });
+}
+
+fn save_points(control_points: &ControlPoints, morph: &Morph, status: &mut StatusMessage) {
+    let path = output_path(POINTS_FILE_NAME);
+    let slot = |i: usize| {
+        morph.slots[i]
+            .as_ref()
+            .map(|points| points.iter().map(MovablePoint::to_saved).collect())
+    };
+    let scene = SavedScene {
+        slot_a: slot(0),
+        slot_b: slot(1),
+        ..control_points.to_saved()
+    };
+    match persistence::save(&path, &scene) {
+        Ok(()) => status.info(format!("Saved {}", path.display())),
+        Err(error) => status.error(format!("Save failed: {error}")),
+    }
+}
+
+fn load_points(
+    control_points: &mut ControlPoints,
+    morph: &mut Morph,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+    status: &mut StatusMessage,
+) {
+    let path = output_path(POINTS_FILE_NAME);
+    let result = persistence::load(&path);
+    apply_loaded_points(
+        result,
+        &path,
+        control_points,
+        morph,
+        history,
+        session,
+        status,
+    );
+}
+
+fn save_session(session: &Session, status: &mut StatusMessage) {
+    let path = output_path(SESSION_FILE_NAME);
+    let result = session::to_ron(session)
+        .map_err(|error| error.to_string())
+        .and_then(|text| std::fs::write(&path, text).map_err(|error| error.to_string()));
+    match result {
+        Ok(()) => status.info(format!(
+            "Recorded {} edits to {}",
+            session.events.len(),
+            path.display()
+        )),
+        Err(error) => status.error(format!("Saving the recording failed: {error}")),
+    }
+}
+
+fn load_session(status: &mut StatusMessage) -> Option<Replay> {
+    let path = output_path(SESSION_FILE_NAME);
+    let result = std::fs::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|text| session::from_ron(&text).map_err(|error| error.to_string()));
+    match result {
+        Ok(session) => {
+            status.info(format!("Replaying {} edits", session.events.len()));
+            Some(Replay::new(session))
+        }
+        Err(error) => {
+            status.error(format!("Replay failed: {}: {error}", path.display()));
+            None
+        }
+    }
+}
+
+// Ctrl+O、设置面板和拖放文件共用：成功时替换控制点，失败时显示错误。
+// 文件里保存了插值的槽时一并替换，没有保存的槽（例如 CSV）保持不变
+fn apply_loaded_points(
+    result: Result<SavedScene, PersistError>,
+    path: &Path,
+    control_points: &mut ControlPoints,
+    morph: &mut Morph,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+    status: &mut StatusMessage,
+) {
+    match result {
+        Ok(mut scene) => {
+            history.0.record(control_points.snapshot());
+            for (slot, saved) in morph
+                .slots
+                .iter_mut()
+                .zip([scene.slot_a.take(), scene.slot_b.take()])
+            {
+                if let Some(saved) = saved {
+                    *slot = Some(saved.into_iter().map(MovablePoint::from_saved).collect());
+                }
+            }
+            control_points.load_saved(scene);
+            session.record_snapshot(control_points);
+            status.info(format!("Loaded {}", path.display()));
+        }
+        Err(error) => status.error(format!("Load failed: {error}")),
+    }
+}
+
+fn update_diagnostics_text(
+    overlay: Res<DiagnosticsOverlay>,
+    store: Res<DiagnosticsStore>,
+    mut text: Query<&mut Text, With<DiagnosticsText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    let content = if overlay.0 {
+        let fps = store
+            .get(&FrameTimeDiagnosticsPlugin::FPS)
+            .and_then(Diagnostic::smoothed);
+        let frame_time = store
+            .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+            .and_then(Diagnostic::smoothed);
+        let curve_time = store.get(&CURVE_BUILD_TIME).and_then(Diagnostic::average);
+        format!(
+            "FPS {}  frame {}\ncurves {}",
+            format_diagnostic(fps, 0),
+            format_millis(frame_time),
+            format_millis(curve_time),
+        )
+    } else {
+        String::new()
+    };
+    if text.0 != content {
+        **text = content;
+    }
+}
+
+// 诊断还没有数据时显示占位符
+fn format_diagnostic(value: Option<f64>, precision: usize) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{v:.precision$}"))
+}
+
+fn format_millis(value: Option<f64>) -> String {
+    format!("{} ms", format_diagnostic(value, 2))
+}
+
+fn update_status_text(
+    time: Res<Time>,
+    mut status: ResMut<StatusMessage>,
+    mut text: Query<(&mut Text, &mut TextColor), With<StatusText>>,
+) {
+    let Ok((mut text, mut color)) = text.get_single_mut() else {
+        return;
+    };
+    if status.remaining > 0.0 {
+        status.remaining -= time.delta_secs();
+        if status.remaining <= 0.0 {
+            status.text.clear();
+        }
+    }
+    if text.0 != status.text {
+        **text = status.text.clone();
+        color.0 = if status.is_error { TOMATO } else { WHITE }.into();
+    }
+}
+
+fn undo_edit(
+    control_points: &mut ControlPoints,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+) {
+    if let Some(snapshot) = history.0.undo(control_points.snapshot()) {
+        control_points.restore(snapshot);
+        session.record_snapshot(control_points);
+    }
+}
+
+fn redo_edit(
+    control_points: &mut ControlPoints,
+    history: &mut EditHistory,
+    session: &mut SessionRecorder,
+) {
+    if let Some(snapshot) = history.0.redo(control_points.snapshot()) {
+        control_points.restore(snapshot);
+        session.record_snapshot(control_points);
+    }
+}
+
+/// 2D 样条演示：注册所有资源、gizmo 配置组和系统。
+///
+/// 可以用 [`SplineShowcasePlugin::with_points`] 预先放入控制点，或用
+/// [`SplineShowcasePlugin::from_options`] 按命令行参数设置初始点集和采样数；
+/// [`SplineShowcasePlugin::headless`] 不注册绘制相关的部分，用于没有渲染的 App（例如测试）。
+/// 无头模式下缺少的输入资源和事件由插件补上，测试不加 `InputPlugin` 时可以直接写入
+/// `ButtonInput` 和 [`MousePosition`]，并用 [`ScreenToWorld::IDENTITY`] 代替相机。
+#[derive(Default)]
+pub struct SplineShowcasePlugin {
+    initial_points: Vec<MovablePoint>,
+    closed: bool,
+    resolution: Option<usize>,
+    seed: Option<u64>,
+    random_count: Option<usize>,
+    headless: bool,
+    // 为 false 时不读取也不保存设置文件
+    persist: bool,
+}
+
+impl SplineShowcasePlugin {
+    pub fn with_points(points: impl IntoIterator<Item = Vec2>) -> Self {
+        Self {
+            initial_points: points.into_iter().map(MovablePoint::new).collect(),
+            ..default()
+        }
+    }
+
+    /// 点文件在这里读取，读取失败时返回的错误带有文件路径。
+    pub fn from_options(options: &cli::Options) -> Result<Self, cli::CliError> {
+        let scene = match (&options.points, options.preset) {
+            (Some(path), _) => persistence::load(path).map_err(|error| cli::CliError::Points {
+                path: path.clone(),
+                message: error.to_string(),
+            })?,
+            (None, Some(preset)) => preset.scene(Vec2::ZERO, PRESET_RADIUS),
+            (None, None) => SavedScene::default(),
+        };
+        let mut control_points = ControlPoints::default();
+        control_points.load_saved(scene);
+        Ok(Self {
+            initial_points: control_points.points,
+            closed: control_points.closed,
+            resolution: options.resolution,
+            seed: options.seed,
+            random_count: options.random_count,
+            persist: !options.no_persist,
+            ..default()
+        })
+    }
+
+    pub fn headless(mut self) -> Self {
+        self.headless = true;
+        self
+    }
+}
+
+impl Plugin for SplineShowcasePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        let control_points = ControlPoints {
+            points: self.initial_points.clone(),
+            closed: self.closed,
+            ..default()
+        };
+        let (key_bindings, warnings) = load_key_bindings();
+        let mut status = StatusMessage::default();
+        if !warnings.is_empty() {
+            status.error(format!(
+                "{KEY_BINDINGS_FILE_NAME}: {} problem(s), see the log",
+                warnings.len()
+            ));
+        }
+        // 保存的设置先插入，命令行给出的分辨率和撒点个数覆盖它
+        let mut settings = if self.persist {
+            load_settings()
+        } else {
+            Settings::default()
+        };
+        if let Some(count) = self.random_count {
+            settings.random_count = count;
+        }
+        if self.persist {
+            app.insert_resource(SettingsPersistence::new(&settings))
+                .add_systems(Startup, restore_camera.after(setup))
+                .add_systems(Last, persist_settings);
+        }
+        settings.insert_into(app);
+        if let Some(resolution) = self.resolution {
+            app.insert_resource(CurveResolution(resolution));
+        }
+        app.register_diagnostic(
+            Diagnostic::new(CURVE_BUILD_TIME)
+                .with_suffix(" ms")
+                .with_max_history_length(CURVE_BUILD_TIME_HISTORY),
+        )
+        .insert_resource(MousePosition::default())
+        .insert_resource(PointerState::default())
+        .init_resource::<ScreenToWorld>()
+        .insert_resource(control_points)
+        .insert_resource(DragState::default())
+        .insert_resource(TouchGesture::default())
+        .insert_resource(EditHistory::default())
+        .insert_resource(status)
+        .insert_resource(PendingExports::default())
+        .insert_resource(CleanCapture::default())
+        .insert_resource(ActiveCurve::default())
+        .insert_resource(CurvatureComb::default())
+        .insert_resource(TangentOverlay::default())
+        .insert_resource(ClosestPoint::default())
+        .insert_resource(IntersectionOverlay::default())
+        .insert_resource(CurveIntersections::default())
+        .insert_resource(BoundsOverlay::default())
+        .insert_resource(CurvatureFeatureOverlay::default())
+        .insert_resource(CurvatureFeatures::default())
+        .insert_resource(BezierSplit::default())
+        .insert_resource(CasteljauConstruction::default())
+        .insert_resource(DegreeElevation::default())
+        .insert_resource(KnotInsertion::default())
+        .insert_resource(ClearAllPrompt::default())
+        .init_resource::<HoveredPoint>()
+        .insert_resource(OffsetOverlay::default())
+        .insert_resource(DeviationPair::default())
+        .insert_resource(CurveDeviation::default())
+        .insert_resource(Probe::default())
+        .insert_resource(ProbeFeet::default())
+        .insert_resource(CurveStats::default())
+        .insert_resource(RandomScatter {
+            next_seed: self.seed,
+            count: self.random_count.unwrap_or(RandomScatter::DEFAULT_COUNT),
+        })
+        .insert_resource(HeatmapScale::default())
+        .insert_resource(PointerCaptured::default())
+        .insert_resource(CameraPan::default())
+        .insert_resource(ViewScale::default())
+        .insert_resource(ParameterizationComparison::default())
+        .insert_resource(LeastSquaresFit::default())
+        .insert_resource(Sketch::default())
+        .init_resource::<Morph>()
+        .init_resource::<ModalTransform>()
+        .init_resource::<SessionRecorder>()
+        .insert_resource(DiagnosticsOverlay::default())
+        .insert_resource(HelpOverlay::default())
+        .insert_resource(key_bindings)
+        .insert_resource(CurveAnimation::default())
+        .insert_resource(MarkerArcLength::default())
+        .insert_resource(TimelineScrub::default())
+        .init_resource::<PointIndex>()
+        .init_resource::<CachedCurves>()
+        .init_resource::<DisplayPoints>()
+        .init_resource::<CoincidentPoints>()
+        .init_resource::<SampledCurves>()
+        .init_resource::<BuiltCurves>()
+        .add_event::<CurveChanged>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                (
+                    index_points,
+                    toggle_help,
+                    session_keys.run_if(help_closed),
+                    (
+                        modal_transform,
+                        handle_keypress,
+                        point_list_keys,
+                        symmetry_keys,
+                        handle_view_keys,
+                        handle_undo_keys,
+                        nudge_points_with_arrows,
+                        reorder_selected_point,
+                        align_selection_keys,
+                        fit_view_keys,
+                        animation_keys,
+                        save_load_keys,
+                        morph_keys,
+                        clipboard_keys,
+                        preset_keys,
+                        random_points_keys,
+                        probe_keys,
+                        insert_knot_keys,
+                        overlay_keys,
+                        load_dropped_file,
+                    )
+                        .chain()
+                        .run_if(help_closed),
+                    export_csv_keys,
+                    export_svg_keys,
+                    screenshot_keys,
+                    poll_exports,
+                    update_least_squares_fit,
+                )
+                    .chain(),
+                (
+                    handle_mouse_move,
+                    move_crosshair_with_stick,
+                    index_points,
+                    (
+                        scrub_timeline,
+                        pan_camera,
+                        delete_point_with_shift_click,
+                        split_bezier_with_click,
+                        sketch_with_mouse,
+                        drag_probe,
+                        move_point_with_mouse,
+                        gamepad_buttons,
+                        add_point_with_right_mouse,
+                        remove_point_with_middle_mouse,
+                        handle_touches,
+                        toggle_lock_with_p,
+                        update_hovered_point,
+                        adjust_weight_with_scroll,
+                        zoom_with_scroll,
+                    )
+                        .chain()
+                        .run_if(help_closed),
+                )
+                    .chain(),
+                // 不绘制时也更新采样，宿主程序的系统可以读取 SampledCurves 和 CurveChanged；
+                // 后面的分析读 BuiltCurves，所以排在重建之后
+                (
+                    ease_display_points,
+                    update_curve_cache,
+                    advance_animation,
+                    update_closest_point,
+                    update_probe_feet,
+                    update_curvature_features,
+                )
+                    .chain(),
+                (
+                    sync_coordinate_labels,
+                    sync_index_labels,
+                    sync_probe_labels,
+                    update_monotone_hint,
+                    update_legend,
+                    update_arc_length_hud,
+                    update_point_count_text,
+                    update_selection_text,
+                    update_closest_point_text,
+                    update_intersection_text,
+                    update_curvature_feature_text,
+                    update_deviation_text,
+                    update_status_text,
+                    update_diagnostics_text,
+                    update_direction_arrow,
+                )
+                    .chain(),
+            )
+                .chain(),
+        );
+        // 只保存部分字段的资源在上面插入之后才能写回
+        settings.apply_fields(app.world_mut());
+        if self.headless {
+            app.init_resource::<ButtonInput<KeyCode>>()
+                .init_resource::<ButtonInput<MouseButton>>()
+                .init_resource::<Touches>()
+                .add_event::<MouseWheel>()
+                .add_event::<PinchGesture>();
+            return;
+        }
+        // 绘制在输入之后、界面文字之前
+        app.add_plugins(CurveGizmosPlugin)
+            .init_gizmo_group::<PointGizmos>()
+            .init_gizmo_group::<OverlayGizmos>()
+            .init_resource::<OffsetCurves>()
+            .init_resource::<InactiveListCurves>()
+            .init_resource::<GhostPreview>()
+            .add_systems(Startup, setup_rendering)
+            .add_systems(
+                Update,
+                (
+                    (
+                        apply_curve_style,
+                        plot_grid,
+                        plot_point,
+                        plot_selection_box,
+                        update_ghost_preview,
+                        update_intersections,
+                        update_offset_curves,
+                        update_curve_deviation,
+                        update_inactive_list_curves,
+                    )
+                        .chain(),
+                    (
+                        (
+                            plot_inactive_lists,
+                            plot_symmetry,
+                            plot_ghost_preview,
+                            plot_line,
+                            plot_parameterization_comparison,
+                            plot_least_squares_fit,
+                            plot_sketch,
+                        )
+                            .chain(),
+                        update_curve_meshes,
+                        plot_curvature_comb,
+                        plot_tangent_overlay,
+                        plot_velocity_strip,
+                        plot_osculating_circle,
+                        plot_closest_point,
+                        plot_intersections,
+                        plot_coincident_points,
+                        plot_bounds,
+                        plot_curvature_features,
+                        plot_bezier_split,
+                        plot_casteljau_construction,
+                        plot_degree_elevation,
+                        plot_knot_insertion,
+                        plot_offset_curves,
+                        plot_probe,
+                        plot_crosshair,
+                        plot_timeline,
+                        update_animation_marker,
+                    )
+                        .chain(),
+                )
+                    .chain()
+                    .after(update_curvature_features)
+                    .before(sync_coordinate_labels),
+            );
+        #[cfg(feature = "egui")]
+        app.add_plugins(panel::SettingsPanelPlugin);
+    }
+}
@@ -0,0 +1,137 @@
+//! 选中的点的模态变换，类似 Blender 的 G/R/S。变换开始时记下光标位置和枢轴（选中点的重心），
+//! 之后每一帧都由原来的位置和光标的当前位置重新计算，不会累积误差。
+
+use bevy::math::{Rot2, Vec2};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransformKind {
+    /// 跟随光标平移
+    Grab,
+    /// 绕枢轴旋转，角度是光标相对枢轴转过的角度
+    Rotate,
+    /// 以枢轴为中心缩放，比例是光标到枢轴的距离之比
+    Scale,
+}
+
+// 开始时光标离枢轴太近，方向和距离都没有意义
+const MIN_START_DISTANCE: f32 = 1e-3;
+
+/// 光标从 `start` 移到 `cursor` 时一次变换的参数。
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PointTransform {
+    pub kind: TransformKind,
+    pub pivot: Vec2,
+    pub start: Vec2,
+    pub cursor: Vec2,
+}
+
+impl PointTransform {
+    pub fn translation(&self) -> Vec2 {
+        self.cursor - self.start
+    }
+
+    /// 逆时针为正的弧度。
+    pub fn angle(&self) -> f32 {
+        let (from, to) = (self.start - self.pivot, self.cursor - self.pivot);
+        if from.length() < MIN_START_DISTANCE || to.length() < MIN_START_DISTANCE {
+            return 0.0;
+        }
+        from.angle_to(to)
+    }
+
+    pub fn scale(&self) -> f32 {
+        let from = self.start.distance(self.pivot);
+        if from < MIN_START_DISTANCE {
+            return 1.0;
+        }
+        self.cursor.distance(self.pivot) / from
+    }
+
+    /// 变换后的位置。
+    pub fn apply(&self, position: Vec2) -> Vec2 {
+        match self.kind {
+            TransformKind::Grab => position + self.translation(),
+            TransformKind::Rotate => {
+                self.pivot + Rot2::radians(self.angle()) * (position - self.pivot)
+            }
+            TransformKind::Scale => self.pivot + (position - self.pivot) * self.scale(),
+        }
+    }
+
+    /// 变换后的切线：平移不改变切线，旋转时一起旋转，缩放时一起缩放。
+    pub fn apply_vector(&self, vector: Vec2) -> Vec2 {
+        match self.kind {
+            TransformKind::Grab => vector,
+            TransformKind::Rotate => Rot2::radians(self.angle()) * vector,
+            TransformKind::Scale => vector * self.scale(),
+        }
+    }
+
+    /// 显示在状态栏的数值。
+    pub fn describe(&self) -> String {
+        match self.kind {
+            TransformKind::Grab => {
+                let d = self.translation();
+                format!("Grab dx {:.1} dy {:.1}", d.x, d.y)
+            }
+            TransformKind::Rotate => format!("Rotate {:.1}°", self.angle().to_degrees()),
+            TransformKind::Scale => format!("Scale ×{:.3}", self.scale()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(kind: TransformKind, cursor: Vec2) -> PointTransform {
+        PointTransform {
+            kind,
+            pivot: Vec2::new(10.0, 10.0),
+            start: Vec2::new(20.0, 10.0),
+            cursor,
+        }
+    }
+
+    fn close(a: Vec2, b: Vec2) -> bool {
+        a.distance(b) < 1e-4
+    }
+
+    #[test]
+    fn grab_follows_the_cursor() {
+        let t = transform(TransformKind::Grab, Vec2::new(25.0, 2.0));
+        assert_eq!(t.apply(Vec2::ZERO), Vec2::new(5.0, -8.0));
+        assert_eq!(t.apply_vector(Vec2::X), Vec2::X);
+        assert_eq!(t.describe(), "Grab dx 5.0 dy -8.0");
+    }
+
+    #[test]
+    fn rotate_follows_the_cursor_angle() {
+        // 光标从枢轴右边转到上边，逆时针 90°
+        let t = transform(TransformKind::Rotate, Vec2::new(10.0, 40.0));
+        assert!((t.angle() - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+        assert!(close(t.apply(Vec2::new(10.0, 0.0)), Vec2::new(20.0, 10.0)));
+        assert!(close(t.apply_vector(Vec2::X), Vec2::Y));
+        assert_eq!(t.describe(), "Rotate 90.0°");
+        // 枢轴本身不动
+        assert!(close(t.apply(t.pivot), t.pivot));
+    }
+
+    #[test]
+    fn scale_uses_the_cursor_distance() {
+        let t = transform(TransformKind::Scale, Vec2::new(10.0, -15.0));
+        assert!((t.scale() - 2.5).abs() < 1e-6);
+        assert!(close(t.apply(Vec2::new(12.0, 10.0)), Vec2::new(15.0, 10.0)));
+        assert_eq!(t.describe(), "Scale ×2.500");
+    }
+
+    // 光标从枢轴上开始时不旋转也不缩放
+    #[test]
+    fn degenerate_start_is_the_identity() {
+        let mut t = transform(TransformKind::Scale, Vec2::new(50.0, 50.0));
+        t.start = t.pivot;
+        assert_eq!(t.scale(), 1.0);
+        t.kind = TransformKind::Rotate;
+        assert_eq!(t.angle(), 0.0);
+    }
+}
@@ -0,0 +1,176 @@
+//! Fritsch–Carlson 单调三次插值，把控制点当作函数 y = f(x)。
+//!
+//! 只有控制点按 x 严格单调时才有意义，此时插值结果在相邻数据点之间不会过冲。
+
+use bevy::math::Vec2;
+
+use crate::curve::{split_parameter, PlanarCurve};
+
+/// x 严格递增或严格递减时返回 true。
+pub fn is_x_monotone(points: &[Vec2]) -> bool {
+    points.windows(2).all(|w| w[1].x > w[0].x) || points.windows(2).all(|w| w[1].x < w[0].x)
+}
+
+pub struct MonotoneCubic {
+    xs: Vec<f32>,
+    ys: Vec<f32>,
+    slopes: Vec<f32>,
+}
+
+impl MonotoneCubic {
+    /// 点数少于 2 或 x 不单调时返回 None；x 递减的输入会被反转。
+    pub fn new(points: &[Vec2]) -> Option<Self> {
+        if points.len() < 2 || !is_x_monotone(points) {
+            return None;
+        }
+        let mut points = points.to_vec();
+        if points[0].x > points[1].x {
+            points.reverse();
+        }
+        let xs: Vec<f32> = points.iter().map(|p| p.x).collect();
+        let ys: Vec<f32> = points.iter().map(|p| p.y).collect();
+        let slopes = limited_slopes(&xs, &ys);
+        Some(Self { xs, ys, slopes })
+    }
+
+    // 第 k 段上局部参数 t（0..=1）处的三次 Hermite 插值
+    fn evaluate_segment(&self, k: usize, t: f32) -> f32 {
+        let h = self.xs[k + 1] - self.xs[k];
+        let t2 = t * t;
+        let t3 = t2 * t;
+        let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+        let h10 = t3 - 2.0 * t2 + t;
+        let h01 = -2.0 * t3 + 3.0 * t2;
+        let h11 = t3 - t2;
+        h00 * self.ys[k]
+            + h10 * h * self.slopes[k]
+            + h01 * self.ys[k + 1]
+            + h11 * h * self.slopes[k + 1]
+    }
+}
+
+// 参数 t 的整数部分是段索引，段内 x 线性变化
+impl PlanarCurve for MonotoneCubic {
+    fn segment_count(&self) -> usize {
+        self.xs.len() - 1
+    }
+
+    fn position(&self, t: f32) -> Vec2 {
+        let (k, u) = split_parameter(t, self.segment_count());
+        let x = self.xs[k] + (self.xs[k + 1] - self.xs[k]) * u;
+        Vec2::new(x, self.evaluate_segment(k, u))
+    }
+}
+
+// 先用相邻割线斜率估计切线，再按 Fritsch–Carlson 条件限制，保证每段单调
+fn limited_slopes(xs: &[f32], ys: &[f32]) -> Vec<f32> {
+    let n = xs.len();
+    let secants: Vec<f32> = (0..n - 1)
+        .map(|k| (ys[k + 1] - ys[k]) / (xs[k + 1] - xs[k]))
+        .collect();
+
+    let mut slopes = vec![0.0; n];
+    slopes[0] = secants[0];
+    slopes[n - 1] = secants[n - 2];
+    for k in 1..n - 1 {
+        // 局部极值处切线为零
+        if secants[k - 1] * secants[k] > 0.0 {
+            slopes[k] = (secants[k - 1] + secants[k]) * 0.5;
+        }
+    }
+
+    for k in 0..n - 1 {
+        if secants[k] == 0.0 {
+            slopes[k] = 0.0;
+            slopes[k + 1] = 0.0;
+            continue;
+        }
+        let alpha = slopes[k] / secants[k];
+        let beta = slopes[k + 1] / secants[k];
+        let radius = alpha.hypot(beta);
+        if radius > 3.0 {
+            let tau = 3.0 / radius;
+            slopes[k] = tau * alpha * secants[k];
+            slopes[k + 1] = tau * beta * secants[k];
+        }
+    }
+    slopes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monotone_points() -> Vec<Vec2> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 1.0),
+            Vec2::new(20.0, 1.5),
+            Vec2::new(30.0, 80.0),
+            Vec2::new(40.0, 81.0),
+            Vec2::new(60.0, 81.0),
+            Vec2::new(70.0, 200.0),
+        ]
+    }
+
+    #[test]
+    fn detects_x_monotonicity() {
+        assert!(is_x_monotone(&monotone_points()));
+        let mut reversed = monotone_points();
+        reversed.reverse();
+        assert!(is_x_monotone(&reversed));
+        let zigzag = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(5.0, 0.0),
+        ];
+        assert!(!is_x_monotone(&zigzag));
+        let repeated_x = [Vec2::new(0.0, 0.0), Vec2::new(0.0, 10.0)];
+        assert!(!is_x_monotone(&repeated_x));
+        assert!(MonotoneCubic::new(&zigzag).is_none());
+    }
+
+    #[test]
+    fn no_overshoot_between_adjacent_samples() {
+        let points = monotone_points();
+        let curve = MonotoneCubic::new(&points).unwrap();
+        let segments = curve.segment_count();
+        let samples: Vec<Vec2> = (0..=segments * 50)
+            .map(|i| curve.position(i as f32 / 50.0))
+            .collect();
+        assert!(samples.windows(2).all(|w| w[1].y >= w[0].y - 1e-4));
+        for w in points.windows(2) {
+            let (lo, hi) = (w[0].y.min(w[1].y), w[0].y.max(w[1].y));
+            for s in samples.iter().filter(|s| s.x >= w[0].x && s.x <= w[1].x) {
+                assert!(s.y >= lo - 1e-4 && s.y <= hi + 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn slopes_satisfy_fritsch_carlson_bound() {
+        let points = monotone_points();
+        let curve = MonotoneCubic::new(&points).unwrap();
+        for (k, w) in points.windows(2).enumerate() {
+            let secant = (w[1].y - w[0].y) / (w[1].x - w[0].x);
+            if secant == 0.0 {
+                assert_eq!(curve.slopes[k], 0.0);
+                assert_eq!(curve.slopes[k + 1], 0.0);
+                continue;
+            }
+            let alpha = curve.slopes[k] / secant;
+            let beta = curve.slopes[k + 1] / secant;
+            assert!(alpha >= 0.0 && beta >= 0.0);
+            assert!(alpha.hypot(beta) <= 3.0 + 1e-4);
+        }
+    }
+
+    #[test]
+    fn interpolates_data_points() {
+        let points = monotone_points();
+        let curve = MonotoneCubic::new(&points).unwrap();
+        for (k, p) in points.iter().enumerate() {
+            assert!(curve.position(k as f32).distance(*p) < 1e-3);
+        }
+    }
+}
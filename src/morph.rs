@@ -0,0 +1,118 @@
+//! 两组保存的点之间的线性插值。对应的点按下标配对，两组的点数必须相同。
+
+use std::fmt;
+
+use crate::points::MovablePoint;
+
+#[derive(Debug, PartialEq)]
+pub enum MorphError {
+    /// 这个槽还没有保存点，0 是 A，1 是 B
+    Empty(usize),
+    CountMismatch(usize, usize),
+}
+
+impl fmt::Display for MorphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MorphError::Empty(slot) => write!(f, "slot {} is empty", slot_name(*slot)),
+            MorphError::CountMismatch(a, b) => {
+                write!(f, "slot A has {a} points but slot B has {b}")
+            }
+        }
+    }
+}
+
+pub fn slot_name(slot: usize) -> char {
+    if slot == 0 {
+        'A'
+    } else {
+        'B'
+    }
+}
+
+/// 两个槽之间 `amount` 处的点，0 是 A，1 是 B。
+pub fn morph(
+    slots: &[Option<Vec<MovablePoint>>; 2],
+    amount: f32,
+) -> Result<Vec<MovablePoint>, MorphError> {
+    let [Some(a), Some(b)] = slots else {
+        let empty = slots.iter().position(Option::is_none).unwrap_or(0);
+        return Err(MorphError::Empty(empty));
+    };
+    if a.len() != b.len() {
+        return Err(MorphError::CountMismatch(a.len(), b.len()));
+    }
+    Ok(lerp_points(a, b, amount))
+}
+
+/// 逐点插值位置、权重和张力。两边都手动设置了切线时插值切线，否则使用自动估计；
+/// 锁定状态取 A 的，结果都不是选中的。
+pub fn lerp_points(a: &[MovablePoint], b: &[MovablePoint], t: f32) -> Vec<MovablePoint> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| MovablePoint {
+            position: a.position.lerp(b.position, t),
+            tangent: match (a.tangent, b.tangent) {
+                (Some(from), Some(to)) => Some(from.lerp(to, t)),
+                _ => None,
+            },
+            weight: a.weight + (b.weight - a.weight) * t,
+            tension: a.tension + (b.tension - a.tension) * t,
+            is_selected: false,
+            is_handle_selected: false,
+            ..a.clone()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::Vec2;
+
+    fn points(positions: &[(f32, f32)]) -> Vec<MovablePoint> {
+        positions
+            .iter()
+            .map(|&(x, y)| MovablePoint::new(Vec2::new(x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn morphs_linearly_between_the_slots() {
+        let mut a = points(&[(0.0, 0.0), (100.0, 0.0)]);
+        a[1].weight = 3.0;
+        a[0].tangent = Some(Vec2::new(10.0, 0.0));
+        let mut b = points(&[(0.0, 100.0), (200.0, 50.0)]);
+        b[0].tangent = Some(Vec2::new(0.0, 10.0));
+        let slots = [Some(a.clone()), Some(b.clone())];
+        let half = morph(&slots, 0.5).unwrap();
+        assert_eq!(half[0].position, Vec2::new(0.0, 50.0));
+        assert_eq!(half[1].position, Vec2::new(150.0, 25.0));
+        assert_eq!(half[1].weight, 2.0);
+        assert_eq!(half[0].tangent, Some(Vec2::new(5.0, 5.0)));
+        assert_eq!(half[1].tangent, None);
+        // 两端就是保存的点
+        for (amount, saved) in [(0.0, &a), (1.0, &b)] {
+            let positions: Vec<Vec2> = morph(&slots, amount)
+                .unwrap()
+                .iter()
+                .map(|p| p.position)
+                .collect();
+            let expected: Vec<Vec2> = saved.iter().map(|p| p.position).collect();
+            assert_eq!(positions, expected);
+        }
+    }
+
+    #[test]
+    fn refuses_empty_or_mismatched_slots() {
+        let mut slots = [None, None];
+        assert_eq!(morph(&slots, 0.5).err(), Some(MorphError::Empty(0)));
+        slots[0] = Some(points(&[(0.0, 0.0), (1.0, 1.0)]));
+        assert_eq!(morph(&slots, 0.5).err(), Some(MorphError::Empty(1)));
+        slots[1] = Some(points(&[(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)]));
+        assert_eq!(
+            morph(&slots, 0.5).err(),
+            Some(MorphError::CountMismatch(2, 3))
+        );
+    }
+}
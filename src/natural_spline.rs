@@ -0,0 +1,288 @@
+//! 自然三次样条：插值所有控制点，C2 连续，默认两端二阶导数为零。
+//! 也可以换成钳制或 not-a-knot 的端点条件，只改变方程组的首尾两行。
+
+use bevy::math::{
+    cubic_splines::{CubicBezier, CubicCurve, CubicGenerator},
+    VectorSpace,
+};
+
+use crate::end_condition::EndCondition;
+
+/// 在均匀参数（第 i 个节点位于 t = i）下求解自然三次样条，
+/// 结果转换为 Bezier 控制点，以便复用 bevy 的 [`CubicCurve`]。
+/// 与 bevy 的构造器一样对任意 [`VectorSpace`] 通用，2D 和 3D 模式共用。
+pub struct NaturalCubicSpline<P: VectorSpace> {
+    points: Vec<P>,
+    end_condition: EndCondition,
+    end_tangents: [P; 2],
+}
+
+impl<P: VectorSpace> NaturalCubicSpline<P> {
+    pub fn new(points: impl IntoIterator<Item = P>) -> Self {
+        Self {
+            points: points.into_iter().collect(),
+            end_condition: EndCondition::Natural,
+            end_tangents: [P::ZERO; 2],
+        }
+    }
+
+    /// 换用其他端点条件，`tangents` 只在钳制时使用。[`EndCondition::Auto`] 与自然条件相同。
+    pub fn with_end_condition(mut self, condition: EndCondition, tangents: [P; 2]) -> Self {
+        self.end_condition = condition;
+        self.end_tangents = tangents;
+        self
+    }
+
+    /// 每一段的 Bezier 控制点，少于两个点时为空。
+    pub fn bezier_segments(&self) -> Vec<[P; 4]> {
+        let n = self.points.len();
+        if n < 2 {
+            return Vec::new();
+        }
+        let derivatives = solve_derivatives(&self.points, self.end_condition, self.end_tangents);
+        (0..n - 1)
+            .map(|i| {
+                let (p0, p1) = (self.points[i], self.points[i + 1]);
+                [
+                    p0,
+                    p0 + derivatives[i] / 3.0,
+                    p1 - derivatives[i + 1] / 3.0,
+                    p1,
+                ]
+            })
+            .collect()
+    }
+}
+
+impl<P: VectorSpace> CubicGenerator<P> for NaturalCubicSpline<P> {
+    type Error = <CubicBezier<P> as CubicGenerator<P>>::Error;
+
+    fn to_curve(&self) -> Result<CubicCurve<P>, Self::Error> {
+        CubicBezier::new(self.bezier_segments()).to_curve()
+    }
+}
+
+// 求解节点处一阶导数的三对角方程组（Thomas 算法），内部的行是
+//   D(i-1) + 4 Di + D(i+1) = 3 (P(i+1) - P(i-1))
+// 首行按端点条件（末行对称）：
+//   自然        2 D0 + D1 = 3 (P1 - P0)
+//   钳制        D0 = T0
+//   not-a-knot  D0 + 2 D1 = (-5 P0 + 4 P1 + P2) / 2，由三阶导数连续的条件与第二行相加消去 D2
+// 各行的系数与点无关，重复点也不会产生零主元。
+fn solve_derivatives<P: VectorSpace>(
+    points: &[P],
+    condition: EndCondition,
+    tangents: [P; 2],
+) -> Vec<P> {
+    let n = points.len();
+    // 三个点时 not-a-knot 的方程组奇异，解就是经过三个点的抛物线
+    if condition == EndCondition::NotAKnot && n == 3 {
+        let (p0, p1, p2) = (points[0], points[1], points[2]);
+        return vec![
+            (p1 * 4.0 - p0 * 3.0 - p2) * 0.5,
+            (p2 - p0) * 0.5,
+            (p0 - p1 * 4.0 + p2 * 3.0) * 0.5,
+        ];
+    }
+    let condition = match condition {
+        EndCondition::NotAKnot if n < 4 => EndCondition::Natural,
+        other => other,
+    };
+
+    // 每行的 (次对角线, 对角线, 上对角线) 系数和右端
+    let row = |i: usize| -> (f32, f32, f32, P) {
+        if i == 0 {
+            match condition {
+                EndCondition::Clamped => (0.0, 1.0, 0.0, tangents[0]),
+                EndCondition::NotAKnot => (
+                    0.0,
+                    1.0,
+                    2.0,
+                    (points[1] * 4.0 + points[2] - points[0] * 5.0) * 0.5,
+                ),
+                EndCondition::Auto | EndCondition::Natural => {
+                    (0.0, 2.0, 1.0, (points[1] - points[0]) * 3.0)
+                }
+            }
+        } else if i == n - 1 {
+            match condition {
+                EndCondition::Clamped => (0.0, 1.0, 0.0, tangents[1]),
+                EndCondition::NotAKnot => (
+                    2.0,
+                    1.0,
+                    0.0,
+                    (points[n - 1] * 5.0 - points[n - 2] * 4.0 - points[n - 3]) * 0.5,
+                ),
+                EndCondition::Auto | EndCondition::Natural => {
+                    (1.0, 2.0, 0.0, (points[n - 1] - points[n - 2]) * 3.0)
+                }
+            }
+        } else {
+            (1.0, 4.0, 1.0, (points[i + 1] - points[i - 1]) * 3.0)
+        }
+    };
+
+    // 前向消元
+    let mut upper = vec![0.0; n];
+    let mut rhs = vec![P::ZERO; n];
+    let (_, diagonal, above, value) = row(0);
+    upper[0] = above / diagonal;
+    rhs[0] = value / diagonal;
+    for i in 1..n {
+        let (below, diagonal, above, value) = row(i);
+        let m = diagonal - below * upper[i - 1];
+        upper[i] = above / m;
+        rhs[i] = (value - rhs[i - 1] * below) / m;
+    }
+
+    // 回代
+    for i in (0..n - 1).rev() {
+        rhs[i] = rhs[i] - rhs[i + 1] * upper[i];
+    }
+    rhs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::{Vec2, Vec3};
+
+    const EPSILON: f32 = 1e-4;
+
+    #[test]
+    fn interpolates_every_knot() {
+        let points = vec![
+            Vec2::new(-200.0, 0.0),
+            Vec2::new(-100.0, 150.0),
+            Vec2::new(0.0, -50.0),
+            Vec2::new(120.0, 80.0),
+            Vec2::new(250.0, 10.0),
+        ];
+        let curve = NaturalCubicSpline::new(points.clone()).to_curve().unwrap();
+        for (i, point) in points.iter().enumerate() {
+            assert!(curve.position(i as f32).distance(*point) < EPSILON);
+        }
+    }
+
+    #[test]
+    fn natural_end_conditions() {
+        let points = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(50.0, 100.0),
+            Vec2::new(100.0, -30.0),
+            Vec2::new(150.0, 60.0),
+        ];
+        let curve = NaturalCubicSpline::new(points.clone()).to_curve().unwrap();
+        let end = (points.len() - 1) as f32;
+        assert!(curve.acceleration(0.0).length() < EPSILON);
+        assert!(curve.acceleration(end).length() < EPSILON);
+    }
+
+    const WAVE: [Vec2; 5] = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(50.0, 100.0),
+        Vec2::new(100.0, -30.0),
+        Vec2::new(150.0, 60.0),
+        Vec2::new(200.0, 20.0),
+    ];
+
+    #[test]
+    fn clamped_end_conditions() {
+        let tangents = [Vec2::new(0.0, 300.0), Vec2::new(80.0, -40.0)];
+        let curve = NaturalCubicSpline::new(WAVE)
+            .with_end_condition(EndCondition::Clamped, tangents)
+            .to_curve()
+            .unwrap();
+        assert!(curve.velocity(0.0).distance(tangents[0]) < EPSILON);
+        assert!(curve.velocity(4.0).distance(tangents[1]) < EPSILON);
+        for (i, point) in WAVE.iter().enumerate() {
+            assert!(curve.position(i as f32).distance(*point) < EPSILON);
+        }
+        // 内部节点仍然 C2 连续
+        for i in 1..4 {
+            let t = i as f32;
+            let before = curve.acceleration(t - 1e-3);
+            let after = curve.acceleration(t + 1e-3);
+            assert!(before.distance(after) < 1.0, "t = {t}");
+        }
+    }
+
+    // 第二个和倒数第二个节点两侧的三阶导数相同；点取自一个三次多项式时 not-a-knot 把它原样还原
+    #[test]
+    fn not_a_knot_end_conditions() {
+        let jerk = |s: &[Vec2; 4]| (s[3] - s[2] * 3.0 + s[1] * 3.0 - s[0]) * 6.0;
+        let segments = NaturalCubicSpline::new(WAVE)
+            .with_end_condition(EndCondition::NotAKnot, [Vec2::ZERO; 2])
+            .bezier_segments();
+        assert!(jerk(&segments[0]).distance(jerk(&segments[1])) < 1e-2);
+        assert!(jerk(&segments[3]).distance(jerk(&segments[2])) < 1e-2);
+
+        let cubic = |x: f32| Vec2::new(x, x * x * x - 4.0 * x * x + x);
+        let points: Vec<Vec2> = (0..5).map(|i| cubic(i as f32)).collect();
+        let curve = NaturalCubicSpline::new(points)
+            .with_end_condition(EndCondition::NotAKnot, [Vec2::ZERO; 2])
+            .to_curve()
+            .unwrap();
+        for t in [0.25, 0.5, 1.5, 3.75] {
+            assert!(curve.position(t).distance(cubic(t)) < 1e-3, "t = {t}");
+        }
+        // 自然条件做不到
+        let natural = NaturalCubicSpline::new((0..5).map(|i| cubic(i as f32)))
+            .to_curve()
+            .unwrap();
+        assert!(natural.position(0.25).distance(cubic(0.25)) > 1e-2);
+    }
+
+    #[test]
+    fn not_a_knot_with_three_points_is_a_parabola() {
+        let parabola = |x: f32| Vec2::new(x, 3.0 * x * x - x);
+        let curve = NaturalCubicSpline::new((0..3).map(|i| parabola(i as f32)))
+            .with_end_condition(EndCondition::NotAKnot, [Vec2::ZERO; 2])
+            .to_curve()
+            .unwrap();
+        for t in [0.0, 0.3, 1.0, 1.7, 2.0] {
+            assert!(curve.position(t).distance(parabola(t)) < EPSILON, "t = {t}");
+        }
+    }
+
+    #[test]
+    fn two_points_is_a_straight_line() {
+        let (a, b) = (Vec2::new(-10.0, 5.0), Vec2::new(30.0, 25.0));
+        let curve = NaturalCubicSpline::new([a, b]).to_curve().unwrap();
+        assert!(curve.position(0.5).distance(a.lerp(b, 0.5)) < EPSILON);
+    }
+
+    #[test]
+    fn duplicate_points_stay_finite() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(20.0, 0.0),
+        ];
+        let curve = NaturalCubicSpline::new(points).to_curve().unwrap();
+        assert!(curve
+            .iter_positions(30)
+            .all(|p| p.x.is_finite() && p.y.is_finite()));
+    }
+
+    #[test]
+    fn interpolates_points_in_space() {
+        let points = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(50.0, 20.0, 80.0),
+            Vec3::new(100.0, -40.0, 10.0),
+            Vec3::new(150.0, 0.0, 60.0),
+        ];
+        let curve = NaturalCubicSpline::new(points).to_curve().unwrap();
+        for (i, point) in points.iter().enumerate() {
+            assert!(curve.position(i as f32).distance(*point) < EPSILON);
+        }
+        assert!(curve.acceleration(0.0).length() < EPSILON);
+    }
+
+    #[test]
+    fn too_few_points_is_an_error() {
+        assert!(NaturalCubicSpline::new([Vec2::ZERO]).to_curve().is_err());
+    }
+}
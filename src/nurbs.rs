@@ -0,0 +1,62 @@
+//! 均匀节点的有理三次 B-Spline（NURBS）。
+//!
+//! bevy 的 `CubicNurbs` 默认使用端点重复的开放节点向量，并且生成的是 `RationalCurve`，
+//! 这里手写一个与 `CubicBSpline` 相同均匀节点的版本，权重全为 1 时两者重合，便于对比。
+
+use bevy::math::Vec2;
+
+use crate::curve::{split_parameter, PlanarCurve};
+
+pub struct RationalBSpline {
+    points: Vec<Vec2>,
+    weights: Vec<f32>,
+}
+
+impl RationalBSpline {
+    pub fn new(
+        points: impl IntoIterator<Item = Vec2>,
+        weights: impl IntoIterator<Item = f32>,
+    ) -> Self {
+        Self {
+            points: points.into_iter().collect(),
+            weights: weights.into_iter().collect(),
+        }
+    }
+
+    /// 在第 `segment` 段的局部参数 `u`（0..=1）处求值。
+    pub fn evaluate_segment(&self, segment: usize, u: f32) -> Vec2 {
+        let basis = uniform_basis(u);
+        let mut numerator = Vec2::ZERO;
+        let mut denominator = 0.0;
+        for (j, b) in basis.iter().enumerate() {
+            let w = b * self.weights[segment + j];
+            numerator += self.points[segment + j] * w;
+            denominator += w;
+        }
+        numerator / denominator
+    }
+}
+
+impl PlanarCurve for RationalBSpline {
+    /// 曲线段数，与 `CubicBSpline` 一致，至少需要 4 个点。
+    fn segment_count(&self) -> usize {
+        self.points.len().min(self.weights.len()).saturating_sub(3)
+    }
+
+    fn position(&self, t: f32) -> Vec2 {
+        let (segment, u) = split_parameter(t, self.segment_count());
+        self.evaluate_segment(segment, u)
+    }
+}
+
+// 均匀三次 B-Spline 基函数
+fn uniform_basis(u: f32) -> [f32; 4] {
+    let u2 = u * u;
+    let u3 = u2 * u;
+    [
+        (1.0 - u).powi(3) / 6.0,
+        (3.0 * u3 - 6.0 * u2 + 4.0) / 6.0,
+        (-3.0 * u3 + 3.0 * u2 + 3.0 * u + 1.0) / 6.0,
+        u3 / 6.0,
+    ]
+}
@@ -0,0 +1,109 @@
+//! 偏移曲线（等距线）：每个采样点沿单位法线移动固定距离。
+//!
+//! 法线取速度逆时针转 90°，正的距离偏向曲线左侧。偏移距离超过凹侧的曲率半径
+//! （1 - d·κ ≤ 0）时偏移曲线在这里反向，形成尖点和燕尾状的自相交，这些采样点标为无效。
+
+use bevy::math::Vec2;
+
+use crate::curve::{signed_curvature, PlanarCurve};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OffsetSample {
+    pub position: Vec2,
+    pub valid: bool,
+}
+
+/// 在 `params` 的每个参数处把曲线偏移 `distance`。速度为零处没有法线，
+/// 使用曲线上的点并标为无效。
+pub fn offset_samples(curve: &dyn PlanarCurve, params: &[f32], distance: f32) -> Vec<OffsetSample> {
+    params
+        .iter()
+        .map(|&t| {
+            let position = curve.position(t);
+            let normal = curve.velocity(t).perp().normalize_or_zero();
+            match signed_curvature(curve, t) {
+                Some(curvature) if normal != Vec2::ZERO => OffsetSample {
+                    position: position + normal * distance,
+                    valid: 1.0 - distance * curvature > 0.0,
+                },
+                _ => OffsetSample {
+                    position,
+                    valid: false,
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    // 逆时针的圆，参数 0..1 走一圈；曲率为 1/半径，左侧法线指向圆心
+    struct Circle(f32);
+
+    impl PlanarCurve for Circle {
+        fn segment_count(&self) -> usize {
+            1
+        }
+
+        fn position(&self, t: f32) -> Vec2 {
+            Vec2::from_angle(t * TAU) * self.0
+        }
+
+        fn velocity(&self, t: f32) -> Vec2 {
+            Vec2::from_angle(t * TAU).perp() * self.0 * TAU
+        }
+
+        fn acceleration(&self, t: f32) -> Vec2 {
+            -Vec2::from_angle(t * TAU) * self.0 * TAU * TAU
+        }
+    }
+
+    fn params(n: usize) -> Vec<f32> {
+        (0..=n).map(|i| i as f32 / n as f32).collect()
+    }
+
+    #[test]
+    fn circle_offsets_are_concentric_circles() {
+        let circle = Circle(100.0);
+        let inner = offset_samples(&circle, &params(32), 30.0);
+        assert!(inner.iter().all(|s| s.valid));
+        assert!(inner
+            .iter()
+            .all(|s| (s.position.length() - 70.0).abs() < 1e-2));
+        let outer = offset_samples(&circle, &params(32), -30.0);
+        assert!(outer.iter().all(|s| s.valid));
+        assert!(outer
+            .iter()
+            .all(|s| (s.position.length() - 130.0).abs() < 1e-2));
+    }
+
+    // 向内偏移超过半径时整圈都越过了曲率中心
+    #[test]
+    fn offset_beyond_the_radius_of_curvature_is_flagged() {
+        let circle = Circle(100.0);
+        let samples = offset_samples(&circle, &params(32), 120.0);
+        assert!(samples.iter().all(|s| !s.valid));
+        // 向外偏移多远都有效
+        let samples = offset_samples(&circle, &params(32), -500.0);
+        assert!(samples.iter().all(|s| s.valid));
+    }
+
+    #[test]
+    fn straight_line_shifts_sideways() {
+        let line = crate::curve::Polyline(vec![Vec2::ZERO, Vec2::new(100.0, 0.0)]);
+        let samples = offset_samples(&line, &[0.0, 0.5, 1.0], 10.0);
+        let positions: Vec<Vec2> = samples.iter().map(|s| s.position).collect();
+        assert_eq!(
+            positions,
+            [
+                Vec2::new(0.0, 10.0),
+                Vec2::new(50.0, 10.0),
+                Vec2::new(100.0, 10.0)
+            ]
+        );
+        assert!(samples.iter().all(|s| s.valid));
+    }
+}
@@ -0,0 +1,329 @@
+//! 可选的 egui 设置面板，只编译 `egui` feature 时启用。
+//!
+//! 面板直接读写快捷键使用的同一批资源，两边始终同步；鼠标在面板上时设置
+//! [`PointerCaptured`]，世界中的点击、拖动和滚轮都会被忽略。
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPlugin};
+
+use crate::{
+    catmull_rom::CENTRIPETAL_ALPHA, handle_keypress, load_points, redo_edit, save_points,
+    undo_edit, ControlPoints, CurveAnimation, CurveKind, CurveResolution, CurveStyle,
+    CurveVisibility, DragState, EditHistory, Morph, Parameterization, PointerCaptured,
+    SamplingMode, SessionRecorder, StatusMessage,
+};
+
+pub struct SettingsPanelPlugin;
+
+impl Plugin for SettingsPanelPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+        // 先于所有输入系统运行，这一帧的 PointerCaptured 已经是最新的
+        app.add_systems(Update, settings_panel.before(handle_keypress));
+    }
+}
+
+// 面板按钮对应的操作，在关闭 egui 的借用之后再执行
+enum PanelAction {
+    Clear,
+    Undo,
+    Redo,
+    Save,
+    Load,
+}
+
+fn settings_panel(
+    mut contexts: EguiContexts,
+    drag: Res<DragState>,
+    mut captured: ResMut<PointerCaptured>,
+    mut control_points: ResMut<ControlPoints>,
+    mut history: ResMut<EditHistory>,
+    mut status: ResMut<StatusMessage>,
+    mut visibility: ResMut<CurveVisibility>,
+    mut resolution: ResMut<CurveResolution>,
+    mut sampling_mode: ResMut<SamplingMode>,
+    mut style: ResMut<CurveStyle>,
+    mut animation: ResMut<CurveAnimation>,
+    mut morph: ResMut<Morph>,
+    mut session: ResMut<SessionRecorder>,
+) {
+    let ctx = contexts.ctx_mut();
+    let mut action = None;
+    // 录制时拖动滑块只在松开时记一次
+    let mut edited = false;
+
+    egui::SidePanel::right("settings_panel")
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.heading("Settings");
+
+            // 有选中的点时只调整选中的点，否则调整全部
+            ui.separator();
+            let selected = control_points.points.iter().any(|p| p.is_selected);
+            let mut tension = control_points
+                .points
+                .iter()
+                .find(|p| p.is_selected || !selected)
+                .map_or(0.5, |p| p.tension);
+            let label = if selected {
+                "Tension (selected)"
+            } else {
+                "Tension (all)"
+            };
+            let response = ui.add_enabled(
+                !control_points.points.is_empty(),
+                egui::Slider::new(&mut tension, 0.0..=1.0).text(label),
+            );
+            // 拖动滑块只记一条历史，点击或键入数值时每次都记录
+            if response.drag_started() {
+                history.0.begin(control_points.snapshot());
+            }
+            if response.changed() {
+                if !response.dragged() {
+                    history.0.record(control_points.snapshot());
+                }
+                for point in control_points
+                    .points
+                    .iter_mut()
+                    .filter(|p| p.is_selected || !selected)
+                {
+                    point.tension = tension;
+                }
+                edited |= !response.dragged();
+            }
+            if response.drag_stopped() {
+                history.0.finish(&control_points.snapshot());
+                edited = true;
+            }
+
+            // 两个槽都保存了点数相同的点时才能拖动
+            let mut amount = morph.amount;
+            let morphable = crate::morph::morph(&morph.slots, amount).is_ok();
+            let response = ui.add_enabled(
+                morphable && matches!(*drag, DragState::Idle),
+                egui::Slider::new(&mut amount, 0.0..=1.0).text("Morph A → B"),
+            );
+            if response.drag_started() {
+                history.0.begin(control_points.snapshot());
+            }
+            if response.changed() {
+                if !response.dragged() {
+                    history.0.record(control_points.snapshot());
+                }
+                morph.amount = amount;
+                let _ = morph.apply(&mut control_points);
+                edited |= !response.dragged();
+            }
+            if response.drag_stopped() {
+                history.0.finish(&control_points.snapshot());
+                edited = true;
+            }
+
+            // 切换到向心参数化时保留已经调过的 alpha
+            let current = control_points.parameterization;
+            let alpha = match current {
+                Parameterization::Centripetal { alpha } => alpha,
+                _ => CENTRIPETAL_ALPHA,
+            };
+            let mut parameterization = current;
+            egui::ComboBox::from_label("Catmull-Rom")
+                .selected_text(match current {
+                    Parameterization::Uniform => "Uniform",
+                    Parameterization::Centripetal { .. } => "Centripetal",
+                    Parameterization::ChordLength => "Chord length",
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(
+                        &mut parameterization,
+                        Parameterization::Uniform,
+                        "Uniform",
+                    );
+                    ui.selectable_value(
+                        &mut parameterization,
+                        Parameterization::Centripetal { alpha },
+                        "Centripetal",
+                    );
+                    ui.selectable_value(
+                        &mut parameterization,
+                        Parameterization::ChordLength,
+                        "Chord length",
+                    );
+                });
+            if let Parameterization::Centripetal { alpha } = &mut parameterization {
+                ui.add(egui::Slider::new(alpha, 0.0..=1.0).text("Alpha"));
+            }
+            if parameterization != current {
+                control_points.parameterization = parameterization;
+                edited = true;
+            }
+
+            let mut samples = resolution.0;
+            if ui
+                .add(
+                    egui::Slider::new(&mut samples, CurveResolution::MIN..=CurveResolution::MAX)
+                        .text("Samples per segment"),
+                )
+                .changed()
+            {
+                resolution.0 = samples;
+            }
+
+            let mut mode = *sampling_mode;
+            egui::ComboBox::from_label("Sampling")
+                .selected_text(mode.name())
+                .show_ui(ui, |ui| {
+                    for option in SamplingMode::ALL {
+                        ui.selectable_value(&mut mode, option, option.name());
+                    }
+                });
+            if mode != *sampling_mode {
+                *sampling_mode = mode;
+            }
+
+            let mut rate = animation.rate;
+            if ui
+                .add(
+                    egui::Slider::new(
+                        &mut rate,
+                        CurveAnimation::MIN_RATE..=CurveAnimation::MAX_RATE,
+                    )
+                    .logarithmic(true)
+                    .text("Playback speed"),
+                )
+                .changed()
+            {
+                animation.set_rate(rate);
+            }
+
+            ui.separator();
+            egui::Grid::new("curves").num_columns(3).show(ui, |ui| {
+                for kind in CurveKind::ALL {
+                    let mut visible = visibility.is_visible(kind);
+                    if ui.checkbox(&mut visible, kind.name()).changed() {
+                        visibility.toggle(kind);
+                    }
+                    let mut color = style.color(kind);
+                    if color_button(ui, &mut color) {
+                        style.curves[kind.index()].color = color;
+                    }
+                    let mut width = style.line_width(kind);
+                    if ui
+                        .add(egui::Slider::new(
+                            &mut width,
+                            CurveStyle::MIN_LINE_WIDTH..=CurveStyle::MAX_LINE_WIDTH,
+                        ))
+                        .changed()
+                    {
+                        style.curves[kind.index()].line_width = width;
+                    }
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+            let point = style.point;
+            let mut colors = [
+                ("Point", point.default_color),
+                ("Selected", point.selected_color),
+                ("Hovered", point.hover_color),
+                ("Handle", point.handle_color),
+                ("Locked", point.locked_color),
+            ];
+            let mut changed = false;
+            ui.horizontal_wrapped(|ui| {
+                for (label, color) in colors.iter_mut() {
+                    ui.label(*label);
+                    changed |= color_button(ui, color);
+                }
+            });
+            if changed {
+                let point = &mut style.point;
+                point.default_color = colors[0].1;
+                point.selected_color = colors[1].1;
+                point.hover_color = colors[2].1;
+                point.handle_color = colors[3].1;
+                point.locked_color = colors[4].1;
+            }
+            let widths = CurveStyle::MIN_LINE_WIDTH..=CurveStyle::MAX_LINE_WIDTH;
+            let mut width = point.line_width;
+            if ui
+                .add(egui::Slider::new(&mut width, widths.clone()).text("Point width"))
+                .changed()
+            {
+                style.point.line_width = width;
+            }
+            let mut width = style.overlay_line_width;
+            if ui
+                .add(egui::Slider::new(&mut width, widths).text("Overlay width"))
+                .changed()
+            {
+                style.overlay_line_width = width;
+            }
+
+            // 拖动点时按钮不可用，与快捷键的限制一致
+            ui.separator();
+            ui.add_enabled_ui(matches!(*drag, DragState::Idle), |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Clear").clicked() {
+                        action = Some(PanelAction::Clear);
+                    }
+                    if ui.button("Undo").clicked() {
+                        action = Some(PanelAction::Undo);
+                    }
+                    if ui.button("Redo").clicked() {
+                        action = Some(PanelAction::Redo);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        action = Some(PanelAction::Save);
+                    }
+                    if ui.button("Load").clicked() {
+                        action = Some(PanelAction::Load);
+                    }
+                });
+            });
+        });
+
+    let is_captured = ctx.wants_pointer_input() || ctx.is_pointer_over_area();
+    if captured.0 != is_captured {
+        captured.0 = is_captured;
+    }
+
+    match action {
+        // 锁定的点与 C 键一样不会被清除
+        Some(PanelAction::Clear) => {
+            if control_points.points.iter().any(|p| !p.locked) {
+                history.0.record(control_points.snapshot());
+                control_points.points.retain(|p| p.locked);
+                edited = true;
+            }
+        }
+        Some(PanelAction::Undo) => undo_edit(&mut control_points, &mut history, &mut session),
+        Some(PanelAction::Redo) => redo_edit(&mut control_points, &mut history, &mut session),
+        Some(PanelAction::Save) => save_points(&control_points, &morph, &mut status),
+        Some(PanelAction::Load) => load_points(
+            &mut control_points,
+            &mut morph,
+            &mut history,
+            &mut session,
+            &mut status,
+        ),
+        None => {}
+    }
+    if edited {
+        session.record_snapshot(&control_points);
+    }
+}
+
+// 颜色按钮，编辑后返回 true；透明度保持不变
+fn color_button(ui: &mut egui::Ui, color: &mut Srgba) -> bool {
+    let mut rgb = [color.red, color.green, color.blue];
+    let changed = ui.color_edit_button_rgb(&mut rgb).changed();
+    if changed {
+        *color = Srgba::new(rgb[0], rgb[1], rgb[2], color.alpha);
+    }
+    changed
+}
@@ -0,0 +1,346 @@
+//! 控制点的保存和读取，保存使用 RON 格式，读取也接受每行一个点的 CSV。
+//!
+//! 文件中只保存编辑相关的数据，颜色、大小等显示参数不写入文件。
+
+use std::{fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{catmull_rom::Parameterization, end_condition::EndCondition};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SavedPoint {
+    pub x: f32,
+    pub y: f32,
+    // None 表示自动估计切线
+    #[serde(default)]
+    pub tangent: Option<(f32, f32)>,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    #[serde(default = "default_tension")]
+    pub tension: f32,
+    #[serde(default)]
+    pub locked: bool,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+fn default_tension() -> f32 {
+    0.5
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct SavedScene {
+    pub points: Vec<SavedPoint>,
+    #[serde(default)]
+    pub closed: bool,
+    // 插值用的两组点（Shift+8、Shift+9），没有保存时不写入文件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot_a: Option<Vec<SavedPoint>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot_b: Option<Vec<SavedPoint>>,
+    // 对称编辑的轴，points 只是编辑的一侧
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symmetry: Option<f32>,
+    // 多个点集时，上面的点是第一个点集，其余的按顺序放在 lists 里；active 是正在编辑的点集
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lists: Vec<SavedList>,
+    #[serde(default)]
+    pub active: usize,
+    // 第一个点集的曲线设置，旧文件里没有时使用默认值
+    #[serde(default)]
+    pub parameterization: Parameterization,
+    #[serde(default)]
+    pub clamped: bool,
+    #[serde(default)]
+    pub end_condition: EndCondition,
+    #[serde(default, skip_serializing_if = "no_end_tangents")]
+    pub end_tangents: [Option<(f32, f32)>; 2],
+}
+
+fn no_end_tangents(tangents: &[Option<(f32, f32)>; 2]) -> bool {
+    tangents.iter().all(Option::is_none)
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct SavedList {
+    #[serde(default)]
+    pub name: String,
+    pub points: Vec<SavedPoint>,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symmetry: Option<f32>,
+    #[serde(default)]
+    pub parameterization: Parameterization,
+    #[serde(default)]
+    pub clamped: bool,
+    #[serde(default)]
+    pub end_condition: EndCondition,
+    #[serde(default, skip_serializing_if = "no_end_tangents")]
+    pub end_tangents: [Option<(f32, f32)>; 2],
+}
+
+#[derive(Debug)]
+pub enum PersistError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    Csv { line: usize, message: String },
+    UnknownFormat,
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistError::Io(error) => write!(f, "{error}"),
+            PersistError::Parse(error) => write!(f, "invalid RON: {error}"),
+            PersistError::Serialize(error) => write!(f, "{error}"),
+            PersistError::Csv { line, message } => {
+                write!(f, "invalid CSV at line {line}: {message}")
+            }
+            PersistError::UnknownFormat => write!(f, "not a RON or CSV points file"),
+        }
+    }
+}
+
+pub fn to_ron(scene: &SavedScene) -> Result<String, PersistError> {
+    ron::ser::to_string_pretty(scene, ron::ser::PrettyConfig::default())
+        .map_err(PersistError::Serialize)
+}
+
+pub fn from_ron(text: &str) -> Result<SavedScene, PersistError> {
+    ron::from_str(text).map_err(PersistError::Parse)
+}
+
+pub fn save(path: &Path, scene: &SavedScene) -> Result<(), PersistError> {
+    fs::write(path, to_ron(scene)?).map_err(PersistError::Io)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PointsFormat {
+    Ron,
+    Csv,
+}
+
+/// 先看扩展名，没有可识别的扩展名时再看第一个非空行。
+pub fn detect_format(path: &Path, text: &str) -> Option<PointsFormat> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("ron") => return Some(PointsFormat::Ron),
+        Some("csv") => return Some(PointsFormat::Csv),
+        _ => {}
+    }
+    let first = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+    if first.starts_with('(') || first.starts_with("points") {
+        Some(PointsFormat::Ron)
+    } else if first.contains(',') {
+        Some(PointsFormat::Csv)
+    } else {
+        None
+    }
+}
+
+/// 每行一个点；第一行可以是表头，此时按名为 x 和 y 的列读取（兼容导出的 `t,x,y`），
+/// 否则使用前两列。
+pub fn from_csv(text: &str) -> Result<SavedScene, PersistError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty())
+        .peekable();
+    let fields =
+        |line: &str| -> Vec<String> { line.split(',').map(|f| f.trim().to_string()).collect() };
+    let (mut x_column, mut y_column) = (0, 1);
+    if let Some(&(line, first)) = lines.peek() {
+        let header = fields(first);
+        if header.iter().any(|f| f.parse::<f32>().is_err()) {
+            let find = |name: &str| header.iter().position(|f| f.eq_ignore_ascii_case(name));
+            let (Some(x), Some(y)) = (find("x"), find("y")) else {
+                return Err(PersistError::Csv {
+                    line,
+                    message: "header has no x and y columns".to_string(),
+                });
+            };
+            (x_column, y_column) = (x, y);
+            lines.next();
+        }
+    }
+    let mut points = Vec::new();
+    for (line, content) in lines {
+        let row = fields(content);
+        let value = |column: usize| -> Result<f32, PersistError> {
+            row.get(column)
+                .and_then(|f| f.parse::<f32>().ok())
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| PersistError::Csv {
+                    line,
+                    message: format!("`{content}` is not a point"),
+                })
+        };
+        points.push(SavedPoint {
+            x: value(x_column)?,
+            y: value(y_column)?,
+            tangent: None,
+            weight: default_weight(),
+            tension: default_tension(),
+            locked: false,
+        });
+    }
+    Ok(SavedScene {
+        points,
+        ..Default::default()
+    })
+}
+
+/// 按格式自动识别读取，Ctrl+O 和拖放文件共用。
+pub fn load(path: &Path) -> Result<SavedScene, PersistError> {
+    let text = fs::read_to_string(path).map_err(PersistError::Io)?;
+    match detect_format(path, &text) {
+        Some(PointsFormat::Ron) => from_ron(&text),
+        Some(PointsFormat::Csv) => from_csv(&text),
+        None => Err(PersistError::UnknownFormat),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene() -> SavedScene {
+        SavedScene {
+            points: vec![
+                SavedPoint {
+                    x: -120.5,
+                    y: 30.25,
+                    tangent: None,
+                    weight: 1.0,
+                    tension: 0.5,
+                    locked: true,
+                },
+                SavedPoint {
+                    x: 80.0,
+                    y: -42.0,
+                    tangent: Some((10.0, -3.5)),
+                    weight: 2.5,
+                    tension: 0.1,
+                    locked: false,
+                },
+            ],
+            closed: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn round_trips_through_a_file() {
+        let path = std::env::temp_dir().join("cubic_spline_round_trip.ron");
+        save(&path, &scene()).unwrap();
+        let loaded = load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded, scene());
+    }
+
+    #[test]
+    fn morph_slots_round_trip_and_are_optional() {
+        let mut with_slots = scene();
+        with_slots.slot_a = Some(with_slots.points.clone());
+        with_slots.slot_b = Some(vec![with_slots.points[0].clone()]);
+        let text = to_ron(&with_slots).unwrap();
+        assert!(text.contains("slot_a"));
+        assert_eq!(from_ron(&text).unwrap(), with_slots);
+        // 没有保存的槽不写入文件，旧文件读出来也是空的
+        assert!(!to_ron(&scene()).unwrap().contains("slot_a"));
+        assert_eq!(from_ron("(points: [])").unwrap().slot_b, None);
+    }
+
+    #[test]
+    fn point_lists_round_trip() {
+        let mut scene = scene();
+        scene.name = "north road".into();
+        scene.clamped = true;
+        scene.lists = vec![SavedList {
+            name: "south road".into(),
+            points: vec![scene.points[1].clone()],
+            closed: false,
+            symmetry: Some(40.0),
+            parameterization: Parameterization::Centripetal { alpha: 0.5 },
+            end_condition: EndCondition::Clamped,
+            end_tangents: [Some((10.0, 0.0)), None],
+            ..Default::default()
+        }];
+        scene.active = 1;
+        assert_eq!(from_ron(&to_ron(&scene).unwrap()).unwrap(), scene);
+        // 只有一个点集的旧文件
+        let single = from_ron("(points: [(x: 1.0, y: 2.0)])").unwrap();
+        assert!(single.lists.is_empty());
+        assert_eq!(single.active, 0);
+    }
+
+    #[test]
+    fn detects_format_by_extension_then_content() {
+        assert_eq!(
+            detect_format(Path::new("a.RON"), "1,2"),
+            Some(PointsFormat::Ron)
+        );
+        assert_eq!(
+            detect_format(Path::new("a.csv"), "(points: [])"),
+            Some(PointsFormat::Csv)
+        );
+        assert_eq!(
+            detect_format(Path::new("points"), "\n  (points: [])"),
+            Some(PointsFormat::Ron)
+        );
+        assert_eq!(
+            detect_format(Path::new("points.txt"), "t,x,y\n0,1,2"),
+            Some(PointsFormat::Csv)
+        );
+        assert_eq!(detect_format(Path::new("image.png"), "\u{89}PNG"), None);
+    }
+
+    #[test]
+    fn reads_csv_with_and_without_header() {
+        let plain = from_csv("1, 2\n\n-3.5,4\n").unwrap();
+        assert_eq!(plain.points.len(), 2);
+        assert_eq!((plain.points[1].x, plain.points[1].y), (-3.5, 4.0));
+
+        let exported = from_csv("t,x,y\n0,10,20\n0.5,30,40\n").unwrap();
+        assert_eq!((exported.points[1].x, exported.points[1].y), (30.0, 40.0));
+
+        assert!(matches!(
+            from_csv("x,y\n1,2\n3,oops"),
+            Err(PersistError::Csv { line: 3, .. })
+        ));
+        assert!(matches!(
+            from_csv("a,b\n1,2"),
+            Err(PersistError::Csv { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let loaded = from_ron("(points: [(x: 1.0, y: 2.0)])").unwrap();
+        assert_eq!(loaded.points[0].weight, 1.0);
+        assert_eq!(loaded.points[0].tension, 0.5);
+        assert!(!loaded.points[0].locked);
+        assert!(!loaded.closed);
+    }
+
+    #[test]
+    fn malformed_or_missing_files_are_errors() {
+        assert!(matches!(
+            from_ron("(points: [(x: 1.0,"),
+            Err(PersistError::Parse(_))
+        ));
+        let missing = std::env::temp_dir().join("cubic_spline_does_not_exist.ron");
+        assert!(matches!(load(&missing), Err(PersistError::Io(_))));
+    }
+}
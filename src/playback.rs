@@ -0,0 +1,133 @@
+//! 标记播放到曲线端点时的行为：停止、从头循环或往返。
+
+use serde::{Deserialize, Serialize};
+
+/// 到达终点后怎么办。
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub enum EndBehavior {
+    Stop,
+    #[default]
+    Loop,
+    PingPong,
+}
+
+impl EndBehavior {
+    pub fn next(self) -> Self {
+        match self {
+            EndBehavior::Stop => EndBehavior::Loop,
+            EndBehavior::Loop => EndBehavior::PingPong,
+            EndBehavior::PingPong => EndBehavior::Stop,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EndBehavior::Stop => "stop at the end",
+            EndBehavior::Loop => "loop",
+            EndBehavior::PingPong => "ping-pong",
+        }
+    }
+}
+
+/// 归一化位置（0..=1）上的播放头，往返时 `forward` 为 false 表示正在往回走。
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Playhead {
+    pub position: f32,
+    pub forward: bool,
+}
+
+impl Default for Playhead {
+    fn default() -> Self {
+        Self::at(0.0)
+    }
+}
+
+impl Playhead {
+    pub fn at(position: f32) -> Self {
+        Self {
+            position: position.clamp(0.0, 1.0),
+            forward: true,
+        }
+    }
+
+    /// 前进 `delta`（归一化长度，不为负）。越过端点的部分不会丢掉：
+    /// 循环时从头接着走，往返时折回反方向，一帧内越过多次端点也一样。
+    pub fn advance(&mut self, delta: f32, end: EndBehavior) {
+        match end {
+            EndBehavior::Stop => *self = Self::at(self.position + delta),
+            EndBehavior::Loop => {
+                self.position = (self.position + delta).rem_euclid(1.0);
+                self.forward = true;
+            }
+            EndBehavior::PingPong => {
+                // 展开成周期为 2 的直线运动：0..1 是去程，1..2 是回程
+                let unfolded = if self.forward {
+                    self.position
+                } else {
+                    2.0 - self.position
+                };
+                let folded = (unfolded + delta).rem_euclid(2.0);
+                self.forward = folded < 1.0;
+                self.position = if self.forward { folded } else { 2.0 - folded };
+            }
+        }
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.position >= 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn advanced(position: f32, forward: bool, delta: f32, end: EndBehavior) -> Playhead {
+        let mut playhead = Playhead { position, forward };
+        playhead.advance(delta, end);
+        playhead
+    }
+
+    #[test]
+    fn stop_clamps_at_the_end() {
+        let playhead = advanced(0.9, true, 0.3, EndBehavior::Stop);
+        assert_eq!(playhead.position, 1.0);
+        assert!(playhead.at_end());
+    }
+
+    #[test]
+    fn loop_carries_the_overshoot() {
+        let playhead = advanced(0.9, true, 0.3, EndBehavior::Loop);
+        assert!((playhead.position - 0.2).abs() < EPSILON);
+        assert!(playhead.forward);
+        // 往返途中切换到循环时恢复向前
+        let playhead = advanced(0.5, false, 0.1, EndBehavior::Loop);
+        assert!((playhead.position - 0.6).abs() < EPSILON);
+    }
+
+    #[test]
+    fn ping_pong_reflects_at_the_boundary() {
+        let playhead = advanced(0.9, true, 0.3, EndBehavior::PingPong);
+        assert!((playhead.position - 0.8).abs() < EPSILON);
+        assert!(!playhead.forward);
+
+        let playhead = advanced(0.1, false, 0.3, EndBehavior::PingPong);
+        assert!((playhead.position - 0.2).abs() < EPSILON);
+        assert!(playhead.forward);
+
+        // 正好落在终点时下一步往回走
+        let playhead = advanced(0.75, true, 0.25, EndBehavior::PingPong);
+        assert!((playhead.position - 1.0).abs() < EPSILON);
+        assert!(!playhead.forward);
+    }
+
+    #[test]
+    fn ping_pong_handles_several_bounces_in_one_step() {
+        // 0.5 -> 1 -> 0 -> 0.7，共走 2.2
+        let playhead = advanced(0.5, true, 2.2, EndBehavior::PingPong);
+        assert!((playhead.position - 0.7).abs() < EPSILON);
+        assert!(playhead.forward);
+    }
+}
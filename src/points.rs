@@ -0,0 +1,670 @@
+//! 控制点、点集和拖动状态。
+
+use super::*;
+
+// 切线手柄相对于点的长度比例，与 Hermite 转 Bezier 的控制点位置一致
+pub(crate) const TANGENT_HANDLE_SCALE: f32 = 1.0 / 3.0;
+// 端点条件箭头的长度比例，比切线手柄长，默认时两者不重叠
+pub(crate) const END_HANDLE_SCALE: f32 = 0.5;
+
+/// 一个控制点；显示大小和选中状态只在内部使用。
+#[derive(Clone, PartialEq)]
+pub struct MovablePoint {
+    pub position: Vec2,
+    /// None 表示使用 Catmull-Rom 风格的自动估计
+    pub tangent: Option<Vec2>,
+    /// NURBS 权重
+    pub weight: f32,
+    /// Cardinal 样条在这个点的张力，0.5 时与 Catmull-Rom 相同
+    pub tension: f32,
+    pub(crate) show_size: f32,
+    pub(crate) selected_size: f32,
+    pub(crate) handle_size: f32,
+    /// 锁定的点不能被选中、拖动或被 C 删除
+    pub locked: bool,
+    pub(crate) is_selected: bool,
+    pub(crate) is_handle_selected: bool,
+}
+
+// 左键拖动的状态
+#[derive(Default, Resource)]
+pub(crate) enum DragState {
+    #[default]
+    Idle,
+    // 平移所有选中的点：start 是按下时的鼠标位置，anchor 是被点中的点按下时的位置，
+    // moved 是已经施加的平移量；每帧由鼠标的总位移重新计算平移，吸附时不会在格子间抖动
+    Points {
+        start: Vec2,
+        anchor: Vec2,
+        moved: Vec2,
+    },
+    // 拖动 is_handle_selected 的切线手柄
+    Handle,
+    // 拖动钳制端点条件的箭头，0 是起点，1 是终点
+    EndTangent(usize),
+    // 拖动对称轴，轴上的点跟着移动
+    SymmetryAxis,
+    // 在空白处按下后拉出的选择框，additive 为 true 时加入已有选择
+    Box {
+        start: Vec2,
+        current: Vec2,
+        additive: bool,
+    },
+}
+
+// 触屏上第一根手指的手势。按下时还不知道是点击、拖动还是长按：移动超过 TOUCH_SLOP
+// 成为拖动，按住不动超过 LONG_PRESS 成为长按，在这之前松开就是点击。
+// 跟踪的手指松开之前，其余手指都被忽略
+#[derive(Default, Resource)]
+pub(crate) enum TouchGesture {
+    #[default]
+    Idle,
+    // start 是按下时的屏幕位置，point 是按下时命中的未锁定点
+    Pressed {
+        id: u64,
+        start: Vec2,
+        started: f32,
+        point: Option<usize>,
+    },
+    // 与 DragState::Points 相同，start 是按下处的世界坐标
+    Dragging {
+        id: u64,
+        start: Vec2,
+        anchor: Vec2,
+        moved: Vec2,
+    },
+    // 已经长按删除了点，或者在空白处拖动；松开之前不再响应
+    Finished {
+        id: u64,
+    },
+}
+
+impl TouchGesture {
+    pub(crate) fn finger(&self) -> Option<u64> {
+        match *self {
+            TouchGesture::Idle => None,
+            TouchGesture::Pressed { id, .. }
+            | TouchGesture::Dragging { id, .. }
+            | TouchGesture::Finished { id } => Some(id),
+        }
+    }
+}
+
+// 屏幕像素
+pub(crate) const TOUCH_SLOP: f32 = 10.0;
+// 秒
+pub(crate) const LONG_PRESS: f32 = 0.6;
+
+/// 所有控制点，按曲线经过的顺序排列。
+#[derive(Default, Resource)]
+pub struct ControlPoints {
+    pub points: Vec<MovablePoint>,
+    /// 闭合模式下曲线首尾相连
+    pub closed: bool,
+    /// Catmull-Rom 的节点间隔
+    pub parameterization: Parameterization,
+    /// 非闭合的 B-Spline 钳制到首尾两个点
+    pub clamped: bool,
+    /// 非闭合的插值样条在首尾的端点条件
+    pub end_condition: EndCondition,
+    /// 钳制的端点条件使用的首尾切线，与点的切线分开保存；None 表示取首尾两段的弦
+    pub end_tangents: [Option<Vec2>; 2],
+    /// 对称编辑的竖直轴 x = axis，None 时关闭；曲线使用加上镜像后的点
+    pub symmetry: Option<f32>,
+    /// 正在编辑的点集的名字，为空时显示为 "Curve n"
+    pub name: String,
+    /// 其它点集，按顺序排列；正在编辑的点集是所有点集中的第 `active` 个
+    pub inactive: Vec<PointList>,
+    pub active: usize,
+}
+
+/// 没有在编辑的点集，连同它自己的曲线设置一起保存。
+#[derive(Clone, PartialEq, Default)]
+pub struct PointList {
+    pub name: String,
+    pub points: Vec<MovablePoint>,
+    pub closed: bool,
+    pub parameterization: Parameterization,
+    pub clamped: bool,
+    pub end_condition: EndCondition,
+    pub end_tangents: [Option<Vec2>; 2],
+    pub symmetry: Option<f32>,
+}
+
+impl PointList {
+    pub(crate) fn to_saved(&self) -> SavedList {
+        SavedList {
+            name: self.name.clone(),
+            points: self.points.iter().map(MovablePoint::to_saved).collect(),
+            closed: self.closed,
+            symmetry: self.symmetry,
+            parameterization: self.parameterization,
+            clamped: self.clamped,
+            end_condition: self.end_condition,
+            end_tangents: self.end_tangents.map(|t| t.map(|t| (t.x, t.y))),
+        }
+    }
+
+    pub(crate) fn from_saved(list: SavedList) -> Self {
+        Self {
+            name: list.name,
+            points: list
+                .points
+                .into_iter()
+                .map(MovablePoint::from_saved)
+                .collect(),
+            closed: list.closed,
+            parameterization: list.parameterization,
+            clamped: list.clamped,
+            end_condition: list.end_condition,
+            end_tangents: list.end_tangents.map(|t| t.map(|(x, y)| Vec2::new(x, y))),
+            symmetry: list.symmetry,
+        }
+    }
+
+    // 用来构造曲线的 ControlPoints，只有这一个点集
+    pub(crate) fn to_control_points(&self) -> ControlPoints {
+        ControlPoints {
+            points: self.points.clone(),
+            closed: self.closed,
+            parameterization: self.parameterization,
+            clamped: self.clamped,
+            end_condition: self.end_condition,
+            end_tangents: self.end_tangents,
+            symmetry: self.symmetry,
+            ..default()
+        }
+    }
+}
+
+/// 撤销历史保存的快照：所有点集连同各自的曲线设置（点不包含选择状态），以及正在编辑哪一个。
+#[derive(Clone, PartialEq)]
+pub(crate) struct Snapshot {
+    active: usize,
+    lists: Vec<PointList>,
+}
+
+/// 点集的显示名字，没有命名时按位置编号。
+pub(crate) fn list_name(name: &str, index: usize) -> String {
+    if name.is_empty() {
+        format!("Curve {}", index + 1)
+    } else {
+        name.to_owned()
+    }
+}
+
+impl Default for MovablePoint {
+    fn default() -> Self {
+        Self {
+            position: Vec2::new(0.0, 0.0),
+            tangent: None,
+            weight: 1.0,
+            tension: 0.5,
+            show_size: 5.0,
+            selected_size: 10.0,
+            handle_size: 3.0,
+            locked: false,
+            is_selected: false,
+            is_handle_selected: false,
+        }
+    }
+}
+
+impl MovablePoint {
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+            ..default()
+        }
+    }
+
+    pub(crate) fn to_saved(&self) -> SavedPoint {
+        SavedPoint {
+            x: self.position.x,
+            y: self.position.y,
+            tangent: self.tangent.map(|t| (t.x, t.y)),
+            weight: self.weight,
+            tension: self.tension,
+            locked: self.locked,
+        }
+    }
+
+    // 读取的点使用默认的显示参数
+    pub(crate) fn from_saved(p: SavedPoint) -> Self {
+        Self {
+            position: Vec2::new(p.x, p.y),
+            tangent: p.tangent.map(|(x, y)| Vec2::new(x, y)),
+            weight: p.weight,
+            tension: p.tension,
+            locked: p.locked,
+            ..default()
+        }
+    }
+}
+
+impl ControlPoints {
+    pub fn positions(&self) -> Vec<Vec2> {
+        self.points.iter().map(|p| p.position).collect()
+    }
+
+    pub(crate) fn tangent(&self, index: usize) -> Vec2 {
+        self.points[index]
+            .tangent
+            .unwrap_or_else(|| estimate_tangent(&self.positions(), index, self.closed))
+    }
+
+    pub(crate) fn tangents(&self) -> Vec<Vec2> {
+        let positions = self.positions();
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                p.tangent
+                    .unwrap_or_else(|| estimate_tangent(&positions, i, self.closed))
+            })
+            .collect()
+    }
+
+    pub(crate) fn handle_position(&self, index: usize) -> Vec2 {
+        self.points[index].position + self.tangent(index) * TANGENT_HANDLE_SCALE
+    }
+
+    /// 钳制端点条件下起点（`end` 为 0）或终点（1）的切线。
+    pub fn end_tangent(&self, end: usize) -> Vec2 {
+        let n = self.points.len();
+        if n < 2 {
+            return Vec2::ZERO;
+        }
+        self.end_tangents[end].unwrap_or_else(|| {
+            let p = |i: usize| self.points[i].position;
+            if end == 0 {
+                p(1) - p(0)
+            } else {
+                p(n - 1) - p(n - 2)
+            }
+        })
+    }
+
+    // 端点箭头所在的点
+    pub(crate) fn end_point(&self, end: usize) -> usize {
+        if end == 0 {
+            0
+        } else {
+            self.points.len() - 1
+        }
+    }
+
+    // 只有非闭合、钳制端点条件时显示首尾的箭头
+    pub(crate) fn has_end_handles(&self) -> bool {
+        self.end_condition == EndCondition::Clamped && !self.closed && self.points.len() >= 2
+    }
+
+    pub(crate) fn end_handle_position(&self, end: usize) -> Vec2 {
+        self.points[self.end_point(end)].position + self.end_tangent(end) * END_HANDLE_SCALE
+    }
+
+    // 选择和删除共用的命中测试，几个点都在范围内时取最近的
+    pub(crate) fn hit_point(
+        &self,
+        index: &PointIndex,
+        position: Vec2,
+        scale: f32,
+    ) -> Option<usize> {
+        self.nearest_point(index, position, scale, 1.0, |_| true)
+    }
+
+    // 只命中未锁定的点，用于选择和移动
+    pub(crate) fn hit_unlocked_point(
+        &self,
+        index: &PointIndex,
+        position: Vec2,
+        scale: f32,
+    ) -> Option<usize> {
+        self.nearest_point(index, position, scale, 1.0, |p| !p.locked)
+    }
+
+    // 方向键移动的点：有选中的点时是全部选中的点，否则是光标下未锁定的点；
+    // 为空时方向键留给播放速度
+    pub(crate) fn arrow_targets(
+        &self,
+        index: &PointIndex,
+        cursor: Option<Vec2>,
+        scale: f32,
+    ) -> Vec<usize> {
+        if self.points.iter().any(|p| p.is_selected) {
+            return self
+                .points
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.is_selected)
+                .map(|(i, _)| i)
+                .collect();
+        }
+        cursor
+            .and_then(|position| self.hit_unlocked_point(index, position, scale))
+            .into_iter()
+            .collect()
+    }
+
+    // 中键删除的判定范围比点击更宽：离光标最近、距离小于两倍选中半径的点
+    pub(crate) fn removable_point(
+        &self,
+        index: &PointIndex,
+        position: Vec2,
+        scale: f32,
+    ) -> Option<usize> {
+        self.nearest_point(index, position, scale, 2.0, |_| true)
+    }
+
+    // 距离小于 reach 倍选中半径的点中最近的一个。索引只用来缩小候选范围，
+    // 距离按当前位置计算；点数与索引不一致时（这一帧增删过点）退回逐个检查
+    pub(crate) fn nearest_point(
+        &self,
+        index: &PointIndex,
+        position: Vec2,
+        scale: f32,
+        reach: f32,
+        accept: impl Fn(&MovablePoint) -> bool,
+    ) -> Option<usize> {
+        let distance = |i: usize| {
+            let point = self.points.get(i).filter(|p| accept(p))?;
+            let d = point.position.distance(position);
+            (d < point.selected_size * scale * reach).then_some(d)
+        };
+        if index.hash.len() == self.points.len() {
+            index
+                .hash
+                .nearest(position, index.max_size * scale * reach, distance)
+        } else {
+            nearest_by_scan(self.points.len(), distance)
+        }
+    }
+
+    // 框选：additive 为 false 时先清空原有选择
+    pub(crate) fn select_in_rect(&mut self, rect: Rect, additive: bool) {
+        if !additive {
+            self.clear_selection();
+        }
+        for point in self.points.iter_mut().filter(|p| !p.locked) {
+            if rect.contains(point.position) {
+                point.is_selected = true;
+            }
+        }
+    }
+
+    pub fn list_count(&self) -> usize {
+        self.inactive.len() + 1
+    }
+
+    // 没有在编辑的点集和它们在所有点集中的位置
+    pub(crate) fn inactive_lists(&self) -> impl Iterator<Item = (usize, &PointList)> {
+        let active = self.active;
+        self.inactive
+            .iter()
+            .enumerate()
+            .map(move |(i, list)| (if i < active { i } else { i + 1 }, list))
+    }
+
+    // 按顺序取出所有点集，之后必须用 set_lists 放回
+    fn take_lists(&mut self) -> Vec<PointList> {
+        let current = PointList {
+            name: std::mem::take(&mut self.name),
+            points: std::mem::take(&mut self.points),
+            closed: self.closed,
+            parameterization: self.parameterization,
+            clamped: self.clamped,
+            end_condition: self.end_condition,
+            end_tangents: self.end_tangents,
+            symmetry: self.symmetry,
+        };
+        let mut lists = std::mem::take(&mut self.inactive);
+        lists.insert(self.active.min(lists.len()), current);
+        lists
+    }
+
+    fn set_lists(&mut self, mut lists: Vec<PointList>, active: usize) {
+        if lists.is_empty() {
+            lists.push(PointList::default());
+        }
+        let active = active.min(lists.len() - 1);
+        let current = lists.remove(active);
+        self.name = current.name;
+        self.points = current.points;
+        self.closed = current.closed;
+        self.parameterization = current.parameterization;
+        self.clamped = current.clamped;
+        self.end_condition = current.end_condition;
+        self.end_tangents = current.end_tangents;
+        self.symmetry = current.symmetry;
+        self.inactive = lists;
+        self.active = active;
+    }
+
+    /// 切换到第 `index` 个点集，之后的编辑都作用在它上面；选择状态随点集保留。
+    pub fn activate(&mut self, index: usize) {
+        if index != self.active && index < self.list_count() {
+            let lists = self.take_lists();
+            self.set_lists(lists, index);
+        }
+    }
+
+    /// 在最后添加一个空的点集并切换过去。
+    pub fn add_list(&mut self) {
+        let index = self.list_count();
+        self.inactive.push(PointList {
+            name: list_name("", index),
+            ..default()
+        });
+        self.activate(index);
+    }
+
+    // 撤销历史保存的快照
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        let lists = self
+            .all_lists()
+            .into_iter()
+            .map(|list| PointList {
+                points: unselected(&list.points),
+                ..list
+            })
+            .collect();
+        Snapshot {
+            active: self.active,
+            lists,
+        }
+    }
+
+    // 恢复快照中的所有点集，并切换回快照时正在编辑的点集；之后添加的点集被去掉
+    pub(crate) fn restore(&mut self, snapshot: Snapshot) {
+        self.set_lists(snapshot.lists, snapshot.active);
+    }
+
+    // 按顺序复制出所有点集
+    fn all_lists(&self) -> Vec<PointList> {
+        let mut lists = self.inactive.clone();
+        lists.insert(
+            self.active,
+            PointList {
+                name: self.name.clone(),
+                points: self.points.clone(),
+                closed: self.closed,
+                parameterization: self.parameterization,
+                clamped: self.clamped,
+                end_condition: self.end_condition,
+                end_tangents: self.end_tangents,
+                symmetry: self.symmetry,
+            },
+        );
+        lists
+    }
+
+    // 恰好选中一个点时返回它的索引
+    pub(crate) fn single_selected(&self) -> Option<usize> {
+        let mut selected = self
+            .points
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_selected)
+            .map(|(i, _)| i);
+        match (selected.next(), selected.next()) {
+            (Some(index), None) => Some(index),
+            _ => None,
+        }
+    }
+
+    // 第一个点集放在 points 里，其余的放在 lists 里
+    pub(crate) fn to_saved(&self) -> SavedScene {
+        let mut lists: Vec<SavedList> = self.all_lists().iter().map(PointList::to_saved).collect();
+        let first = lists.remove(0);
+        SavedScene {
+            points: first.points,
+            closed: first.closed,
+            symmetry: first.symmetry,
+            name: first.name,
+            parameterization: first.parameterization,
+            clamped: first.clamped,
+            end_condition: first.end_condition,
+            end_tangents: first.end_tangents,
+            lists,
+            active: self.active,
+            ..Default::default()
+        }
+    }
+
+    // 替换所有点集，每个点集使用文件里保存的曲线设置
+    pub(crate) fn load_saved(&mut self, scene: SavedScene) {
+        let first = SavedList {
+            name: scene.name,
+            points: scene.points,
+            closed: scene.closed,
+            symmetry: scene.symmetry,
+            parameterization: scene.parameterization,
+            clamped: scene.clamped,
+            end_condition: scene.end_condition,
+            end_tangents: scene.end_tangents,
+        };
+        let lists = std::iter::once(first)
+            .chain(scene.lists)
+            .map(PointList::from_saved)
+            .collect();
+        self.set_lists(lists, scene.active);
+    }
+
+    // 只替换正在编辑的点集，例如预设
+    pub(crate) fn load_into_active(&mut self, scene: SavedScene) {
+        self.points = scene
+            .points
+            .into_iter()
+            .map(MovablePoint::from_saved)
+            .collect();
+        self.closed = scene.closed;
+        self.symmetry = scene.symmetry;
+    }
+
+    /// 曲线使用的点：对称编辑时加上镜像点，否则就是编辑的点。
+    pub fn curve_points(&self) -> Cow<'_, [MovablePoint]> {
+        match self.symmetry {
+            Some(axis) => Cow::Owned(symmetric_points(&self.points, axis)),
+            None => Cow::Borrowed(&self.points),
+        }
+    }
+
+    // 所有移动点的编辑都经过这里：对称编辑时轴上的点只沿轴移动，x 留在轴上。
+    // 返回点实际到达的位置
+    pub(crate) fn move_point_to(&mut self, index: usize, position: Vec2) -> Vec2 {
+        let point = &mut self.points[index];
+        point.position = match self.symmetry {
+            Some(axis) if on_axis(point.position, axis) => Vec2::new(axis, position.y),
+            _ => position,
+        };
+        point.position
+    }
+
+    pub(crate) fn move_point_by(&mut self, index: usize, delta: Vec2) -> Vec2 {
+        let position = self.points[index].position + delta;
+        self.move_point_to(index, position)
+    }
+
+    pub(crate) fn clear_selection(&mut self) {
+        for point in self.points.iter_mut() {
+            point.is_selected = false;
+            point.is_handle_selected = false;
+        }
+    }
+}
+
+fn unselected(points: &[MovablePoint]) -> Vec<MovablePoint> {
+    points
+        .iter()
+        .map(|p| MovablePoint {
+            is_selected: false,
+            is_handle_selected: false,
+            ..p.clone()
+        })
+        .collect()
+}
+
+// 右键点击离控制多边形某条边足够近（屏幕像素）时插入到这条边的两个端点之间
+pub(crate) const INSERT_DISTANCE: f32 = 12.0;
+
+// 对称编辑时离轴这么近（屏幕像素）的新点放到轴上，按下处离轴这么近时拖动轴
+pub(crate) const AXIS_SNAP_DISTANCE: f32 = 8.0;
+
+// 返回新点应插入的索引，离所有边都太远时返回 None；
+// 点击共享顶点附近时取距离更近的边，距离相同时取索引较小的边
+pub(crate) fn insertion_index(
+    positions: &[Vec2],
+    point: Vec2,
+    closed: bool,
+    max_distance: f32,
+) -> Option<usize> {
+    let n = positions.len();
+    if n < 2 {
+        return None;
+    }
+    // 闭合时最后一条边连回第一个点，插入到末尾即位于两者之间
+    let edges = if closed && n > 2 { n } else { n - 1 };
+    (0..edges)
+        .map(|i| {
+            let distance = distance_to_segment(point, positions[i], positions[(i + 1) % n]);
+            (i + 1, distance)
+        })
+        .filter(|&(_, distance)| distance <= max_distance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+// 权重越大点画得越大，变化幅度较小
+pub(crate) fn weighted_size(point: &MovablePoint) -> f32 {
+    point.show_size * (1.0 + 0.25 * point.weight.ln())
+}
+
+// 格子边长（世界单位），与默认的选中半径同一量级
+pub(crate) const POINT_INDEX_CELL_SIZE: f32 = 32.0;
+
+/// 控制点位置的空间索引，控制点变化时由 [`index_points`] 重建。
+#[derive(Resource)]
+pub(crate) struct PointIndex {
+    pub(crate) hash: SpatialHash,
+    // 建立索引时最大的选中半径，决定查询范围
+    pub(crate) max_size: f32,
+}
+
+impl Default for PointIndex {
+    fn default() -> Self {
+        Self {
+            hash: SpatialHash::new(POINT_INDEX_CELL_SIZE),
+            max_size: 0.0,
+        }
+    }
+}
+
+// 键盘和鼠标两组系统之前各运行一次，前一组改过的点在后一组里也能命中
+pub(crate) fn index_points(control_points: Res<ControlPoints>, mut index: ResMut<PointIndex>) {
+    if !control_points.is_changed() {
+        return;
+    }
+    let points = &control_points.points;
+    index.hash.rebuild(points.iter().map(|p| p.position));
+    index.max_size = points.iter().map(|p| p.selected_size).fold(0.0, f32::max);
+}
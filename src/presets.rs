@@ -0,0 +1,202 @@
+//! 内置的点集，命令行的 `--preset` 和 F5–F9 共用。每个形状都画在以原点为中心的
+//! [-1, 1] 方框内，使用时再缩放平移到需要的位置。
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::{SavedPoint, SavedScene};
+
+/// 内置的初始点集。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Preset {
+    Circle,
+    Sine,
+    Zigzag,
+    SCurve,
+    Star,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 5] = [
+        Preset::Circle,
+        Preset::Sine,
+        Preset::Zigzag,
+        Preset::SCurve,
+        Preset::Star,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Circle => "circle",
+            Preset::Sine => "sine",
+            Preset::Zigzag => "zigzag",
+            Preset::SCurve => "s-curve",
+            Preset::Star => "star",
+        }
+    }
+
+    // 圆和星形是闭合的，其余是开放曲线
+    pub fn closed(self) -> bool {
+        matches!(self, Preset::Circle | Preset::Star)
+    }
+
+    pub fn points(self) -> Vec<Vec2> {
+        match self {
+            Preset::Circle => circle(),
+            Preset::Sine => sine(),
+            Preset::Zigzag => zigzag(),
+            Preset::SCurve => s_curve(),
+            Preset::Star => star(),
+        }
+    }
+
+    /// 以 `center` 为中心、按 `radius` 缩放后的场景。
+    pub(crate) fn scene(self, center: Vec2, radius: f32) -> SavedScene {
+        SavedScene {
+            points: place(&self.points(), center, radius)
+                .into_iter()
+                .map(|p| SavedPoint {
+                    x: p.x,
+                    y: p.y,
+                    tangent: None,
+                    weight: 1.0,
+                    tension: 0.5,
+                    locked: false,
+                })
+                .collect(),
+            closed: self.closed(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 把单位大小的形状缩放 `radius` 倍后平移到 `center`。
+pub fn place(points: &[Vec2], center: Vec2, radius: f32) -> Vec<Vec2> {
+    points.iter().map(|&p| center + p * radius).collect()
+}
+
+/// 圆上均匀分布的 8 个点，闭合后用样条近似圆。
+pub fn circle() -> Vec<Vec2> {
+    (0..8)
+        .map(|i| Vec2::from_angle(i as f32 / 8.0 * TAU))
+        .collect()
+}
+
+/// 一个周期的正弦波，12 个点。
+pub fn sine() -> Vec<Vec2> {
+    (0..12)
+        .map(|i| {
+            let t = i as f32 / 11.0;
+            Vec2::new(-1.0 + 2.0 * t, 0.5 * (t * TAU).sin())
+        })
+        .collect()
+}
+
+/// 上下交替的 7 个点。
+pub fn zigzag() -> Vec<Vec2> {
+    (0..7)
+        .map(|i| {
+            let y = if i % 2 == 0 { -0.35 } else { 0.35 };
+            Vec2::new(-1.0 + i as f32 / 3.0, y)
+        })
+        .collect()
+}
+
+/// 从上到下的 S 形：上半段弯向左，下半段弯向右。
+pub fn s_curve() -> Vec<Vec2> {
+    (0..9)
+        .map(|i| {
+            let t = i as f32 / 8.0;
+            Vec2::new(-0.6 * (t * TAU).sin(), 1.0 - 2.0 * t)
+        })
+        .collect()
+}
+
+/// 五角星，外角和内角交替共 10 个点，第一个外角朝上。
+pub fn star() -> Vec<Vec2> {
+    (0..10)
+        .map(|i| {
+            let radius = if i % 2 == 0 { 1.0 } else { 0.4 };
+            Vec2::from_angle(FRAC_PI_2 + i as f32 / 10.0 * TAU) * radius
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::Rect;
+
+    fn assert_bounds(points: &[Vec2], expected: Rect) {
+        let bounds = crate::view::bounds(points.iter().copied()).unwrap();
+        assert!(
+            bounds.min.distance(expected.min) < 0.05 && bounds.max.distance(expected.max) < 0.05,
+            "{bounds:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn circle_is_eight_points_on_the_unit_circle() {
+        let points = circle();
+        assert_eq!(points.len(), 8);
+        assert!(points.iter().all(|p| (p.length() - 1.0).abs() < 1e-5));
+        assert_bounds(&points, Rect::new(-1.0, -1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn sine_covers_one_period() {
+        let points = sine();
+        assert_eq!(points.len(), 12);
+        assert_eq!(points[0], Vec2::new(-1.0, 0.0));
+        assert!(points[11].distance(Vec2::new(1.0, 0.0)) < 1e-5);
+        assert_bounds(&points, Rect::new(-1.0, -0.5, 1.0, 0.5));
+    }
+
+    #[test]
+    fn zigzag_alternates() {
+        let points = zigzag();
+        assert_eq!(points.len(), 7);
+        assert!(points.windows(2).all(|w| w[0].y == -w[1].y));
+        assert_bounds(&points, Rect::new(-1.0, -0.35, 1.0, 0.35));
+    }
+
+    #[test]
+    fn s_curve_runs_top_to_bottom() {
+        let points = s_curve();
+        assert_eq!(points.len(), 9);
+        assert!(points.windows(2).all(|w| w[1].y < w[0].y));
+        // 上半段在左，下半段在右
+        assert!(points[2].x < 0.0 && points[6].x > 0.0);
+        assert_bounds(&points, Rect::new(-0.6, -1.0, 0.6, 1.0));
+    }
+
+    #[test]
+    fn star_alternates_outer_and_inner_corners() {
+        let points = star();
+        assert_eq!(points.len(), 10);
+        assert!(points[0].distance(Vec2::Y) < 1e-5);
+        for (i, p) in points.iter().enumerate() {
+            let radius = if i % 2 == 0 { 1.0 } else { 0.4 };
+            assert!((p.length() - radius).abs() < 1e-5);
+        }
+        assert_bounds(&points, Rect::new(-0.95, -0.81, 0.95, 1.0));
+    }
+
+    #[test]
+    fn scenes_are_placed_and_closed_as_declared() {
+        let center = Vec2::new(100.0, -50.0);
+        for preset in Preset::ALL {
+            let scene = preset.scene(center, 200.0);
+            assert_eq!(scene.points.len(), preset.points().len(), "{preset:?}");
+            assert_eq!(scene.closed, preset.closed());
+            assert!(scene.points.iter().all(|p| {
+                let offset = (Vec2::new(p.x, p.y) - center).abs();
+                offset.max_element() <= 200.0 + 1e-3
+            }));
+        }
+        assert!(Preset::Circle.scene(Vec2::ZERO, 1.0).closed);
+        assert!(!Preset::Sine.scene(Vec2::ZERO, 1.0).closed);
+    }
+}
@@ -0,0 +1,51 @@
+//! 二次 Bezier 曲线，用三点 de Casteljau 求值，用于和三次曲线对比。
+
+use bevy::math::Vec2;
+
+use crate::curve::{split_parameter, PlanarCurve};
+
+/// 共享端点的二次 Bezier 链：0-2、2-4、4-6……，凑不满一组的尾部点被忽略。
+pub struct QuadraticBezierChain {
+    segments: Vec<[Vec2; 3]>,
+}
+
+impl QuadraticBezierChain {
+    pub fn new(points: &[Vec2]) -> Self {
+        Self {
+            segments: points
+                .windows(3)
+                .step_by(2)
+                .map(|w| [w[0], w[1], w[2]])
+                .collect(),
+        }
+    }
+}
+
+impl PlanarCurve for QuadraticBezierChain {
+    fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    fn position(&self, t: f32) -> Vec2 {
+        let (index, u) = split_parameter(t, self.segments.len());
+        de_casteljau(self.segments[index], u)
+    }
+
+    fn velocity(&self, t: f32) -> Vec2 {
+        let (index, u) = split_parameter(t, self.segments.len());
+        let [p0, p1, p2] = self.segments[index];
+        2.0 * (1.0 - u) * (p1 - p0) + 2.0 * u * (p2 - p1)
+    }
+
+    fn acceleration(&self, t: f32) -> Vec2 {
+        let (index, _) = split_parameter(t, self.segments.len());
+        let [p0, p1, p2] = self.segments[index];
+        2.0 * (p2 - 2.0 * p1 + p0)
+    }
+}
+
+pub fn de_casteljau([p0, p1, p2]: [Vec2; 3], t: f32) -> Vec2 {
+    let a = p0.lerp(p1, t);
+    let b = p1.lerp(p2, t);
+    a.lerp(b, t)
+}
@@ -0,0 +1,106 @@
+//! F10 的随机点集。发生器只由种子决定，同样的种子在同样的视图里撒出同样的点。
+//!
+//! 用拒绝采样保持点之间的最小距离，避免两个点几乎重合让样条退化；
+//! 连续很多次都放不下时把最小距离缩小一些再试，点数总能满足。
+
+use bevy::math::{Rect, Vec2};
+
+// 每个点放不下时重试的次数，超过后最小距离乘以 RELAX
+const ATTEMPTS: usize = 64;
+const RELAX: f32 = 0.8;
+
+/// splitmix64，状态只有一个 u64，种子为 0 也能正常工作。
+#[derive(Clone, Debug)]
+pub struct SeededRng(u64);
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// [0, 1) 上均匀分布。
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// 在 `area` 里撒 `count` 个点，两两之间的距离尽量不小于 `min_distance`。
+pub fn scatter(rng: &mut SeededRng, count: usize, area: Rect, min_distance: f32) -> Vec<Vec2> {
+    let mut points: Vec<Vec2> = Vec::with_capacity(count);
+    let mut min_distance = min_distance;
+    let mut failures = 0;
+    while points.len() < count {
+        let candidate = area.min + Vec2::new(rng.next_f32(), rng.next_f32()) * area.size();
+        if points.iter().all(|p| p.distance(candidate) >= min_distance) {
+            points.push(candidate);
+            failures = 0;
+        } else {
+            failures += 1;
+            if failures == ATTEMPTS {
+                min_distance *= RELAX;
+                failures = 0;
+            }
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        min: Vec2::new(-400.0, -300.0),
+        max: Vec2::new(400.0, 300.0),
+    };
+
+    fn min_pairwise(points: &[Vec2]) -> f32 {
+        let mut smallest = f32::INFINITY;
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                smallest = smallest.min(a.distance(*b));
+            }
+        }
+        smallest
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_points() {
+        let a = scatter(&mut SeededRng::new(42), 8, AREA, 100.0);
+        let b = scatter(&mut SeededRng::new(42), 8, AREA, 100.0);
+        let c = scatter(&mut SeededRng::new(43), 8, AREA, 100.0);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn points_stay_inside_and_apart() {
+        let points = scatter(&mut SeededRng::new(7), 20, AREA, 80.0);
+        assert_eq!(points.len(), 20);
+        assert!(points.iter().all(|p| AREA.contains(*p)));
+        assert!(min_pairwise(&points) >= 80.0);
+    }
+
+    // 放不下这么多点时放宽距离，而不是无限重试
+    #[test]
+    fn impossible_spacing_is_relaxed() {
+        let tiny = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let points = scatter(&mut SeededRng::new(1), 50, tiny, 100.0);
+        assert_eq!(points.len(), 50);
+        assert!(min_pairwise(&points) > 0.0);
+    }
+
+    #[test]
+    fn floats_are_in_the_unit_interval() {
+        let mut rng = SeededRng::new(0);
+        assert!((0..10_000).all(|_| (0.0..1.0).contains(&rng.next_f32())));
+    }
+}
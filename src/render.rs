@@ -0,0 +1,1696 @@
+//! 所有 gizmo 绘制系统和动画标记的更新。
+
+use super::*;
+
+pub(crate) fn apply_curve_style(
+    style: Res<CurveStyle>,
+    mut config_store: ResMut<GizmoConfigStore>,
+) {
+    if !style.is_changed() {
+        return;
+    }
+    for kind in CurveKind::ALL {
+        if let Some((config, _)) = config_store.get_config_mut_dyn(&curve_gizmo_group(kind)) {
+            config.line_width = style.line_width(kind);
+        }
+    }
+    config_store.config_mut::<PointGizmos>().0.line_width = style.point.line_width;
+    config_store.config_mut::<OverlayGizmos>().0.line_width = style.overlay_line_width;
+}
+
+// 一条曲线这一帧要画的内容，与具体的 gizmo 配置组无关
+pub(crate) enum CurveDrawing<'a> {
+    Dashed {
+        vertices: &'a [Vec2],
+        style: &'a DashStyle,
+        color: Srgba,
+    },
+    Curve {
+        samples: &'a Samples,
+        paint: &'a CurvePaint,
+        domain_end: f32,
+        // 低分辨率时把采样点也画出来
+        sample_dots: Option<Srgba>,
+    },
+}
+
+impl CurveDrawing<'_> {
+    pub(crate) fn draw<G: GizmoConfigGroup>(&self, gizmos: &mut Gizmos<G>) {
+        match self {
+            CurveDrawing::Dashed {
+                vertices,
+                style,
+                color,
+            } => draw_dashed_polyline(gizmos, vertices, style, *color),
+            CurveDrawing::Curve {
+                samples,
+                paint,
+                domain_end,
+                sample_dots,
+            } => {
+                render_curve(gizmos, samples, paint, *domain_end);
+                if let Some(color) = sample_dots {
+                    for sample in &samples.positions {
+                        gizmos.circle_2d(Isometry2d::from_xy(sample.x, sample.y), 1.5, *color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// 只在绘制时需要的 gizmo 配置和网格实体，不绘制的 App 中不运行
+pub(crate) fn setup_rendering(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // gizmo 只能画轮廓，实心的标记用网格；匀速标记在上层
+    let marker_mesh = meshes.add(Circle::new(ANIMATION_MARKER_RADIUS));
+    for (mode, z) in [
+        (PlaybackMode::UniformT, 1.0),
+        (PlaybackMode::ConstantSpeed, 1.1),
+    ] {
+        commands.spawn((
+            Mesh2d(marker_mesh.clone()),
+            MeshMaterial2d(materials.add(ColorMaterial::from_color(CONSTANT_SPEED_MARKER_COLOR))),
+            Transform::from_xyz(0.0, 0.0, z),
+            Visibility::Hidden,
+            AnimationMarker(mode),
+        ));
+    }
+    // 箭头尖朝 +x，旋转角就是朝向
+    let length = DIRECTION_ARROW_LENGTH;
+    commands.spawn((
+        Mesh2d(meshes.add(Triangle2d::new(
+            Vec2::new(length * 0.6, 0.0),
+            Vec2::new(-length * 0.4, length * 0.35),
+            Vec2::new(-length * 0.4, -length * 0.35),
+        ))),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(GOLD))),
+        Transform::from_xyz(0.0, 0.0, 1.2),
+        Visibility::Hidden,
+        DirectionArrow::default(),
+    ));
+    // 网格模式下每种曲线一个实体，颜色全部来自顶点，材质只用一个白色的
+    let curve_material = materials.add(ColorMaterial::from_color(WHITE));
+    for kind in CurveKind::ALL {
+        if kind == CurveKind::ControlPolygon {
+            continue;
+        }
+        commands.spawn((
+            Mesh2d(meshes.add(Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            ))),
+            MeshMaterial2d(curve_material.clone()),
+            Transform::default(),
+            Visibility::Hidden,
+            CurveMesh(kind),
+        ));
+    }
+}
+
+// 在编辑之后、重建曲线之前运行。点数变化时开始一次过渡；点数不变而位置变了
+// （拖动、微调）时结束正在进行的过渡，直接操作保持 1:1
+pub(crate) fn ease_display_points(
+    control_points: Res<ControlPoints>,
+    time: Res<Time>,
+    mut display: ResMut<DisplayPoints>,
+) {
+    if control_points.is_changed() {
+        let DisplayPoints {
+            previous,
+            list,
+            transition,
+        } = &mut *display;
+        let points = &control_points.points;
+        // 切换点集不是增删点
+        if control_points.active != *list {
+            *list = control_points.active;
+            *transition = None;
+        } else if points.len() != previous.len() {
+            let shown = match transition {
+                Some(transition) => transition.shown(),
+                None => previous.iter().map(|p| p.position).collect(),
+            };
+            *transition = PointTransition::between(previous, &shown, points);
+        } else if points
+            .iter()
+            .zip(previous.iter())
+            .any(|(a, b)| a.position != b.position)
+        {
+            *transition = None;
+        }
+        previous.clone_from(points);
+    }
+    // 没有过渡时不触碰资源，缓存不会每帧重建
+    if display.transition.is_none() {
+        return;
+    }
+    if display
+        .transition
+        .as_mut()
+        .is_some_and(|transition| transition.advance(time.delta_secs()))
+    {
+        display.transition = None;
+    }
+}
+
+// 采样一条曲线放进缓存；curvatures 只在 `heatmap` 时计算
+fn cache_curve(
+    kind: CurveKind,
+    curve: &dyn PlanarCurve,
+    sampling_mode: SamplingMode,
+    resolution: usize,
+    epsilon: f32,
+    heatmap: bool,
+) -> CachedCurve {
+    // 控制多边形只取顶点，用虚线绘制
+    let samples = if kind == CurveKind::ControlPolygon {
+        curve.sample(resolution)
+    } else {
+        sample_curve(curve, sampling_mode, resolution, epsilon)
+    };
+    let curvatures = if heatmap && kind != CurveKind::ControlPolygon {
+        segment_curvatures(curve, &samples)
+    } else {
+        Vec::new()
+    };
+    CachedCurve {
+        samples,
+        domain_end: curve.domain_end(),
+        segments: curve.segment_count(),
+        curvatures,
+    }
+}
+
+// 控制点或采样设置变化时重建所有显示中的曲线，拖动点时在同一帧内更新；
+// 相机移动和窗口缩放不改变世界坐标中的采样，不会触发重建。
+// 各类曲线不论是否显示都构造并放进 BuiltCurves，显示时采样的就是这一份。
+// 重建后采样点与 SampledCurves 中的不同的曲线才更新它并发送 CurveChanged
+pub(crate) fn update_curve_cache(
+    control_points: Res<ControlPoints>,
+    display: Res<DisplayPoints>,
+    visibility: Res<CurveVisibility>,
+    sampling_mode: Res<SamplingMode>,
+    resolution: Res<CurveResolution>,
+    epsilon: Res<AdaptiveEpsilon>,
+    color_mode: Res<ColorMode>,
+    mut cache: ResMut<CachedCurves>,
+    mut stats: ResMut<CurveStats>,
+    mut coincident: ResMut<CoincidentPoints>,
+    mut built: ResMut<BuiltCurves>,
+    mut sampled: ResMut<SampledCurves>,
+    mut changed: EventWriter<CurveChanged>,
+    mut diagnostics: Diagnostics,
+    mut inputs: Local<CurveInputs>,
+) {
+    let dirty = control_points.is_changed()
+        || display.is_changed()
+        || visibility.is_changed()
+        || sampling_mode.is_changed()
+        || resolution.is_changed()
+        || epsilon.is_changed()
+        || color_mode.is_changed();
+    if !dirty {
+        // 没有重建的帧记为 0，平均值反映缓存省下的时间
+        diagnostics.add_measurement(&CURVE_BUILD_TIME, || 0.0);
+        return;
+    }
+    // 只统计曲线构造和采样
+    let started = Instant::now();
+    let eased = display.transition.as_ref().map(|transition| ControlPoints {
+        points: transition.points(),
+        closed: control_points.closed,
+        parameterization: control_points.parameterization,
+        clamped: control_points.clamped,
+        end_condition: control_points.end_condition,
+        end_tangents: control_points.end_tangents,
+        symmetry: control_points.symmetry,
+        ..default()
+    });
+    inputs.collect(eased.as_ref().unwrap_or(&*control_points));
+    if coincident.0 != inputs.coincident {
+        coincident.0.clone_from(&inputs.coincident);
+    }
+    for kind in CurveKind::ALL {
+        let visible = visibility.is_visible(kind);
+        let sample = |curve: &dyn PlanarCurve| {
+            cache_curve(
+                kind,
+                curve,
+                *sampling_mode,
+                resolution.0,
+                epsilon.0,
+                *color_mode == ColorMode::Heatmap,
+            )
+        };
+        match built.slot_mut(kind) {
+            Some(slot) => *slot = cubic_from_inputs(kind, &inputs),
+            None => built.others[kind.index()] = curve_from_inputs(kind, &inputs),
+        }
+        cache.0[kind.index()] = built.curve(kind).filter(|_| visible).map(sample);
+    }
+    let build_time = started.elapsed();
+    diagnostics.add_measurement(&CURVE_BUILD_TIME, || build_time.as_secs_f64() * 1000.0);
+    for (stat, cached) in stats.0.iter_mut().zip(&cache.0) {
+        *stat = cached.as_ref().map(|cached| CurveStat {
+            length: polyline_length(&cached.samples.positions),
+            samples: cached.samples.len(),
+            segments: cached.segments,
+        });
+    }
+    for kind in CurveKind::ALL {
+        let positions = cache.0[kind.index()]
+            .as_ref()
+            .map_or(&[][..], |cached| cached.samples.positions.as_slice());
+        if sampled.get(kind) == positions {
+            continue;
+        }
+        let samples = Arc::new(positions.to_vec());
+        sampled.0[kind.index()] = Arc::clone(&samples);
+        changed.send(CurveChanged {
+            curve: kind,
+            samples,
+        });
+    }
+}
+
+// 在 update_curve_cache 之后运行，只在采样缓存或开关变化时重新求交
+pub(crate) fn update_intersections(
+    cache: Res<CachedCurves>,
+    overlay: Res<IntersectionOverlay>,
+    mut intersections: ResMut<CurveIntersections>,
+) {
+    if !cache.is_changed() && !overlay.is_changed() {
+        return;
+    }
+    intersections.crossings.clear();
+    intersections.self_crossings.clear();
+    if !overlay.crossings {
+        return;
+    }
+    // 控制多边形不是曲线，不参与求交
+    let curves: Vec<&[Vec2]> = CurveKind::ALL
+        .into_iter()
+        .filter(|kind| *kind != CurveKind::ControlPolygon)
+        .filter_map(|kind| cache.0[kind.index()].as_ref())
+        .map(|cached| cached.samples.positions.as_slice())
+        .collect();
+    for (i, a) in curves.iter().enumerate() {
+        for b in &curves[i + 1..] {
+            let hits = polyline_intersections(a, b, INTERSECTION_EPSILON);
+            intersections.crossings.extend(hits);
+        }
+        if overlay.self_crossings {
+            let hits = self_intersections(a, INTERSECTION_EPSILON);
+            intersections.self_crossings.extend(hits);
+        }
+    }
+}
+
+// 偏移用缓存里的采样参数和采样时的那条曲线，和曲线本身的采样一一对应
+pub(crate) fn update_offset_curves(
+    cache: Res<CachedCurves>,
+    built: Res<BuiltCurves>,
+    overlay: Res<OffsetOverlay>,
+    active: Res<ActiveCurve>,
+    mut offsets: ResMut<OffsetCurves>,
+) {
+    if !cache.is_changed() && !overlay.is_changed() && !active.is_changed() {
+        return;
+    }
+    let OffsetCurves { sides, crossings } = &mut *offsets;
+    sides.iter_mut().for_each(Vec::clear);
+    crossings.clear();
+    if !overlay.enabled {
+        return;
+    }
+    let Some(cached) = &cache.0[active.0.index()] else {
+        return;
+    };
+    let Some(curve) = built.curve(active.0) else {
+        return;
+    };
+    for (side, sign) in sides.iter_mut().zip([1.0, -1.0]) {
+        *side = offset_samples(curve, &cached.samples.params, overlay.distance * sign);
+        let positions: Vec<Vec2> = side.iter().map(|s| s.position).collect();
+        crossings.extend(self_intersections(&positions, INTERSECTION_EPSILON));
+    }
+}
+
+// 第一条曲线用缓存的采样点，第二条取构造好的曲线以便求最近点；只在缓存或曲线对变化时计算
+pub(crate) fn update_curve_deviation(
+    cache: Res<CachedCurves>,
+    pair: Res<DeviationPair>,
+    built: Res<BuiltCurves>,
+    mut current: ResMut<CurveDeviation>,
+) {
+    if !cache.is_changed() && !pair.is_changed() {
+        return;
+    }
+    let (from, to) = pair.kinds();
+    let result = match (&cache.0[from.index()], &cache.0[to.index()]) {
+        (Some(cached), Some(_)) => built
+            .curve(to)
+            .and_then(|curve| deviation(&cached.samples.positions, curve)),
+        _ => None,
+    };
+    if current.0 != result {
+        current.0 = result;
+    }
+}
+
+pub(crate) fn plot_line(
+    mut gizmos: CurveGizmos,
+    cache: Res<CachedCurves>,
+    control_points: Res<ControlPoints>,
+    resolution: Res<CurveResolution>,
+    dash_style: Res<DashStyle>,
+    color_mode: Res<ColorMode>,
+    gradients: Res<GradientSettings>,
+    mut heatmap_scale: ResMut<HeatmapScale>,
+    time: Res<Time>,
+    style: Res<CurveStyle>,
+    render_mode: Res<CurveRenderMode>,
+) {
+    for kind in CurveKind::ALL {
+        let Some(cached) = &cache.0[kind.index()] else {
+            continue;
+        };
+        let color = style.curve_color(kind, control_points.closed);
+        if kind == CurveKind::ControlPolygon {
+            // 控制多边形用虚线绘制，和曲线区分开
+            gizmos.draw(
+                kind,
+                &CurveDrawing::Dashed {
+                    vertices: &cached.samples.positions,
+                    style: &dash_style,
+                    color,
+                },
+            );
+            continue;
+        }
+        let mut scale = 0.0;
+        if *color_mode == ColorMode::Heatmap {
+            let target = heatmap::percentile(&cached.curvatures, HEATMAP_PERCENTILE).unwrap_or(0.0);
+            let smoothed = &mut heatmap_scale.0[kind.index()];
+            scale = match *smoothed {
+                Some(current) => {
+                    heatmap::smooth_towards(current, target, HEATMAP_SCALE_RATE, time.delta_secs())
+                }
+                None => target,
+            };
+            *smoothed = Some(scale);
+        }
+        // 网格模式下热力图的上限照样在这里平滑，曲线由 update_curve_meshes 画
+        if render_mode.join().is_some() {
+            continue;
+        }
+        let paint = curve_paint(kind, color, *color_mode, &gradients, cached, scale);
+        let sample_dots = (resolution.0 < CurveResolution::SHOW_SAMPLES_BELOW).then_some(color);
+        gizmos.draw(
+            kind,
+            &CurveDrawing::Curve {
+                samples: &cached.samples,
+                paint: &paint,
+                domain_end: cached.domain_end,
+                sample_dots,
+            },
+        );
+    }
+}
+
+// 光标处右键会加上的点：按住右键、拖动、变换、光标在界面上时没有预览。
+// 光标不动时不重建，曲线与 update_curve_cache 一样采样
+pub(crate) fn update_ghost_preview(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cursor: WorldCursor,
+    grid: Res<GridSettings>,
+    captured: Res<PointerCaptured>,
+    drag: Res<DragState>,
+    modal: Res<ModalTransform>,
+    control_points: Res<ControlPoints>,
+    visibility: Res<CurveVisibility>,
+    sampling_mode: Res<SamplingMode>,
+    resolution: Res<CurveResolution>,
+    epsilon: Res<AdaptiveEpsilon>,
+    view: Res<ViewScale>,
+    mut ghost: ResMut<GhostPreview>,
+    mut inputs: Local<CurveInputs>,
+) {
+    let adding = bindings
+        .button(Action::AddPoint)
+        .is_some_and(|button| mouse.pressed(button));
+    let point = cursor
+        .position()
+        .filter(|_| !adding && !captured.0 && matches!(*drag, DragState::Idle) && modal.0.is_none())
+        .map(|world_position| {
+            let position = snapped_position(world_position, &grid, &bindings, &keyboard);
+            prospective_point(&control_points, world_position, position, view.0)
+        });
+    let dirty = ghost.point != point
+        || control_points.is_changed()
+        || visibility.is_changed()
+        || sampling_mode.is_changed()
+        || resolution.is_changed()
+        || epsilon.is_changed();
+    if !dirty {
+        return;
+    }
+    ghost.point = point;
+    let Some((index, position)) = point else {
+        ghost.curves = Default::default();
+        return;
+    };
+    let mut points = control_points.points.clone();
+    let new_point = MovablePoint {
+        position,
+        ..default()
+    };
+    match index {
+        Some(index) => points.insert(index, new_point),
+        None => points.push(new_point),
+    }
+    inputs.collect(&ControlPoints {
+        points,
+        closed: control_points.closed,
+        parameterization: control_points.parameterization,
+        clamped: control_points.clamped,
+        end_condition: control_points.end_condition,
+        end_tangents: control_points.end_tangents,
+        symmetry: control_points.symmetry,
+        ..default()
+    });
+    for kind in CurveKind::ALL {
+        ghost.curves[kind.index()] = visibility
+            .is_visible(kind)
+            .then(|| curve_from_inputs(kind, &inputs))
+            .flatten()
+            .map(|curve| {
+                cache_curve(
+                    kind,
+                    curve.as_ref(),
+                    *sampling_mode,
+                    resolution.0,
+                    epsilon.0,
+                    false,
+                )
+            });
+    }
+}
+
+// 预览画在真实的曲线下面
+pub(crate) fn plot_ghost_preview(
+    mut gizmos: CurveGizmos,
+    mut point_gizmos: Gizmos<PointGizmos>,
+    ghost: Res<GhostPreview>,
+    control_points: Res<ControlPoints>,
+    dash_style: Res<DashStyle>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+) {
+    let Some((_, position)) = ghost.point else {
+        return;
+    };
+    for kind in CurveKind::ALL {
+        let Some(cached) = &ghost.curves[kind.index()] else {
+            continue;
+        };
+        let color = style
+            .curve_color(kind, control_points.closed)
+            .with_alpha(GHOST_ALPHA);
+        let drawing = if kind == CurveKind::ControlPolygon {
+            CurveDrawing::Dashed {
+                vertices: &cached.samples.positions,
+                style: &dash_style,
+                color,
+            }
+        } else {
+            CurveDrawing::Curve {
+                samples: &cached.samples,
+                paint: &CurvePaint::Solid(color),
+                domain_end: cached.domain_end,
+                sample_dots: None,
+            }
+        };
+        gizmos.draw(kind, &drawing);
+    }
+    point_gizmos.circle_2d(
+        Isometry2d::from_translation(position),
+        MovablePoint::default().show_size * view.0,
+        list_point_color(&style.point, control_points.active).with_alpha(GHOST_ALPHA),
+    );
+}
+
+// 没有在编辑的点集的显示中的曲线，与 update_curve_cache 一样只在控制点或采样设置变化时重新构造和采样
+pub(crate) fn update_inactive_list_curves(
+    control_points: Res<ControlPoints>,
+    visibility: Res<CurveVisibility>,
+    sampling_mode: Res<SamplingMode>,
+    resolution: Res<CurveResolution>,
+    epsilon: Res<AdaptiveEpsilon>,
+    mut curves: ResMut<InactiveListCurves>,
+) {
+    let dirty = control_points.is_changed()
+        || visibility.is_changed()
+        || sampling_mode.is_changed()
+        || resolution.is_changed()
+        || epsilon.is_changed();
+    if !dirty {
+        return;
+    }
+    curves.0.clear();
+    for (_, list) in control_points.inactive_lists() {
+        let inputs = CurveInputs::new(&list.to_control_points());
+        for kind in CurveKind::ALL {
+            if !visibility.is_visible(kind) {
+                continue;
+            }
+            let Some(curve) = curve_from_inputs(kind, &inputs) else {
+                continue;
+            };
+            curves.0.push(InactiveListCurve {
+                kind,
+                closed: list.closed,
+                samples: sample_curve(curve.as_ref(), *sampling_mode, resolution.0, epsilon.0),
+                domain_end: curve.domain_end(),
+            });
+        }
+    }
+}
+
+// 没有在编辑的点集：缓存的曲线用半透明的颜色画出；点画成各点集颜色的小圆，没有手柄
+pub(crate) fn plot_inactive_lists(
+    mut gizmos: CurveGizmos,
+    mut point_gizmos: Gizmos<PointGizmos>,
+    control_points: Res<ControlPoints>,
+    curves: Res<InactiveListCurves>,
+    dash_style: Res<DashStyle>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+) {
+    for curve in &curves.0 {
+        let color = style
+            .curve_color(curve.kind, curve.closed)
+            .with_alpha(INACTIVE_LIST_ALPHA);
+        let drawing = if curve.kind == CurveKind::ControlPolygon {
+            CurveDrawing::Dashed {
+                vertices: &curve.samples.positions,
+                style: &dash_style,
+                color,
+            }
+        } else {
+            CurveDrawing::Curve {
+                samples: &curve.samples,
+                paint: &CurvePaint::Solid(color),
+                domain_end: curve.domain_end,
+                sample_dots: None,
+            }
+        };
+        gizmos.draw(curve.kind, &drawing);
+    }
+    for (index, list) in control_points.inactive_lists() {
+        let color = list_point_color(&style.point, index).with_alpha(INACTIVE_LIST_ALPHA);
+        for point in &list.points {
+            point_gizmos.circle_2d(
+                Isometry2d::from_translation(point.position),
+                point.show_size * view.0,
+                color,
+            );
+        }
+    }
+}
+
+// 与非均匀参数化的 Catmull-Rom 对照的均匀版本，用同一种颜色的半透明线画出
+pub(crate) fn plot_parameterization_comparison(
+    mut gizmos: CurveGizmos,
+    comparison: Res<ParameterizationComparison>,
+    cache: Res<CachedCurves>,
+    control_points: Res<ControlPoints>,
+    sampling_mode: Res<SamplingMode>,
+    resolution: Res<CurveResolution>,
+    epsilon: Res<AdaptiveEpsilon>,
+    style: Res<CurveStyle>,
+) {
+    let kind = CurveKind::CatmullRom;
+    if !comparison.0
+        || control_points.parameterization == Parameterization::Uniform
+        || cache.0[kind.index()].is_none()
+    {
+        return;
+    }
+    let uniform = ControlPoints {
+        points: control_points.points.clone(),
+        closed: control_points.closed,
+        parameterization: Parameterization::Uniform,
+        clamped: control_points.clamped,
+        end_condition: control_points.end_condition,
+        end_tangents: control_points.end_tangents,
+        symmetry: control_points.symmetry,
+        ..default()
+    };
+    let Some(curve) = build_curve(kind, &uniform) else {
+        return;
+    };
+    let samples = sample_curve(curve.as_ref(), *sampling_mode, resolution.0, epsilon.0);
+    let color = style
+        .curve_color(kind, control_points.closed)
+        .with_alpha(0.35);
+    gizmos.draw(
+        kind,
+        &CurveDrawing::Curve {
+            samples: &samples,
+            paint: &CurvePaint::Solid(color),
+            domain_end: curve.domain_end(),
+            sample_dots: None,
+        },
+    );
+}
+
+fn curve_paint(
+    kind: CurveKind,
+    color: Srgba,
+    color_mode: ColorMode,
+    gradients: &GradientSettings,
+    cached: &CachedCurve,
+    heatmap_scale: f32,
+) -> CurvePaint {
+    match color_mode {
+        ColorMode::Solid => CurvePaint::Solid(color),
+        ColorMode::Gradient => {
+            let (start, end) = gradients.endpoints(kind);
+            CurvePaint::Gradient(start, end)
+        }
+        ColorMode::Heatmap => CurvePaint::Heatmap {
+            curvatures: cached.curvatures.clone(),
+            scale: heatmap_scale,
+        },
+    }
+}
+
+// 网格模式下把缓存的采样折线扩成三角形，在 plot_line 平滑热力图上限之后运行。
+// 每种曲线的网格资源原地改写，拖动时不会每帧新建资源；线宽是屏幕像素，缩放后也要重建
+pub(crate) fn update_curve_meshes(
+    cache: Res<CachedCurves>,
+    control_points: Res<ControlPoints>,
+    render_mode: Res<CurveRenderMode>,
+    color_mode: Res<ColorMode>,
+    gradients: Res<GradientSettings>,
+    heatmap_scale: Res<HeatmapScale>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut curves: Query<(&CurveMesh, &Mesh2d, &mut Visibility)>,
+) {
+    let dirty = cache.is_changed()
+        || render_mode.is_changed()
+        || color_mode.is_changed()
+        || gradients.is_changed()
+        || heatmap_scale.is_changed()
+        || style.is_changed()
+        || view.is_changed();
+    if !dirty {
+        return;
+    }
+    for (curve, mesh, mut visibility) in &mut curves {
+        let kind = curve.0;
+        let stroke = match (render_mode.join(), &cache.0[kind.index()]) {
+            (Some(join), Some(cached)) => {
+                let width = style.line_width(kind) * view.0;
+                Some((
+                    stroke_polyline(&cached.samples.positions, width, join),
+                    cached,
+                ))
+            }
+            _ => None,
+        };
+        let Some((stroke, cached)) = stroke.filter(|(stroke, _)| !stroke.indices.is_empty()) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        let Some(mesh) = meshes.get_mut(&mesh.0) else {
+            continue;
+        };
+        let color = style.curve_color(kind, control_points.closed);
+        let scale = heatmap_scale.0[kind.index()].unwrap_or(0.0);
+        let paint = curve_paint(kind, color, *color_mode, &gradients, cached, scale);
+        let colors = sample_colors(&cached.samples.params, &paint, cached.domain_end);
+        let positions: Vec<[f32; 3]> = stroke.positions.iter().map(|p| [p.x, p.y, 0.0]).collect();
+        let vertex_colors: Vec<[f32; 4]> = stroke
+            .sources
+            .iter()
+            .map(|&i| LinearRgba::from(colors[i]).to_f32_array())
+            .collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vertex_colors);
+        mesh.insert_indices(Indices::U32(stroke.indices));
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+// 在当前曲线的采样点上画垂直于切线的梳齿，长度与曲率成正比，梳齿末端连成折线
+pub(crate) fn plot_curvature_comb(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    built: Res<BuiltCurves>,
+    active: Res<ActiveCurve>,
+    comb: Res<CurvatureComb>,
+    style: Res<CurveStyle>,
+) {
+    if !comb.enabled {
+        return;
+    }
+    let Some(curve) = built.curve(active.0) else {
+        return;
+    };
+    let color = style.color(active.0).with_alpha(0.6);
+    let samples = curve.segment_count() * COMB_SAMPLES_PER_SEGMENT;
+    let step = curve.domain_end() / samples as f32;
+    let mut tips = Vec::new();
+    for i in 0..=samples {
+        let t = i as f32 * step;
+        let Some(curvature) = signed_curvature(curve, t) else {
+            // 速度为零的退化处断开梳齿连线
+            gizmos.linestrip_2d(tips.drain(..), color);
+            continue;
+        };
+        let position = curve.position(t);
+        let normal = curve.velocity(t).normalize().perp();
+        let tip = position - normal * curvature * comb.scale;
+        gizmos.line_2d(position, tip, color);
+        tips.push(tip);
+    }
+    gizmos.linestrip_2d(tips, color);
+}
+
+// 切线方向来自曲线速度，归一化后按固定的世界长度绘制，法线为其垂直方向
+pub(crate) fn plot_tangent_overlay(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    built: Res<BuiltCurves>,
+    active: Res<ActiveCurve>,
+    overlay: Res<TangentOverlay>,
+) {
+    if !overlay.0 {
+        return;
+    }
+    let Some(curve) = built.curve(active.0) else {
+        return;
+    };
+    let samples = curve.segment_count() * SAMPLES_PER_SEGMENT;
+    let step = curve.domain_end() / samples as f32;
+    for i in (0..=samples).step_by(TANGENT_SAMPLE_STRIDE) {
+        let t = i as f32 * step;
+        // 速度接近零时方向没有意义，直接跳过
+        let velocity = curve.velocity(t);
+        if velocity.length_squared() < 1e-8 {
+            continue;
+        }
+        let direction = velocity.normalize();
+        let normal = direction.perp();
+        let position = curve.position(t);
+        let tip = position + direction * TANGENT_LENGTH;
+        gizmos.line_2d(position, tip, WHITE);
+        let head = tip - direction * 6.0;
+        gizmos.line_2d(tip, head + normal * 4.0, WHITE);
+        gizmos.line_2d(tip, head - normal * 4.0, WHITE);
+        gizmos.line_2d(position, position + normal * NORMAL_LENGTH, LIGHT_GRAY);
+    }
+}
+
+// 在屏幕底部画当前曲线的 |velocity| 随 t 的变化，纵轴按当前最大速度缩放；
+// 图框按屏幕像素定义，每帧换算到世界坐标，视图移动时也固定在屏幕上
+pub(crate) fn plot_velocity_strip(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    built: Res<BuiltCurves>,
+    active: Res<ActiveCurve>,
+    mouse_position: Res<MousePosition>,
+    windows: Query<&Window>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    clean_capture: Res<CleanCapture>,
+    style: Res<CurveStyle>,
+) {
+    if clean_capture.is_hiding() {
+        return;
+    }
+    let Some(curve) = built.curve(active.0) else {
+        return;
+    };
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let to_world = |screen: Vec2| camera.viewport_to_world_2d(camera_transform, screen).ok();
+    let bottom = window.height() - VELOCITY_PLOT_BOTTOM;
+    let top = bottom - VELOCITY_PLOT_HEIGHT;
+    let (Some(top_left), Some(bottom_right)) = (
+        to_world(Vec2::new(VELOCITY_PLOT_MARGIN, top)),
+        to_world(Vec2::new(window.width() - VELOCITY_PLOT_MARGIN, bottom)),
+    ) else {
+        return;
+    };
+    let size = Vec2::new(bottom_right.x - top_left.x, top_left.y - bottom_right.y);
+    let origin = Vec2::new(top_left.x, bottom_right.y);
+
+    let domain_end = curve.domain_end();
+    let speeds: Vec<f32> = (0..=VELOCITY_PLOT_SAMPLES)
+        .map(|i| {
+            let t = i as f32 / VELOCITY_PLOT_SAMPLES as f32 * domain_end;
+            curve.velocity(t).length()
+        })
+        .collect();
+    let max_speed = speeds.iter().copied().fold(0.0, f32::max);
+    // 横轴是归一化参数 t / domain_end
+    let to_plot = |u: f32, y: f32| origin + Vec2::new(u * size.x, y * size.y);
+
+    let frame = GRAY.with_alpha(0.6);
+    gizmos.linestrip_2d(
+        [
+            to_plot(0.0, 0.0),
+            to_plot(1.0, 0.0),
+            to_plot(1.0, 1.0),
+            to_plot(0.0, 1.0),
+            to_plot(0.0, 0.0),
+        ],
+        frame,
+    );
+    // t=0 和 t=1 的刻度画在图框下方，段边界画短刻度
+    let tick = size.y * 0.12;
+    for u in [0.0, 1.0] {
+        gizmos.line_2d(to_plot(u, 0.0), to_plot(u, 0.0) - Vec2::Y * tick, WHITE);
+    }
+    for segment in 1..curve.segment_count() {
+        let u = segment as f32 / domain_end;
+        gizmos.line_2d(
+            to_plot(u, 0.0),
+            to_plot(u, 0.0) - Vec2::Y * tick * 0.5,
+            frame,
+        );
+    }
+
+    if max_speed > 0.0 {
+        gizmos.linestrip_2d(
+            speeds.iter().enumerate().map(|(i, speed)| {
+                to_plot(i as f32 / VELOCITY_PLOT_SAMPLES as f32, speed / max_speed)
+            }),
+            style.color(active.0),
+        );
+    }
+
+    // 竖线标出离鼠标最近的曲线点对应的参数
+    let Some(mouse_point) = mouse_position.0.and_then(to_world) else {
+        return;
+    };
+    let u = closest_parameter(curve, mouse_point) / domain_end;
+    gizmos.line_2d(to_plot(u, 0.0), to_plot(u, 1.0), WHITE);
+}
+
+// 鼠标靠近当前曲线时，在最近点处画密切圆：半径为 1/曲率，圆心沿法线方向
+pub(crate) fn plot_osculating_circle(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    built: Res<BuiltCurves>,
+    active: Res<ActiveCurve>,
+    style: Res<CurveStyle>,
+    mouse_position: Res<MousePosition>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    view: Res<ViewScale>,
+) {
+    let Some(mouse_position) = mouse_position.0 else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let Ok(mouse_point) = camera.viewport_to_world_2d(camera_transform, mouse_position) else {
+        return;
+    };
+    let Some(curve) = built.curve(active.0) else {
+        return;
+    };
+    let t = closest_parameter(curve, mouse_point);
+    let contact = curve.position(t);
+    if contact.distance(mouse_point) > OSCULATING_HOVER_DISTANCE * view.0 {
+        return;
+    }
+    let color = style.color(active.0);
+    gizmos.circle_2d(Isometry2d::from_xy(contact.x, contact.y), 3.0, color);
+    let Some(curvature) = signed_curvature(curve, t) else {
+        return;
+    };
+    let radius = 1.0 / curvature.abs();
+    if radius > OSCULATING_MAX_RADIUS {
+        return;
+    }
+    // 曲率为正时曲线向左转，圆心在速度方向的左侧
+    let normal = curve.velocity(t).normalize().perp() * curvature.signum();
+    let center = contact + normal * radius;
+    gizmos.circle_2d(
+        Isometry2d::from_xy(center.x, center.y),
+        radius,
+        color.with_alpha(0.5),
+    );
+    gizmos.line_2d(contact, center, color.with_alpha(0.3));
+}
+
+// 光标到当前曲线上最近点的细连线，最近点处画一个小圆
+pub(crate) fn plot_closest_point(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    closest: Res<ClosestPoint>,
+    cursor: WorldCursor,
+    active: Res<ActiveCurve>,
+    style: Res<CurveStyle>,
+) {
+    let (Some((_, position)), Some(cursor)) = (closest.hit, cursor.position()) else {
+        return;
+    };
+    let color = style.color(active.0);
+    gizmos.line_2d(cursor, position, color.with_alpha(0.4));
+    gizmos.circle_2d(Isometry2d::from_translation(position), 4.0, color);
+}
+
+// 两两交点画成斜十字，自身交点画成正十字；缩放后在屏幕上大小不变
+pub(crate) fn plot_intersections(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    intersections: Res<CurveIntersections>,
+    view: Res<ViewScale>,
+) {
+    let size = INTERSECTION_MARK_SIZE * view.0;
+    for &point in &intersections.crossings {
+        gizmos.line_2d(point - Vec2::splat(size), point + Vec2::splat(size), WHITE);
+        gizmos.line_2d(
+            point + Vec2::new(-size, size),
+            point + Vec2::new(size, -size),
+            WHITE,
+        );
+    }
+    for &point in &intersections.self_crossings {
+        gizmos.line_2d(point - Vec2::X * size, point + Vec2::X * size, YELLOW);
+        gizmos.line_2d(point - Vec2::Y * size, point + Vec2::Y * size, YELLOW);
+    }
+}
+
+// 被合并的重合点画成一个橙红色的三角形，只在有插值样条显示时提示
+pub(crate) fn plot_coincident_points(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    coincident: Res<CoincidentPoints>,
+    visibility: Res<CurveVisibility>,
+    view: Res<ViewScale>,
+) {
+    if !CurveKind::ALL
+        .into_iter()
+        .any(|kind| kind.interpolates() && visibility.is_visible(kind))
+    {
+        return;
+    }
+    let size = COINCIDENT_MARK_SIZE * view.0;
+    for &point in &coincident.0 {
+        let corners = [90.0_f32, 210.0, 330.0]
+            .map(|degrees| point + Vec2::from_angle(degrees.to_radians()) * size);
+        gizmos.linestrip_2d([corners[0], corners[1], corners[2], corners[0]], ORANGE_RED);
+        gizmos.line_2d(point, point + Vec2::Y * size * 0.4, ORANGE_RED);
+    }
+}
+
+// 包围盒取自采样缓存，凸包每帧由控制点重新计算，拖动时两者都跟着更新。
+// Bézier 和 B 样条曲线总在控制点的凸包之内
+pub(crate) fn plot_bounds(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    overlay: Res<BoundsOverlay>,
+    cache: Res<CachedCurves>,
+    control_points: Res<ControlPoints>,
+    active: Res<ActiveCurve>,
+    dash_style: Res<DashStyle>,
+    style: Res<CurveStyle>,
+) {
+    if overlay.bounding_box {
+        let samples = cache.0[active.0.index()]
+            .as_ref()
+            .map(|cached| cached.samples.positions.as_slice())
+            .unwrap_or_default();
+        if let Some((&first, rest)) = samples.split_first() {
+            let bounds = rest
+                .iter()
+                .fold(Rect::from_corners(first, first), |r, &p| r.union_point(p));
+            let corners = [
+                bounds.min,
+                Vec2::new(bounds.max.x, bounds.min.y),
+                bounds.max,
+                Vec2::new(bounds.min.x, bounds.max.y),
+                bounds.min,
+            ];
+            let color = style.color(active.0).with_alpha(0.6);
+            draw_dashed_polyline(&mut gizmos, &corners, &dash_style, color);
+        }
+    }
+    if overlay.hull {
+        let hull = convex_hull(&control_points.positions());
+        match hull.len() {
+            0 => {}
+            1 => {
+                gizmos.circle_2d(Isometry2d::from_translation(hull[0]), 2.0, LIGHT_GRAY);
+            }
+            _ => {
+                let closed = hull.iter().chain(hull.first()).copied();
+                gizmos.linestrip_2d(closed, LIGHT_GRAY.with_alpha(0.5));
+            }
+        }
+    }
+}
+
+// 拐点画空心菱形，曲率最大值用几层同心菱形画成实心
+pub(crate) fn plot_curvature_features(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    features: Res<CurvatureFeatures>,
+    active: Res<ActiveCurve>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+) {
+    let color = style.color(active.0);
+    let size = FEATURE_MARK_SIZE * view.0;
+    for feature in &features.0 {
+        let layers: &[f32] = match feature.kind {
+            FeatureKind::Inflection => &[1.0],
+            FeatureKind::Maximum => &[1.0, 0.75, 0.5, 0.25],
+        };
+        for layer in layers {
+            gizmos.linestrip_2d(diamond(feature.position, size * layer), color);
+        }
+    }
+}
+
+fn diamond(center: Vec2, radius: f32) -> [Vec2; 5] {
+    [Vec2::X, Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y, Vec2::X].map(|c| center + c * radius)
+}
+
+// 两个端点都有效的小段用曲线的颜色，越过曲率中心反向的部分画成红色，自交点画红圈
+pub(crate) fn plot_offset_curves(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    offsets: Res<OffsetCurves>,
+    active: Res<ActiveCurve>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+) {
+    let color = style.color(active.0).with_alpha(0.7);
+    for side in &offsets.sides {
+        let valid: Vec<bool> = side.windows(2).map(|w| w[0].valid && w[1].valid).collect();
+        // 有效和无效的小段各自连成折线
+        let mut start = 0;
+        while start < valid.len() {
+            let run = valid[start..]
+                .iter()
+                .take_while(|v| **v == valid[start])
+                .count();
+            let run_color = if valid[start] { color } else { RED };
+            let positions = side[start..=start + run].iter().map(|s| s.position);
+            gizmos.linestrip_2d(positions, run_color);
+            start += run;
+        }
+    }
+    for &point in &offsets.crossings {
+        gizmos.circle_2d(Isometry2d::from_translation(point), 4.0 * view.0, RED);
+    }
+}
+
+// 细分后的两半用两种颜色画在原曲线上面，被细分的那一段的两组新控制点画成虚线控制多边形
+pub(crate) fn plot_bezier_split(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    split: Res<BezierSplit>,
+    control_points: Res<ControlPoints>,
+    resolution: Res<CurveResolution>,
+    dash_style: Res<DashStyle>,
+    view: Res<ViewScale>,
+) {
+    let Some(t) = split.0 else {
+        return;
+    };
+    let chain = bezier_chain(&control_points.positions());
+    let Some((before, after)) = split_chain(&chain, t) else {
+        return;
+    };
+    let new_segments = [before[before.len() - 1], after[0]];
+    for ((half, segment), color) in [before, after].iter().zip(new_segments).zip(SPLIT_COLORS) {
+        if let Ok(curve) = CubicBezier::new(half.clone()).to_curve() {
+            gizmos.linestrip_2d(curve.iter_positions(resolution.0 * half.len()), color);
+        }
+        draw_dashed_polyline(&mut gizmos, &segment, &dash_style, color.with_alpha(0.6));
+        for point in &segment[1..3] {
+            gizmos.circle_2d(Isometry2d::from_translation(*point), 3.0 * view.0, color);
+        }
+    }
+    gizmos.circle_2d(
+        Isometry2d::from_translation(new_segments[1][0]),
+        5.0 * view.0,
+        WHITE,
+    );
+}
+
+// 控制点和 plot_line 一样来自 bezier_chain，只画参数所在的那一段；
+// 动画的 t 是整条链上的归一化位置，播放时构造沿着整条链移动
+pub(crate) fn plot_casteljau_construction(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    construction: Res<CasteljauConstruction>,
+    visibility: Res<CurveVisibility>,
+    animation: Res<CurveAnimation>,
+    control_points: Res<ControlPoints>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+) {
+    if !construction.0 || !visibility.is_visible(CurveKind::Bezier) {
+        return;
+    }
+    let chain = bezier_chain(&control_points.positions());
+    if chain.is_empty() {
+        return;
+    }
+    let (index, u) = split_parameter(animation.t.position * chain.len() as f32, chain.len());
+    let segment = chain[index];
+    let (first, second, point) = casteljau_levels(segment, u);
+    // 每深一层颜色更接近白色
+    let base = style.color(CurveKind::Bezier);
+    let shade = |level: usize| base.mix(&WHITE, level as f32 * 0.25);
+    let levels: [&[Vec2]; 3] = [&segment, &first, &second];
+    for (level, points) in levels.into_iter().enumerate() {
+        let color = shade(level);
+        gizmos.linestrip_2d(points.iter().copied(), color.with_alpha(0.8));
+        for &p in points {
+            gizmos.circle_2d(Isometry2d::from_translation(p), 3.0 * view.0, color);
+        }
+    }
+    gizmos.circle_2d(Isometry2d::from_translation(point), 5.0 * view.0, shade(3));
+}
+
+// 每一段升阶后的控制多边形画成虚线，阶数越高越淡，并逐渐偏向白色；
+// 曲线本身不变，plot_line 画的原曲线就是这些多边形收敛的目标
+pub(crate) fn plot_degree_elevation(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    elevation: Res<DegreeElevation>,
+    visibility: Res<CurveVisibility>,
+    control_points: Res<ControlPoints>,
+    style: Res<CurveStyle>,
+    dash_style: Res<DashStyle>,
+    view: Res<ViewScale>,
+) {
+    if elevation.0 == 0 || !visibility.is_visible(CurveKind::Bezier) {
+        return;
+    }
+    let base = style.color(CurveKind::Bezier);
+    for segment in bezier_chain(&control_points.positions()) {
+        for (level, polygon) in elevations(segment, elevation.0).iter().enumerate() {
+            let fade = (level + 1) as f32 / (elevation.0 + 1) as f32;
+            let color = base.mix(&WHITE, fade * 0.5).with_alpha(0.9 - fade * 0.6);
+            draw_dashed_polyline(&mut gizmos, polygon, &dash_style, color);
+            for &p in &polygon[1..polygon.len() - 1] {
+                gizmos.circle_2d(Isometry2d::from_translation(p), 2.0 * view.0, color);
+            }
+        }
+    }
+}
+
+// 插入节点后的控制多边形画在原来的控制多边形上面，插入的节点在曲线上标成小方块。
+// 删除控制点后落到参数范围外的节点，以及同一处第四次插入的节点被跳过
+pub(crate) fn plot_knot_insertion(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    knots: Res<KnotInsertion>,
+    visibility: Res<CurveVisibility>,
+    control_points: Res<ControlPoints>,
+    view: Res<ViewScale>,
+) {
+    if knots.0.is_empty() || control_points.closed || !visibility.is_visible(CurveKind::BSpline) {
+        return;
+    }
+    // 钳制时节点插入作用在首尾重复后的控制多边形上，与画出的 B-Spline 一致
+    let positions = control_points.positions();
+    let positions = if control_points.clamped && !control_points.closed {
+        clamped_b_spline_points(&positions)
+    } else {
+        positions
+    };
+    let Some(mut spline) = KnotVectorBSpline::uniform(&positions) else {
+        return;
+    };
+    let inserted: Vec<f32> = knots
+        .0
+        .iter()
+        .copied()
+        .filter(|&t| spline.insert(t))
+        .collect();
+    gizmos.linestrip_2d(spline.points.iter().copied(), REFINED_POLYGON_COLOR);
+    for &p in &spline.points {
+        gizmos.circle_2d(
+            Isometry2d::from_translation(p),
+            3.0 * view.0,
+            REFINED_POLYGON_COLOR,
+        );
+    }
+    for t in inserted {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(spline.position(t)),
+            Vec2::splat(6.0 * view.0),
+            WHITE,
+        );
+    }
+}
+
+// 记录中的手绘路径用淡色画出
+pub(crate) fn plot_sketch(mut gizmos: Gizmos<OverlayGizmos>, sketch: Res<Sketch>) {
+    if sketch.recording {
+        gizmos.linestrip_2d(sketch.raw.iter().copied(), SKETCH_COLOR);
+    }
+}
+
+// 拟合出的 B-Spline 和计算出的控制多边形
+pub(crate) fn plot_least_squares_fit(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    fit: Res<LeastSquaresFit>,
+    resolution: Res<CurveResolution>,
+    view: Res<ViewScale>,
+) {
+    let Some(result) = &fit.result else {
+        return;
+    };
+    let spline = &result.spline;
+    let (start, end) = spline.domain();
+    let samples = ((end - start) as usize * resolution.0).max(2);
+    gizmos.linestrip_2d(
+        (0..=samples).map(|i| spline.position(start + (end - start) * i as f32 / samples as f32)),
+        FIT_CURVE_COLOR,
+    );
+    gizmos.linestrip_2d(spline.points.iter().copied(), FIT_POLYGON_COLOR);
+    for &p in &spline.points {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(p),
+            Vec2::splat(6.0 * view.0),
+            FIT_POLYGON_COLOR,
+        );
+    }
+}
+
+// 手柄拥有光标时画出准星，按屏幕像素保持大小
+pub(crate) fn plot_crosshair(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    pointer: Res<PointerState>,
+    view: Res<ViewScale>,
+) {
+    if pointer.device != PointerDevice::Gamepad {
+        return;
+    }
+    let size = CROSSHAIR_SIZE * view.0;
+    let center = pointer.crosshair;
+    gizmos.line_2d(center - Vec2::X * size, center + Vec2::X * size, WHITE);
+    gizmos.line_2d(center - Vec2::Y * size, center + Vec2::Y * size, WHITE);
+    gizmos.circle_2d(Isometry2d::from_translation(center), size * 0.6, WHITE);
+}
+
+// 探针画成菱形，和圆形的控制点区分开；到每条曲线的垂线用曲线的颜色
+pub(crate) fn plot_probe(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    probe: Res<Probe>,
+    feet: Res<ProbeFeet>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+) {
+    let Some(position) = probe.position else {
+        return;
+    };
+    for foot in &feet.0 {
+        let color = style.color(foot.kind);
+        gizmos.line_2d(position, foot.position, color.with_alpha(0.5));
+        gizmos.circle_2d(
+            Isometry2d::from_translation(foot.position),
+            3.0 * view.0,
+            color,
+        );
+    }
+    gizmos.linestrip_2d(diamond(position, PROBE_SIZE * view.0), MAGENTA);
+}
+
+// 把标记放到当前曲线上；CubicCurve 的参数域是 0..段数，不是 0..1。
+// 匀速标记在弧长表里二分查找累计弧长对应的参数
+pub(crate) fn update_animation_marker(
+    animation: Res<CurveAnimation>,
+    arc_length: Res<MarkerArcLength>,
+    built: Res<BuiltCurves>,
+    active: Res<ActiveCurve>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut markers: Query<(
+        &AnimationMarker,
+        &mut Transform,
+        &mut Visibility,
+        &MeshMaterial2d<ColorMaterial>,
+    )>,
+) {
+    let curve = built.curve(active.0);
+    for (marker, mut transform, mut visibility, material) in markers.iter_mut() {
+        let t = animation
+            .mode
+            .shows(marker.0)
+            .then(|| marker_parameter(marker.0, &animation, curve, &arc_length))
+            .flatten();
+        let (Some(t), Some(curve)) = (t, curve) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        let position = curve.position(t);
+        transform.translation = position.extend(transform.translation.z);
+        transform.scale = Vec3::splat(view.0);
+        *visibility = Visibility::Visible;
+        let color = Color::from(match marker.0 {
+            PlaybackMode::ConstantSpeed => CONSTANT_SPEED_MARKER_COLOR,
+            _ => style.color(active.0),
+        });
+        if let Some(material) = materials.get_mut(&material.0) {
+            if material.color != color {
+                material.color = color;
+            }
+        }
+    }
+}
+
+// 在标记推进之后运行：箭头跟随按参数前进的标记，只显示匀速标记时跟随匀速标记；
+// 速度接近零的尖点处保持原来的朝向
+pub(crate) fn update_direction_arrow(
+    time: Res<Time>,
+    animation: Res<CurveAnimation>,
+    arc_length: Res<MarkerArcLength>,
+    built: Res<BuiltCurves>,
+    active: Res<ActiveCurve>,
+    view: Res<ViewScale>,
+    mut arrow: Query<(&mut DirectionArrow, &mut Transform, &mut Visibility)>,
+) {
+    let Ok((mut arrow, mut transform, mut visibility)) = arrow.get_single_mut() else {
+        return;
+    };
+    let lead = match animation.mode {
+        PlaybackMode::ConstantSpeed => PlaybackMode::ConstantSpeed,
+        _ => PlaybackMode::UniformT,
+    };
+    let curve = built.curve(active.0);
+    let Some(t) = marker_parameter(lead, &animation, curve, &arc_length) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let Some(curve) = curve else {
+        return;
+    };
+    arrow.0.steer(curve.velocity(t), time.delta_secs());
+    let position = curve.position(t);
+    *transform = Transform::from_translation(position.extend(transform.translation.z))
+        .with_rotation(Quat::from_rotation_z(arrow.0.angle))
+        .with_scale(Vec3::splat(view.0));
+    *visibility = Visibility::Visible;
+}
+
+// 时间轴：进度条和当前位置的竖线，曲线不存在时不绘制；
+// 与速度图一样按屏幕像素定义，每帧换算到世界坐标
+pub(crate) fn plot_timeline(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    control_points: Res<ControlPoints>,
+    active: Res<ActiveCurve>,
+    animation: Res<CurveAnimation>,
+    scrub: Res<TimelineScrub>,
+    windows: Query<&Window>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    clean_capture: Res<CleanCapture>,
+    style: Res<CurveStyle>,
+) {
+    if clean_capture.is_hiding() || !active.0.can_draw(&control_points.positions()) {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let to_world = |screen: Vec2| camera.viewport_to_world_2d(camera_transform, screen).ok();
+    let rect = timeline_rect(window);
+    let (Some(top_left), Some(bottom_right)) = (to_world(rect.min), to_world(rect.max)) else {
+        return;
+    };
+    let bottom_left = Vec2::new(top_left.x, bottom_right.y);
+    let top_right = Vec2::new(bottom_right.x, top_left.y);
+    let frame = if scrub.0 { WHITE } else { GRAY.with_alpha(0.6) };
+    gizmos.linestrip_2d(
+        [top_left, top_right, bottom_right, bottom_left, top_left],
+        frame,
+    );
+    let middle = (top_left.y + bottom_right.y) * 0.5;
+    let at = |u: f32| top_left.x + (bottom_right.x - top_left.x) * u;
+    let color = style.color(active.0);
+    gizmos.line_2d(
+        Vec2::new(top_left.x, middle),
+        Vec2::new(at(animation.t.position), middle),
+        color,
+    );
+    let x = at(animation.t.position);
+    gizmos.line_2d(
+        Vec2::new(x, top_left.y),
+        Vec2::new(x, bottom_right.y),
+        WHITE,
+    );
+}
+
+// 张力偏离 0.5 时点的色相随之偏移，张力 0 和 1 各偏移 120°
+pub(crate) fn tension_color(point: &MovablePoint, base: Srgba) -> Srgba {
+    Hsla::from(base)
+        .rotate_hue((point.tension - 0.5) * 240.0)
+        .into()
+}
+
+pub(crate) fn point_color(point: &MovablePoint, style: &PointStyle) -> Srgba {
+    if point.is_selected {
+        style.selected_color
+    } else if point.locked {
+        style.locked_color
+    } else {
+        tension_color(point, style.default_color)
+    }
+}
+
+// 第一个点集使用配置的颜色，其余的点集依次使用 LIST_POINT_COLORS
+pub(crate) fn list_point_color(style: &PointStyle, index: usize) -> Srgba {
+    match index {
+        0 => style.default_color,
+        i => LIST_POINT_COLORS[(i - 1) % LIST_POINT_COLORS.len()],
+    }
+}
+
+pub(crate) fn plot_point(
+    mut gizmos: Gizmos<PointGizmos>,
+    control_points: Res<ControlPoints>,
+    hovered: Res<HoveredPoint>,
+    drag: Res<DragState>,
+    fit: Res<LeastSquaresFit>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+) {
+    let points = &control_points.points;
+    let style = &PointStyle {
+        default_color: list_point_color(&style.point, control_points.active),
+        ..style.point
+    };
+    // 拟合模式下点只是数据，画成小圆点，没有手柄
+    if fit.enabled {
+        for point in points {
+            gizmos.circle_2d(
+                Isometry2d::from_translation(point.position),
+                DATA_POINT_SIZE * view.0,
+                point_color(point, style),
+            );
+        }
+        return;
+    }
+    // 钳制的端点条件：首尾各一个箭头，方向和长度就是端点切线
+    if control_points.has_end_handles() {
+        for end in 0..2 {
+            let color = if matches!(*drag, DragState::EndTangent(e) if e == end) {
+                style.selected_color
+            } else {
+                style.handle_color
+            };
+            let start = points[control_points.end_point(end)].position;
+            gizmos.arrow_2d(start, control_points.end_handle_position(end), color);
+        }
+    }
+    // 切线手柄：一条短线加末端的小圆
+    if points.len() >= 2 {
+        for (i, point) in points.iter().enumerate() {
+            let handle = control_points.handle_position(i);
+            let color = if point.is_handle_selected {
+                style.selected_color
+            } else {
+                style.handle_color
+            };
+            gizmos.line_2d(point.position, handle, color);
+            gizmos.circle_2d(
+                Isometry2d::from_xy(handle.x, handle.y),
+                point.handle_size * view.0,
+                color,
+            );
+        }
+    }
+    // 点的大小按像素计，缩放时在屏幕上保持不变
+    for (i, point) in points.iter().enumerate() {
+        let isometry = Isometry2d::from_xy(point.position.x, point.position.y);
+        // 悬停的点外面画一圈命中范围，与点击的判定半径相同；选中的点保持选中的颜色
+        let color = if hovered.0 == Some(i) {
+            gizmos.circle_2d(
+                isometry,
+                point.selected_size * view.0,
+                style.hover_color.with_alpha(0.6),
+            );
+            if point.is_selected {
+                style.selected_color
+            } else {
+                style.hover_color
+            }
+        } else {
+            point_color(point, style)
+        };
+        let radius = weighted_size(point) * view.0;
+        gizmos.circle_2d(isometry, radius, color);
+        // 锁定的点外面加一圈方框
+        if point.locked {
+            let size = Vec2::splat(radius * 3.0);
+            gizmos.rect_2d(isometry, size, style.locked_color);
+        }
+    }
+}
+
+// 对称编辑时画出贯穿视口的对称轴和半透明的镜像点；镜像点不能直接点中，随编辑的点一起变化
+pub(crate) fn plot_symmetry(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    mut point_gizmos: Gizmos<PointGizmos>,
+    control_points: Res<ControlPoints>,
+    drag: Res<DragState>,
+    style: Res<CurveStyle>,
+    view: Res<ViewScale>,
+    windows: Query<&Window>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+) {
+    let Some(axis) = control_points.symmetry else {
+        return;
+    };
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), camera.get_single())
+    else {
+        return;
+    };
+    let (Ok(a), Ok(b)) = (
+        camera.viewport_to_world_2d(camera_transform, Vec2::ZERO),
+        camera.viewport_to_world_2d(camera_transform, window.size()),
+    ) else {
+        return;
+    };
+    let visible = Rect::from_corners(a, b);
+    let color = if matches!(*drag, DragState::SymmetryAxis) {
+        style.point.selected_color
+    } else {
+        SYMMETRY_AXIS_COLOR
+    };
+    gizmos.line_2d(
+        Vec2::new(axis, visible.min.y),
+        Vec2::new(axis, visible.max.y),
+        color,
+    );
+    let color = list_point_color(&style.point, control_points.active).with_alpha(0.5);
+    for point in &control_points.points {
+        if !on_axis(point.position, axis) {
+            point_gizmos.circle_2d(
+                Isometry2d::from_translation(mirror(point.position, axis)),
+                weighted_size(point) * view.0,
+                color,
+            );
+        }
+    }
+}
+
+// 吸附启用时画出可见范围内的淡色网格线
+pub(crate) fn plot_grid(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    grid: Res<GridSettings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    windows: Query<&Window>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+) {
+    let ctrl = bindings.pressed(Action::SnapToGrid, &keyboard);
+    if !grid.is_snapping(ctrl) || grid.spacing <= 0.0 {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let (Ok(a), Ok(b)) = (
+        camera.viewport_to_world_2d(camera_transform, Vec2::ZERO),
+        camera.viewport_to_world_2d(camera_transform, window.size()),
+    ) else {
+        return;
+    };
+    let view = Rect::from_corners(a, b);
+    let color = GRAY.with_alpha(0.15);
+    let first = (view.min / grid.spacing).ceil() * grid.spacing;
+    let mut x = first.x;
+    while x <= view.max.x {
+        gizmos.line_2d(Vec2::new(x, view.min.y), Vec2::new(x, view.max.y), color);
+        x += grid.spacing;
+    }
+    let mut y = first.y;
+    while y <= view.max.y {
+        gizmos.line_2d(Vec2::new(view.min.x, y), Vec2::new(view.max.x, y), color);
+        y += grid.spacing;
+    }
+}
+
+pub(crate) fn plot_selection_box(mut gizmos: Gizmos<OverlayGizmos>, drag: Res<DragState>) {
+    if let DragState::Box { start, current, .. } = *drag {
+        let rect = Rect::from_corners(start, current);
+        gizmos.rect_2d(
+            Isometry2d::from_translation(rect.center()),
+            rect.size(),
+            LIGHT_SKY_BLUE,
+        );
+    }
+}
+
+// 辅助函数，用于渲染曲线的采样点
+// 采样与绘制分开，弧长等统计和热力图的曲率可以复用同一组点
+pub(crate) fn render_curve<G: GizmoConfigGroup>(
+    gizmos: &mut Gizmos<G>,
+    samples: &Samples,
+    paint: &CurvePaint,
+    domain_end: f32,
+) {
+    if let CurvePaint::Solid(color) = paint {
+        gizmos.linestrip_2d(samples.positions.iter().copied(), *color);
+        return;
+    }
+    let colors = segment_colors(&samples.params, paint, domain_end);
+    for (w, color) in samples.positions.windows(2).zip(colors) {
+        gizmos.line_2d(w[0], w[1], color);
+    }
+}
+
+// 用 line_2d 绘制虚线折线，分段见 dash_segments
+pub(crate) fn draw_dashed_polyline<G: GizmoConfigGroup>(
+    gizmos: &mut Gizmos<G>,
+    points: &[Vec2],
+    style: &DashStyle,
+    color: Srgba,
+) {
+    if style.dash <= 0.0 || style.gap <= 0.0 {
+        gizmos.linestrip_2d(points.iter().copied(), color);
+        return;
+    }
+    for segment in dash_segments(points, style.dash, style.gap).chunks_exact(2) {
+        gizmos.line_2d(segment[0], segment[1], color);
+    }
+}
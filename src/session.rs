@@ -0,0 +1,177 @@
+//! 编辑过程的录制和回放。录制时每一次离散的编辑连同距开始录制的秒数记进日志，
+//! 停止时与开始录制时的点一起写成 RON 文件；回放先恢复这些点，再按原来的时间间隔
+//! 把编辑依次交给编辑操作本身执行。没有专门记录方式的编辑记下编辑之后的整个场景。
+
+use bevy::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{persistence::SavedScene, presets::Preset};
+
+/// 一次离散的编辑，下标都是当时的点在正在编辑的点集中的位置。
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum SessionEdit {
+    Add {
+        index: usize,
+        position: Vec2,
+    },
+    /// 拖动一个点；一次拖动多个选中的点时每个点一条
+    Move {
+        index: usize,
+        from: Vec2,
+        to: Vec2,
+    },
+    Remove {
+        index: usize,
+    },
+    Preset {
+        preset: Preset,
+        center: Vec2,
+        radius: f32,
+    },
+    /// 切换正在编辑的点集，之后的下标都是这个点集中的
+    SwitchList {
+        index: usize,
+    },
+    /// 对齐、变换、撤销等编辑之后的所有点集，回放时整个替换
+    Snapshot {
+        scene: Box<SavedScene>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SessionEvent {
+    /// 距开始录制的秒数
+    pub time: f32,
+    pub edit: SessionEdit,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct Session {
+    /// 开始录制时的所有点集
+    pub initial: SavedScene,
+    pub events: Vec<SessionEvent>,
+}
+
+pub fn to_ron(session: &Session) -> Result<String, ron::Error> {
+    ron::ser::to_string_pretty(session, ron::ser::PrettyConfig::default())
+}
+
+pub fn from_ron(text: &str) -> Result<Session, ron::error::SpannedError> {
+    ron::from_str(text)
+}
+
+/// 回放的进度。
+pub struct Replay {
+    session: Session,
+    next: usize,
+    elapsed: f32,
+}
+
+impl Replay {
+    pub fn new(session: Session) -> Self {
+        Self {
+            session,
+            next: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn initial(&self) -> &SavedScene {
+        &self.session.initial
+    }
+
+    /// 前进 `dt` 秒，按顺序返回这段时间里到期的编辑。
+    pub fn advance(&mut self, dt: f32) -> Vec<SessionEdit> {
+        self.elapsed += dt;
+        let due = self.session.events[self.next..]
+            .iter()
+            .take_while(|event| event.time <= self.elapsed)
+            .count();
+        let edits = self.session.events[self.next..self.next + due]
+            .iter()
+            .map(|event| event.edit.clone())
+            .collect();
+        self.next += due;
+        edits
+    }
+
+    pub fn finished(&self) -> bool {
+        self.next == self.session.events.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        let event = |time, edit| SessionEvent { time, edit };
+        Session {
+            initial: SavedScene::default(),
+            events: vec![
+                event(
+                    0.5,
+                    SessionEdit::Add {
+                        index: 0,
+                        position: Vec2::new(10.0, 20.0),
+                    },
+                ),
+                event(
+                    0.75,
+                    SessionEdit::Move {
+                        index: 0,
+                        from: Vec2::new(10.0, 20.0),
+                        to: Vec2::new(-5.0, 0.0),
+                    },
+                ),
+                event(2.0, SessionEdit::Remove { index: 0 }),
+                event(
+                    2.0,
+                    SessionEdit::Preset {
+                        preset: Preset::Star,
+                        center: Vec2::ZERO,
+                        radius: 100.0,
+                    },
+                ),
+                event(3.0, SessionEdit::SwitchList { index: 1 }),
+                event(
+                    3.5,
+                    SessionEdit::Snapshot {
+                        scene: Box::new(SavedScene {
+                            closed: true,
+                            active: 1,
+                            ..SavedScene::default()
+                        }),
+                    },
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn edits_come_due_at_their_recorded_time() {
+        let mut replay = Replay::new(session());
+        assert!(replay.advance(0.4).is_empty());
+        assert_eq!(replay.advance(0.2).len(), 1);
+        // 一帧跨过多个编辑时按顺序一起返回
+        let edits = replay.advance(1.5);
+        assert!(matches!(
+            edits[..],
+            [
+                SessionEdit::Move { .. },
+                SessionEdit::Remove { .. },
+                SessionEdit::Preset { .. }
+            ]
+        ));
+        assert_eq!(replay.advance(1.5).len(), 2);
+        assert!(replay.finished());
+        assert!(replay.advance(10.0).is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_ron() {
+        let session = session();
+        assert_eq!(from_ron(&to_ron(&session).unwrap()).unwrap(), session);
+        assert!(from_ron("(events: 3)").is_err());
+    }
+}
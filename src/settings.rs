@@ -0,0 +1,287 @@
+//! 显示和编辑设置在两次运行之间的持久化：启动时从 `showcase_settings.ron` 读取，
+//! 设置改动后稍等片刻写回，退出时也会写入。
+//!
+//! 要保存的资源只在 `persisted_settings!` 里列出一次，[`Settings`] 的字段、读取后插入资源和从资源收集设置
+//! 都由它生成。`resources` 里的资源整个保存；`fields` 里的资源还带有运行时的状态（播放位置、拟合结果等），
+//! 只保存列出的字段，读取后写回已经插入的资源。张力、权重等属于控制点的数据随场景保存，不在这里。
+//! 文件损坏或版本不符时使用默认设置，由调用方写进日志。
+
+use std::{fmt, fs, io, path::Path};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    playback::EndBehavior, ActiveCurve, AdaptiveEpsilon, ColorMode, ControlPoints,
+    CoordinateLabels, CurvatureComb, CurveAnimation, CurveKind, CurveRenderMode, CurveResolution,
+    CurveStyle, CurveVisibility, DashStyle, EndCondition, GradientSettings, GridSettings,
+    IndexLabels, LeastSquaresFit, OffsetOverlay, Parameterization, PlaybackMode, RandomScatter,
+    SamplingMode, Sketch,
+};
+
+/// 文件格式的版本，字段的含义改变时加一；其他版本的文件不读取。
+pub const VERSION: u32 = 1;
+
+/// 上一次退出时的相机中心和缩放。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct SavedCamera {
+    pub center: Vec2,
+    pub scale: f32,
+}
+
+macro_rules! persisted_settings {
+    (
+        resources { $($field:ident: $resource:ty,)* }
+        fields { $($name:ident: $value:ty = $owner:ident . $part:tt,)* }
+    ) => {
+        /// 所有保存的设置，文件里缺少的字段使用对应资源的默认值。
+        #[derive(Serialize, Deserialize, Clone)]
+        #[serde(default)]
+        pub(crate) struct Settings {
+            pub(crate) version: u32,
+            pub(crate) camera: Option<SavedCamera>,
+            $(pub(crate) $field: $resource,)*
+            $(pub(crate) $name: $value,)*
+        }
+
+        impl Default for Settings {
+            fn default() -> Self {
+                Self {
+                    version: VERSION,
+                    camera: None,
+                    $($field: default(),)*
+                    $($name: $owner::default().$part,)*
+                }
+            }
+        }
+
+        impl Settings {
+            /// 把整个保存的设置插入为资源，替换已有的值。
+            pub(crate) fn insert_into(&self, app: &mut App) {
+                $(app.insert_resource(self.$field.clone());)*
+            }
+
+            /// 把保存的字段写回资源，这些资源必须已经插入。
+            pub(crate) fn apply_fields(&self, world: &mut World) {
+                $(world.resource_mut::<$owner>().$part = self.$name.clone();)*
+            }
+        }
+
+        /// 保存的各个资源，用来收集设置、判断设置是否改动过。
+        /// 一个系统参数最多 16 个字段，两组资源分开放
+        #[derive(SystemParam)]
+        pub(crate) struct SettingsResources<'w> {
+            whole: WholeResources<'w>,
+            parts: PartResources<'w>,
+        }
+
+        #[derive(SystemParam)]
+        struct WholeResources<'w> {
+            $($field: Res<'w, $resource>,)*
+        }
+
+        #[derive(SystemParam)]
+        struct PartResources<'w> {
+            $($name: Res<'w, $owner>,)*
+        }
+
+        impl SettingsResources<'_> {
+            /// 整个保存的资源是否改动过。只保存部分字段的资源几乎每帧都在变，
+            /// 用 [`Self::fields_differ`] 按值比较。
+            pub(crate) fn is_changed(&self) -> bool {
+                false $(|| self.whole.$field.is_changed())*
+            }
+
+            pub(crate) fn fields_differ(&self, settings: &Settings) -> bool {
+                false $(|| self.parts.$name.$part != settings.$name)*
+            }
+
+            pub(crate) fn to_settings(&self, camera: Option<SavedCamera>) -> Settings {
+                Settings {
+                    version: VERSION,
+                    camera,
+                    $($field: (*self.whole.$field).clone(),)*
+                    $($name: self.parts.$name.$part.clone(),)*
+                }
+            }
+        }
+    };
+}
+
+persisted_settings! {
+    resources {
+        resolution: CurveResolution,
+        adaptive_epsilon: AdaptiveEpsilon,
+        sampling: SamplingMode,
+        visibility: CurveVisibility,
+        color_mode: ColorMode,
+        gradients: GradientSettings,
+        render_mode: CurveRenderMode,
+        style: CurveStyle,
+        dashes: DashStyle,
+        grid: GridSettings,
+        coordinate_labels: CoordinateLabels,
+        index_labels: IndexLabels,
+    }
+    fields {
+        active_curve: CurveKind = ActiveCurve.0,
+        comb_scale: f32 = CurvatureComb.scale,
+        playback_rate: f32 = CurveAnimation.rate,
+        playback_mode: PlaybackMode = CurveAnimation.mode,
+        end_behavior: EndBehavior = CurveAnimation.end,
+        // 正在编辑的点集的曲线设置，其余点集的随场景保存
+        parameterization: Parameterization = ControlPoints.parameterization,
+        clamped: bool = ControlPoints.clamped,
+        end_condition: EndCondition = ControlPoints.end_condition,
+        offset_distance: f32 = OffsetOverlay.distance,
+        random_count: usize = RandomScatter.count,
+        sketch_tolerance: f32 = Sketch.tolerance,
+        fit_control_count: usize = LeastSquaresFit.control_count,
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Version(u32),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(error) => write!(f, "{error}"),
+            SettingsError::Parse(error) => write!(f, "invalid RON: {error}"),
+            SettingsError::Version(version) => {
+                write!(f, "version {version} is not supported (expected {VERSION})")
+            }
+        }
+    }
+}
+
+// 先只读出版本号，其他字段的格式可能已经变了
+#[derive(Deserialize)]
+struct Header {
+    #[serde(default)]
+    version: u32,
+}
+
+pub(crate) fn load(path: &Path) -> Result<Settings, SettingsError> {
+    from_ron(&fs::read_to_string(path).map_err(SettingsError::Io)?)
+}
+
+pub(crate) fn from_ron(text: &str) -> Result<Settings, SettingsError> {
+    let header: Header = ron::from_str(text).map_err(SettingsError::Parse)?;
+    if header.version != VERSION {
+        return Err(SettingsError::Version(header.version));
+    }
+    ron::from_str(text).map_err(SettingsError::Parse)
+}
+
+pub(crate) fn to_ron(settings: &Settings) -> Result<String, ron::Error> {
+    ron::ser::to_string_pretty(settings, ron::ser::PrettyConfig::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_ron() {
+        let mut settings = Settings::default();
+        settings.resolution.0 = 42;
+        settings.grid.enabled = true;
+        settings.color_mode = ColorMode::Heatmap;
+        settings.camera = Some(SavedCamera {
+            center: Vec2::new(120.0, -40.0),
+            scale: 0.5,
+        });
+        let text = to_ron(&settings).unwrap();
+        let loaded = from_ron(&text).unwrap();
+        assert_eq!(loaded.resolution.0, 42);
+        assert!(loaded.grid.enabled);
+        assert_eq!(loaded.color_mode, ColorMode::Heatmap);
+        assert_eq!(loaded.camera, settings.camera);
+        assert_eq!(to_ron(&loaded).unwrap(), text);
+    }
+
+    // 只保存部分字段的资源，每个字段都能读回
+    #[test]
+    fn resource_fields_round_trip() {
+        let settings = Settings {
+            active_curve: CurveKind::Akima,
+            comb_scale: 500.0,
+            playback_rate: 2.5,
+            playback_mode: PlaybackMode::Both,
+            end_behavior: EndBehavior::PingPong,
+            parameterization: Parameterization::Centripetal { alpha: 0.5 },
+            clamped: true,
+            end_condition: EndCondition::NotAKnot,
+            offset_distance: 75.0,
+            random_count: 20,
+            sketch_tolerance: 8.0,
+            fit_control_count: 12,
+            ..default()
+        };
+        let text = to_ron(&settings).unwrap();
+        let loaded = from_ron(&text).unwrap();
+        assert_eq!(loaded.active_curve, CurveKind::Akima);
+        assert_eq!(loaded.comb_scale, 500.0);
+        assert_eq!(loaded.playback_rate, 2.5);
+        assert!(loaded.playback_mode == PlaybackMode::Both);
+        assert_eq!(loaded.end_behavior, EndBehavior::PingPong);
+        assert_eq!(
+            loaded.parameterization,
+            Parameterization::Centripetal { alpha: 0.5 }
+        );
+        assert!(loaded.clamped);
+        assert_eq!(loaded.end_condition, EndCondition::NotAKnot);
+        assert_eq!(loaded.offset_distance, 75.0);
+        assert_eq!(loaded.random_count, 20);
+        assert_eq!(loaded.sketch_tolerance, 8.0);
+        assert_eq!(loaded.fit_control_count, 12);
+        assert_eq!(to_ron(&loaded).unwrap(), text);
+        // 缺少时与资源的默认值相同
+        let defaults = from_ron("(version: 1)").unwrap();
+        assert_eq!(defaults.active_curve, ActiveCurve::default().0);
+        assert_eq!(defaults.random_count, RandomScatter::default().count);
+        assert_eq!(
+            defaults.fit_control_count,
+            LeastSquaresFit::default().control_count
+        );
+    }
+
+    // 只写了部分字段的文件，其余使用默认值
+    #[test]
+    fn missing_fields_use_the_defaults() {
+        let loaded = from_ron("(version: 1, resolution: (12))").unwrap();
+        assert_eq!(loaded.resolution.0, 12);
+        assert_eq!(loaded.camera, None);
+        assert_eq!(
+            to_ron(&Settings {
+                resolution: CurveResolution(12),
+                ..default()
+            })
+            .unwrap(),
+            to_ron(&loaded).unwrap()
+        );
+    }
+
+    #[test]
+    fn corrupt_or_other_versions_are_rejected() {
+        assert!(matches!(
+            from_ron("(version: 1, resolution: \"many\")"),
+            Err(SettingsError::Parse(_))
+        ));
+        assert!(matches!(from_ron("not ron"), Err(SettingsError::Parse(_))));
+        assert!(matches!(
+            from_ron("(version: 7, resolution: (12))"),
+            Err(SettingsError::Version(7))
+        ));
+        // 没有版本号的文件也不读取
+        assert!(matches!(
+            from_ron("(resolution: (12))"),
+            Err(SettingsError::Version(0))
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+//! 手绘路径的化简。Ramer–Douglas–Peucker 保留首尾两点，找出离两点连线最远的点，
+//! 距离超过容差时保留它并对两边递归，否则去掉中间所有的点。
+
+use bevy::math::Vec2;
+
+// 点到线段 ab 的距离；a、b 重合时是到 a 的距离
+fn segment_distance(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared <= f32::EPSILON {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
+
+/// 化简折线，保留的点与原来的折线相差不超过 `tolerance`。少于三个点时原样返回。
+/// 用显式的栈代替递归，几千个点的路径也不会栈溢出。
+pub fn ramer_douglas_peucker(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    let n = points.len();
+    if n < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; n];
+    keep[0] = true;
+    keep[n - 1] = true;
+    let mut stack = vec![(0, n - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let farthest = (first + 1..last)
+            .map(|i| (i, segment_distance(points[i], points[first], points[last])))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((index, distance)) = farthest {
+            if distance > tolerance {
+                keep[index] = true;
+                stack.push((first, index));
+                stack.push((index, last));
+            }
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(p, kept)| kept.then_some(*p))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 四分之一圆，密集采样
+    fn arc() -> Vec<Vec2> {
+        (0..=200)
+            .map(|i| Vec2::from_angle(i as f32 / 200.0 * std::f32::consts::FRAC_PI_2) * 100.0)
+            .collect()
+    }
+
+    #[test]
+    fn straight_lines_collapse_to_their_end_points() {
+        let line: Vec<Vec2> = (0..50)
+            .map(|i| Vec2::new(i as f32, 2.0 * i as f32))
+            .collect();
+        assert_eq!(ramer_douglas_peucker(&line, 0.5), [line[0], line[49]]);
+    }
+
+    #[test]
+    fn corners_are_kept() {
+        let mut path: Vec<Vec2> = (0..=10).map(|i| Vec2::new(i as f32 * 10.0, 0.0)).collect();
+        path.extend((1..=10).map(|i| Vec2::new(100.0, i as f32 * 10.0)));
+        let simplified = ramer_douglas_peucker(&path, 1.0);
+        assert_eq!(
+            simplified,
+            [Vec2::ZERO, Vec2::new(100.0, 0.0), Vec2::new(100.0, 100.0)]
+        );
+    }
+
+    // 每个被去掉的点到化简后折线的距离都不超过容差，容差越小保留的点越多
+    #[test]
+    fn stays_within_tolerance() {
+        let path = arc();
+        let mut previous = 0;
+        for tolerance in [10.0, 3.0, 1.0, 0.1] {
+            let simplified = ramer_douglas_peucker(&path, tolerance);
+            assert!(simplified.len() > previous, "{tolerance}");
+            previous = simplified.len();
+            assert_eq!(simplified.first(), path.first());
+            assert_eq!(simplified.last(), path.last());
+            for p in &path {
+                let distance = simplified
+                    .windows(2)
+                    .map(|w| segment_distance(*p, w[0], w[1]))
+                    .fold(f32::INFINITY, f32::min);
+                assert!(distance <= tolerance + 1e-4, "{tolerance}: {distance}");
+            }
+        }
+    }
+
+    #[test]
+    fn short_paths_are_returned_unchanged() {
+        assert!(ramer_douglas_peucker(&[], 1.0).is_empty());
+        let two = [Vec2::ZERO, Vec2::ONE];
+        assert_eq!(ramer_douglas_peucker(&two, 1.0), two);
+        // 首尾重合的闭合路径按到首点的距离化简
+        let ring = [
+            Vec2::ZERO,
+            Vec2::new(10.0, 0.0),
+            Vec2::new(5.0, 8.0),
+            Vec2::ZERO,
+        ];
+        assert_eq!(ramer_douglas_peucker(&ring, 1.0).len(), 4);
+    }
+}
@@ -0,0 +1,488 @@
+//! 3D 模式，用 `--3d` 启动：控制点位于空间中，曲线在 3D 中绘制。
+//!
+//! 鼠标操作映射到 z = 0 的地面：右键在地面上添加点，左键拖动点在它所在的水平面内移动，
+//! Shift 拖动改变点的高度；中键（或 Space + 左键）拖动环绕相机，滚轮拉近拉远。
+//! 只有 bevy 构造器支持的曲线类型有 3D 版本，其余类型只在 2D 模式中显示。
+
+use bevy::{
+    color::palettes::css::DARK_GRAY,
+    input::mouse::{MouseMotion, MouseWheel},
+    prelude::*,
+};
+use std::f32::consts::FRAC_PI_2;
+
+use crate::{
+    apply_curve_style, cubic_curve, estimate_tangent, load_key_bindings, wheel_steps, Action,
+    CurveGizmos, CurveGizmosPlugin, CurveKind, CurveResolution, CurveStyle, CurveVisibility,
+    KeyBindings,
+};
+
+// 光标离点的屏幕距离小于这个像素值时命中
+const PICK_RADIUS: f32 = 12.0;
+const POINT_RADIUS: f32 = 6.0;
+// 环绕相机每像素转过的弧度
+const ORBIT_SPEED: f32 = 0.005;
+// 滚轮每格缩放相机距离的倍数
+const ZOOM_STEP: f32 = 1.1;
+const MIN_DISTANCE: f32 = 50.0;
+const MAX_DISTANCE: f32 = 5000.0;
+// 俯仰角不到正上方和正下方，避免 looking_at 的上方向退化
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.05;
+// Shift 拖动时每像素改变的高度，按相机距离缩放
+const HEIGHT_PER_PIXEL: f32 = 0.002;
+const GRID_CELLS: u32 = 20;
+const GRID_SPACING: f32 = 50.0;
+
+pub struct SpaceModePlugin;
+
+impl Plugin for SpaceModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(CurveGizmosPlugin)
+            .insert_resource(SpacePoints::default())
+            .insert_resource(SpaceDrag::default())
+            .insert_resource(CurveVisibility::default())
+            .insert_resource(CurveResolution::default())
+            .insert_resource(CurveStyle::default())
+            .insert_resource(load_key_bindings().0)
+            .add_systems(Startup, setup_space)
+            .add_systems(
+                Update,
+                (
+                    space_keys,
+                    orbit_camera,
+                    drag_space_point,
+                    add_space_point,
+                    apply_curve_style,
+                    plot_ground,
+                    plot_space_points,
+                    plot_space_curves,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone)]
+struct SpacePoint {
+    position: Vec3,
+    tension: f32,
+    is_selected: bool,
+}
+
+impl SpacePoint {
+    fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            tension: 0.5,
+            is_selected: false,
+        }
+    }
+}
+
+#[derive(Default, Resource)]
+struct SpacePoints {
+    points: Vec<SpacePoint>,
+    closed: bool,
+}
+
+impl SpacePoints {
+    fn positions(&self) -> Vec<Vec3> {
+        self.points.iter().map(|p| p.position).collect()
+    }
+
+    // 曲线的采样点，点数不足或这种曲线没有 3D 版本时返回 None；
+    // Hermite 的切线和 2D 中没有手动设置时一样自动估计
+    fn curve(&self, kind: CurveKind, samples_per_segment: usize) -> Option<Vec<Vec3>> {
+        let mut positions = self.positions();
+        if positions.len() < 2 {
+            return None;
+        }
+        if kind == CurveKind::ControlPolygon {
+            if self.closed {
+                positions.push(positions[0]);
+            }
+            return Some(positions);
+        }
+        let tensions: Vec<f32> = self.points.iter().map(|p| p.tension).collect();
+        let tangents: Vec<Vec3> = (0..positions.len())
+            .map(|i| estimate_tangent(&positions, i, self.closed))
+            .collect();
+        let curve = cubic_curve(kind, &positions, &tensions, &tangents, self.closed)?;
+        let segments = curve.segments().len();
+        (segments > 0).then(|| {
+            curve
+                .iter_positions(segments * samples_per_segment)
+                .collect()
+        })
+    }
+
+    fn clear_selection(&mut self) {
+        for point in self.points.iter_mut() {
+            point.is_selected = false;
+        }
+    }
+}
+
+// 正在拖动的点，以及拖动的是高度还是水平位置
+#[derive(Default, Resource)]
+enum SpaceDrag {
+    #[default]
+    Idle,
+    Orbit(MouseButton),
+    Point {
+        index: usize,
+        height: bool,
+        last_cursor: Vec2,
+    },
+}
+
+// 相机绕 focus 旋转；yaw 从 x 轴起算，pitch 是相对地面的仰角
+#[derive(Component)]
+struct OrbitCamera {
+    focus: Vec3,
+    yaw: f32,
+    pitch: f32,
+    distance: f32,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            focus: Vec3::ZERO,
+            yaw: -FRAC_PI_2,
+            pitch: 0.6,
+            distance: 900.0,
+        }
+    }
+}
+
+impl OrbitCamera {
+    fn transform(&self) -> Transform {
+        let direction = Vec3::new(
+            self.pitch.cos() * self.yaw.cos(),
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+        );
+        Transform::from_translation(self.focus + direction * self.distance)
+            .looking_at(self.focus, Vec3::Z)
+    }
+}
+
+// 射线与高度为 height 的水平面的交点，射线平行于平面或背离平面时返回 None
+fn plane_point(ray: Ray3d, height: f32) -> Option<Vec3> {
+    ray.intersect_plane(Vec3::Z * height, InfinitePlane3d::new(Vec3::Z))
+        .map(|distance| ray.get_point(distance))
+}
+
+fn setup_space(mut commands: Commands) {
+    let orbit = OrbitCamera::default();
+    commands.spawn((
+        Camera3dBundle {
+            transform: orbit.transform(),
+            ..default()
+        },
+        orbit,
+    ));
+    commands.spawn((
+        Text::new(
+            "3D mode: right click adds a point on the ground, drag to move, \
+             Shift+drag changes height, middle drag orbits.\n\
+             NURBS, Akima, monotone and quadratic curves are only drawn in 2D.",
+        ),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+// 与 2D 模式相同的按键：删除最后一个点、取消选择、闭合曲线和显示/隐藏曲线
+fn space_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    drag: Res<SpaceDrag>,
+    mut points: ResMut<SpacePoints>,
+    mut visibility: ResMut<CurveVisibility>,
+) {
+    if matches!(*drag, SpaceDrag::Idle) && bindings.just_pressed(Action::PopPoint, &keyboard) {
+        points.points.pop();
+    }
+    if bindings.just_pressed(Action::ClearSelection, &keyboard) {
+        points.clear_selection();
+    }
+    if bindings.just_pressed(Action::ToggleClosed, &keyboard) {
+        points.closed = !points.closed;
+    }
+    for kind in CurveKind::ALL {
+        if bindings.just_pressed(Action::ToggleCurve(kind), &keyboard) {
+            visibility.toggle(kind);
+        }
+    }
+}
+
+fn orbit_camera(
+    input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    mut drag: ResMut<SpaceDrag>,
+    mut camera: Query<(&mut OrbitCamera, &mut Transform)>,
+) {
+    let Ok((mut orbit, mut transform)) = camera.get_single_mut() else {
+        return;
+    };
+    let delta: Vec2 = motion.read().map(|event| event.delta).sum();
+    match *drag {
+        SpaceDrag::Idle => {
+            if let Some(button) = bindings.clicked_button(Action::PanCamera, &input, &keyboard) {
+                *drag = SpaceDrag::Orbit(button);
+            }
+        }
+        SpaceDrag::Orbit(button) if !input.pressed(button) => *drag = SpaceDrag::Idle,
+        SpaceDrag::Orbit(_) => {
+            orbit.yaw -= delta.x * ORBIT_SPEED;
+            orbit.pitch = (orbit.pitch + delta.y * ORBIT_SPEED).clamp(-MAX_PITCH, MAX_PITCH);
+        }
+        SpaceDrag::Point { .. } => {}
+    }
+    let steps: f32 = wheel.read().map(wheel_steps).sum();
+    if steps != 0.0 && bindings.modifiers_held(Action::Zoom, &keyboard) {
+        orbit.distance =
+            (orbit.distance * ZOOM_STEP.powf(-steps)).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+    if orbit.is_changed() {
+        *transform = orbit.transform();
+    }
+}
+
+// 左键按在点上开始拖动：普通拖动沿点所在的水平面移动，Shift 拖动只改变高度
+fn drag_space_point(
+    input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    windows: Query<&Window>,
+    camera: Query<(&Camera, &GlobalTransform, &OrbitCamera)>,
+    mut drag: ResMut<SpaceDrag>,
+    mut points: ResMut<SpacePoints>,
+) {
+    let Some(cursor) = windows.get_single().ok().and_then(Window::cursor_position) else {
+        return;
+    };
+    let Ok((camera, camera_transform, orbit)) = camera.get_single() else {
+        return;
+    };
+    match *drag {
+        SpaceDrag::Idle => {
+            if !bindings.clicked(Action::DragPoint, &input, &keyboard) {
+                return;
+            }
+            let hit = points
+                .points
+                .iter()
+                .enumerate()
+                .filter_map(|(i, p)| {
+                    let screen = camera
+                        .world_to_viewport(camera_transform, p.position)
+                        .ok()?;
+                    Some((i, screen.distance(cursor)))
+                })
+                .filter(|&(_, distance)| distance < PICK_RADIUS)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            points.clear_selection();
+            if let Some((index, _)) = hit {
+                points.points[index].is_selected = true;
+                *drag = SpaceDrag::Point {
+                    index,
+                    height: bindings.clicked(Action::AdjustHeight, &input, &keyboard),
+                    last_cursor: cursor,
+                };
+            }
+        }
+        SpaceDrag::Point { .. }
+            if !bindings
+                .button(Action::DragPoint)
+                .is_some_and(|button| input.pressed(button)) =>
+        {
+            *drag = SpaceDrag::Idle;
+        }
+        SpaceDrag::Point {
+            index,
+            height,
+            last_cursor,
+        } => {
+            let Some(point) = points.points.get_mut(index) else {
+                *drag = SpaceDrag::Idle;
+                return;
+            };
+            if height {
+                // 屏幕 y 向下，向上拖动抬高点
+                point.position.z += (last_cursor.y - cursor.y) * HEIGHT_PER_PIXEL * orbit.distance;
+            } else if let Some(target) = camera
+                .viewport_to_world(camera_transform, cursor)
+                .ok()
+                .and_then(|ray| plane_point(ray, point.position.z))
+            {
+                point.position = target;
+            }
+            *drag = SpaceDrag::Point {
+                index,
+                height,
+                last_cursor: cursor,
+            };
+        }
+        SpaceDrag::Orbit(_) => {}
+    }
+}
+
+fn add_space_point(
+    input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    windows: Query<&Window>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    drag: Res<SpaceDrag>,
+    mut points: ResMut<SpacePoints>,
+) {
+    if !matches!(*drag, SpaceDrag::Idle) || !bindings.clicked(Action::AddPoint, &input, &keyboard) {
+        return;
+    }
+    let Some(cursor) = windows.get_single().ok().and_then(Window::cursor_position) else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    if let Some(position) = camera
+        .viewport_to_world(camera_transform, cursor)
+        .ok()
+        .and_then(|ray| plane_point(ray, 0.0))
+    {
+        points.points.push(SpacePoint::new(position));
+    }
+}
+
+fn plot_ground(mut gizmos: Gizmos) {
+    gizmos.grid(
+        Isometry3d::IDENTITY,
+        UVec2::splat(GRID_CELLS),
+        Vec2::splat(GRID_SPACING),
+        DARK_GRAY,
+    );
+}
+
+// 每个点向地面画一条竖线，方便看出高度
+fn plot_space_points(mut gizmos: Gizmos, points: Res<SpacePoints>, style: Res<CurveStyle>) {
+    for point in &points.points {
+        let color = if point.is_selected {
+            style.point.selected_color
+        } else {
+            style.point.default_color
+        };
+        gizmos.sphere(
+            Isometry3d::from_translation(point.position),
+            POINT_RADIUS,
+            color,
+        );
+        gizmos.line(point.position, point.position.with_z(0.0), DARK_GRAY);
+    }
+}
+
+fn plot_space_curves(
+    mut gizmos: CurveGizmos,
+    points: Res<SpacePoints>,
+    visibility: Res<CurveVisibility>,
+    resolution: Res<CurveResolution>,
+    style: Res<CurveStyle>,
+) {
+    for kind in CurveKind::ALL {
+        if !visibility.is_visible(kind) {
+            continue;
+        }
+        if let Some(positions) = points.curve(kind, resolution.0) {
+            gizmos.linestrip(kind, positions, style.curve_color(kind, points.closed));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn space_points(positions: &[Vec3]) -> SpacePoints {
+        SpacePoints {
+            points: positions.iter().copied().map(SpacePoint::new).collect(),
+            closed: false,
+        }
+    }
+
+    #[test]
+    fn ray_hits_the_plane_at_the_given_height() {
+        let ray = Ray3d::new(Vec3::new(10.0, 20.0, 100.0), Dir3::NEG_Z);
+        assert_eq!(plane_point(ray, 0.0), Some(Vec3::new(10.0, 20.0, 0.0)));
+        assert_eq!(plane_point(ray, 40.0), Some(Vec3::new(10.0, 20.0, 40.0)));
+        // 平行于地面的射线没有交点
+        let parallel = Ray3d::new(Vec3::new(0.0, 0.0, 10.0), Dir3::X);
+        assert_eq!(plane_point(parallel, 0.0), None);
+    }
+
+    #[test]
+    fn orbit_camera_looks_at_the_focus() {
+        let orbit = OrbitCamera {
+            focus: Vec3::new(100.0, 50.0, 0.0),
+            ..default()
+        };
+        let transform = orbit.transform();
+        assert!((transform.translation.distance(orbit.focus) - orbit.distance).abs() < 1e-3);
+        assert!(transform.translation.z > 0.0);
+        let forward = transform.forward();
+        let to_focus = (orbit.focus - transform.translation).normalize();
+        assert!(forward.dot(to_focus) > 0.999);
+    }
+
+    #[test]
+    fn curves_pass_through_points_in_space() {
+        let positions = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(100.0, 50.0, 80.0),
+            Vec3::new(200.0, -30.0, 20.0),
+            Vec3::new(300.0, 10.0, 60.0),
+        ];
+        let points = space_points(&positions);
+        for kind in [
+            CurveKind::CatmullRom,
+            CurveKind::Hermite,
+            CurveKind::Natural,
+        ] {
+            let samples = points.curve(kind, 8).unwrap();
+            for (i, position) in positions.iter().enumerate() {
+                assert!(samples[i * 8].distance(*position) < 1e-3, "{kind:?}");
+            }
+        }
+        assert!(points.curve(CurveKind::BSpline, 8).is_some());
+        assert!(points.curve(CurveKind::Bezier, 8).is_some());
+    }
+
+    #[test]
+    fn planar_only_curves_are_skipped() {
+        let points = space_points(&[Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z]);
+        for kind in [
+            CurveKind::Nurbs,
+            CurveKind::Akima,
+            CurveKind::Monotone,
+            CurveKind::Quadratic,
+        ] {
+            assert!(points.curve(kind, 8).is_none());
+        }
+        let mut closed = space_points(&[Vec3::ZERO, Vec3::X, Vec3::Y]);
+        closed.closed = true;
+        let polygon = closed.curve(CurveKind::ControlPolygon, 8).unwrap();
+        assert_eq!(polygon.first(), polygon.last());
+    }
+}
@@ -0,0 +1,154 @@
+//! 按固定大小的格子索引点的位置，用于点很多时的命中测试。
+
+use std::collections::HashMap;
+
+use bevy::math::{IVec2, Vec2};
+
+/// 每个格子记录落在其中的点的下标。查询只检查覆盖查询圆的格子，
+/// 点的分布不太集中时每次查询的代价与总点数无关。
+#[derive(Debug)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<usize>>,
+    len: usize,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    /// 建立索引时的点数。
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    // 仍然有点的格子沿用已分配的 Vec，点只是移动时重建很少需要重新分配
+    pub fn rebuild(&mut self, positions: impl IntoIterator<Item = Vec2>) {
+        for indices in self.cells.values_mut() {
+            indices.clear();
+        }
+        self.len = 0;
+        for position in positions {
+            let cell = self.cell(position);
+            self.cells.entry(cell).or_default().push(self.len);
+            self.len += 1;
+        }
+        self.cells.retain(|_, indices| !indices.is_empty());
+    }
+
+    /// `radius` 以内离 `center` 最近的点。`distance` 返回点到 `center` 的距离，
+    /// 返回 None 的点不参与；距离相同时下标小的优先，与 [`nearest_by_scan`] 一致。
+    pub fn nearest(
+        &self,
+        center: Vec2,
+        radius: f32,
+        distance: impl Fn(usize) -> Option<f32>,
+    ) -> Option<usize> {
+        let min = self.cell(center - Vec2::splat(radius));
+        let max = self.cell(center + Vec2::splat(radius));
+        let mut best: Option<(usize, f32)> = None;
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let Some(indices) = self.cells.get(&IVec2::new(x, y)) else {
+                    continue;
+                };
+                for &i in indices {
+                    let Some(d) = distance(i) else {
+                        continue;
+                    };
+                    if best.map_or(true, |(j, best)| (d, i) < (best, j)) {
+                        best = Some((i, d));
+                    }
+                }
+            }
+        }
+        best.map(|(i, _)| i)
+    }
+}
+
+/// 逐个检查 `0..len`，结果与 [`SpatialHash::nearest`] 相同；索引过期时使用。
+pub fn nearest_by_scan(len: usize, distance: impl Fn(usize) -> Option<f32>) -> Option<usize> {
+    (0..len)
+        .filter_map(|i| distance(i).map(|d| (i, d)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 简单的线性同余发生器，测试结果可以复现
+    fn random_points(n: usize, extent: f32) -> Vec<Vec2> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 40) as f32 / (1u64 << 24) as f32
+        };
+        (0..n)
+            .map(|_| Vec2::new(next(), next()) * 2.0 * extent - extent)
+            .collect()
+    }
+
+    #[test]
+    fn matches_brute_force_on_random_points() {
+        let points = random_points(10_000, 1000.0);
+        let mut index = SpatialHash::new(32.0);
+        index.rebuild(points.iter().copied());
+        assert_eq!(index.len(), points.len());
+        let radius = 15.0;
+        for center in random_points(2_000, 1000.0) {
+            let distance = |i: usize| {
+                let d = points[i].distance(center);
+                (d < radius).then_some(d)
+            };
+            assert_eq!(
+                index.nearest(center, radius, distance),
+                nearest_by_scan(points.len(), distance),
+                "{center}"
+            );
+        }
+    }
+
+    #[test]
+    fn nearest_wins_over_vec_order() {
+        let points = [Vec2::new(8.0, 0.0), Vec2::new(2.0, 0.0)];
+        let mut index = SpatialHash::new(32.0);
+        index.rebuild(points);
+        let distance = |i: usize| Some(points[i].distance(Vec2::ZERO));
+        assert_eq!(index.nearest(Vec2::ZERO, 10.0, distance), Some(1));
+        // 距离相同时取下标小的
+        let distance = |i: usize| Some(points[i].distance(Vec2::new(5.0, 0.0)));
+        assert_eq!(index.nearest(Vec2::new(5.0, 0.0), 10.0, distance), Some(0));
+    }
+
+    #[test]
+    fn query_spans_cell_borders_and_negative_coordinates() {
+        let points = [Vec2::new(-1.0, -1.0), Vec2::new(100.0, 100.0)];
+        let mut index = SpatialHash::new(32.0);
+        index.rebuild(points);
+        let center = Vec2::new(1.0, 1.0);
+        let distance = |i: usize| Some(points[i].distance(center)).filter(|d| *d < 5.0);
+        assert_eq!(index.nearest(center, 5.0, distance), Some(0));
+    }
+
+    #[test]
+    fn rebuild_forgets_old_points() {
+        let mut index = SpatialHash::new(32.0);
+        index.rebuild([Vec2::ZERO, Vec2::new(5.0, 0.0)]);
+        index.rebuild([Vec2::new(500.0, 0.0)]);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.nearest(Vec2::ZERO, 10.0, |_| Some(0.0)), None);
+    }
+}
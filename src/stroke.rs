@@ -0,0 +1,159 @@
+//! 把折线扩成有宽度的三角形网格，代替 gizmo 画粗线。
+//!
+//! 每一段是一个四边形，相邻两段在采样点处用斜接（miter）共用一对顶点；
+//! 转角太尖、斜接会伸得很远时退回斜切（bevel），在外侧补一个三角形。
+
+use bevy::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+// 斜接长度超过半线宽的这么多倍时改用斜切
+const MITER_LIMIT: f32 = 4.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Join {
+    Miter,
+    Bevel,
+}
+
+/// 三角形列表。`sources[i]` 是第 i 个顶点所属的采样点下标，用来给顶点取颜色。
+#[derive(Default, Debug)]
+pub struct Stroke {
+    pub positions: Vec<Vec2>,
+    pub sources: Vec<usize>,
+    pub indices: Vec<u32>,
+}
+
+impl Stroke {
+    fn push(&mut self, position: Vec2, source: usize) -> u32 {
+        self.positions.push(position);
+        self.sources.push(source);
+        (self.positions.len() - 1) as u32
+    }
+}
+
+/// 以 `width` 为线宽扩展折线。重合的相邻采样点只取一个，少于两个不同的点时网格为空。
+pub fn stroke_polyline(points: &[Vec2], width: f32, join: Join) -> Stroke {
+    let mut stroke = Stroke::default();
+    let mut kept: Vec<usize> = Vec::with_capacity(points.len());
+    for (i, p) in points.iter().enumerate() {
+        match kept.last() {
+            Some(&last) if points[last].distance_squared(*p) <= f32::EPSILON => {}
+            _ => kept.push(i),
+        }
+    }
+    if kept.len() < 2 {
+        return stroke;
+    }
+    let half = width * 0.5;
+    let normals: Vec<Vec2> = kept
+        .windows(2)
+        .map(|w| (points[w[1]] - points[w[0]]).normalize().perp())
+        .collect();
+
+    // 每个采样点与前一段相接的一对顶点和与后一段相接的一对顶点；斜接时两对相同
+    let mut incoming = Vec::with_capacity(kept.len());
+    let mut outgoing = Vec::with_capacity(kept.len());
+    for (k, &i) in kept.iter().enumerate() {
+        let p = points[i];
+        let mut pair = |normal: Vec2| (stroke.push(p + normal, i), stroke.push(p - normal, i));
+        if k == 0 || k == kept.len() - 1 {
+            let ends = pair(normals[k.min(normals.len() - 1)] * half);
+            incoming.push(ends);
+            outgoing.push(ends);
+            continue;
+        }
+        let (before, after) = (normals[k - 1], normals[k]);
+        let bisector = (before + after).normalize_or_zero();
+        let cos = bisector.dot(after);
+        if join == Join::Miter && cos * MITER_LIMIT > 1.0 {
+            let shared = pair(bisector * half / cos);
+            incoming.push(shared);
+            outgoing.push(shared);
+            continue;
+        }
+        let into = pair(before * half);
+        let out = pair(after * half);
+        let center = stroke.push(p, i);
+        // 两侧各补一个三角形，内侧那个落在两段四边形的重叠里
+        stroke
+            .indices
+            .extend([center, into.0, out.0, center, into.1, out.1]);
+        incoming.push(into);
+        outgoing.push(out);
+    }
+    for k in 0..kept.len() - 1 {
+        let (a, b) = outgoing[k];
+        let (c, d) = incoming[k + 1];
+        stroke.indices.extend([a, b, c, b, d, c]);
+    }
+    stroke
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(actual.distance(expected) < 1e-4, "{actual} != {expected}");
+    }
+
+    // 三角形面积之和；重叠部分会重复计算
+    fn area(stroke: &Stroke) -> f32 {
+        stroke
+            .indices
+            .chunks(3)
+            .map(|t| {
+                let [a, b, c] = [0, 1, 2].map(|j| stroke.positions[t[j] as usize]);
+                (b - a).perp_dot(c - a).abs() * 0.5
+            })
+            .sum()
+    }
+
+    #[test]
+    fn straight_line_becomes_a_rectangle() {
+        let points = [Vec2::ZERO, Vec2::new(50.0, 0.0), Vec2::new(100.0, 0.0)];
+        let stroke = stroke_polyline(&points, 4.0, Join::Miter);
+        assert_eq!(stroke.positions.len(), 6);
+        assert_eq!(stroke.indices.len(), 12);
+        assert!((area(&stroke) - 400.0).abs() < 1e-3);
+        assert!(stroke.positions.iter().all(|p| p.y.abs() == 2.0));
+        assert_eq!(stroke.sources, [0, 0, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn right_angle_miter_reaches_the_outer_corner() {
+        let points = [Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0)];
+        let stroke = stroke_polyline(&points, 2.0, Join::Miter);
+        assert_eq!(stroke.positions.len(), 6);
+        // 左转，外侧在右下方
+        assert_near(stroke.positions[2], Vec2::new(9.0, 1.0));
+        assert_near(stroke.positions[3], Vec2::new(11.0, -1.0));
+    }
+
+    #[test]
+    fn sharp_turns_and_bevels_add_a_join() {
+        let hairpin = [Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(0.0, 0.5)];
+        let stroke = stroke_polyline(&hairpin, 2.0, Join::Miter);
+        // 斜接超出上限，中间的采样点有两对顶点加一个中心点
+        assert_eq!(stroke.positions.len(), 9);
+        assert!(stroke.positions.iter().all(|p| p.x < 11.0 + 1e-3));
+
+        let corner = [Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0)];
+        let stroke = stroke_polyline(&corner, 2.0, Join::Bevel);
+        assert_eq!(stroke.positions.len(), 9);
+        assert_eq!(stroke.indices.len(), 6 + 12);
+        assert!(stroke.positions.contains(&Vec2::new(10.0, -1.0)));
+        assert!(stroke.positions.contains(&Vec2::new(11.0, 0.0)));
+    }
+
+    #[test]
+    fn duplicate_points_are_skipped() {
+        let points = [Vec2::ZERO, Vec2::ZERO, Vec2::X, Vec2::X];
+        let stroke = stroke_polyline(&points, 1.0, Join::Miter);
+        assert_eq!(stroke.indices.len(), 6);
+        assert_eq!(stroke.sources, [0, 0, 2, 2]);
+        assert!(stroke_polyline(&[Vec2::ONE; 3], 1.0, Join::Miter)
+            .indices
+            .is_empty());
+    }
+}
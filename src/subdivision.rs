@@ -0,0 +1,131 @@
+//! de Casteljau 细分：在参数 t 处把三次 Bezier 段或首尾相接的 Bezier 链一分为二。
+
+use bevy::math::VectorSpace;
+
+use crate::curve::split_parameter;
+
+/// de Casteljau 算法在段内参数 `u` 处的各层插值点：相邻控制点插值得到三个点，
+/// 再插值得到两个点，最后一个点就在曲线上。
+pub fn casteljau_levels<P: VectorSpace>(segment: [P; 4], u: f32) -> ([P; 3], [P; 2], P) {
+    let [p0, p1, p2, p3] = segment;
+    let first = [p0.lerp(p1, u), p1.lerp(p2, u), p2.lerp(p3, u)];
+    let second = [first[0].lerp(first[1], u), first[1].lerp(first[2], u)];
+    (first, second, second[0].lerp(second[1], u))
+}
+
+/// 在段内参数 `u`（0..=1）处细分一段三次 Bezier，返回两半的控制点。
+/// 左半段在 0..=1 上走过原来的 0..=u，右半段走过 u..=1。
+pub fn split_cubic<P: VectorSpace>(segment: [P; 4], u: f32) -> ([P; 4], [P; 4]) {
+    let (first, second, split) = casteljau_levels(segment, u);
+    (
+        [segment[0], first[0], second[0], split],
+        [split, second[1], first[2], segment[3]],
+    )
+}
+
+/// 在全局参数 `t`（0..=段数，超出时截断）处把 Bezier 链分成两条链。
+/// `t` 所在的段被细分，其余的段原样归入前一半或后一半；链为空时返回 None。
+pub fn split_chain<P: VectorSpace>(chain: &[[P; 4]], t: f32) -> Option<(Vec<[P; 4]>, Vec<[P; 4]>)> {
+    if chain.is_empty() {
+        return None;
+    }
+    let (index, u) = split_parameter(t, chain.len());
+    let (left, right) = split_cubic(chain[index], u);
+    let mut before = chain[..index].to_vec();
+    before.push(left);
+    let mut after = vec![right];
+    after.extend_from_slice(&chain[index + 1..]);
+    Some((before, after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::{
+        cubic_splines::{CubicBezier, CubicCurve, CubicGenerator},
+        Vec2,
+    };
+
+    fn curve(chain: &[[Vec2; 4]]) -> CubicCurve<Vec2> {
+        CubicBezier::new(chain.to_vec()).to_curve().unwrap()
+    }
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(actual.distance(expected) < 1e-3, "{actual} != {expected}");
+    }
+
+    const SEGMENT: [Vec2; 4] = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(50.0, 120.0),
+        Vec2::new(150.0, -80.0),
+        Vec2::new(200.0, 40.0),
+    ];
+
+    // 两半重新参数化之后与原曲线逐点重合
+    #[test]
+    fn halves_trace_the_original_segment() {
+        let original = curve(&[SEGMENT]);
+        for u in [0.0, 0.25, 0.6, 1.0] {
+            let (left, right) = split_cubic(SEGMENT, u);
+            let (left, right) = (curve(&[left]), curve(&[right]));
+            for i in 0..=10 {
+                let s = i as f32 / 10.0;
+                assert_near(left.position(s), original.position(s * u));
+                assert_near(right.position(s), original.position(u + s * (1.0 - u)));
+            }
+        }
+    }
+
+    #[test]
+    fn split_point_is_on_the_curve_and_keeps_end_points() {
+        let (left, right) = split_cubic(SEGMENT, 0.3);
+        assert_eq!(left[0], SEGMENT[0]);
+        assert_eq!(right[3], SEGMENT[3]);
+        assert_eq!(left[3], right[0]);
+        assert_near(left[3], curve(&[SEGMENT]).position(0.3));
+        // 切线方向连续：分点两侧的控制点与分点共线
+        let incoming = left[3] - left[2];
+        let outgoing = right[1] - right[0];
+        assert!(incoming.perp_dot(outgoing).abs() < 1e-2 * incoming.length() * outgoing.length());
+    }
+
+    #[test]
+    fn each_level_interpolates_the_previous_one() {
+        let u = 0.4;
+        let (first, second, point) = casteljau_levels(SEGMENT, u);
+        for (p, w) in first.iter().zip(SEGMENT.windows(2)) {
+            assert_near(*p, w[0].lerp(w[1], u));
+        }
+        for (p, w) in second.iter().zip(first.windows(2)) {
+            assert_near(*p, w[0].lerp(w[1], u));
+        }
+        assert_near(point, curve(&[SEGMENT]).position(u));
+        // 最后一层的连线就是曲线在这一点的切线
+        let tangent = second[1] - second[0];
+        let velocity = curve(&[SEGMENT]).velocity(u);
+        assert!(tangent.perp_dot(velocity).abs() < 1e-3 * tangent.length() * velocity.length());
+    }
+
+    #[test]
+    fn chain_split_keeps_the_other_segments() {
+        let second = [
+            Vec2::new(200.0, 40.0),
+            Vec2::new(250.0, 160.0),
+            Vec2::new(320.0, 160.0),
+            Vec2::new(360.0, 0.0),
+        ];
+        let chain = [SEGMENT, second];
+        let (before, after) = split_chain(&chain, 1.5).unwrap();
+        assert_eq!(before.len(), 2);
+        assert_eq!(before[0], SEGMENT);
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0][3], second[3]);
+        assert_near(after[0][0], curve(&chain).position(1.5));
+
+        // 超出参数域时截断到末端，后一半退化成一个点
+        let (before, after) = split_chain(&chain, 5.0).unwrap();
+        assert_eq!(before.len(), 2);
+        assert!(after[0].iter().all(|p| *p == second[3]));
+        assert!(split_chain::<Vec2>(&[], 0.5).is_none());
+    }
+}
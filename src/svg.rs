@@ -0,0 +1,221 @@
+//! 手写的 SVG 输出，把场景中的折线、三次 Bezier 路径和圆写成一个文件。
+//!
+//! 世界坐标 y 轴向上，SVG 向下，写入时翻转 y；viewBox 取所有元素的包围盒再加上边距。
+
+use std::fmt::Write;
+
+use bevy::{
+    color::Srgba,
+    math::{Rect, Vec2},
+};
+
+pub enum SvgElement {
+    // dash 为 Some((实线长度, 间隔)) 时画虚线
+    Polyline {
+        points: Vec<Vec2>,
+        color: Srgba,
+        width: f32,
+        dash: Option<(f32, f32)>,
+    },
+    // 首尾相接的三次 Bezier 段，直接对应 SVG 的 C 命令
+    CubicPath {
+        segments: Vec<[Vec2; 4]>,
+        color: Srgba,
+        width: f32,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+        color: Srgba,
+        width: f32,
+    },
+}
+
+impl SvgElement {
+    fn bounds(&self) -> Option<Rect> {
+        let points: Vec<Vec2> = match self {
+            SvgElement::Polyline { points, .. } => points.clone(),
+            SvgElement::CubicPath { segments, .. } => segments.iter().flatten().copied().collect(),
+            SvgElement::Circle { center, radius, .. } => {
+                vec![
+                    *center - Vec2::splat(*radius),
+                    *center + Vec2::splat(*radius),
+                ]
+            }
+        };
+        let (first, rest) = points.split_first()?;
+        Some(
+            rest.iter()
+                .fold(Rect::from_corners(*first, *first), |rect, p| {
+                    rect.union_point(*p)
+                }),
+        )
+    }
+}
+
+// 用 0 - y 而不是 -y，避免输出 -0
+fn flip(p: Vec2) -> Vec2 {
+    Vec2::new(p.x, 0.0 - p.y)
+}
+
+// 颜色和不透明度分开写，兼容只认 #rrggbb 的查看器
+fn paint(color: Srgba) -> String {
+    let hex = color.with_alpha(1.0).to_hex();
+    if color.alpha < 1.0 {
+        format!("\"{hex}\" stroke-opacity=\"{:.3}\"", color.alpha)
+    } else {
+        format!("\"{hex}\"")
+    }
+}
+
+pub fn to_svg(elements: &[SvgElement], padding: f32) -> String {
+    let bounds = elements
+        .iter()
+        .filter_map(SvgElement::bounds)
+        .reduce(|a, b| a.union(b))
+        .unwrap_or(Rect::from_corners(Vec2::ZERO, Vec2::ZERO))
+        .inflate(padding);
+    // 翻转后原来的 max.y 成为顶部
+    let origin = Vec2::new(bounds.min.x, -bounds.max.y);
+    let size = bounds.size();
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+        origin.x, origin.y, size.x, size.y
+    );
+    for element in elements {
+        match element {
+            SvgElement::Polyline {
+                points,
+                color,
+                width,
+                dash,
+            } => {
+                let coordinates: Vec<String> = points
+                    .iter()
+                    .map(|p| {
+                        let p = flip(*p);
+                        format!("{},{}", p.x, p.y)
+                    })
+                    .collect();
+                let dash = dash
+                    .map(|(on, off)| format!(" stroke-dasharray=\"{on} {off}\""))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    svg,
+                    "  <polyline points=\"{}\" fill=\"none\" stroke={} stroke-width=\"{width}\"{dash}/>",
+                    coordinates.join(" "),
+                    paint(*color)
+                );
+            }
+            SvgElement::CubicPath {
+                segments,
+                color,
+                width,
+            } => {
+                let Some(first) = segments.first() else {
+                    continue;
+                };
+                let start = flip(first[0]);
+                let mut d = format!("M {} {}", start.x, start.y);
+                for segment in segments {
+                    let [_, c1, c2, end] = segment.map(flip);
+                    let _ = write!(
+                        d,
+                        " C {} {} {} {} {} {}",
+                        c1.x, c1.y, c2.x, c2.y, end.x, end.y
+                    );
+                }
+                let _ = writeln!(
+                    svg,
+                    "  <path d=\"{d}\" fill=\"none\" stroke={} stroke-width=\"{width}\"/>",
+                    paint(*color)
+                );
+            }
+            SvgElement::Circle {
+                center,
+                radius,
+                color,
+                width,
+            } => {
+                let center = flip(*center);
+                let _ = writeln!(
+                    svg,
+                    "  <circle cx=\"{}\" cy=\"{}\" r=\"{radius}\" fill=\"none\" stroke={} stroke-width=\"{width}\"/>",
+                    center.x,
+                    center.y,
+                    paint(*color)
+                );
+            }
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute<'a>(text: &'a str, name: &str) -> &'a str {
+        let start = text.find(&format!("{name}=\"")).unwrap() + name.len() + 2;
+        let end = start + text[start..].find('"').unwrap();
+        &text[start..end]
+    }
+
+    #[test]
+    fn writes_elements_and_flipped_view_box() {
+        let elements = [
+            SvgElement::Polyline {
+                points: vec![Vec2::new(0.0, 0.0), Vec2::new(100.0, 50.0)],
+                color: Srgba::WHITE,
+                width: 2.0,
+                dash: Some((8.0, 6.0)),
+            },
+            SvgElement::CubicPath {
+                segments: vec![[
+                    Vec2::new(0.0, 0.0),
+                    Vec2::new(10.0, 40.0),
+                    Vec2::new(90.0, 40.0),
+                    Vec2::new(100.0, 0.0),
+                ]],
+                color: Srgba::rgb(0.0, 1.0, 0.0),
+                width: 2.0,
+            },
+            SvgElement::Circle {
+                center: Vec2::new(100.0, 50.0),
+                radius: 5.0,
+                color: Srgba::RED.with_alpha(0.5),
+                width: 5.0,
+            },
+        ];
+        let svg = to_svg(&elements, 10.0);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert_eq!(svg.matches("<circle").count(), 1);
+        assert_eq!(svg.matches(" C ").count(), 1);
+
+        // 包围盒 x: 0..105，y: 0..55，翻转 y 后加上 10 的边距
+        let view_box: Vec<f32> = attribute(&svg, "viewBox")
+            .split(' ')
+            .map(|v| v.parse().unwrap())
+            .collect();
+        assert_eq!(view_box, vec![-10.0, -65.0, 125.0, 75.0]);
+
+        assert_eq!(attribute(&svg, "points"), "0,0 100,-50");
+        assert_eq!(attribute(&svg, "stroke-dasharray"), "8 6");
+        assert_eq!(attribute(&svg, "cy"), "-50");
+        assert_eq!(attribute(&svg, "stroke-opacity"), "0.500");
+    }
+
+    #[test]
+    fn empty_scene_is_still_valid() {
+        let svg = to_svg(&[], 10.0);
+        assert_eq!(attribute(&svg, "viewBox"), "-10 -10 20 20");
+        assert!(!svg.contains("<polyline"));
+    }
+}
@@ -0,0 +1,111 @@
+//! 对称编辑：竖直的对称轴 x = axis 一侧是编辑的点，另一侧是它们的镜像。
+//!
+//! 曲线使用的点是编辑的点加上倒序的镜像点，落在轴上的点是自己的镜像，只出现一次；
+//! 这样开放曲线从编辑的一侧走到另一侧，两端都在轴上的闭合曲线绕一圈回到起点，整条曲线都关于轴对称。
+
+use bevy::math::Vec2;
+
+use crate::points::MovablePoint;
+
+pub fn mirror(position: Vec2, axis: f32) -> Vec2 {
+    Vec2::new(2.0 * axis - position.x, position.y)
+}
+
+/// 轴上的点必须正好在轴上，拖动和添加时由编辑操作吸附上去。
+pub fn on_axis(position: Vec2, axis: f32) -> bool {
+    position.x == axis
+}
+
+/// 曲线使用的对称点列。镜像点的顺序反过来，手动设置的切线随之镜像并反向；
+/// 轴上的点手动设置的切线只保留垂直于轴的分量，曲线在那里不会折出尖角。
+pub fn symmetric_points(points: &[MovablePoint], axis: f32) -> Vec<MovablePoint> {
+    let mut combined: Vec<MovablePoint> = points
+        .iter()
+        .map(|p| MovablePoint {
+            tangent: match p.tangent {
+                Some(t) if on_axis(p.position, axis) => Some(Vec2::new(t.x, 0.0)),
+                tangent => tangent,
+            },
+            ..p.clone()
+        })
+        .collect();
+    combined.extend(
+        points
+            .iter()
+            .rev()
+            .filter(|p| !on_axis(p.position, axis))
+            .map(|p| MovablePoint {
+                position: mirror(p.position, axis),
+                tangent: p.tangent.map(|t| Vec2::new(t.x, -t.y)),
+                is_selected: false,
+                is_handle_selected: false,
+                ..p.clone()
+            }),
+    );
+    combined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(positions: &[(f32, f32)]) -> Vec<MovablePoint> {
+        positions
+            .iter()
+            .map(|&(x, y)| MovablePoint::new(Vec2::new(x, y)))
+            .collect()
+    }
+
+    fn positions(points: &[MovablePoint]) -> Vec<Vec2> {
+        points.iter().map(|p| p.position).collect()
+    }
+
+    #[test]
+    fn mirrors_are_appended_in_reverse() {
+        let half = points(&[(-100.0, 0.0), (-50.0, 80.0)]);
+        assert_eq!(
+            positions(&symmetric_points(&half, 0.0)),
+            [
+                Vec2::new(-100.0, 0.0),
+                Vec2::new(-50.0, 80.0),
+                Vec2::new(50.0, 80.0),
+                Vec2::new(100.0, 0.0),
+            ]
+        );
+        // 轴不在原点
+        assert_eq!(mirror(Vec2::new(10.0, 5.0), 30.0), Vec2::new(50.0, 5.0));
+    }
+
+    #[test]
+    fn points_on_the_axis_are_their_own_mirror() {
+        let mut half = points(&[(20.0, 100.0), (-60.0, 0.0), (20.0, -100.0)]);
+        half[0].tangent = Some(Vec2::new(-30.0, 10.0));
+        half[1].tangent = Some(Vec2::new(5.0, -40.0));
+        let combined = symmetric_points(&half, 20.0);
+        assert_eq!(
+            positions(&combined),
+            [
+                Vec2::new(20.0, 100.0),
+                Vec2::new(-60.0, 0.0),
+                Vec2::new(20.0, -100.0),
+                Vec2::new(100.0, 0.0),
+            ]
+        );
+        assert_eq!(combined[0].tangent, Some(Vec2::new(-30.0, 0.0)));
+        // 镜像后倒序经过，切线的 x 分量不变、y 分量反号
+        assert_eq!(combined[3].tangent, Some(Vec2::new(5.0, 40.0)));
+    }
+
+    // 闭合的对称点列经过镜像后与自身倒序相同
+    #[test]
+    fn combined_points_are_symmetric() {
+        let half = points(&[(0.0, 50.0), (-40.0, 20.0), (-30.0, -60.0), (0.0, -80.0)]);
+        let combined = symmetric_points(&half, 0.0);
+        assert_eq!(combined.len(), 6);
+        let n = combined.len();
+        for i in 0..n {
+            let opposite = combined[(n - i) % n].position;
+            assert_eq!(mirror(combined[i].position, 0.0), opposite);
+        }
+    }
+}
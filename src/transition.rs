@@ -0,0 +1,198 @@
+//! 增删控制点时的过渡：曲线用一份缓动中的点构造，新点从插入处的邻点之间长出来，
+//! 删掉的点先缩回邻点之间再消失。
+//!
+//! 新旧两组点按位置比较公共的前缀和后缀，中间只有一侧有点时才是一次增删；
+//! 两侧中间都有点（例如读取了另一个文件）时不做过渡。
+
+use bevy::math::Vec2;
+
+use crate::points::MovablePoint;
+
+// 秒
+pub(crate) const TRANSITION_DURATION: f32 = 0.2;
+
+struct Easing {
+    point: MovablePoint,
+    from: Vec2,
+    to: Vec2,
+    // 已经删掉的点，过渡结束后不再显示
+    removed: bool,
+}
+
+pub(crate) struct PointTransition {
+    entries: Vec<Easing>,
+    elapsed: f32,
+}
+
+impl PointTransition {
+    /// 控制点从 `before` 变成 `after` 时的过渡。`shown` 是 `before` 中每个点当前显示的位置，
+    /// 上一次过渡还没结束时从那里继续。点数不变或不是一次连续的增删时返回 None。
+    pub(crate) fn between(
+        before: &[MovablePoint],
+        shown: &[Vec2],
+        after: &[MovablePoint],
+    ) -> Option<Self> {
+        if before.len() == after.len() || before.is_empty() || after.is_empty() {
+            return None;
+        }
+        let same = |a: &MovablePoint, b: &MovablePoint| a.position == b.position;
+        let shorter = before.len().min(after.len());
+        let prefix = before
+            .iter()
+            .zip(after)
+            .take_while(|(a, b)| same(a, b))
+            .count();
+        let suffix = before
+            .iter()
+            .rev()
+            .zip(after.iter().rev())
+            .take(shorter - prefix)
+            .take_while(|(a, b)| same(a, b))
+            .count();
+        let (removed, inserted) = (
+            &before[prefix..before.len() - suffix],
+            &after[prefix..after.len() - suffix],
+        );
+        if !removed.is_empty() && !inserted.is_empty() {
+            return None;
+        }
+        // 增删处两侧留下的点的中点；只有一侧有点时就是那个点
+        let left = prefix.checked_sub(1).map(|i| after[i].position);
+        let right = after.get(prefix + inserted.len()).map(|p| p.position);
+        let anchor = match (left, right) {
+            (Some(a), Some(b)) => a.lerp(b, 0.5),
+            (Some(p), None) | (None, Some(p)) => p,
+            (None, None) => return None,
+        };
+        let kept = |point: &MovablePoint, from: Vec2| Easing {
+            point: point.clone(),
+            from,
+            to: point.position,
+            removed: false,
+        };
+        let mut entries: Vec<Easing> = Vec::with_capacity(before.len().max(after.len()));
+        entries.extend((0..prefix).map(|i| kept(&after[i], shown[i])));
+        entries.extend(inserted.iter().map(|point| kept(point, anchor)));
+        entries.extend(
+            removed
+                .iter()
+                .zip(&shown[prefix..])
+                .map(|(point, &from)| Easing {
+                    point: point.clone(),
+                    from,
+                    to: anchor,
+                    removed: true,
+                }),
+        );
+        let tail = after.len() - suffix;
+        let shown_tail = before.len() - suffix;
+        entries.extend((0..suffix).map(|i| kept(&after[tail + i], shown[shown_tail + i])));
+        Some(Self {
+            entries,
+            elapsed: 0.0,
+        })
+    }
+
+    /// 前进 `dt` 秒，过渡结束时返回 true。
+    pub(crate) fn advance(&mut self, dt: f32) -> bool {
+        self.elapsed += dt;
+        self.elapsed >= TRANSITION_DURATION
+    }
+
+    // 先快后慢
+    fn eased(&self, entry: &Easing) -> Vec2 {
+        let s = (self.elapsed / TRANSITION_DURATION).clamp(0.0, 1.0);
+        entry.from.lerp(entry.to, 1.0 - (1.0 - s).powi(3))
+    }
+
+    /// 这一刻用来构造曲线的点，包括还在缩回的已删除的点。
+    pub(crate) fn points(&self) -> Vec<MovablePoint> {
+        self.entries
+            .iter()
+            .map(|entry| MovablePoint {
+                position: self.eased(entry),
+                ..entry.point.clone()
+            })
+            .collect()
+    }
+
+    /// 目标控制点当前显示的位置，顺序与 `between` 的 `after` 一致。
+    pub(crate) fn shown(&self) -> Vec<Vec2> {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.removed)
+            .map(|entry| self.eased(entry))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(positions: &[Vec2]) -> Vec<MovablePoint> {
+        positions
+            .iter()
+            .map(|&position| MovablePoint {
+                position,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn positions(points: &[MovablePoint]) -> Vec<Vec2> {
+        points.iter().map(|p| p.position).collect()
+    }
+
+    const A: Vec2 = Vec2::new(0.0, 0.0);
+    const B: Vec2 = Vec2::new(100.0, 0.0);
+    const C: Vec2 = Vec2::new(100.0, 100.0);
+
+    #[test]
+    fn inserted_point_grows_out_of_its_neighbours() {
+        let (before, after) = (points(&[A, B]), points(&[A, C, B]));
+        let mut transition = PointTransition::between(&before, &[A, B], &after).unwrap();
+        assert_eq!(positions(&transition.points()), [A, A.lerp(B, 0.5), B]);
+        assert!(!transition.advance(TRANSITION_DURATION * 0.5));
+        let halfway = transition.points()[1].position;
+        assert!(halfway.y > 50.0 && halfway.y < 100.0, "{halfway}");
+        assert!(transition.advance(TRANSITION_DURATION * 0.5));
+        assert_eq!(positions(&transition.points()), [A, C, B]);
+
+        // 追加在末尾的点从原来的最后一个点长出来
+        let appended = PointTransition::between(&before, &[A, B], &points(&[A, B, C])).unwrap();
+        assert_eq!(appended.points()[2].position, B);
+    }
+
+    #[test]
+    fn removed_point_collapses_before_disappearing() {
+        let (before, after) = (points(&[A, C, B]), points(&[A, B]));
+        let mut transition = PointTransition::between(&before, &[A, C, B], &after).unwrap();
+        assert_eq!(positions(&transition.points()), [A, C, B]);
+        assert_eq!(transition.shown(), [A, B]);
+        transition.advance(TRANSITION_DURATION);
+        assert_eq!(positions(&transition.points()), [A, A.lerp(B, 0.5), B]);
+    }
+
+    // 上一次过渡中途再加点，已有的点从当前显示的位置继续
+    #[test]
+    fn chained_edits_continue_from_the_shown_positions() {
+        let mut first =
+            PointTransition::between(&points(&[A, B]), &[A, B], &points(&[A, C, B])).unwrap();
+        first.advance(TRANSITION_DURATION * 0.5);
+        let shown = first.shown();
+        let second =
+            PointTransition::between(&points(&[A, C, B]), &shown, &points(&[A, C, B, A])).unwrap();
+        assert_eq!(second.points()[1].position, shown[1]);
+        assert_eq!(second.points()[3].position, B);
+    }
+
+    #[test]
+    fn replacing_or_emptying_skips_the_transition() {
+        let before = points(&[A, B]);
+        assert!(PointTransition::between(&before, &[A, B], &points(&[C, C, C])).is_none());
+        assert!(PointTransition::between(&before, &[A, B], &points(&[B, A])).is_none());
+        assert!(PointTransition::between(&before, &[A, B], &[]).is_none());
+        assert!(PointTransition::between(&[], &[], &before).is_none());
+    }
+}
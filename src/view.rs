@@ -0,0 +1,70 @@
+//! 相机视图的计算：缩放锚点和适应窗口的视图。
+
+use bevy::math::{Rect, Vec2};
+
+/// 以 `anchor` 为不动点缩放 `ratio` 倍后相机中心的位置。
+pub fn zoom_about(center: Vec2, anchor: Vec2, ratio: f32) -> Vec2 {
+    anchor + (center - anchor) * ratio
+}
+
+/// 包含所有点的最小矩形，没有点时返回 None。
+pub fn bounds(points: impl IntoIterator<Item = Vec2>) -> Option<Rect> {
+    points.into_iter().fold(None, |rect: Option<Rect>, p| {
+        Some(match rect {
+            Some(rect) => rect.union_point(p),
+            None => Rect::from_corners(p, p),
+        })
+    })
+}
+
+/// 让 `bounds` 正好放进 `viewport`（像素）的相机中心和缩放，四周各留出 `padding` 比例的边距。
+/// 矩形退化成一个点或视口为空时返回 None；只有一个方向退化时按另一个方向缩放。
+pub fn fit_view(bounds: Rect, viewport: Vec2, padding: f32) -> Option<(Vec2, f32)> {
+    let size = bounds.size();
+    if size.max_element() <= 0.0 || viewport.min_element() <= 0.0 {
+        return None;
+    }
+    let scale = (size / viewport).max_element() * (1.0 + 2.0 * padding);
+    Some((bounds.center(), scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zooming_keeps_the_anchor_fixed() {
+        let center = Vec2::new(10.0, -5.0);
+        let anchor = Vec2::new(40.0, 20.0);
+        let ratio = 0.5;
+        let zoomed = zoom_about(center, anchor, ratio);
+        // 锚点相对中心的偏移按同样的比例缩小，屏幕位置不变
+        assert!(((anchor - zoomed) - (anchor - center) * ratio).length() < 1e-5);
+        assert_eq!(zoom_about(center, anchor, 1.0), center);
+    }
+
+    #[test]
+    fn fits_the_larger_dimension_with_padding() {
+        let rect = Rect::new(100.0, 0.0, 300.0, 50.0);
+        let (center, scale) = fit_view(rect, Vec2::new(800.0, 600.0), 0.1).unwrap();
+        assert_eq!(center, Vec2::new(200.0, 25.0));
+        assert!((scale - 200.0 / 800.0 * 1.2).abs() < 1e-6);
+
+        let tall = Rect::new(0.0, 0.0, 10.0, 1200.0);
+        let (_, scale) = fit_view(tall, Vec2::new(800.0, 600.0), 0.0).unwrap();
+        assert!((scale - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn degenerate_bounds_do_not_fit() {
+        assert_eq!(bounds([]), None);
+        let single = bounds([Vec2::new(3.0, 4.0)]).unwrap();
+        assert_eq!(fit_view(single, Vec2::new(800.0, 600.0), 0.1), None);
+        // 水平的一排点只按宽度缩放
+        let row = bounds([Vec2::new(0.0, 5.0), Vec2::new(400.0, 5.0)]).unwrap();
+        let (center, scale) = fit_view(row, Vec2::new(800.0, 600.0), 0.0).unwrap();
+        assert_eq!(center, Vec2::new(200.0, 5.0));
+        assert!((scale - 0.5).abs() < 1e-6);
+        assert_eq!(fit_view(row, Vec2::ZERO, 0.1), None);
+    }
+}
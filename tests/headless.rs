@@ -0,0 +1,1101 @@
+//! 不创建窗口、不渲染地运行演示插件。
+
+use std::time::Duration;
+
+use bevy::{
+    input::{
+        mouse::{MouseScrollUnit, MouseWheel},
+        touch::{touch_screen_input_system, TouchInput, TouchPhase},
+        InputPlugin,
+    },
+    prelude::*,
+    time::TimeUpdateStrategy,
+    window::{ExitCondition, WindowPlugin},
+};
+use my_bevy_game::{
+    cli, BuiltCurves, ControlPoints, CurveChanged, CurveKind, EndCondition, MousePosition,
+    MovablePoint, Parameterization, SampledCurves, ScreenToWorld, SplineShowcasePlugin,
+};
+
+fn headless_app(points: &[Vec2]) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        InputPlugin,
+        WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            ..default()
+        },
+        SplineShowcasePlugin::with_points(points.iter().copied()).headless(),
+    ));
+    app
+}
+
+// 不加 InputPlugin，由测试直接写入输入资源；光标坐标原样当作世界坐标
+fn driven_app(points: &[Vec2]) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            ..default()
+        },
+        SplineShowcasePlugin::with_points(points.iter().copied()).headless(),
+    ))
+    .insert_resource(ScreenToWorld::IDENTITY);
+    app.update();
+    app
+}
+
+// 运行一帧，然后像 InputPlugin 一样清除这一帧的 just_pressed / just_released
+fn frame(app: &mut App) {
+    app.update();
+    let world = app.world_mut();
+    world.resource_mut::<ButtonInput<MouseButton>>().clear();
+    world.resource_mut::<ButtonInput<KeyCode>>().clear();
+}
+
+fn move_cursor(app: &mut App, position: Vec2) {
+    app.world_mut().resource_mut::<MousePosition>().0 = Some(position);
+}
+
+fn mouse(app: &mut App) -> Mut<'_, ButtonInput<MouseButton>> {
+    app.world_mut().resource_mut::<ButtonInput<MouseButton>>()
+}
+
+#[test]
+fn runs_a_few_frames_with_seeded_points() {
+    let points = [
+        Vec2::new(-200.0, 0.0),
+        Vec2::new(0.0, 150.0),
+        Vec2::new(200.0, 0.0),
+    ];
+    let mut app = headless_app(&points);
+    for _ in 0..5 {
+        app.update();
+    }
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.positions(), points);
+    assert!(!control_points.closed);
+}
+
+#[test]
+fn control_points_can_be_edited_between_frames() {
+    let mut app = headless_app(&[]);
+    app.update();
+    app.world_mut()
+        .resource_mut::<ControlPoints>()
+        .points
+        .push(MovablePoint::new(Vec2::new(10.0, 20.0)));
+    for _ in 0..3 {
+        app.update();
+    }
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.points.len(), 1);
+    assert_eq!(control_points.points[0].tension, 0.5);
+}
+
+#[test]
+fn right_click_adds_a_point_under_the_cursor() {
+    let mut app = driven_app(&[]);
+    move_cursor(&mut app, Vec2::new(30.0, -40.0));
+    mouse(&mut app).press(MouseButton::Right);
+    frame(&mut app);
+    mouse(&mut app).release(MouseButton::Right);
+    frame(&mut app);
+
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.positions(), [Vec2::new(30.0, -40.0)]);
+}
+
+#[test]
+fn left_drag_moves_the_point_under_the_cursor() {
+    let mut app = driven_app(&[Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)]);
+    // 偏离中心抓取，点按位移移动而不是跳到光标处
+    move_cursor(&mut app, Vec2::new(102.0, 3.0));
+    mouse(&mut app).press(MouseButton::Left);
+    frame(&mut app);
+    move_cursor(&mut app, Vec2::new(152.0, 3.0));
+    frame(&mut app);
+    mouse(&mut app).release(MouseButton::Left);
+    frame(&mut app);
+
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(
+        control_points.positions(),
+        [Vec2::new(-100.0, 0.0), Vec2::new(150.0, 0.0)]
+    );
+}
+
+fn keyboard(app: &mut App) -> Mut<'_, ButtonInput<KeyCode>> {
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>()
+}
+
+fn drag(app: &mut App, from: Vec2, to: Vec2) {
+    move_cursor(app, from);
+    mouse(app).press(MouseButton::Left);
+    frame(app);
+    move_cursor(app, to);
+    frame(app);
+    mouse(app).release(MouseButton::Left);
+    frame(app);
+}
+
+// 探针放在控制点上时拖动的是探针；移除探针后同样的拖动移动控制点
+#[test]
+fn probe_is_dragged_instead_of_the_point_beneath_it() {
+    let points = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+    let mut app = driven_app(&points);
+    move_cursor(&mut app, points[1]);
+    keyboard(&mut app).press(KeyCode::KeyX);
+    frame(&mut app);
+    keyboard(&mut app).release(KeyCode::KeyX);
+    frame(&mut app);
+
+    drag(&mut app, points[1], Vec2::new(100.0, 50.0));
+    drag(&mut app, Vec2::new(100.0, 50.0), points[1]);
+    assert_eq!(app.world().resource::<ControlPoints>().positions(), points);
+
+    keyboard(&mut app).press(KeyCode::ShiftLeft);
+    keyboard(&mut app).press(KeyCode::KeyX);
+    frame(&mut app);
+    keyboard(&mut app).release_all();
+    frame(&mut app);
+    drag(&mut app, points[1], Vec2::new(100.0, -50.0));
+    assert_eq!(
+        app.world().resource::<ControlPoints>().positions(),
+        [points[0], Vec2::new(100.0, -50.0)]
+    );
+}
+
+// 触摸经由 TouchInput 事件进入 Touches，和 InputPlugin 一样在 PreUpdate 处理；每帧固定 100ms
+fn touch_app(points: &[Vec2]) -> App {
+    let mut app = driven_app(points);
+    app.add_event::<TouchInput>()
+        .add_systems(PreUpdate, touch_screen_input_system)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+    app
+}
+
+fn touch(app: &mut App, phase: TouchPhase, id: u64, position: Vec2) {
+    app.world_mut().send_event(TouchInput {
+        phase,
+        position,
+        window: Entity::PLACEHOLDER,
+        force: None,
+        id,
+    });
+}
+
+#[test]
+fn tap_on_empty_space_adds_a_point() {
+    let mut app = touch_app(&[]);
+    touch(&mut app, TouchPhase::Started, 0, Vec2::new(30.0, -40.0));
+    frame(&mut app);
+    touch(&mut app, TouchPhase::Ended, 0, Vec2::new(31.0, -40.0));
+    frame(&mut app);
+
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.positions(), [Vec2::new(31.0, -40.0)]);
+}
+
+// 第二根手指同时拖动另一个点，只有第一根手指起作用
+#[test]
+fn first_finger_drags_a_point_and_the_second_is_ignored() {
+    let points = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+    let mut app = touch_app(&points);
+    touch(&mut app, TouchPhase::Started, 1, points[1]);
+    frame(&mut app);
+    touch(&mut app, TouchPhase::Started, 2, points[0]);
+    frame(&mut app);
+    touch(&mut app, TouchPhase::Moved, 1, Vec2::new(150.0, 20.0));
+    touch(&mut app, TouchPhase::Moved, 2, Vec2::new(-150.0, 50.0));
+    frame(&mut app);
+    touch(&mut app, TouchPhase::Ended, 1, Vec2::new(150.0, 20.0));
+    touch(&mut app, TouchPhase::Ended, 2, Vec2::new(-150.0, 50.0));
+    frame(&mut app);
+
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(
+        control_points.positions(),
+        [points[0], Vec2::new(150.0, 20.0)]
+    );
+}
+
+#[test]
+fn long_press_deletes_the_point() {
+    let points = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+    let mut app = touch_app(&points);
+    touch(&mut app, TouchPhase::Started, 0, points[1]);
+    for _ in 0..8 {
+        frame(&mut app);
+    }
+    touch(&mut app, TouchPhase::Ended, 0, points[1]);
+    frame(&mut app);
+
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.positions(), points[..1]);
+}
+
+#[test]
+fn c_removes_the_last_point() {
+    let points = [
+        Vec2::new(-200.0, 0.0),
+        Vec2::new(0.0, 150.0),
+        Vec2::new(200.0, 0.0),
+    ];
+    let mut app = driven_app(&points);
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyC);
+    frame(&mut app);
+
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.positions(), points[..2]);
+}
+
+#[test]
+fn command_line_preset_seeds_the_scene() {
+    let options = cli::parse(["--preset", "circle"].map(String::from)).unwrap();
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            ..default()
+        },
+        SplineShowcasePlugin::from_options(&options)
+            .unwrap()
+            .headless(),
+    ));
+    app.update();
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.points.len(), 8);
+    assert!(control_points.closed);
+}
+
+#[test]
+fn function_keys_load_presets_and_undo_restores() {
+    let points = [Vec2::new(-200.0, 0.0), Vec2::new(200.0, 0.0)];
+    let mut app = driven_app(&points);
+    keyboard(&mut app).press(KeyCode::F9);
+    frame(&mut app);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.points.len(), 10);
+    assert!(control_points.closed);
+
+    keyboard(&mut app).release(KeyCode::F9);
+    keyboard(&mut app).press(KeyCode::F6);
+    frame(&mut app);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.points.len(), 12);
+    assert!(!control_points.closed);
+
+    keyboard(&mut app).release(KeyCode::F6);
+    keyboard(&mut app).press(KeyCode::ControlLeft);
+    keyboard(&mut app).press(KeyCode::KeyZ);
+    frame(&mut app);
+    keyboard(&mut app).release(KeyCode::KeyZ);
+    keyboard(&mut app).press(KeyCode::KeyZ);
+    frame(&mut app);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.positions(), points);
+    assert!(!control_points.closed);
+}
+
+// 同一个种子两次启动撒出同样的点，第二次按 F10 换一组
+#[test]
+fn seeded_random_points_are_reproducible() {
+    let scatter = || {
+        let options =
+            cli::parse(["--seed", "42", "--random-count", "6"].map(String::from)).unwrap();
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            },
+            SplineShowcasePlugin::from_options(&options)
+                .unwrap()
+                .headless(),
+        ));
+        app.update();
+        let mut layouts = Vec::new();
+        for _ in 0..2 {
+            keyboard(&mut app).press(KeyCode::F10);
+            frame(&mut app);
+            keyboard(&mut app).release(KeyCode::F10);
+            layouts.push(app.world().resource::<ControlPoints>().positions());
+        }
+        layouts
+    };
+    let first = scatter();
+    assert_eq!(first[0].len(), 6);
+    assert_ne!(first[0], first[1]);
+    assert_eq!(first, scatter());
+}
+
+#[test]
+fn clearing_all_points_needs_a_second_press() {
+    let points = [
+        Vec2::new(-200.0, 0.0),
+        Vec2::new(0.0, 150.0),
+        Vec2::new(200.0, 0.0),
+    ];
+    let mut app = driven_app(&points);
+    keyboard(&mut app).press(KeyCode::Delete);
+    frame(&mut app);
+    assert_eq!(app.world().resource::<ControlPoints>().points.len(), 3);
+
+    keyboard(&mut app).release(KeyCode::Delete);
+    frame(&mut app);
+    keyboard(&mut app).press(KeyCode::Delete);
+    frame(&mut app);
+    assert!(app.world().resource::<ControlPoints>().points.is_empty());
+    // 空的场景照常运行
+    for _ in 0..3 {
+        frame(&mut app);
+    }
+
+    keyboard(&mut app).release(KeyCode::Delete);
+    keyboard(&mut app).press(KeyCode::ControlLeft);
+    keyboard(&mut app).press(KeyCode::KeyZ);
+    frame(&mut app);
+    assert_eq!(app.world().resource::<ControlPoints>().positions(), points);
+}
+
+fn tap_key(app: &mut App, key: KeyCode) {
+    keyboard(app).press(key);
+    frame(app);
+    keyboard(app).release(key);
+    frame(app);
+}
+
+// 点击选中的点在松开鼠标、光标离开后仍然选中，可以继续用键盘编辑；Esc 取消选择
+#[test]
+fn selection_survives_mouse_release() {
+    let points = [
+        Vec2::new(-100.0, 0.0),
+        Vec2::new(100.0, 0.0),
+        Vec2::new(0.0, 100.0),
+    ];
+    let mut app = driven_app(&points);
+    drag(&mut app, points[1], points[1]);
+    move_cursor(&mut app, Vec2::new(0.0, -300.0));
+    frame(&mut app);
+    tap_key(&mut app, KeyCode::ArrowRight);
+    let moved = app.world().resource::<ControlPoints>().positions()[1];
+    assert!(moved.x > points[1].x && moved.y == 0.0, "{moved}");
+
+    tap_key(&mut app, KeyCode::Backspace);
+    assert_eq!(
+        app.world().resource::<ControlPoints>().positions(),
+        [points[0], points[2]]
+    );
+
+    drag(&mut app, points[0], points[0]);
+    tap_key(&mut app, KeyCode::Escape);
+    move_cursor(&mut app, Vec2::new(0.0, -300.0));
+    tap_key(&mut app, KeyCode::ArrowRight);
+    assert_eq!(
+        app.world().resource::<ControlPoints>().positions(),
+        [points[0], points[2]]
+    );
+}
+
+fn shows_text(app: &mut App, prefix: &str) -> bool {
+    app.world_mut()
+        .query::<&Text>()
+        .iter(app.world())
+        .any(|text| text.0.starts_with(prefix))
+}
+
+// 没有选中的点时方向键移动光标下的点，这时不调节播放速度；光标离开之后上下键才改变速度
+#[test]
+fn arrows_nudge_the_hovered_point_instead_of_changing_playback_speed() {
+    let points = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+    let mut app = driven_app(&points);
+    move_cursor(&mut app, points[1]);
+    frame(&mut app);
+    tap_key(&mut app, KeyCode::ArrowUp);
+    let moved = app.world().resource::<ControlPoints>().positions()[1];
+    assert!(moved.y > points[1].y && moved.x == points[1].x, "{moved}");
+    assert!(!shows_text(&mut app, "Playback"));
+
+    move_cursor(&mut app, Vec2::new(0.0, -300.0));
+    frame(&mut app);
+    tap_key(&mut app, KeyCode::ArrowUp);
+    assert_eq!(
+        app.world().resource::<ControlPoints>().positions()[1],
+        moved
+    );
+    assert!(shows_text(&mut app, "Playback"));
+}
+
+fn scroll(app: &mut App, lines: f32) {
+    app.world_mut().send_event(MouseWheel {
+        unit: MouseScrollUnit::Line,
+        x: 0.0,
+        y: lines,
+        window: Entity::PLACEHOLDER,
+    });
+    frame(app);
+}
+
+// 滚轮调整的是悬停高亮的点，而不是选中的点；空白处的滚轮照常缩放
+#[test]
+fn scroll_adjusts_the_hovered_point() {
+    let points = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+    let mut app = driven_app(&points);
+    drag(&mut app, points[0], points[0]);
+    move_cursor(&mut app, points[1] + Vec2::new(4.0, 0.0));
+    frame(&mut app);
+    scroll(&mut app, 1.0);
+    let weights = |app: &App| -> Vec<f32> {
+        let control_points = app.world().resource::<ControlPoints>();
+        control_points.points.iter().map(|p| p.weight).collect()
+    };
+    assert_eq!(weights(&app)[0], 1.0);
+    assert!((weights(&app)[1] - 1.1).abs() < 1e-5);
+
+    move_cursor(&mut app, Vec2::new(0.0, 200.0));
+    frame(&mut app);
+    scroll(&mut app, 1.0);
+    assert_eq!(weights(&app)[0], 1.0);
+    assert!((weights(&app)[1] - 1.1).abs() < 1e-5);
+}
+
+fn label_texts(app: &mut App) -> Vec<String> {
+    let mut query = app.world_mut().query::<&Text2d>();
+    let mut texts: Vec<String> = query.iter(app.world()).map(|text| text.0.clone()).collect();
+    texts.sort();
+    texts
+}
+
+// Shift+N 打开索引标签，增删点的同一帧标签跟着增删
+#[test]
+fn index_labels_follow_added_and_removed_points() {
+    let points = [
+        Vec2::new(-100.0, 0.0),
+        Vec2::new(0.0, 100.0),
+        Vec2::new(100.0, 0.0),
+    ];
+    let mut app = driven_app(&points);
+    assert!(label_texts(&mut app).is_empty());
+    keyboard(&mut app).press(KeyCode::ShiftLeft);
+    tap_key(&mut app, KeyCode::KeyN);
+    keyboard(&mut app).release(KeyCode::ShiftLeft);
+    frame(&mut app);
+    assert_eq!(label_texts(&mut app), ["0", "1", "2"]);
+
+    move_cursor(&mut app, Vec2::new(0.0, -300.0));
+    mouse(&mut app).press(MouseButton::Right);
+    frame(&mut app);
+    assert_eq!(app.world().resource::<ControlPoints>().points.len(), 4);
+    assert_eq!(label_texts(&mut app), ["0", "1", "2", "3"]);
+    mouse(&mut app).release(MouseButton::Right);
+    frame(&mut app);
+
+    tap_key(&mut app, KeyCode::KeyC);
+    assert_eq!(label_texts(&mut app), ["0", "1", "2"]);
+}
+
+#[test]
+fn y_cycles_the_catmull_rom_parameterization() {
+    let mut app = driven_app(&[Vec2::ZERO, Vec2::new(100.0, 0.0), Vec2::new(110.0, 50.0)]);
+    let parameterization = |app: &App| app.world().resource::<ControlPoints>().parameterization;
+    assert_eq!(parameterization(&app), Parameterization::Uniform);
+    tap_key(&mut app, KeyCode::KeyY);
+    assert!(matches!(
+        parameterization(&app),
+        Parameterization::Centripetal { .. }
+    ));
+    tap_key(&mut app, KeyCode::KeyY);
+    assert_eq!(parameterization(&app), Parameterization::ChordLength);
+    tap_key(&mut app, KeyCode::KeyY);
+    assert_eq!(parameterization(&app), Parameterization::Uniform);
+}
+
+// Shift+L 只改变 B-Spline 的构造方式，控制点不变
+#[test]
+fn shift_l_clamps_the_b_spline_without_touching_the_points() {
+    let points = [
+        Vec2::new(-150.0, 0.0),
+        Vec2::new(-50.0, 100.0),
+        Vec2::new(50.0, -100.0),
+        Vec2::new(150.0, 0.0),
+    ];
+    let mut app = driven_app(&points);
+    keyboard(&mut app).press(KeyCode::ShiftLeft);
+    tap_key(&mut app, KeyCode::KeyL);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert!(control_points.clamped);
+    assert!(!control_points.closed);
+    assert_eq!(control_points.positions(), points);
+}
+
+// Shift+T 切换端点条件；钳制时拖动起点的箭头只改变端点切线，点不动
+#[test]
+fn shift_t_cycles_end_conditions_and_the_start_arrow_drags() {
+    let points = [
+        Vec2::new(-150.0, 0.0),
+        Vec2::new(-50.0, 100.0),
+        Vec2::new(50.0, -100.0),
+        Vec2::new(150.0, 0.0),
+    ];
+    let mut app = driven_app(&points);
+    let end_condition = |app: &App| app.world().resource::<ControlPoints>().end_condition;
+    keyboard(&mut app).press(KeyCode::ShiftLeft);
+    tap_key(&mut app, KeyCode::KeyT);
+    assert_eq!(end_condition(&app), EndCondition::Natural);
+    tap_key(&mut app, KeyCode::KeyT);
+    assert_eq!(end_condition(&app), EndCondition::Clamped);
+    keyboard(&mut app).release(KeyCode::ShiftLeft);
+    frame(&mut app);
+
+    // 还没拖动过时切线是第一段的弦，箭头长度是切线的一半
+    let chord = points[1] - points[0];
+    let start = app.world().resource::<ControlPoints>().end_tangent(0);
+    assert!(start.distance(chord) < 1e-4);
+    let arrow = points[0] + chord * 0.5;
+    drag(&mut app, arrow, arrow + Vec2::new(0.0, 40.0));
+    let control_points = app.world().resource::<ControlPoints>();
+    let dragged = control_points.end_tangent(0);
+    assert!(
+        dragged.distance(chord + Vec2::new(0.0, 80.0)) < 1e-3,
+        "{dragged}"
+    );
+    assert_eq!(control_points.end_tangents[1], None);
+    assert_eq!(control_points.positions(), points);
+}
+
+// Shift+V 把 Catmull-Rom 换成 Bezier 控制网，Shift+H 再拟合回原来的点
+#[test]
+fn shift_v_converts_to_bezier_and_shift_h_fits_back() {
+    let points = [
+        Vec2::new(-150.0, 0.0),
+        Vec2::new(-50.0, 100.0),
+        Vec2::new(50.0, -100.0),
+        Vec2::new(150.0, 0.0),
+    ];
+    let mut app = driven_app(&points);
+    keyboard(&mut app).press(KeyCode::ShiftLeft);
+    tap_key(&mut app, KeyCode::KeyV);
+    let net = app.world().resource::<ControlPoints>().positions();
+    assert_eq!(net.len(), 10);
+    for (i, point) in points.iter().enumerate() {
+        assert!(net[i * 3].distance(*point) < 1e-4);
+    }
+
+    tap_key(&mut app, KeyCode::KeyH);
+    let fitted = app.world().resource::<ControlPoints>().positions();
+    assert_eq!(fitted.len(), points.len());
+    for (a, b) in fitted.iter().zip(&points) {
+        assert!(a.distance(*b) < 1e-4);
+    }
+}
+
+// 按住 ` 拖出一个直角，松开后化简成三个点；Shift+; 减小容差后从原始路径重新化简
+#[test]
+fn backquote_sketch_is_simplified_into_points() {
+    let mut app = driven_app(&[Vec2::new(-100.0, -100.0), Vec2::new(100.0, -100.0)]);
+    keyboard(&mut app).press(KeyCode::Backquote);
+    let mut path: Vec<Vec2> = (0..=20)
+        .map(|i| Vec2::new(i as f32 * 10.0 - 100.0, 0.0))
+        .collect();
+    // 竖的一边稍微抖动，容差更小时会多保留一些点
+    path.extend((1..=20).map(|i| Vec2::new(100.0 + (i % 2) as f32 * 2.0, i as f32 * 10.0)));
+    move_cursor(&mut app, path[0]);
+    mouse(&mut app).press(MouseButton::Left);
+    frame(&mut app);
+    for &p in &path[1..] {
+        move_cursor(&mut app, p);
+        frame(&mut app);
+    }
+    mouse(&mut app).release(MouseButton::Left);
+    frame(&mut app);
+    keyboard(&mut app).release(KeyCode::Backquote);
+    frame(&mut app);
+
+    let points = app.world().resource::<ControlPoints>().positions();
+    assert_eq!(points.len(), 3, "{points:?}");
+    assert!(points[1].distance(Vec2::new(100.0, 0.0)) < 1e-3);
+
+    keyboard(&mut app).press(KeyCode::ShiftLeft);
+    for _ in 0..8 {
+        tap_key(&mut app, KeyCode::Semicolon);
+    }
+    let refined = app.world().resource::<ControlPoints>().positions();
+    assert!(refined.len() > 3, "{refined:?}");
+    assert_eq!(refined.first(), points.first());
+}
+
+// Shift+8、Shift+9 保存两组点，按住 Shift+, 从 B 向 A 插值；每帧固定 100ms，速度每秒 0.5
+#[test]
+fn shift_comma_morphs_between_the_stored_slots() {
+    let a = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+    let b = [Vec2::new(-100.0, 200.0), Vec2::new(300.0, 0.0)];
+    let mut app = driven_app(&a);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    keyboard(&mut app).press(KeyCode::ShiftLeft);
+    tap_key(&mut app, KeyCode::Digit8);
+    app.world_mut().resource_mut::<ControlPoints>().points =
+        b.iter().copied().map(MovablePoint::new).collect();
+    tap_key(&mut app, KeyCode::Digit9);
+
+    keyboard(&mut app).press(KeyCode::Comma);
+    for _ in 0..5 {
+        frame(&mut app);
+    }
+    keyboard(&mut app).release(KeyCode::Comma);
+    frame(&mut app);
+    let points = app.world().resource::<ControlPoints>().positions();
+    for ((p, a), b) in points.iter().zip(a).zip(b) {
+        assert!(p.distance(a.lerp(b, 0.75)) < 1e-3, "{points:?}");
+    }
+
+    // 点数不同时拒绝插值，当前的点保持不变
+    app.world_mut()
+        .resource_mut::<ControlPoints>()
+        .points
+        .push(MovablePoint::new(Vec2::ZERO));
+    tap_key(&mut app, KeyCode::Digit9);
+    keyboard(&mut app).press(KeyCode::Comma);
+    frame(&mut app);
+    frame(&mut app);
+    assert_eq!(app.world().resource::<ControlPoints>().points.len(), 3);
+    assert_eq!(
+        app.world().resource::<ControlPoints>().positions()[2],
+        Vec2::ZERO
+    );
+}
+
+// Shift+Tab 添加点集，编辑只作用在当前点集上；Tab 切换，撤销按快照切回编辑时的点集
+#[test]
+fn tab_switches_between_independent_point_lists() {
+    let original = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+    let mut app = driven_app(&original);
+    keyboard(&mut app).press(KeyCode::ShiftLeft);
+    tap_key(&mut app, KeyCode::Tab);
+    keyboard(&mut app).release(KeyCode::ShiftLeft);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(
+        (control_points.active, control_points.inactive.len()),
+        (1, 1)
+    );
+    assert!(control_points.points.is_empty());
+
+    move_cursor(&mut app, Vec2::new(30.0, -40.0));
+    mouse(&mut app).press(MouseButton::Right);
+    frame(&mut app);
+    mouse(&mut app).release(MouseButton::Right);
+    frame(&mut app);
+    tap_key(&mut app, KeyCode::Tab);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.active, 0);
+    assert_eq!(control_points.positions(), original);
+    assert_eq!(
+        control_points.inactive[0].points[0].position,
+        Vec2::new(30.0, -40.0)
+    );
+
+    // 在第一个点集上撤销：回到第二个点集并去掉新加的点，再撤销一次去掉第二个点集
+    keyboard(&mut app).press(KeyCode::ControlLeft);
+    tap_key(&mut app, KeyCode::KeyZ);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.active, 1);
+    assert!(control_points.points.is_empty());
+    tap_key(&mut app, KeyCode::KeyZ);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.list_count(), 1);
+    assert_eq!(control_points.positions(), original);
+}
+
+// 方向键和拖动一样，对称编辑时轴上的点只沿轴移动
+#[test]
+fn nudging_keeps_axis_points_on_the_symmetry_axis() {
+    let mut app = driven_app(&[Vec2::new(-100.0, 0.0), Vec2::new(0.0, 80.0)]);
+    {
+        let mut control_points = app.world_mut().resource_mut::<ControlPoints>();
+        control_points.symmetry = Some(0.0);
+        for point in control_points.points.iter_mut() {
+            point.is_selected = true;
+        }
+    }
+    move_cursor(&mut app, Vec2::new(0.0, -300.0));
+    frame(&mut app);
+    keyboard(&mut app).press(KeyCode::ArrowRight);
+    keyboard(&mut app).press(KeyCode::ArrowUp);
+    frame(&mut app);
+    keyboard(&mut app).release(KeyCode::ArrowRight);
+    keyboard(&mut app).release(KeyCode::ArrowUp);
+    frame(&mut app);
+    let positions = app.world().resource::<ControlPoints>().positions();
+    assert_eq!(positions, [Vec2::new(-99.0, 1.0), Vec2::new(0.0, 81.0)]);
+}
+
+// Ctrl+Shift+Y 打开对称编辑：靠近轴加的点落在轴上，拖动轴时轴上的点跟着走；关闭时镜像点变成真正的点
+#[test]
+fn symmetry_mirrors_edits_and_bakes_when_turned_off() {
+    let mut app = driven_app(&[Vec2::new(-100.0, 0.0), Vec2::new(-50.0, 80.0)]);
+    let toggle = |app: &mut App| {
+        keyboard(app).press(KeyCode::ControlLeft);
+        keyboard(app).press(KeyCode::ShiftLeft);
+        tap_key(app, KeyCode::KeyY);
+        keyboard(app).release(KeyCode::ControlLeft);
+        keyboard(app).release(KeyCode::ShiftLeft);
+        frame(app);
+    };
+    toggle(&mut app);
+    assert_eq!(app.world().resource::<ControlPoints>().symmetry, Some(0.0));
+
+    move_cursor(&mut app, Vec2::new(3.0, 120.0));
+    mouse(&mut app).press(MouseButton::Right);
+    frame(&mut app);
+    mouse(&mut app).release(MouseButton::Right);
+    frame(&mut app);
+    assert_eq!(
+        app.world().resource::<ControlPoints>().positions()[2],
+        Vec2::new(0.0, 120.0)
+    );
+
+    drag(&mut app, Vec2::new(0.0, -200.0), Vec2::new(20.0, -200.0));
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.symmetry, Some(20.0));
+    assert_eq!(control_points.positions()[2], Vec2::new(20.0, 120.0));
+
+    toggle(&mut app);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.symmetry, None);
+    assert_eq!(
+        control_points.positions(),
+        [
+            Vec2::new(-100.0, 0.0),
+            Vec2::new(-50.0, 80.0),
+            Vec2::new(20.0, 120.0),
+            Vec2::new(90.0, 80.0),
+            Vec2::new(140.0, 0.0),
+        ]
+    );
+}
+
+// Alt+G 跟随光标平移选中的点，左键确认，可以撤销；Alt+S 缩放时 Esc 取消并恢复原来的位置
+#[test]
+fn modal_transforms_move_the_selection_until_confirmed_or_cancelled() {
+    let points = [
+        Vec2::new(-100.0, 0.0),
+        Vec2::new(100.0, 0.0),
+        Vec2::new(0.0, 100.0),
+    ];
+    let mut app = driven_app(&points);
+    {
+        let mut control_points = app.world_mut().resource_mut::<ControlPoints>();
+        control_points.points[0].is_selected = true;
+        control_points.points[1].is_selected = true;
+    }
+    move_cursor(&mut app, Vec2::new(0.0, -200.0));
+    frame(&mut app);
+    keyboard(&mut app).press(KeyCode::AltLeft);
+    tap_key(&mut app, KeyCode::KeyG);
+    keyboard(&mut app).release(KeyCode::AltLeft);
+    move_cursor(&mut app, Vec2::new(30.0, -180.0));
+    frame(&mut app);
+    mouse(&mut app).press(MouseButton::Left);
+    frame(&mut app);
+    mouse(&mut app).release(MouseButton::Left);
+    frame(&mut app);
+    let moved = [
+        Vec2::new(-70.0, 20.0),
+        Vec2::new(130.0, 20.0),
+        Vec2::new(0.0, 100.0),
+    ];
+    assert_eq!(app.world().resource::<ControlPoints>().positions(), moved);
+    // 确认之后光标移动不再影响点
+    move_cursor(&mut app, Vec2::new(-50.0, -250.0));
+    frame(&mut app);
+    assert_eq!(app.world().resource::<ControlPoints>().positions(), moved);
+
+    // 枢轴是两个选中点的重心 (30, 20)，光标到它的距离变为两倍
+    move_cursor(&mut app, Vec2::new(30.0, 0.0));
+    frame(&mut app);
+    keyboard(&mut app).press(KeyCode::AltLeft);
+    tap_key(&mut app, KeyCode::KeyS);
+    keyboard(&mut app).release(KeyCode::AltLeft);
+    move_cursor(&mut app, Vec2::new(30.0, -20.0));
+    frame(&mut app);
+    let scaled = app.world().resource::<ControlPoints>().positions();
+    assert!(
+        scaled[0].distance(Vec2::new(-170.0, 20.0)) < 1e-3,
+        "{scaled:?}"
+    );
+    tap_key(&mut app, KeyCode::Escape);
+    let control_points = app.world().resource::<ControlPoints>();
+    assert_eq!(control_points.positions(), moved);
+    // Esc 被变换消耗，选择仍然保留
+    assert!(control_points.points[0].is_selected);
+
+    keyboard(&mut app).press(KeyCode::ControlLeft);
+    tap_key(&mut app, KeyCode::KeyZ);
+    assert_eq!(app.world().resource::<ControlPoints>().positions(), points);
+}
+
+// Alt+H 对齐、Alt+D 分布选中的点，未选中的点不动；选中的点太少时不做任何事
+#[test]
+fn alt_keys_align_and_distribute_the_selection() {
+    let points = [
+        Vec2::new(-100.0, 0.0),
+        Vec2::new(-20.0, 60.0),
+        Vec2::new(0.0, 200.0),
+        Vec2::new(80.0, -30.0),
+        Vec2::new(100.0, 30.0),
+    ];
+    let mut app = driven_app(&points);
+    let alt_tap = |app: &mut App, key: KeyCode| {
+        keyboard(app).press(KeyCode::AltLeft);
+        tap_key(app, key);
+        keyboard(app).release(KeyCode::AltLeft);
+        frame(app);
+    };
+    app.world_mut().resource_mut::<ControlPoints>().points[0].is_selected = true;
+    alt_tap(&mut app, KeyCode::KeyH);
+    assert_eq!(app.world().resource::<ControlPoints>().positions(), points);
+
+    for i in [1, 3, 4] {
+        app.world_mut().resource_mut::<ControlPoints>().points[i].is_selected = true;
+    }
+    alt_tap(&mut app, KeyCode::KeyH);
+    let aligned = app.world().resource::<ControlPoints>().positions();
+    assert!(aligned
+        .iter()
+        .enumerate()
+        .all(|(i, p)| p.y == if i == 2 { 200.0 } else { 15.0 }));
+
+    alt_tap(&mut app, KeyCode::KeyD);
+    let distributed = app.world().resource::<ControlPoints>().positions();
+    let expected = [
+        Vec2::new(-100.0, 15.0),
+        Vec2::new(-100.0 / 3.0, 15.0),
+        Vec2::new(0.0, 200.0),
+        Vec2::new(100.0 / 3.0, 15.0),
+        Vec2::new(100.0, 15.0),
+    ];
+    for (p, e) in distributed.iter().zip(expected) {
+        assert!(p.distance(e) < 1e-3, "{distributed:?}");
+    }
+
+    keyboard(&mut app).press(KeyCode::ControlLeft);
+    tap_key(&mut app, KeyCode::KeyZ);
+    tap_key(&mut app, KeyCode::KeyZ);
+    assert_eq!(app.world().resource::<ControlPoints>().positions(), points);
+}
+
+fn ctrl_tap(app: &mut App, key: KeyCode) {
+    keyboard(app).press(KeyCode::ControlLeft);
+    keyboard(app).press(key);
+    frame(app);
+    keyboard(app).release(key);
+    keyboard(app).release(KeyCode::ControlLeft);
+    frame(app);
+}
+
+// Ctrl+F6 录制加点和拖动，Ctrl+F7 从录制开始时的点重新执行它们；回放中按键会中止回放
+#[test]
+fn recorded_edits_replay_and_a_key_press_aborts() {
+    let start = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+    let mut app = driven_app(&start);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    ctrl_tap(&mut app, KeyCode::F6);
+    move_cursor(&mut app, Vec2::new(250.0, 80.0));
+    mouse(&mut app).press(MouseButton::Right);
+    frame(&mut app);
+    mouse(&mut app).release(MouseButton::Right);
+    frame(&mut app);
+    drag(&mut app, Vec2::new(-100.0, 0.0), Vec2::new(-150.0, 40.0));
+    ctrl_tap(&mut app, KeyCode::F6);
+    let edited = app.world().resource::<ControlPoints>().positions();
+    assert_eq!(edited.len(), 3);
+    assert_eq!(edited[0], Vec2::new(-150.0, 40.0));
+
+    ctrl_tap(&mut app, KeyCode::F7);
+    for _ in 0..20 {
+        frame(&mut app);
+    }
+    assert_eq!(app.world().resource::<ControlPoints>().positions(), edited);
+
+    // 回放刚开始，还没有执行任何编辑时按下 C 中止，C 本身照常删除最后一个点
+    ctrl_tap(&mut app, KeyCode::F7);
+    assert_eq!(app.world().resource::<ControlPoints>().positions(), start);
+    tap_key(&mut app, KeyCode::KeyC);
+    for _ in 0..20 {
+        frame(&mut app);
+    }
+    assert_eq!(
+        app.world().resource::<ControlPoints>().positions(),
+        [Vec2::new(-100.0, 0.0)]
+    );
+}
+
+// 没有专门记录方式的编辑（这里是 L 闭合曲线）、添加点集和切换点集也能回放，
+// 切换之后的加点落在录制时的点集上
+#[test]
+fn replay_covers_list_switches_and_whole_scene_edits() {
+    let start = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+    let mut app = driven_app(&start);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    ctrl_tap(&mut app, KeyCode::F6);
+    tap_key(&mut app, KeyCode::KeyL);
+    keyboard(&mut app).press(KeyCode::ShiftLeft);
+    tap_key(&mut app, KeyCode::Tab);
+    keyboard(&mut app).release(KeyCode::ShiftLeft);
+    tap_key(&mut app, KeyCode::Tab);
+    move_cursor(&mut app, Vec2::new(0.0, 150.0));
+    mouse(&mut app).press(MouseButton::Right);
+    frame(&mut app);
+    mouse(&mut app).release(MouseButton::Right);
+    frame(&mut app);
+    ctrl_tap(&mut app, KeyCode::F6);
+    let summary = |app: &App| {
+        let control_points = app.world().resource::<ControlPoints>();
+        (
+            control_points.active,
+            control_points.closed,
+            control_points.positions(),
+            control_points
+                .inactive
+                .iter()
+                .map(|list| list.points.iter().map(|p| p.position).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+        )
+    };
+    let edited = summary(&app);
+    assert_eq!(
+        edited,
+        (
+            0,
+            true,
+            vec![start[0], start[1], Vec2::new(0.0, 150.0)],
+            vec![Vec::new()]
+        )
+    );
+
+    ctrl_tap(&mut app, KeyCode::F7);
+    assert_eq!(app.world().resource::<ControlPoints>().list_count(), 1);
+    for _ in 0..20 {
+        frame(&mut app);
+    }
+    assert_eq!(summary(&app), edited);
+}
+
+#[derive(Default, Resource)]
+struct ReceivedCurves(Vec<CurveChanged>);
+
+fn receive_curves(mut events: EventReader<CurveChanged>, mut received: ResMut<ReceivedCurves>) {
+    received.0.extend(events.read().cloned());
+}
+
+fn take_received(app: &mut App) -> Vec<CurveChanged> {
+    std::mem::take(&mut app.world_mut().resource_mut::<ReceivedCurves>().0)
+}
+
+// 宿主程序的系统只在曲线的采样点确实改变时收到 CurveChanged，事件里的采样点就是 SampledCurves 中的
+#[test]
+fn curve_changed_is_sent_only_when_the_samples_change() {
+    let mut app = driven_app(&[
+        Vec2::new(-200.0, 0.0),
+        Vec2::new(0.0, 150.0),
+        Vec2::new(200.0, 0.0),
+    ]);
+    app.init_resource::<ReceivedCurves>()
+        .add_systems(Update, receive_curves);
+    frame(&mut app);
+    let received = take_received(&mut app);
+    assert!(received
+        .iter()
+        .any(|event| event.curve == CurveKind::CatmullRom));
+    let sampled = app.world().resource::<SampledCurves>();
+    for event in &received {
+        assert_eq!(event.samples[..], *sampled.get(event.curve));
+    }
+
+    // 静止的帧和没有改变点的写入都不发送
+    for _ in 0..5 {
+        frame(&mut app);
+    }
+    app.world_mut()
+        .resource_mut::<ControlPoints>()
+        .set_changed();
+    frame(&mut app);
+    frame(&mut app);
+    assert!(take_received(&mut app).is_empty());
+
+    drag(&mut app, Vec2::new(0.0, 150.0), Vec2::new(0.0, 250.0));
+    frame(&mut app);
+    let received = take_received(&mut app);
+    let catmull_rom: Vec<&CurveChanged> = received
+        .iter()
+        .filter(|event| event.curve == CurveKind::CatmullRom)
+        .collect();
+    // 拖动只移动了一帧
+    assert_eq!(catmull_rom.len(), 1);
+    let sampled = app.world().resource::<SampledCurves>();
+    assert_eq!(
+        catmull_rom[0].samples[..],
+        *sampled.get(CurveKind::CatmullRom)
+    );
+    assert!(sampled
+        .get(CurveKind::CatmullRom)
+        .iter()
+        .any(|p| p.y > 200.0));
+}
+
+// BuiltCurves 里的曲线就是被采样的曲线；点数不够时清空
+#[test]
+fn built_curves_match_the_samples_and_clear_without_enough_points() {
+    let mut app = driven_app(&[
+        Vec2::new(-200.0, 0.0),
+        Vec2::new(-70.0, 150.0),
+        Vec2::new(70.0, -150.0),
+        Vec2::new(200.0, 0.0),
+    ]);
+    frame(&mut app);
+    let world = app.world();
+    let built = world.resource::<BuiltCurves>();
+    let sampled = world.resource::<SampledCurves>();
+    for kind in [CurveKind::BSpline, CurveKind::CatmullRom, CurveKind::Bezier] {
+        let curve = built.get(kind).unwrap();
+        let samples = sampled.get(kind);
+        let end = curve.segments().len() as f32;
+        assert!(curve.position(0.0).distance(samples[0]) < 1e-3);
+        assert!(curve.position(end).distance(samples[samples.len() - 1]) < 1e-3);
+    }
+    assert!(built.get(CurveKind::ControlPolygon).is_none());
+
+    // 删除点后等过渡动画结束
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.world_mut()
+        .resource_mut::<ControlPoints>()
+        .points
+        .truncate(1);
+    for _ in 0..10 {
+        frame(&mut app);
+    }
+    let built = app.world().resource::<BuiltCurves>();
+    assert!(built.b_spline.is_none());
+    assert!(built.catmull_rom.is_none());
+    assert!(built.bezier.is_none());
+}